@@ -0,0 +1,47 @@
+//! Compares getting the row heights every frame with the cache against recomputing them each time.
+//! Run with `cargo bench --bench row_heights`
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use log_viewer::core::{
+    data::Data,
+    data_display_options::{DataDisplayOptions, RowHeight},
+};
+
+/// Number of rows in the generated input
+const ROW_COUNT: usize = 200_000;
+
+/// JSON lines where every tenth message spans a few lines
+fn generated_input() -> String {
+    (0..ROW_COUNT)
+        .map(|i| {
+            let msg = if i % 10 == 0 {
+                format!("request {i} failed\\n  at handler\\n  at server")
+            } else {
+                format!("request {i} done")
+            };
+            format!(r#"{{"time": "2024-02-10T03:10:25Z", "level": 30, "msg": "{msg}"}}"#)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn row_heights(c: &mut Criterion) {
+    let options = DataDisplayOptions::default();
+    let mut data = Data::try_from((&options, generated_input().as_str()))
+        .expect("generated input should parse");
+    let settings = RowHeight::default();
+    let mut group = c.benchmark_group("row_heights_200k");
+    group.bench_function("cached", |b| b.iter(|| data.row_heights(&settings, 14.0)));
+    // Alternating the text height makes every call miss the cache
+    let mut is_taller = false;
+    group.bench_function("uncached", |b| {
+        b.iter(|| {
+            is_taller = !is_taller;
+            data.row_heights(&settings, if is_taller { 15.0 } else { 14.0 })
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, row_heights);
+criterion_main!(benches);
@@ -1,44 +1,275 @@
-use self::{data::Data, data_display_options::DataDisplayOptions};
+use self::{
+    data::{Data, DataParser, FieldContent, FilterJob},
+    data_display_options::{DataDisplayOptions, LoadRange, LogFormat, RowParseErrorHandling},
+};
+use crate::core::{data, data_display_options, display_profiles::DisplayProfiles, table_export};
+use annotations::Annotations;
 #[cfg(not(target_arch = "wasm32"))]
 use anyhow::{bail, Context};
-use data::filter::{Comparator, FieldSpecifier, FilterConfig, FilterOn};
+#[cfg(all(feature = "cloudwatch", not(target_arch = "wasm32")))]
+use cloudwatch::{CloudWatch, CloudWatchQuery};
+use colored_fields_ui::ColoredFieldsUi;
+use data::decode::{self, TextEncoding};
+use data::filter::{Comparator, FieldSpecifier, FilterConfig, FilterOn, IgnoreRule, LevelFilter};
+use data::sort::SortCompare;
+#[cfg(not(target_arch = "wasm32"))]
+use data::stream::StreamParser;
+use detached_details::DetachedDetails;
+use details_keyboard::{DetailsKeyAction, DetailsKeyboard};
+use display_profiles_ui::{DisplayProfilesUi, ProfileAction};
 use egui::{
     text::{CCursor, CCursorRange},
     Align, KeyboardShortcut,
 };
 use egui_extras::{Column, TableBuilder};
+#[cfg(not(target_arch = "wasm32"))]
+use external_editor::ExternalEditor;
+use field_formatters_ui::FieldFormattersUi;
+use field_plot::FieldPlot;
+use field_schema::FieldSchema;
+use filter_diff::FilterDiff;
+use filter_sharing::{FilterImport, FilterSharing};
+use filter_undo::FilterUndo;
+#[cfg(not(target_arch = "wasm32"))]
+use folder_watch::FolderWatch;
+use json_tree::JsonTree;
+use level_conversion_ui::LevelConversionUi;
+use live_pause::LivePause;
+use live_source::LiveSource;
 use log::info;
+use match_count::MatchCounter;
+use message_templates::MessageTemplates;
+use minimap::Minimap;
+use range_filters::RangeFiltersUi;
+use related_rows::{RelatedRows, RelatedRowsCache};
+use request_summary::RequestSummary;
+use request_waterfall::RequestWaterfall;
+use reset::{ResetChoices, ResetDialog};
+use row_actions::{equal_to_value_filter, ContextMenuRegistry};
+pub use row_actions::{ActionGroup, MenuContext, RowAction, RowCommand, SimpleAction};
+use row_diff::RowDiff;
+use search_history::SearchHistory;
+use selection_history::SelectionHistory;
+#[cfg(target_arch = "wasm32")]
+use share_link::SharedView;
 use shortcut::Shortcuts;
+use shortcut_editor::ShortcutEditor;
+#[cfg(not(target_arch = "wasm32"))]
+use single_instance::SingleInstance;
+use split_view::{SplitView, SplitViewAction};
+#[cfg(not(target_arch = "wasm32"))]
+use ssh_tail::SshTail;
+use stack_trace::StackTraceView;
+use status_bar::{StatusBar, StatusInfo};
 use std::{
-    hash::{DefaultHasher, Hash, Hasher},
+    collections::BTreeMap,
     path::PathBuf,
     sync::{Arc, LazyLock, Mutex},
 };
-
-mod data;
-mod data_display_options;
+use tasks::{execute, ChunkedTask, TaskHandle, TaskManager, TaskOutcome, ROWS_PER_CHUNK};
+use time_jump_ui::TimeJumpUi;
+
+use data::line_parser::LineParser;
+use top_values::TopValues;
+use trace_view::TraceView;
+use value_viewer::ValueViewer;
+use watches::Watches;
+use websocket_source::WebSocketSource;
+use workspaces::{SavedFilters, Workspace, WorkspaceAction, Workspaces};
+
+mod annotations;
+#[cfg(all(feature = "cloudwatch", not(target_arch = "wasm32")))]
+mod cloudwatch;
+mod colored_fields_ui;
+mod detached_details;
+mod details_keyboard;
+mod display_profiles_ui;
+#[cfg(not(target_arch = "wasm32"))]
+mod external_editor;
+mod field_formatters_ui;
+mod field_plot;
+mod field_schema;
+mod filter_diff;
+mod filter_sharing;
+mod filter_undo;
+#[cfg(not(target_arch = "wasm32"))]
+mod folder_watch;
+mod json_tree;
+mod level_conversion_ui;
+mod live_pause;
+mod live_source;
+mod match_count;
+mod message_templates;
+mod minimap;
+#[cfg(not(target_arch = "wasm32"))]
+mod paths;
+mod range_filters;
+mod related_rows;
+mod request_summary;
+mod request_waterfall;
+mod reset;
+mod row_actions;
+mod row_diff;
+mod row_highlights_ui;
+mod search_history;
+mod selection_history;
+#[cfg(any(target_arch = "wasm32", test))]
+mod share_link;
 mod shortcut;
+mod shortcut_editor;
+#[cfg(not(target_arch = "wasm32"))]
+mod single_instance;
+mod split_view;
+#[cfg(not(target_arch = "wasm32"))]
+mod ssh_tail;
+mod stack_trace;
+mod status_bar;
+mod tasks;
+mod time_jump_ui;
+mod top_values;
+mod trace_view;
+mod value_viewer;
+mod watches;
+#[cfg(not(target_arch = "wasm32"))]
+mod websocket_client;
+mod websocket_source;
+mod workspaces;
 
 #[derive(serde::Deserialize, serde::Serialize)]
 #[serde(default)] // if we add new fields, give them default values when deserializing old state
 pub struct LogViewerApp {
     data: Option<Data>,
     data_display_options: DataDisplayOptions,
+    display_profiles: DisplayProfiles,
+    watches: Watches,
+    /// Name of the display profile the current options came from
+    active_profile: Option<String>,
     start_open_path: Arc<Mutex<Option<PathBuf>>>,
     last_filename: Arc<Mutex<Option<PathBuf>>>,
     show_last_filename: bool,
+    /// Shows a text box under each column header to filter on that column
+    show_column_filters: bool,
+    /// Shows the controls above the rows (hidden to fit more rows on small screens)
+    show_controls: bool,
+    /// Shows the details of the selected row below the rows
+    show_details: bool,
+    /// Shows the last row at the top of the main list (only the display is reversed, next still means the later row)
+    is_newest_first: bool,
+    /// Applies the filter shortly after the search key is edited
+    should_filter_as_you_type: bool,
     track_item_align: Option<Align>,
     shortcuts: Shortcuts,
+    /// Field copied from the selected row with [`Shortcuts::copy_key_field`] (eg. to paste the id into other tools)
+    copy_key_field_name: String,
     should_scroll_to_end_on_load: bool,
+    /// Keep the newest row selected and in view as rows are added, turned off when the user scrolls up
+    is_following: bool,
     /// Allows the user to dim the warning by clicking on it
     should_highlight_field_warning: bool,
+    trace_view: TraceView,
+    filter_diff: FilterDiff,
+    row_diff: RowDiff,
+    annotations: Annotations,
+    minimap: Minimap,
+    top_values: TopValues,
+    message_templates: MessageTemplates,
+    value_viewer: ValueViewer,
+    request_summary: RequestSummary,
+    request_waterfall: RequestWaterfall,
+    field_plot: FieldPlot,
+    field_schema: FieldSchema,
+    split_view: SplitView,
+    status_bar: StatusBar,
+    stack_trace_view: StackTraceView,
+    reset_dialog: ResetDialog,
+    shortcut_editor: ShortcutEditor,
+    workspaces: Workspaces,
+    search_history: SearchHistory,
+    #[cfg(not(target_arch = "wasm32"))]
+    ssh_tail: SshTail,
+    websocket_source: WebSocketSource,
+    #[cfg(all(feature = "cloudwatch", not(target_arch = "wasm32")))]
+    cloudwatch: CloudWatch,
+    #[cfg(not(target_arch = "wasm32"))]
+    folder_watch: FolderWatch,
+    #[cfg(not(target_arch = "wasm32"))]
+    external_editor: ExternalEditor,
+    #[cfg(not(target_arch = "wasm32"))]
+    #[serde(skip)]
+    single_instance: Option<SingleInstance>,
 
+    /// Set when started in safe mode, the saved state was not loaded and is not overwritten
+    #[serde(skip)]
+    is_safe_mode: bool,
+    /// View from the link the app was opened with, applied to the next data loaded
+    #[cfg(target_arch = "wasm32")]
+    #[serde(skip)]
+    pending_shared_view: Option<SharedView>,
     #[serde(skip)]
     should_focus_search: bool,
+    /// Only fields with a name or value containing this text are shown in the details pane
+    #[serde(skip)]
+    details_filter: String,
+    /// Text in the column filter boxes which is only applied when editing is done
+    #[serde(skip)]
+    column_filter_edits: BTreeMap<String, String>,
+    /// Names of the fields in the data offered when choosing the field to filter on, computed on first use
+    #[serde(skip)]
+    field_names: Option<Vec<String>>,
+    /// Time of the last press of the shortcut for the first row, used when it must be pressed twice
+    #[serde(skip)]
+    pending_first_press: Option<f64>,
+    /// Time of the last edit to the search key not yet applied (only used when filtering as you type)
+    #[serde(skip)]
+    search_key_edit_time: Option<f64>,
     #[serde(skip)]
     should_scroll: bool,
+    /// Row (index into all rows) to scroll to the top of the main table without selecting it
+    #[serde(skip)]
+    scroll_to_real_index: Option<usize>,
+    /// Index into all rows of the first row shown in the main table when it was last drawn
+    #[serde(skip)]
+    first_visible_real_index: Option<usize>,
+    #[serde(skip)]
+    tasks: TaskManager,
+    /// Filter being applied a chunk of rows per frame, see [`Self::start_filter`]
+    #[serde(skip)]
+    filter_task: Option<(FilterJob, ChunkedTask)>,
+    /// Results of completed tasks to show the user (Err for failures)
+    #[serde(skip)]
+    task_messages: Vec<Result<String, String>>,
+    #[serde(skip)]
+    details_json_tree: JsonTree,
     #[serde(skip)]
-    loading_status: LoadingStatus,
+    details_keyboard: DetailsKeyboard,
+    #[serde(skip)]
+    range_filters_ui: RangeFiltersUi,
+    #[serde(skip)]
+    match_counter: MatchCounter,
+    #[serde(skip)]
+    filter_undo: FilterUndo,
+    #[serde(skip)]
+    filter_sharing: FilterSharing,
+    #[serde(skip)]
+    selection_history: SelectionHistory,
+    #[serde(skip)]
+    time_jump_ui: TimeJumpUi,
+    #[serde(skip)]
+    live_pause: LivePause,
+    #[serde(skip)]
+    colored_fields_ui: ColoredFieldsUi,
+    #[serde(skip)]
+    display_profiles_ui: DisplayProfilesUi,
+    #[serde(skip)]
+    field_formatters_ui: FieldFormattersUi,
+    #[serde(skip)]
+    level_conversion_ui: LevelConversionUi,
+    #[serde(skip)]
+    related_rows: RelatedRowsCache,
+    #[serde(skip)]
+    context_menu: ContextMenuRegistry,
+    #[serde(skip)]
+    detached_details: DetachedDetails,
 }
 
 impl Default for LogViewerApp {
@@ -46,53 +277,196 @@ impl Default for LogViewerApp {
         Self {
             data: Default::default(),
             data_display_options: Default::default(),
+            display_profiles: Default::default(),
+            watches: Default::default(),
+            active_profile: Default::default(),
             start_open_path: Default::default(),
-            loading_status: Default::default(),
+            tasks: Default::default(),
+            filter_task: Default::default(),
+            task_messages: Default::default(),
             last_filename: Default::default(),
             track_item_align: Some(Align::Center),
             shortcuts: Default::default(),
+            copy_key_field_name: DEFAULT_COPY_KEY_FIELD.to_string(),
             should_scroll_to_end_on_load: Default::default(),
+            is_following: Default::default(),
             should_highlight_field_warning: true,
             should_focus_search: Default::default(),
+            details_filter: Default::default(),
+            column_filter_edits: Default::default(),
+            field_names: Default::default(),
+            pending_first_press: Default::default(),
+            search_key_edit_time: Default::default(),
             should_scroll: Default::default(),
+            scroll_to_real_index: Default::default(),
+            first_visible_real_index: Default::default(),
             show_last_filename: true,
+            show_column_filters: Default::default(),
+            show_controls: true,
+            show_details: true,
+            is_newest_first: Default::default(),
+            should_filter_as_you_type: Default::default(),
+            trace_view: Default::default(),
+            filter_diff: Default::default(),
+            row_diff: Default::default(),
+            annotations: Default::default(),
+            minimap: Default::default(),
+            top_values: Default::default(),
+            message_templates: Default::default(),
+            value_viewer: Default::default(),
+            request_summary: Default::default(),
+            request_waterfall: Default::default(),
+            field_plot: Default::default(),
+            field_schema: Default::default(),
+            stack_trace_view: Default::default(),
+            split_view: Default::default(),
+            status_bar: Default::default(),
+            reset_dialog: Default::default(),
+            shortcut_editor: Default::default(),
+            workspaces: Default::default(),
+            search_history: Default::default(),
+            is_safe_mode: Default::default(),
+            #[cfg(target_arch = "wasm32")]
+            pending_shared_view: Default::default(),
+            #[cfg(not(target_arch = "wasm32"))]
+            ssh_tail: Default::default(),
+            websocket_source: Default::default(),
+            #[cfg(all(feature = "cloudwatch", not(target_arch = "wasm32")))]
+            cloudwatch: Default::default(),
+            #[cfg(not(target_arch = "wasm32"))]
+            folder_watch: Default::default(),
+            #[cfg(not(target_arch = "wasm32"))]
+            external_editor: Default::default(),
+            #[cfg(not(target_arch = "wasm32"))]
+            single_instance: Default::default(),
+            details_json_tree: Default::default(),
+            range_filters_ui: Default::default(),
+            match_counter: Default::default(),
+            filter_undo: Default::default(),
+            filter_sharing: Default::default(),
+            selection_history: Default::default(),
+            time_jump_ui: Default::default(),
+            live_pause: Default::default(),
+            details_keyboard: Default::default(),
+            colored_fields_ui: Default::default(),
+            display_profiles_ui: Default::default(),
+            field_formatters_ui: Default::default(),
+            level_conversion_ui: Default::default(),
+            related_rows: Default::default(),
+            context_menu: Default::default(),
+            detached_details: Default::default(),
         }
     }
 }
 
-#[derive(Default)]
-pub enum LoadingStatus {
-    #[default]
-    NotInProgress,
-    InProgress(poll_promise::Promise<Box<LoadingStatus>>),
-    Failed(String),
-    Success(String),
-}
-
 impl LogViewerApp {
     /// Called once before the first frame.
     pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
         // This is also where you can customize the look and feel of egui using
         // `cc.egui_ctx.set_visuals` and `cc.egui_ctx.set_fonts`.
 
+        if is_safe_mode_requested(cc) {
+            info!("Starting in safe mode, saved state ignored");
+            // egui restores its memory before the app is created
+            cc.egui_ctx.memory_mut(|mem| *mem = Default::default());
+            return Self {
+                is_safe_mode: true,
+                ..Default::default()
+            };
+        }
+
         // Load previous app state (if any).
         // Note that you must enable the `persistence` feature for this to work.
-        if let Some(storage) = cc.storage {
-            info!("Storage found");
-            return eframe::get_value(storage, eframe::APP_KEY).unwrap_or_else(|| {
-                info!("failed to load data");
-                Default::default()
-            });
+        #[allow(unused_mut)] // Only changed on the web
+        let mut result: Self = match cc.storage {
+            Some(storage) => {
+                info!("Storage found");
+                eframe::get_value(storage, eframe::APP_KEY).unwrap_or_else(|| {
+                    info!("failed to load data");
+                    Default::default()
+                })
+            }
+            None => Default::default(),
+        };
+
+        #[cfg(target_arch = "wasm32")]
+        if let Some(view) = SharedView::from_fragment(&cc.integration_info.web_info.location.hash) {
+            info!("Opening the view from the link");
+            view.apply_to_options(&mut result.data_display_options);
+            result.pending_shared_view = Some(view);
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            result.single_instance = SingleInstance::listen(cc.egui_ctx.clone());
+            if let Some(path) = single_instance::file_argument() {
+                result.open_path(cc.egui_ctx.clone(), path);
+            }
         }
 
-        Default::default()
+        result
+    }
+
+    /// Natively, if a file was passed on the command line and another instance is running, sends the file to it.
+    /// Returns true if the running instance opened the file (so no new window is needed)
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn open_in_running_instance() -> bool {
+        single_instance::file_argument()
+            .is_some_and(|path| single_instance::send_to_running_instance(&path))
+    }
+
+    /// Adds a parser that can be chosen as the log format in the options (shown as `name`).
+    /// Parsers are not saved so they must be registered each time the app is created
+    pub fn register_line_parser(
+        &mut self,
+        name: impl Into<String>,
+        factory: impl Fn() -> Box<dyn LineParser> + Send + Sync + 'static,
+    ) {
+        self.data_display_options
+            .parsers
+            .register(name.into(), Arc::new(factory));
+    }
+
+    /// Adds an entry to the context menu of rows and cells (shown with the built-in actions of the same group).
+    /// Actions are not saved so they must be registered each time the app is created
+    pub fn register_row_action(&mut self, action: impl RowAction + 'static) {
+        self.context_menu.register(action);
     }
 
     fn show_log_lines(&mut self, ui: &mut egui::Ui) {
+        let is_newest_first = self.is_newest_first;
+        if self.is_following
+            && ui.rect_contains_pointer(ui.clip_rect())
+            && ui.input(|i| {
+                let delta = i.smooth_scroll_delta.y;
+                if is_newest_first {
+                    delta < 0.0
+                } else {
+                    delta > 0.0
+                }
+            })
+        {
+            // User scrolled away from the newest rows to look at older rows
+            self.is_following = false;
+        }
+        // Converts between the index of a row in the table and its position in the visible rows
+        let display_index = |len: usize, index: usize| {
+            if is_newest_first {
+                len - 1 - index
+            } else {
+                index
+            }
+        };
+
         let text_height = egui::TextStyle::Body
             .resolve(ui.style())
             .size
             .max(ui.spacing().interact_size.y);
+        let header_height = if self.show_column_filters {
+            2.0 * text_height + ui.spacing().item_spacing.y
+        } else {
+            text_height
+        };
 
         let mut table_builder = TableBuilder::new(ui)
             .striped(true)
@@ -100,58 +474,133 @@ impl LogViewerApp {
             // .stick_to_bottom(self.scroll_to_end_on_load) // Removed because it disabled scroll on move if selected
             .cell_layout(egui::Layout::left_to_right(egui::Align::LEFT));
 
-        // Set all columns but the last to auto, last should be remainder which is set after the loop
+        // Set all columns but the last to auto, last should be remainder which is set after the loop.
+        // Clipped so long values are truncated instead of making the columns wider than the window
         let n = self.data_display_options.main_list_fields().len();
         for _ in 0..n - 1 {
-            table_builder = table_builder.column(Column::auto());
+            table_builder = table_builder.column(Column::auto().clip(true));
         }
         table_builder = table_builder
-            .column(Column::remainder())
+            .column(Column::remainder().clip(true))
             .min_scrolled_height(0.0);
 
         // Make table clickable
         table_builder = table_builder.sense(egui::Sense::click());
 
+        // Scrolling to the selected row takes priority over scrolling to keep in sync with the split view
+        let scroll_to_real_index = self.scroll_to_real_index.take();
         table_builder = match (self.should_scroll, self.data.as_ref()) {
             (true, Some(data)) => {
                 self.should_scroll = false;
                 if let Some(selected_row) = data.selected_row {
-                    table_builder.scroll_to_row(selected_row, self.track_item_align)
+                    table_builder.scroll_to_row(
+                        display_index(data.len(), selected_row),
+                        self.track_item_align,
+                    )
                 } else {
                     table_builder
                 }
             }
-            (true, None) | (false, _) => {
+            (false, Some(data)) => {
+                match scroll_to_real_index.and_then(|x| data.position_of_real(x)) {
+                    Some(position) => table_builder
+                        .scroll_to_row(display_index(data.len(), position), Some(Align::TOP)),
+                    None => table_builder,
+                }
+            }
+            (_, None) => {
                 self.should_scroll = false;
                 table_builder
             }
         };
 
-        let table = table_builder.header(text_height, |mut header| {
+        let mut is_column_filter_edit_done = false;
+        let table = table_builder.header(header_height, |mut header| {
             for field_name in self.data_display_options.main_list_fields() {
                 header.col(|ui| {
-                    ui.strong(field_name);
+                    ui.vertical(|ui| {
+                        ui.strong(field_name);
+                        if self.show_column_filters {
+                            let text = self
+                                .column_filter_edits
+                                .entry(field_name.clone())
+                                .or_insert_with(|| {
+                                    self.data
+                                        .as_ref()
+                                        .and_then(|data| data.column_filters().get(field_name))
+                                        .cloned()
+                                        .unwrap_or_default()
+                                });
+                            is_column_filter_edit_done |= ui
+                                .add(
+                                    egui::TextEdit::singleline(text)
+                                        .hint_text("Contains")
+                                        .desired_width(f32::INFINITY),
+                                )
+                                .lost_focus();
+                        }
+                    });
                 });
             }
         });
+        if let (true, Some(data)) = (is_column_filter_edit_done, self.data.as_mut()) {
+            let mut column_filters = self.column_filter_edits.clone();
+            column_filters.retain(|_, text| !text.is_empty());
+            if &column_filters != data.column_filters() {
+                data.set_column_filters(column_filters, self.data_display_options.common_fields());
+                self.should_scroll = true;
+            }
+        }
 
+        let mut row_command = None;
+        let mut group_to_toggle = None;
+        let mut run_to_toggle = None;
+        let mut first_visible = None;
         if let Some(data) = &mut self.data {
+            self.annotations.refresh(
+                data,
+                self.data_display_options.row_idx_field_name.as_deref(),
+            );
+            self.message_templates.update_rare(data);
             table.body(|body| {
-                // TODO 3: Figure out if calculating these values only once is worth it.
-                // TODO 4: Remove hard coded "msg"
-                let heights: Vec<f32> = data
-                    .rows_iter()
-                    .map(|x| {
-                        (1f32).max(x.field_value("msg").display().lines().count() as f32)
-                            * text_height
-                    })
-                    .collect();
-                body.heterogeneous_rows(heights.into_iter(), |mut row| {
-                    let row_index = row.index();
-                    let log_row = &data
+                let heights = data.row_heights(&self.data_display_options.row_height, text_height);
+                let len = data.len();
+                let display_heights = (0..len).map(|i| heights[display_index(len, i)]);
+                body.heterogeneous_rows(display_heights, |mut row| {
+                    let row_index = display_index(len, row.index());
+                    // Only the rows in view are drawn so the first drawn is the first visible
+                    first_visible.get_or_insert_with(|| data.get_real_index(row_index));
+                    let log_row = data
                         .rows_iter()
                         .nth(row_index)
                         .expect("len was passed above should only be valid indices");
+                    let note = self.annotations.note(data.get_real_index(row_index));
+                    let is_context_only = data.is_context_only_row(data.get_real_index(row_index));
+                    let run = data
+                        .run_length(data.get_real_index(row_index))
+                        .map(|length| {
+                            let real_index = data.get_real_index(row_index);
+                            (real_index, length, data.is_run_expanded(real_index))
+                        });
+                    let group_header = data.group_header(data.get_real_index(row_index));
+                    let time_gaps = &self.data_display_options.time_gaps;
+                    let time_gap = data
+                        .time_gap_before(row_index, &time_gaps.time_field_name)
+                        .and_then(|gap| time_gaps.marked_gap(gap));
+                    let highlight = self.data_display_options.row_highlight(log_row);
+                    let is_rare = self
+                        .message_templates
+                        .is_rare(data.get_real_index(row_index));
+                    let tint = highlight
+                        .and_then(|x| x.background)
+                        .or_else(|| is_rare.then_some(RARE_MESSAGE_COLOR))
+                        .or_else(|| {
+                            self.data_display_options.row_tint_color(
+                                &log_row
+                                    .field_value(self.data_display_options.level_field_name())
+                                    .display_str(),
+                            )
+                        });
 
                     let emphasis_info = if let Some(selected_row) = data.selected_row {
                         row.set_selected(selected_row == row_index);
@@ -185,11 +634,115 @@ impl LogViewerApp {
                         let should_emphasize_field =
                             Some((field_idx, field_value)) == emphasis_info;
 
-                        row.col(|ui| {
+                        let (_, response) = row.col(|ui| {
+                            if let Some(tint) = tint {
+                                // Expanded to cover the spacing between cells
+                                let rect = ui.max_rect().expand2(ui.spacing().item_spacing * 0.5);
+                                ui.painter().rect_filled(rect, 0.0, tint);
+                            }
+                            if let Some(gap) = time_gap {
+                                let rect = ui.max_rect().expand2(ui.spacing().item_spacing * 0.5);
+                                // Drawn between the row and the row before it in time
+                                let y = if is_newest_first {
+                                    rect.bottom()
+                                } else {
+                                    rect.top()
+                                };
+                                ui.painter().hline(
+                                    rect.x_range(),
+                                    y,
+                                    egui::Stroke::new(2.0, TIME_GAP_COLOR),
+                                );
+                                if field_idx == 0 {
+                                    ui.label(
+                                        egui::RichText::new(format!("+{}", time_gap_text(gap)))
+                                            .small()
+                                            .color(TIME_GAP_COLOR),
+                                    )
+                                    .on_hover_text("Time since the previous row");
+                                }
+                            }
+                            if let (0, Some((group_id, is_collapsed))) = (field_idx, group_header) {
+                                let (icon, hint) = if is_collapsed {
+                                    ("⏵", "Expand group")
+                                } else {
+                                    ("⏷", "Collapse group")
+                                };
+                                if ui.small_button(icon).on_hover_text(hint).clicked() {
+                                    group_to_toggle = Some(group_id);
+                                }
+                            }
+                            if let (0, Some((real_index, length, is_expanded))) = (field_idx, run) {
+                                let hint = if is_expanded {
+                                    "Hide repeats"
+                                } else {
+                                    "Show repeats"
+                                };
+                                if ui
+                                    .small_button(format!("×{length}"))
+                                    .on_hover_text(hint)
+                                    .clicked()
+                                {
+                                    run_to_toggle = Some(real_index);
+                                }
+                            }
+                            if let (0, Some(note)) = (field_idx, note) {
+                                ui.label("📝").on_hover_text(note);
+                            }
+                            let text = field_value.display_str();
+                            let color = self
+                                .data_display_options
+                                .field_color(field_name, &text)
+                                .or_else(|| highlight.and_then(|x| x.text_color));
+                            let text = self.data_display_options.formatted_value(field_name, &text);
+                            let mut text = match color {
+                                Some(color) => egui::RichText::new(text).color(color),
+                                None => egui::RichText::new(text),
+                            };
                             if should_emphasize_field {
-                                ui.strong(field_value.display());
-                            } else {
-                                ui.label(field_value.display());
+                                text = text.strong();
+                            }
+                            if is_context_only {
+                                text = text.weak();
+                            }
+                            // Laid out separately from painting (as `Label` does) to replace its tooltip for truncated text
+                            let (position, galley, label_response) =
+                                egui::Label::new(text).layout_in_ui(ui);
+                            let is_truncated = galley.elided;
+                            egui::text_selection::LabelSelectionState::label_text_selection(
+                                ui,
+                                &label_response,
+                                position,
+                                galley,
+                                ui.visuals().text_color(),
+                                egui::Stroke::NONE,
+                            );
+                            if is_truncated {
+                                label_response.on_hover_ui(|ui| {
+                                    value_viewer::full_value_tooltip_ui(
+                                        ui,
+                                        &field_value.display_str(),
+                                    )
+                                });
+                            }
+                        });
+                        if let content @ FieldContent::Present(_) = field_value {
+                            if response.double_clicked()
+                                || (response.hovered()
+                                    && shortcut::consume(&response.ctx, &self.shortcuts.view_value))
+                            {
+                                self.value_viewer.open(field_name, &content.display());
+                            }
+                        }
+                        response.context_menu(|ui| {
+                            let context = MenuContext {
+                                real_index: data.get_real_index(row_index),
+                                row: log_row,
+                                field_name: Some(field_name),
+                                display_options: &self.data_display_options,
+                            };
+                            if let Some(command) = self.context_menu.show(ui, &context) {
+                                row_command = Some(command);
                             }
                         });
                     }
@@ -208,6 +761,100 @@ impl LogViewerApp {
             // No data so empty body
             table.body(|_| {});
         }
+        self.first_visible_real_index = first_visible;
+        if let (Some(group_id), Some(data)) = (group_to_toggle, self.data.as_mut()) {
+            data.toggle_group(group_id, self.data_display_options.common_fields());
+        }
+        if let (Some(real_index), Some(data)) = (run_to_toggle, self.data.as_mut()) {
+            data.toggle_run(real_index, self.data_display_options.common_fields());
+        }
+        if let Some(command) = row_command {
+            self.perform_row_command(ui.ctx(), command);
+        }
+    }
+
+    fn minimap_ui(&mut self, ui: &mut egui::Ui) {
+        let Some(data) = self.data.as_mut() else {
+            return;
+        };
+        if let Some(real_index) = self.minimap.show(
+            ui,
+            data,
+            self.data_display_options.level_field_name(),
+            self.data_display_options.common_fields(),
+        ) {
+            self.perform_row_command(ui.ctx(), RowCommand::Select(real_index));
+        }
+    }
+
+    /// Carries out an action chosen from a row context menu or the details pane
+    fn perform_row_command(&mut self, ctx: &egui::Context, command: RowCommand) {
+        let common_fields = self.data_display_options.common_fields();
+        match command {
+            RowCommand::CopyText(text) => ctx.copy_text(text),
+            RowCommand::MarkForCompare(real_index) => self.row_diff.mark(real_index),
+            RowCommand::Annotate(real_index) => {
+                if let Some(data) = self.data.as_ref() {
+                    self.annotations.refresh(
+                        data,
+                        self.data_display_options.row_idx_field_name.as_deref(),
+                    );
+                }
+                self.annotations.start_edit(real_index);
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            RowCommand::OpenInEditor(real_index) => self.open_in_editor(real_index),
+            #[cfg(not(target_arch = "wasm32"))]
+            RowCommand::OpenSource(real_index) => self.open_source(real_index),
+            RowCommand::ViewValue(field_name, value) => self.value_viewer.open(&field_name, &value),
+            RowCommand::TogglePinned(field_name) => {
+                self.data_display_options.toggle_pinned(&field_name)
+            }
+            RowCommand::ToggleCorrelation(field_name) => self
+                .data_display_options
+                .toggle_correlation_field(&field_name),
+            RowCommand::Filter(filter) => {
+                if let Some(data) = self.data.as_mut() {
+                    self.search_history.record(&filter);
+                    data.filter = Some(filter);
+                    self.start_filter(ctx);
+                }
+            }
+            RowCommand::Ignore(filter) => {
+                if let Some(data) = self.data.as_mut() {
+                    data.ignore_matching(filter, common_fields);
+                    self.should_scroll = true;
+                }
+            }
+            RowCommand::Select(real_index) => {
+                if let Some(data) = self.data.as_mut() {
+                    data.select_real_index(real_index, common_fields);
+                    self.should_scroll = true;
+                }
+            }
+            RowCommand::Hide(real_index) => {
+                if let Some(data) = self.data.as_mut() {
+                    data.select_real_index(real_index, common_fields);
+                    data.hide_selected();
+                    self.should_scroll = true;
+                }
+            }
+        }
+    }
+
+    fn import_filters(&mut self, ctx: &egui::Context, import: FilterImport) {
+        match import {
+            FilterImport::Filter(filter) => {
+                self.perform_row_command(ctx, RowCommand::Filter(filter))
+            }
+            FilterImport::All(filters) => match self.data.as_mut() {
+                Some(data) => {
+                    (*filters).apply_to(data, self.data_display_options.common_fields());
+                    self.should_scroll = true;
+                }
+                None => self.workspaces.set_pending_filters(*filters),
+            },
+        }
     }
 
     fn show_log_details(&mut self, ui: &mut egui::Ui) {
@@ -216,6 +863,18 @@ impl LogViewerApp {
             return;
         };
 
+        // Cloned so they are still available after the mutable borrow to get the display values
+        let related_rows = self
+            .related_rows
+            .get(data, &self.data_display_options.correlation_fields)
+            .clone();
+        let selected_row = data.selected_row().map(|row| row.to_owned_row());
+        let selected_real_index = data.selected_real_index();
+        let nested_values: BTreeMap<&str, &serde_json::Value> = selected_row
+            .iter()
+            .flat_map(|row| row.as_row().nested_fields())
+            .collect();
+
         let Some((selected_values, fields_matching_filter)) = data
             .selected_row_data_as_slice_with_filter_matching_fields(
                 self.data_display_options.common_fields(),
@@ -224,6 +883,66 @@ impl LogViewerApp {
             ui.label("No row Selected");
             return;
         };
+        if let Some(note) = selected_real_index.and_then(|x| self.annotations.note(x)) {
+            ui.label(format!("📝 {note}"));
+        }
+        let mut row_command = None;
+
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.details_filter)
+                    .hint_text("Filter fields by name or value"),
+            );
+            if !self.details_filter.is_empty() && ui.small_button("✖").clicked() {
+                self.details_filter.clear();
+            }
+            if let Some(real_index) = selected_real_index {
+                if ui
+                    .button("⧉ Pop Out")
+                    .on_hover_text("Shows this row in a separate window that stays on this row")
+                    .clicked()
+                {
+                    self.detached_details.open(real_index);
+                }
+                #[cfg(not(target_arch = "wasm32"))]
+                if ui
+                    .button("✏ Open in Editor")
+                    .on_hover_text("Opens the file at the line of this row using the editor command in the options")
+                    .clicked()
+                {
+                    row_command = Some(RowCommand::OpenInEditor(real_index));
+                }
+            }
+        });
+
+        let details_filter = self.details_filter.to_lowercase();
+        let mut display_order = self
+            .data_display_options
+            .details_display_order(selected_values.iter().map(|(title, _)| title.as_str()));
+        if !details_filter.is_empty() {
+            display_order.retain(|&i| {
+                let (title, value) = &selected_values[i];
+                title.to_lowercase().contains(&details_filter)
+                    || value.to_lowercase().contains(&details_filter)
+            });
+        }
+
+        let (focused_field, key_action) = self.details_keyboard.update(display_order.len());
+        if let (Some(focused), Some(action)) = (focused_field, key_action) {
+            let (title, value) = &selected_values[display_order[focused]];
+            match action {
+                DetailsKeyAction::Copy => ui.ctx().copy_text(value.clone()),
+                DetailsKeyAction::Expand => match nested_values.get(title.as_str()) {
+                    Some(nested) if JsonTree::is_nested(nested) => {
+                        self.details_json_tree.toggle(title)
+                    }
+                    _ => self.value_viewer.open(title, value),
+                },
+            }
+        }
+        if self.details_keyboard.is_active() {
+            ui.weak("↑/↓ move between fields, Enter expands, Ctrl+C copies, Esc leaves");
+        }
 
         let color_matching_field = ui.visuals().strong_text_color();
         let color_normal_field = ui.visuals().text_color();
@@ -237,16 +956,22 @@ impl LogViewerApp {
             .resizable(true)
             .cell_layout(egui::Layout::left_to_right(egui::Align::LEFT))
             .column(Column::auto())
+            .column(Column::auto().resizable(false))
             .column(Column::remainder())
             .min_scrolled_height(0.0);
 
         // Clicks not needed but adds highlight row
         table_builder = table_builder.sense(egui::Sense::click());
+        if let Some(focused) = focused_field.filter(|_| self.details_keyboard.take_should_scroll())
+        {
+            table_builder = table_builder.scroll_to_row(focused, None);
+        }
 
         let table = table_builder.header(text_height, |mut header| {
             header.col(|ui| {
                 ui.strong("Field Name");
             });
+            header.col(|_ui| {});
             header.col(|ui| {
                 ui.strong("Field Value");
             });
@@ -254,14 +979,27 @@ impl LogViewerApp {
 
         table.body(|body| {
             // TODO 3: Figure out if calculating these values only once is worth it.
-            let heights: Vec<f32> = selected_values
+            let json_tree = &mut self.details_json_tree;
+            let heights: Vec<f32> = display_order
                 .iter()
-                .map(|x| (1f32).max(x.1.lines().count() as f32) * text_height)
+                .map(|&i| {
+                    let (title, value) = &selected_values[i];
+                    let line_count = match nested_values.get(title.as_str()) {
+                        Some(nested) => json_tree.line_count(title, nested),
+                        None if stack_trace::is_stack_trace(value) => {
+                            self.stack_trace_view.line_count(value)
+                        }
+                        None => value.lines().count(),
+                    };
+                    (1f32).max(line_count as f32) * text_height
+                })
                 .collect();
             body.heterogeneous_rows(heights.iter().cloned(), |mut row| {
-                let row_index = row.index();
-                let (title, value) = &selected_values[row_index];
-                let color = if fields_matching_filter.contains(&row_index) {
+                row.set_selected(focused_field == Some(row.index()));
+                let field_index = display_order[row.index()];
+                let (title, value) = &selected_values[field_index];
+                let is_pinned = self.data_display_options.is_pinned(title);
+                let color = if fields_matching_filter.contains(&field_index) {
                     color_matching_field
                 } else {
                     color_normal_field
@@ -270,137 +1008,1113 @@ impl LogViewerApp {
                     ui.colored_label(color, title);
                 });
                 row.col(|ui| {
-                    ui.colored_label(color, value.to_string());
+                    if ui
+                        .selectable_label(is_pinned, "📌")
+                        .on_hover_text(if is_pinned {
+                            "Unpin field"
+                        } else {
+                            "Pin field to the top"
+                        })
+                        .clicked()
+                    {
+                        row_command = Some(RowCommand::TogglePinned(title.clone()));
+                    }
+                    if ui
+                        .small_button("📋")
+                        .on_hover_text("Copy value (Shift+Click to copy key=value)")
+                        .clicked()
+                    {
+                        let text = if ui.input(|i| i.modifiers.shift) {
+                            format!("{title}={value}")
+                        } else {
+                            value.clone()
+                        };
+                        ui.ctx().copy_text(text);
+                    }
                 });
-            });
-        });
-    }
-
-    fn ui_loading(&mut self, ui: &mut egui::Ui) {
-        match &self.loading_status {
-            LoadingStatus::NotInProgress => {
-                self.data_load_ui(ui);
-                ui.separator();
-                self.navigation_and_filtering_ui(ui);
-            }
-            LoadingStatus::InProgress(promise) => {
-                if promise.ready().is_some() {
-                    let mut temp = LoadingStatus::default();
-                    std::mem::swap(&mut temp, &mut self.loading_status);
-                    let LoadingStatus::InProgress(owned_promise) = temp else {
-                        unreachable!("we are sure of this because we just did a match on this")
-                    };
-                    self.loading_status = *owned_promise.block_and_take(); // We know the promise is ready at this point
-                } else {
-                    ui.spinner();
-                }
-            }
-            LoadingStatus::Failed(err_msg) => {
-                let msg = format!("Loading failed: {err_msg}");
-                if ui.button("Clear Error Status").clicked() {
-                    self.loading_status = LoadingStatus::NotInProgress;
-                }
-                ui.colored_label(ui.visuals().error_fg_color, msg);
-            }
-            LoadingStatus::Success(data) => {
-                self.loading_status = match Data::try_from((&self.data_display_options, &data[..]))
-                {
-                    Ok(mut data) => {
-                        if let Some(old_data) = self.data.as_mut() {
-                            // Preserve settings across loads of the data
-                            data.take_config(old_data, self.data_display_options.common_fields());
+                row.col(|ui| match nested_values.get(title.as_str()) {
+                    Some(nested) if JsonTree::is_nested(nested) => {
+                        json_tree.show(ui, title, nested, color);
+                    }
+                    _ if stack_trace::is_stack_trace(value) => {
+                        self.stack_trace_view.show(ui, value, color);
+                    }
+                    _ => {
+                        let formatted = self.data_display_options.formatted_value(title, value);
+                        let response = ui.colored_label(color, formatted.as_ref());
+                        if formatted != value.as_str() {
+                            response.on_hover_text(value);
                         }
-                        self.data = Some(data);
-                        if self.should_scroll_to_end_on_load {
-                            self.move_selected_last();
-                        } else {
-                            self.should_scroll = true;
+                        #[cfg(not(target_arch = "wasm32"))]
+                        if let Some(real_index) = selected_real_index
+                            .filter(|_| *title == self.external_editor.file_field_name)
+                        {
+                            if ui
+                                .small_button("↗")
+                                .on_hover_text("Open the source location in the editor")
+                                .clicked()
+                            {
+                                row_command = Some(RowCommand::OpenSource(real_index));
+                            }
+                        }
+                        if let Some(related) = related_rows.get(title) {
+                            Self::related_rows_ui(ui, title, value, related, &mut row_command);
                         }
-                        LoadingStatus::NotInProgress
                     }
-                    Err(e) => LoadingStatus::Failed(clean_msg(format!("{e:?}"))),
+                });
+                if let (Some(selected_row), Some(real_index)) =
+                    (selected_row.as_ref(), selected_real_index)
+                {
+                    row.response().context_menu(|ui| {
+                        let context = MenuContext {
+                            real_index,
+                            row: selected_row.as_row(),
+                            field_name: Some(title),
+                            display_options: &self.data_display_options,
+                        };
+                        if let Some(command) = self.context_menu.show(ui, &context) {
+                            row_command = Some(command);
+                        }
+                    });
                 }
-            }
+            });
+        });
+
+        if let Some(command) = row_command {
+            self.perform_row_command(ui.ctx(), command);
         }
     }
 
-    fn initiate_loading(&self, ctx: egui::Context) -> LoadingStatus {
-        let start_open_path = Arc::clone(&self.start_open_path);
-        let last_filename = Arc::clone(&self.last_filename);
-        LoadingStatus::InProgress(execute(async move {
-            let mut dialog = rfd::AsyncFileDialog::new();
-            if let Some(path) = start_open_path.lock().unwrap().as_mut() {
-                dialog = dialog.set_directory(path);
-            }
-            let Some(file) = dialog.pick_file().await else {
-                // user canceled loading
-                return Box::new(LoadingStatus::NotInProgress);
-            };
-            #[cfg(not(target_arch = "wasm32"))]
-            if let Some(parent) = file.path().parent() {
-                *start_open_path.lock().unwrap() = Some(PathBuf::from(parent));
+    /// Shows the number of other rows sharing the value with buttons to jump to the next one or filter to all of them
+    fn related_rows_ui(
+        ui: &mut egui::Ui,
+        field_name: &str,
+        value: &str,
+        related: &RelatedRows,
+        command: &mut Option<RowCommand>,
+    ) {
+        if let Some(next) = related.next {
+            if ui
+                .small_button(format!("🔗 {} other(s)", related.other_count))
+                .on_hover_text("Jump to the next row with this value")
+                .clicked()
+            {
+                *command = Some(RowCommand::Select(next));
             }
-            *last_filename.lock().unwrap() = Some(PathBuf::from(file.file_name()));
-            let text = file.read().await;
-
-            // Uncomment the following line to simulate taking long to load, only works on native
-            // tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
+            if ui
+                .small_button("🔍")
+                .on_hover_text("Filter to rows with this value")
+                .clicked()
+            {
+                *command = Some(RowCommand::Filter(equal_to_value_filter(
+                    field_name.to_string(),
+                    value.to_string(),
+                )));
+            }
+        } else {
+            ui.weak("(no other rows)");
+        }
+    }
 
-            // If not present screen will not refresh until next paint (comment out to test, works better with the sleep above to demonstrate)
-            ctx.request_repaint();
+    fn ui_loading(&mut self, ui: &mut egui::Ui) {
+        self.collect_finished_tasks();
+        self.data_load_ui(ui);
+        self.tasks.ui(ui);
+        self.task_messages_ui(ui);
+        ui.separator();
+        self.navigation_and_filtering_ui(ui);
+    }
 
-            Box::new(match String::from_utf8(text) {
-                Ok(val) => LoadingStatus::Success(val),
-                Err(e) => LoadingStatus::Failed(format!("{e}")),
-            })
-        }))
+    fn collect_finished_tasks(&mut self) {
+        for (name, outcome) in self.tasks.take_finished() {
+            match outcome {
+                TaskOutcome::Loaded(data) => self.set_loaded_data(*data),
+                TaskOutcome::LoadedWithProfile { data, profile } => {
+                    self.use_display_profile(&profile);
+                    self.set_loaded_data(*data);
+                }
+                TaskOutcome::LoadedForCompare { data, name } => {
+                    self.split_view.set_compare_data(*data, name)
+                }
+                TaskOutcome::Done(msg) => self.task_messages.push(Ok(format!("{name}: {msg}"))),
+                TaskOutcome::Failed(msg) => self
+                    .task_messages
+                    .push(Err(format!("{name} failed: {msg}"))),
+                TaskOutcome::Cancelled => info!("{name} was cancelled"),
+            }
+        }
     }
 
-    fn ui_options(&mut self, ui: &mut egui::Ui) {
-        ui.collapsing("Options", |ui| {
-            ui.checkbox(&mut self.show_last_filename, "Show last filename");
-            ui.checkbox(
-                &mut self.should_scroll_to_end_on_load,
-                "Scroll to end on load",
-            )
-            .on_hover_text(shortcut_hint_text(
-                ui,
-                "Only has an effect when a new file is loaded",
-                &self.shortcuts.auto_scroll,
-            ));
-            ui.horizontal(|ui| {
-                ui.label("Item align:");
-                self.should_scroll |= ui
-                    .radio_value(&mut self.track_item_align, Some(Align::Min), "Top")
-                    .clicked();
-                self.should_scroll |= ui
-                    .radio_value(&mut self.track_item_align, Some(Align::Center), "Center")
-                    .clicked();
-                self.should_scroll |= ui
-                    .radio_value(&mut self.track_item_align, Some(Align::Max), "Bottom")
-                    .clicked();
-                self.should_scroll |= ui
-                    .radio_value(&mut self.track_item_align, None, "None (Bring into view)")
-                    .clicked();
+    fn task_messages_ui(&mut self, ui: &mut egui::Ui) {
+        if self.task_messages.is_empty() {
+            return;
+        }
+        ui.horizontal(|ui| {
+            if ui.button("Clear Status").clicked() {
+                self.task_messages.clear();
+            }
+            ui.vertical(|ui| {
+                for msg in self.task_messages.iter() {
+                    match msg {
+                        Ok(msg) => ui.label(msg),
+                        Err(msg) => ui.colored_label(ui.visuals().error_fg_color, msg),
+                    };
+                }
             });
         });
     }
 
-    fn move_selected_prev(&mut self) {
-        if let Some(data) = self.data.as_mut() {
-            data.move_selected_to_prev();
+    /// Applies the data's filter a chunk of rows per frame (see [`Self::step_filter`]) so filtering large logs does
+    /// not freeze the UI. Replaces the filter being applied if there is one
+    fn start_filter(&mut self, ctx: &egui::Context) {
+        self.filter_task = self
+            .data
+            .as_ref()
+            .and_then(FilterJob::new)
+            .map(|job| (job, ChunkedTask::new("Filtering", ctx.clone())));
+        self.step_filter();
+    }
+
+    /// Checks the next chunk of rows against the filter being applied, should be called every frame
+    fn step_filter(&mut self) {
+        let (Some((job, task)), Some(data)) = (self.filter_task.as_mut(), self.data.as_mut())
+        else {
+            return;
+        };
+        if task.is_cancel_requested() {
+            self.filter_task = None;
+        } else if job.step(
+            data,
+            self.data_display_options.common_fields(),
+            ROWS_PER_CHUNK,
+        ) {
+            self.filter_task = None;
             self.should_scroll = true;
+        } else {
+            task.report_progress(&mut self.tasks, job.progress(data));
         }
     }
 
-    fn move_selected_next(&mut self) {
-        if let Some(data) = self.data.as_mut() {
+    fn set_loaded_data(&mut self, mut data: Data) {
+        #[cfg(not(target_arch = "wasm32"))]
+        let should_keep_config = !self.folder_watch.take_should_discard_filters();
+        #[cfg(target_arch = "wasm32")]
+        let should_keep_config = true;
+        if let (true, Some(old_data)) = (should_keep_config, self.data.as_mut()) {
+            // Preserve settings across loads of the data
+            data.take_config(
+                old_data,
+                self.data_display_options.common_fields(),
+                self.data_display_options.row_idx_field_name.as_deref(),
+            );
+        }
+        if let Some(filters) = self.workspaces.take_pending_filters() {
+            filters.apply_to(&mut data, self.data_display_options.common_fields());
+        }
+        #[cfg(target_arch = "wasm32")]
+        if let Some(view) = self.pending_shared_view.take() {
+            view.apply_to_data(&mut data, self.data_display_options.common_fields());
+        }
+        self.data = Some(data);
+        self.filter_task = None;
+        self.invalidate_data_caches();
+        self.annotations.set_file_key(self.current_file_key());
+        if self.should_scroll_to_end_on_load || self.is_following {
+            self.move_selected_last();
+        } else {
+            self.should_scroll = true;
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    /// Path of the file last loaded if both the folder and the filename are known
+    fn loaded_file_path(&self) -> Option<PathBuf> {
+        let folder = self.start_open_path.lock().unwrap().clone()?;
+        let filename = self.last_filename.lock().unwrap().clone()?;
+        Some(paths::expand(&folder).join(filename))
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    /// Opens the file last loaded in the external editor at the line the row came from
+    fn open_in_editor(&mut self, real_index: usize) {
+        let Some(file_path) = self.loaded_file_path() else {
+            self.task_messages
+                .push(Err("Open in editor failed: no file loaded".into()));
+            return;
+        };
+        // The row number field holds the index of the line (when present) as rows may not be from all lines
+        let line_index = self
+            .data
+            .as_ref()
+            .zip(self.data_display_options.row_idx_field_name.as_deref())
+            .and_then(|(data, field_name)| {
+                data.row_by_real_index(real_index)?
+                    .field_value(field_name)
+                    .as_u64()
+            })
+            .map_or(real_index, |x| x as usize);
+        if let Err(e) = self.external_editor.open(&file_path, line_index + 1) {
+            self.task_messages
+                .push(Err(format!("Open in editor failed: {e:#}")));
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    /// Opens the source file and line logged in the row in the external editor
+    fn open_source(&mut self, real_index: usize) {
+        let location = self
+            .data
+            .as_ref()
+            .and_then(|data| data.row_by_real_index(real_index))
+            .and_then(|row| self.external_editor.source_location(row));
+        let Some((file_path, line)) = location else {
+            self.task_messages.push(Err(format!(
+                "Open source failed: row has no {:?} field",
+                self.external_editor.file_field_name
+            )));
+            return;
+        };
+        if let Err(e) = self.external_editor.open(&file_path, line) {
+            self.task_messages
+                .push(Err(format!("Open source failed: {e:#}")));
+        }
+    }
+
+    /// Identifies the file last loaded (includes the folder when available)
+    fn current_file_key(&self) -> Option<String> {
+        let filename = self.last_filename.lock().unwrap().clone()?;
+        if let Some(folder) = self.start_open_path.lock().unwrap().as_ref() {
+            return Some(folder.join(filename).display().to_string());
+        }
+        Some(filename.display().to_string())
+    }
+
+    /// Restores the chosen parts of the state to their defaults
+    fn reset(&mut self, ctx: &egui::Context, choices: ResetChoices) {
+        let ResetChoices {
+            should_reset_settings,
+            should_reset_layout,
+            should_reset_data,
+            should_reset_annotations,
+        } = choices;
+        let defaults = Self::default();
+        if should_reset_settings {
+            let parsers = std::mem::take(&mut self.data_display_options.parsers);
+            self.data_display_options = defaults.data_display_options;
+            self.data_display_options.parsers = parsers;
+            self.display_profiles = defaults.display_profiles;
+            self.active_profile = defaults.active_profile;
+            self.watches = defaults.watches;
+            self.shortcuts = defaults.shortcuts;
+            self.copy_key_field_name = defaults.copy_key_field_name;
+            self.show_last_filename = defaults.show_last_filename;
+            self.show_column_filters = defaults.show_column_filters;
+            self.show_controls = defaults.show_controls;
+            self.show_details = defaults.show_details;
+            self.is_newest_first = defaults.is_newest_first;
+            self.should_filter_as_you_type = defaults.should_filter_as_you_type;
+            self.track_item_align = defaults.track_item_align;
+            self.should_scroll_to_end_on_load = defaults.should_scroll_to_end_on_load;
+            self.is_following = defaults.is_following;
+            // Set through the lock as background loads hold clones of the Arc
+            *self.start_open_path.lock().unwrap() = None;
+        }
+        if should_reset_layout {
+            ctx.memory_mut(|mem| *mem = Default::default());
+        }
+        if should_reset_data {
+            self.data = None;
+            *self.last_filename.lock().unwrap() = None;
+        }
+        if should_reset_annotations {
+            let file_key = self.current_file_key();
+            self.annotations = defaults.annotations;
+            self.annotations.set_file_key(file_key);
+        }
+        self.invalidate_data_caches();
+    }
+
+    /// Explains safe mode and allows leaving it by keeping the current state
+    fn safe_mode_ui(&mut self, ui: &mut egui::Ui) {
+        if !self.is_safe_mode {
+            return;
+        }
+        ui.horizontal(|ui| {
+            ui.colored_label(
+                ui.visuals().warn_fg_color,
+                "Safe mode: saved state was not loaded and will not be overwritten",
+            );
+            if ui
+                .button("Keep Current State")
+                .on_hover_text("Saves the current state in place of the saved state on exit")
+                .clicked()
+            {
+                self.is_safe_mode = false;
+            }
+        });
+        ui.separator();
+    }
+
+    fn perform_workspace_action(&mut self, ctx: &egui::Context, action: WorkspaceAction) {
+        match action {
+            WorkspaceAction::Save(name) => {
+                let workspace = Workspace {
+                    data_display_options: self.data_display_options.clone(),
+                    shortcuts: self.shortcuts.clone(),
+                    filters: SavedFilters::new(self.data.as_ref()),
+                    start_open_path: self.start_open_path.lock().unwrap().clone(),
+                    last_filename: self.last_filename.lock().unwrap().clone(),
+                };
+                self.workspaces.insert(name, workspace);
+            }
+            WorkspaceAction::Load(name) => {
+                let Some(Workspace {
+                    data_display_options,
+                    shortcuts,
+                    filters,
+                    start_open_path,
+                    last_filename,
+                }) = self.workspaces.load(&name)
+                else {
+                    return;
+                };
+                let is_other_file = *self.start_open_path.lock().unwrap() != start_open_path
+                    || *self.last_filename.lock().unwrap() != last_filename;
+                let parsers = std::mem::take(&mut self.data_display_options.parsers);
+                self.data_display_options = data_display_options;
+                self.data_display_options.parsers = parsers;
+                self.shortcuts = shortcuts;
+                *self.start_open_path.lock().unwrap() = start_open_path;
+                *self.last_filename.lock().unwrap() = last_filename;
+                match self.data.as_mut() {
+                    Some(data) => {
+                        filters.apply_to(data, self.data_display_options.common_fields());
+                        self.should_scroll = true;
+                    }
+                    None => self.workspaces.set_pending_filters(filters),
+                }
+                self.invalidate_data_caches();
+                #[cfg(not(target_arch = "wasm32"))]
+                if is_other_file && self.last_filename.lock().unwrap().is_some() {
+                    self.reload_file(ctx.clone());
+                }
+                #[cfg(target_arch = "wasm32")]
+                let _ = (ctx, is_other_file); // Files can only be opened by the user on web
+            }
+        }
+    }
+
+    /// Clears anything computed from the previous data
+    fn invalidate_data_caches(&mut self) {
+        self.trace_view.invalidate();
+        self.filter_diff.invalidate();
+        self.row_diff.invalidate();
+        self.annotations.invalidate();
+        self.range_filters_ui.invalidate();
+        self.related_rows.invalidate();
+        self.minimap.invalidate();
+        self.status_bar.invalidate();
+        self.top_values.invalidate();
+        self.message_templates.invalidate();
+        self.request_summary.invalidate();
+        self.request_waterfall.invalidate();
+        self.field_schema.invalidate();
+        self.field_plot.invalidate();
+        self.detached_details.invalidate();
+        self.split_view.invalidate();
+        self.match_counter.invalidate();
+        self.selection_history.invalidate();
+        self.watches.invalidate();
+        self.field_names = None;
+        self.column_filter_edits.clear();
+    }
+
+    /// Keeps the indices held outside of the data pointing at the same rows after the oldest `count` rows were dropped
+    /// and recomputes the views of the rows
+    fn on_rows_dropped(&mut self, count: usize) {
+        self.selection_history.on_rows_dropped(count);
+        self.detached_details.on_rows_dropped(count);
+        self.row_diff.on_rows_dropped(count);
+        self.watches.on_rows_dropped(count);
+        self.annotations.on_rows_dropped(count);
+        self.trace_view.invalidate();
+        self.filter_diff.invalidate();
+        self.minimap.invalidate();
+        self.status_bar.invalidate();
+        self.top_values.invalidate();
+        self.message_templates.invalidate();
+        self.request_summary.invalidate();
+        self.request_waterfall.invalidate();
+        self.field_schema.invalidate();
+        self.field_plot.invalidate();
+        self.split_view.invalidate();
+        self.match_counter.invalidate();
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn live_sources(&mut self) -> [&mut dyn LiveSource; 2] {
+        [&mut self.ssh_tail, &mut self.websocket_source]
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn live_sources(&mut self) -> [&mut dyn LiveSource; 1] {
+        [&mut self.websocket_source]
+    }
+
+    /// The live source currently receiving lines (only one is connected at a time)
+    fn live_source(&mut self) -> Option<&mut dyn LiveSource> {
+        self.live_sources().into_iter().find(|x| x.is_connected())
+    }
+
+    /// Appends any lines received from the live source
+    fn update_live_source(&mut self, ctx: &egui::Context) {
+        let mut started_index = None;
+        for (i, source) in self.live_sources().into_iter().enumerate() {
+            if source.show(ctx) {
+                started_index = Some(i);
+            }
+        }
+        if let Some(started_index) = started_index {
+            let mut file_key = String::new();
+            for (i, source) in self.live_sources().into_iter().enumerate() {
+                if i == started_index {
+                    file_key = source.source_description();
+                } else if source.is_connected() {
+                    source.disconnect_with_error(
+                        "Disconnected because another live source was started".to_string(),
+                    );
+                }
+            }
+            // New session so start with no rows
+            self.set_loaded_data(Data::default());
+            self.annotations.set_file_key(Some(file_key));
+            self.live_pause.clear();
+        }
+        let lines = self
+            .live_source()
+            .map(|x| x.take_new_lines())
+            .unwrap_or_default();
+        let lines = self
+            .live_pause
+            .pass_through(lines, &self.data_display_options.live_row_limit);
+        self.append_live_lines(lines);
+    }
+
+    /// Adds lines from the live source to the data
+    fn append_live_lines(&mut self, lines: Vec<String>) {
+        if lines.is_empty() {
+            return;
+        }
+        let data = self.data.get_or_insert_with(Default::default);
+        let append_result = data.append_lines(
+            &self.data_display_options,
+            lines.iter().map(String::as_str),
+            self.data_display_options.common_fields(),
+        );
+        let excess = self
+            .data_display_options
+            .live_row_limit
+            .excess(data.total_len_unfiltered());
+        if excess > 0 {
+            data.drop_oldest_rows(excess, self.data_display_options.common_fields());
+            self.on_rows_dropped(excess);
+        }
+        if let (Err(e), Some(source)) = (append_result, self.live_source()) {
+            source.disconnect_with_error(format!("Stopped receiving lines: {e:?}"));
+        }
+        self.range_filters_ui.invalidate();
+        self.related_rows.invalidate();
+        self.annotations.invalidate();
+        self.field_names = None;
+        if self.is_following {
+            self.move_selected_last();
+        }
+    }
+
+    fn initiate_loading(&mut self, ctx: egui::Context) {
+        let start_open_path = Arc::clone(&self.start_open_path);
+        let last_filename = Arc::clone(&self.last_filename);
+        let data_display_options = self.data_display_options.clone();
+        let display_profiles = self.display_profiles.clone();
+        self.tasks.spawn("Open file", |handle| {
+            execute(async move {
+                let mut dialog = rfd::AsyncFileDialog::new();
+                if let Some(path) = start_open_path.lock().unwrap().as_ref() {
+                    #[cfg(not(target_arch = "wasm32"))]
+                    let path = paths::expand(path);
+                    dialog = dialog.set_directory(path);
+                }
+                let Some(file) = dialog.pick_file().await else {
+                    // user canceled loading
+                    return TaskOutcome::Cancelled;
+                };
+                #[cfg(not(target_arch = "wasm32"))]
+                if let Some(parent) = file.path().parent() {
+                    // Stored relative to home so the setting can be shared between users
+                    *start_open_path.lock().unwrap() = Some(paths::contract_home(parent));
+                }
+                *last_filename.lock().unwrap() = Some(PathBuf::from(file.file_name()));
+
+                // Uncomment the following line to simulate taking long to load, only works on native
+                // tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
+
+                #[cfg(not(target_arch = "wasm32"))]
+                let result = {
+                    let (data_display_options, profile) = options_for_file(
+                        &display_profiles,
+                        data_display_options,
+                        &file.file_name(),
+                        &read_sample(file.path()),
+                    );
+                    let outcome =
+                        load_file(&data_display_options, file.path(), &handle, &ctx).await;
+                    with_profile(outcome, profile)
+                };
+                #[cfg(target_arch = "wasm32")]
+                let result = {
+                    let bytes = file.read().await;
+                    let (data_display_options, profile) = options_for_file(
+                        &display_profiles,
+                        data_display_options,
+                        &file.file_name(),
+                        &bytes,
+                    );
+                    let outcome =
+                        parse_loaded_text(&data_display_options, bytes, &handle, &ctx).await;
+                    with_profile(outcome, profile)
+                };
+
+                // If not present screen will not refresh until next paint (comment out to test, works better with the sleep above to demonstrate)
+                ctx.request_repaint();
+
+                result
+            })
+        });
+    }
+
+    /// Loads a file to show in the split view beside the main data
+    fn initiate_compare_loading(&mut self, ctx: egui::Context) {
+        let start_open_path = self.start_open_path.lock().unwrap().clone();
+        let data_display_options = self.data_display_options.clone();
+        self.tasks.spawn("Open file to compare", |handle| {
+            execute(async move {
+                let mut dialog = rfd::AsyncFileDialog::new();
+                if let Some(path) = start_open_path {
+                    #[cfg(not(target_arch = "wasm32"))]
+                    let path = paths::expand(&path);
+                    dialog = dialog.set_directory(path);
+                }
+                let Some(file) = dialog.pick_file().await else {
+                    return TaskOutcome::Cancelled;
+                };
+                #[cfg(not(target_arch = "wasm32"))]
+                let outcome = load_file(&data_display_options, file.path(), &handle, &ctx).await;
+                #[cfg(target_arch = "wasm32")]
+                let outcome =
+                    parse_loaded_text(&data_display_options, file.read().await, &handle, &ctx)
+                        .await;
+                let result = match outcome {
+                    TaskOutcome::Loaded(data) => TaskOutcome::LoadedForCompare {
+                        data,
+                        name: file.file_name(),
+                    },
+                    other => other,
+                };
+                ctx.request_repaint();
+                result
+            })
+        });
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    /// Reads and parses the file in the background
+    fn spawn_load_from_path(&mut self, ctx: egui::Context, file_path: PathBuf) {
+        let data_display_options = self.data_display_options.clone();
+        let display_profiles = self.display_profiles.clone();
+        self.tasks
+            .spawn(format!("Load {}", file_path.display()), |handle| {
+                execute(async move {
+                    let file_name = file_path
+                        .file_name()
+                        .map(|x| x.to_string_lossy())
+                        .unwrap_or_default();
+                    let (data_display_options, profile) = options_for_file(
+                        &display_profiles,
+                        data_display_options,
+                        &file_name,
+                        &read_sample(&file_path),
+                    );
+                    let result = load_file(&data_display_options, &file_path, &handle, &ctx).await;
+                    ctx.request_repaint();
+                    with_profile(result, profile)
+                })
+            });
+    }
+
+    #[cfg(all(feature = "cloudwatch", not(target_arch = "wasm32")))]
+    /// Fetches the events from CloudWatch and parses them in the background
+    fn spawn_cloudwatch_load(&mut self, ctx: egui::Context, query: CloudWatchQuery) {
+        let data_display_options = self.data_display_options.clone();
+        self.tasks.spawn(
+            format!("Load CloudWatch {}", query.description()),
+            |handle| {
+                execute(async move {
+                    let result = match cloudwatch::fetch_events(&query, &handle).await {
+                        Ok(text) => {
+                            parse_loaded_text(
+                                &data_display_options,
+                                text.into_bytes(),
+                                &handle,
+                                &ctx,
+                            )
+                            .await
+                        }
+                        Err(e) => TaskOutcome::Failed(format!("{e:#}")),
+                    };
+                    ctx.request_repaint();
+                    result
+                })
+            },
+        );
+    }
+
+    /// Switches to the options of the display profile (the registered parsers are kept)
+    fn use_display_profile(&mut self, name: &str) {
+        let Some(profile) = self.display_profiles.get(name) else {
+            return;
+        };
+        let parsers = std::mem::take(&mut self.data_display_options.parsers);
+        self.data_display_options = profile.options.clone();
+        self.data_display_options.parsers = parsers;
+        self.active_profile = Some(name.to_string());
+        self.task_messages
+            .push(Ok(format!("Using display profile \"{name}\"")));
+    }
+
+    fn ui_options(&mut self, ui: &mut egui::Ui) {
+        ui.collapsing("Options", |ui| {
+            ui.checkbox(&mut self.show_last_filename, "Show last filename");
+            ui.checkbox(&mut self.show_column_filters, "Show column filters")
+                .on_hover_text("Text boxes under the column headers to filter on each column");
+            if ui
+                .checkbox(&mut self.is_newest_first, "Newest first")
+                .on_hover_text("Shows the last rows at the top of the list")
+                .changed()
+            {
+                self.should_scroll = true;
+            }
+            ui.checkbox(&mut self.should_filter_as_you_type, "Filter as you type")
+                .on_hover_text("Applies the filter shortly after the search key is changed");
+            #[cfg(not(target_arch = "wasm32"))]
+            self.ui_start_folder(ui);
+            #[cfg(not(target_arch = "wasm32"))]
+            self.external_editor.ui(ui);
+            ui.checkbox(
+                &mut self.should_scroll_to_end_on_load,
+                "Scroll to end on load",
+            )
+            .on_hover_text(shortcut_hint_text(
+                ui,
+                "Only has an effect when a new file is loaded",
+                &self.shortcuts.auto_scroll,
+            ));
+            ui.horizontal(|ui| {
+                ui.label("Item align:");
+                self.should_scroll |= ui
+                    .radio_value(&mut self.track_item_align, Some(Align::Min), "Top")
+                    .clicked();
+                self.should_scroll |= ui
+                    .radio_value(&mut self.track_item_align, Some(Align::Center), "Center")
+                    .clicked();
+                self.should_scroll |= ui
+                    .radio_value(&mut self.track_item_align, Some(Align::Max), "Bottom")
+                    .clicked();
+                self.should_scroll |= ui
+                    .radio_value(&mut self.track_item_align, None, "None (Bring into view)")
+                    .clicked();
+            });
+            ui.horizontal(|ui| {
+                ui.label("Copy key field:").on_hover_text(shortcut_hint_text(
+                    ui,
+                    "Field copied from the selected row",
+                    &self.shortcuts.copy_key_field,
+                ));
+                let field_names = match self.data.as_ref() {
+                    Some(data) => self.field_names.get_or_insert_with(|| data.field_names()),
+                    None => &Vec::new(),
+                };
+                field_name_ui(ui, &mut self.copy_key_field_name, field_names);
+            });
+            ui.horizontal(|ui| {
+                ui.label("Navigation keys:")
+                    .on_hover_text("Only the keys to move between rows and matches change, other shortcuts are kept");
+                if ui
+                    .button("Default")
+                    .on_hover_text("Arrow keys to move, home/end for first/last, ctrl+f to search and F3/shift+F3 for next/previous match")
+                    .clicked()
+                {
+                    self.shortcuts.use_navigation_of(Shortcuts::default());
+                }
+                if ui
+                    .button("Vim")
+                    .on_hover_text("j/k to move, gg/G for first/last, / to search and n/N for next/previous match")
+                    .clicked()
+                {
+                    self.shortcuts.use_navigation_of(Shortcuts::vim());
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.label("Log format:")
+                    .on_hover_text("Takes effect when the next file is loaded");
+                let options = &mut self.data_display_options;
+                let log_format = &mut options.log_format;
+                ui.radio_value(log_format, LogFormat::Json, "JSON");
+                ui.radio_value(log_format, LogFormat::TracingJson, "tracing JSON")
+                    .on_hover_text("JSON from tracing-subscriber with the event's fields and span names shown as columns");
+                ui.radio_value(log_format, LogFormat::Logfmt, "logfmt")
+                    .on_hover_text("key=value pairs separated by spaces");
+                ui.radio_value(log_format, LogFormat::Regex, "Regex")
+                    .on_hover_text("Fields are taken from the named groups of a pattern");
+                ui.radio_value(log_format, LogFormat::Csv, "CSV")
+                    .on_hover_text("Comma separated values with the field names on the first line");
+                ui.radio_value(log_format, LogFormat::CiRunner, "CI Runner")
+                    .on_hover_text("Plain text logs from CI (eg. GitHub Actions) with ::group:: sections folded");
+                for name in options.parsers.names() {
+                    ui.radio_value(log_format, LogFormat::Custom(name.to_string()), name);
+                }
+            });
+            if self.data_display_options.log_format == LogFormat::Regex {
+                ui.horizontal(|ui| {
+                    ui.label("Pattern:");
+                    ui.text_edit_singleline(&mut self.data_display_options.regex_pattern)
+                        .on_hover_text("Named groups become fields (eg. (?P<msg>.*))");
+                });
+            }
+            ui.horizontal(|ui| {
+                let settings = &mut self.data_display_options.cold_storage;
+                ui.checkbox(&mut settings.is_enabled, "Cold storage")
+                    .on_hover_text("Saves memory by keeping rows hidden by filters as text until they are needed");
+                ui.add_enabled(
+                    settings.is_enabled,
+                    egui::DragValue::new(&mut settings.max_visible_percent)
+                        .range(1..=100)
+                        .prefix("when at most ")
+                        .suffix("% of rows are visible"),
+                );
+                if let Some(data) = self.data.as_ref() {
+                    ui.label(format!(
+                        "({} rows in cold storage)",
+                        as_string_with_separators(data.cold_count())
+                    ));
+                }
+            });
+            ui.horizontal(|ui| {
+                let limit = &mut self.data_display_options.live_row_limit;
+                ui.checkbox(&mut limit.is_enabled, "Keep at most")
+                    .on_hover_text("While receiving rows from a live source the oldest rows are dropped once there are more than this");
+                ui.add_enabled(
+                    limit.is_enabled,
+                    egui::DragValue::new(&mut limit.max_rows)
+                        .range(1_000..=usize::MAX)
+                        .speed(1_000)
+                        .suffix(" live rows"),
+                );
+                if let Some(data) = self.data.as_ref().filter(|x| x.dropped_count() > 0) {
+                    ui.label(format!(
+                        "({} older rows dropped)",
+                        as_string_with_separators(data.dropped_count())
+                    ));
+                }
+            });
+            ui.collapsing("Display Profiles", |ui| {
+                let action = self.display_profiles_ui.ui(
+                    ui,
+                    &mut self.display_profiles,
+                    self.active_profile.as_deref(),
+                );
+                match action {
+                    Some(ProfileAction::Save(name)) => {
+                        self.display_profiles
+                            .save(&name, &self.data_display_options);
+                        self.active_profile = Some(name);
+                    }
+                    Some(ProfileAction::Use(name)) => self.use_display_profile(&name),
+                    None => {}
+                }
+            });
+            ui.collapsing("Watches", |ui| {
+                ui.label("Each watch shows a badge with the number of matching rows and how many arrived since it was clicked");
+                let current_filter = self.data.as_ref().and_then(|x| x.filter.as_ref());
+                self.watches.ui(ui, current_filter);
+            });
+            ui.collapsing("Load Range", |ui| self.ui_load_range(ui));
+            ui.collapsing("Parse Errors", |ui| self.ui_row_parse_error_handling(ui));
+            ui.collapsing("Nested Fields", |ui| {
+                let flatten_nested = &mut self.data_display_options.flatten_nested;
+                ui.label("Changes apply to files loaded afterwards");
+                ui.checkbox(&mut flatten_nested.is_enabled, "Flatten nested objects")
+                    .on_hover_text("Fields of nested objects become fields named like parent.child so they can be used as columns and in filters");
+                ui.add_enabled(
+                    flatten_nested.is_enabled,
+                    egui::Slider::new(&mut flatten_nested.max_depth, 1..=10).text("max depth"),
+                );
+            });
+            ui.collapsing("Row Script", |ui| self.ui_row_script(ui));
+            ui.collapsing("Field Colors", |ui| {
+                self.colored_fields_ui
+                    .ui(ui, &mut self.data_display_options.colored_fields);
+                ui.separator();
+                ui.label("Rules (used in order for values without a color above)");
+                self.colored_fields_ui
+                    .rules_ui(ui, &mut self.data_display_options.color_rules);
+                ui.horizontal(|ui| {
+                    let row_tint = &mut self.data_display_options.row_tint;
+                    ui.checkbox(&mut row_tint.is_enabled, "Tint rows by level")
+                        .on_hover_text("Uses the colors of the level field for the row background");
+                    ui.add_enabled(
+                        row_tint.is_enabled,
+                        egui::Slider::new(&mut row_tint.alpha, 0..=255).text("opacity"),
+                    );
+                });
+            });
+            ui.collapsing("Row Highlights", |ui| {
+                ui.label("Colors whole rows, the first enabled rule that matches is used");
+                row_highlights_ui::ui(ui, &mut self.data_display_options.row_highlights);
+            });
+            ui.collapsing("Row Height", |ui| {
+                let row_height = &mut self.data_display_options.row_height;
+                ui.label("Rows are as tall as the value with the most lines among these fields (one per line)");
+                let mut text = row_height.field_names.join("\n");
+                if ui
+                    .add(egui::TextEdit::multiline(&mut text).desired_rows(2))
+                    .changed()
+                {
+                    row_height.field_names = text.split('\n').map(String::from).collect();
+                }
+                ui.horizontal(|ui| {
+                    let mut is_limited = row_height.max_lines.is_some();
+                    if ui.checkbox(&mut is_limited, "Limit to").changed() {
+                        row_height.max_lines = is_limited.then_some(DEFAULT_MAX_ROW_LINES);
+                    }
+                    if let Some(max_lines) = row_height.max_lines.as_mut() {
+                        ui.add(
+                            egui::DragValue::new(max_lines)
+                                .range(1..=1000)
+                                .suffix(" lines"),
+                        );
+                    }
+                });
+            });
+            ui.collapsing("Stack Traces", |ui| self.stack_trace_view.ui(ui));
+            ui.collapsing("Field Formatters", |ui| {
+                ui.label("Change how values are shown without changing the data");
+                self.field_formatters_ui
+                    .ui(ui, &mut self.data_display_options.field_formatters);
+            });
+            ui.collapsing("Time Gaps", |ui| {
+                ui.horizontal(|ui| {
+                    let time_gaps = &mut self.data_display_options.time_gaps;
+                    ui.checkbox(&mut time_gaps.is_enabled, "Mark time gaps over")
+                        .on_hover_text("Draws a line between rows with more time between them");
+                    ui.add_enabled_ui(time_gaps.is_enabled, |ui| {
+                        ui.add(
+                            egui::DragValue::new(&mut time_gaps.threshold_ms)
+                                .speed(100)
+                                .suffix(" ms"),
+                        );
+                        ui.label("using field");
+                        ui.add(
+                            egui::TextEdit::singleline(&mut time_gaps.time_field_name)
+                                .desired_width(80.0),
+                        );
+                    });
+                });
+            });
+            ui.collapsing("Level Conversion", |ui| {
+                let should_apply = self
+                    .level_conversion_ui
+                    .ui(ui, &mut self.data_display_options.level_conversion);
+                if let (true, Some(data)) = (should_apply, self.data.as_mut()) {
+                    data.set_level_conversion(
+                        self.data_display_options.level_conversion.as_ref(),
+                        self.data_display_options.common_fields(),
+                    );
+                    self.invalidate_data_caches();
+                }
+            });
+        });
+    }
+
+    fn ui_row_script(&mut self, ui: &mut egui::Ui) {
+        let settings = &mut self.data_display_options.row_script;
+        ui.label("Changes apply to files loaded afterwards");
+        ui.checkbox(&mut settings.is_enabled, "Run script on each row")
+            .on_hover_text("A rhai script that can change the fields in the map `row`, the row is dropped if the script returns false");
+        ui.add_enabled(
+            settings.is_enabled,
+            egui::TextEdit::multiline(&mut settings.script)
+                .code_editor()
+                .desired_rows(4)
+                .hint_text("row.message = row.msg;\nrow.remove(\"msg\");\nrow.level != 10 // Drops trace rows"),
+        );
+    }
+
+    fn ui_load_range(&mut self, ui: &mut egui::Ui) {
+        let load_range = &mut self.data_display_options.load_range;
+        ui.label("Changes apply to files loaded afterwards (parsing only part of a very large file is faster and uses less memory)");
+        ui.horizontal(|ui| {
+            if ui.radio(*load_range == LoadRange::All, "All").clicked() {
+                *load_range = LoadRange::All;
+            }
+            if ui
+                .radio(matches!(load_range, LoadRange::Head(_)), "First")
+                .clicked()
+            {
+                *load_range = LoadRange::Head(DEFAULT_PARTIAL_LINES);
+            }
+            if ui
+                .radio(matches!(load_range, LoadRange::Tail(_)), "Last")
+                .clicked()
+            {
+                *load_range = LoadRange::Tail(DEFAULT_PARTIAL_LINES);
+            }
+            if ui
+                .radio(matches!(load_range, LoadRange::Lines { .. }), "Range")
+                .clicked()
+            {
+                *load_range = LoadRange::Lines {
+                    first: 1,
+                    last: DEFAULT_PARTIAL_LINES,
+                };
+            }
+        });
+        ui.horizontal(|ui| match load_range {
+            LoadRange::All => {}
+            LoadRange::Head(n) | LoadRange::Tail(n) => {
+                ui.add(
+                    egui::DragValue::new(n)
+                        .range(1..=usize::MAX)
+                        .suffix(" lines"),
+                );
+            }
+            LoadRange::Lines { first, last } => {
+                ui.label("From line");
+                ui.add(egui::DragValue::new(first).range(1..=usize::MAX));
+                ui.label("to line");
+                ui.add(egui::DragValue::new(last).range(*first..=usize::MAX));
+            }
+        });
+    }
+
+    fn ui_row_parse_error_handling(&mut self, ui: &mut egui::Ui) {
+        let handling = &mut self.data_display_options.row_parse_error_handling;
+        ui.label("Changes apply to files loaded afterwards");
+        ui.horizontal(|ui| {
+            let is_abort = matches!(handling, RowParseErrorHandling::AbortOnAnyErrors);
+            if ui.radio(is_abort, "Abort on any errors").clicked() {
+                *handling = RowParseErrorHandling::AbortOnAnyErrors;
+            }
+            let is_convert = matches!(handling, RowParseErrorHandling::ConvertFailedLines { .. });
+            if ui
+                .radio(is_convert, "Convert failed lines")
+                .on_hover_text(
+                    "Lines that are not valid JSON are loaded as a row with the line in one field",
+                )
+                .clicked()
+                && !is_convert
+            {
+                *handling = RowParseErrorHandling::default();
+            }
+            let is_join = matches!(handling, RowParseErrorHandling::JoinToPreviousRow { .. });
+            if ui
+                .radio(is_join, "Join to previous row")
+                .on_hover_text("Lines that fail to parse are added to a field of the row before them (eg. the lines of a stack trace)")
+                .clicked()
+                && !is_join
+            {
+                *handling = RowParseErrorHandling::JoinToPreviousRow {
+                    field_name: "msg".into(),
+                };
+            }
+        });
+        if let RowParseErrorHandling::JoinToPreviousRow { field_name } = handling {
+            ui.horizontal(|ui| {
+                ui.label("Field to add lines to");
+                ui.text_edit_singleline(field_name);
+            });
+        }
+        if let RowParseErrorHandling::ConvertFailedLines {
+            raw_line_field_name,
+            parse_error_field_name,
+        } = handling
+        {
+            egui::Grid::new("parse error fields").show(ui, |ui| {
+                ui.label("Raw line field");
+                ui.text_edit_singleline(raw_line_field_name);
+                ui.end_row();
+
+                let mut has_error_field = parse_error_field_name.is_some();
+                ui.checkbox(&mut has_error_field, "Error message field");
+                match (has_error_field, parse_error_field_name.as_mut()) {
+                    (true, Some(field_name)) => {
+                        ui.text_edit_singleline(field_name);
+                    }
+                    (true, None) => *parse_error_field_name = Some("parse_err".into()),
+                    (false, Some(_)) => *parse_error_field_name = None,
+                    (false, None) => {}
+                }
+                ui.end_row();
+            });
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn ui_start_folder(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Start folder:");
+            let mut start_open_path = self.start_open_path.lock().unwrap();
+            let mut text = start_open_path
+                .as_ref()
+                .map(|x| x.display().to_string())
+                .unwrap_or_default();
+            if ui
+                .text_edit_singleline(&mut text)
+                .on_hover_text(
+                    "Supports ~, $VAR, ${VAR} and paths relative to the working directory",
+                )
+                .changed()
+            {
+                *start_open_path = (!text.is_empty()).then(|| PathBuf::from(text));
+            }
+            if let Some(path) = start_open_path.as_ref() {
+                let expanded = paths::expand(path);
+                if !expanded.is_dir() {
+                    ui.colored_label(
+                        ui.visuals().warn_fg_color,
+                        format!("Not found: {}", expanded.display()),
+                    );
+                }
+            }
+        });
+    }
+
+    fn move_selected_prev(&mut self) {
+        self.is_following = false;
+        if let Some(data) = self.data.as_mut() {
+            data.move_selected_to_prev();
+            self.should_scroll = true;
+        }
+    }
+
+    fn move_selected_next(&mut self) {
+        if let Some(data) = self.data.as_mut() {
             data.move_selected_to_next();
             self.should_scroll = true;
         }
     }
 
     fn move_selected_first(&mut self) {
+        self.is_following = false;
         if let Some(data) = self.data.as_mut() {
             data.move_selected_to_first();
             self.should_scroll = true;
@@ -425,64 +2139,466 @@ impl LogViewerApp {
     }
 
     #[cfg(not(target_arch = "wasm32"))]
-    /// Attempts to read the contents of the last loaded file and return it in a loading status otherwise returns an error loading status
-    fn reload_file(&self) -> LoadingStatus {
+    /// Starts loading the last loaded file again
+    fn reload_file(&mut self, ctx: egui::Context) {
         let Some(folder) = self.start_open_path.lock().unwrap().clone() else {
-            return LoadingStatus::Failed("no staring folder available".into());
+            self.task_messages
+                .push(Err("Reload failed: no staring folder available".into()));
+            return;
         };
         let Some(filename) = self.last_filename.lock().unwrap().clone() else {
-            return LoadingStatus::Failed("no last filename available".into());
+            self.task_messages
+                .push(Err("Reload failed: no last filename available".into()));
+            return;
         };
-        let file_path = folder.join(filename);
-        match std::fs::read_to_string(file_path) {
-            Ok(val) => LoadingStatus::Success(val),
-            Err(e) => LoadingStatus::Failed(format!("error loading file: {e:?}")),
-        }
+        let file_path = paths::expand(&folder).join(filename);
+        self.spawn_load_from_path(ctx, file_path);
     }
 
     #[cfg(not(target_arch = "wasm32"))]
-    fn load_most_recent_file(&self) -> LoadingStatus {
+    fn load_most_recent_file(&mut self, ctx: egui::Context) {
         let Some(folder) = self.start_open_path.lock().unwrap().clone() else {
-            return LoadingStatus::Failed("unable to find starting folder".into());
+            self.task_messages.push(Err(
+                "Load most recent failed: unable to find starting folder".into(),
+            ));
+            return;
         };
-        match get_most_recent_file(&folder) {
-            Ok(path) => match std::fs::read_to_string(&path) {
-                Ok(val) => {
-                    *self.last_filename.lock().unwrap() =
-                        Some(PathBuf::from(path.file_name().unwrap()));
-                    LoadingStatus::Success(val)
-                }
-                Err(e) => LoadingStatus::Failed(format!("error loading file: {e:?}")),
-            },
-            Err(e) => LoadingStatus::Failed(format!(
+        match get_most_recent_file(&paths::expand(&folder)) {
+            Ok(path) => {
+                *self.last_filename.lock().unwrap() =
+                    Some(PathBuf::from(path.file_name().unwrap()));
+                self.spawn_load_from_path(ctx, path);
+            }
+            Err(e) => self.task_messages.push(Err(format!(
                 "unable to determine most recent file in starting directory '{}'. Error: {e}",
                 folder.display()
-            )),
+            ))),
+        }
+    }
+
+    /// Loads the file and remembers it as the last file opened (so reloading it works)
+    #[cfg(not(target_arch = "wasm32"))]
+    fn open_path(&mut self, ctx: egui::Context, path: PathBuf) {
+        if let Some(parent) = path.parent().filter(|x| !x.as_os_str().is_empty()) {
+            *self.start_open_path.lock().unwrap() = Some(paths::contract_home(parent));
+        }
+        *self.last_filename.lock().unwrap() = path.file_name().map(PathBuf::from);
+        self.spawn_load_from_path(ctx, path);
+    }
+
+    /// Opens the files other instances were started with
+    #[cfg(not(target_arch = "wasm32"))]
+    fn update_single_instance(&mut self, ctx: &egui::Context) {
+        let Some(single_instance) = self.single_instance.as_ref() else {
+            return;
+        };
+        for path in single_instance.take_opened() {
+            self.open_path(ctx.clone(), path);
+            ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+        }
+    }
+
+    /// Switches to a newer file in the starting folder if watching is enabled
+    #[cfg(not(target_arch = "wasm32"))]
+    fn update_folder_watch(&mut self, ctx: &egui::Context) {
+        let Some(folder) = self.start_open_path.lock().unwrap().clone() else {
+            return;
+        };
+        let folder = paths::expand(&folder);
+        if let Some(path) = self
+            .folder_watch
+            .poll(ctx, || get_most_recent_file(&folder))
+        {
+            *self.last_filename.lock().unwrap() = path.file_name().map(PathBuf::from);
+            self.spawn_load_from_path(ctx.clone(), path);
         }
     }
 
     /// These shortcuts are always enabled
     fn check_global_shortcuts(&mut self, ui: &mut egui::Ui) {
-        if ui.input_mut(|i| i.consume_shortcut(&self.shortcuts.search)) {
+        // First so the keys used to move between fields do not also move between rows
+        self.details_keyboard
+            .capture_keys(ui.ctx(), &self.shortcuts.focus_details);
+        if shortcut::consume(ui.ctx(), &self.shortcuts.search) {
+            self.show_controls = true; // The search box is in the controls
             self.focus_search_text_edit();
         }
-        if ui.input_mut(|i| i.consume_shortcut(&self.shortcuts.auto_scroll)) {
-            self.should_scroll_to_end_on_load = !self.should_scroll_to_end_on_load;
+        if shortcut::consume(ui.ctx(), &self.shortcuts.auto_scroll) {
+            self.should_scroll_to_end_on_load = !self.should_scroll_to_end_on_load;
+        }
+        if shortcut::consume(ui.ctx(), &self.shortcuts.toggle_controls) {
+            self.show_controls = !self.show_controls;
+        }
+        if shortcut::consume(ui.ctx(), &self.shortcuts.toggle_details) {
+            self.show_details = !self.show_details;
+        }
+    }
+
+    fn navigation_and_filtering_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            self.navigation_ui(ui);
+            ui.separator();
+            self.filtering_ui(ui);
+        });
+        self.levels_ui(ui);
+        ui.horizontal(|ui| {
+            self.filter_undo_ui(ui);
+            self.unfilter_ui(ui);
+            self.hidden_rows_ui(ui);
+            self.groups_ui(ui);
+            self.duplicates_ui(ui);
+            self.parse_failures_ui(ui);
+            self.partial_load_ui(ui);
+            self.decoding_ui(ui);
+        });
+        if let Some(data) = self.data.as_mut() {
+            let active_count = data.range_filters().len();
+            let title = if active_count > 0 {
+                format!("Range Filters ({active_count} active)")
+            } else {
+                "Range Filters".to_string()
+            };
+            egui::CollapsingHeader::new(title)
+                .id_salt("range filters")
+                .show(ui, |ui| {
+                    let common_fields = self.data_display_options.common_fields().clone();
+                    self.range_filters_ui.ui(
+                        ui,
+                        data,
+                        &mut self.data_display_options.range_filter_fields,
+                        &common_fields,
+                    );
+                });
+        }
+        self.ignore_list_ui(ui);
+        self.sort_ui(ui);
+    }
+
+    /// Primary and secondary sort keys with a button to go back to file order
+    fn sort_ui(&mut self, ui: &mut egui::Ui) {
+        let Some(data) = self.data.as_mut() else {
+            return;
+        };
+        let title = if data.sort().is_some() {
+            "Sort (active)"
+        } else {
+            "Sort"
+        };
+        egui::CollapsingHeader::new(title)
+            .id_salt("sort")
+            .show(ui, |ui| {
+                let mut sort = data.sort().cloned().unwrap_or_default();
+                sort.keys.resize_with(2, Default::default);
+                let original = sort.clone();
+                egui::Grid::new("sort keys").show(ui, |ui| {
+                    for (i, key) in sort.keys.iter_mut().enumerate() {
+                        ui.label(if i == 0 { "Sort by" } else { "Then by" });
+                        let selected_text = if key.field_name.is_empty() {
+                            "(none)"
+                        } else {
+                            key.field_name.as_str()
+                        };
+                        egui::ComboBox::from_id_salt(("sort field", i))
+                            .selected_text(selected_text)
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut key.field_name, String::new(), "(none)");
+                                let field_names =
+                                    self.field_names.get_or_insert_with(|| data.field_names());
+                                for field_name in field_names.iter() {
+                                    ui.selectable_value(
+                                        &mut key.field_name,
+                                        field_name.clone(),
+                                        field_name,
+                                    );
+                                }
+                            });
+                        let direction = if key.is_descending {
+                            "⏷ Descending"
+                        } else {
+                            "⏶ Ascending"
+                        };
+                        if ui.button(direction).clicked() {
+                            key.is_descending = !key.is_descending;
+                        }
+                        egui::ComboBox::from_id_salt(("sort compare as", i))
+                            .selected_text(key.compare_as.to_string())
+                            .show_ui(ui, |ui| {
+                                for compare_as in SortCompare::ALL {
+                                    ui.selectable_value(
+                                        &mut key.compare_as,
+                                        compare_as,
+                                        compare_as.to_string(),
+                                    );
+                                }
+                            });
+                        ui.end_row();
+                    }
+                });
+                let common_fields = self.data_display_options.common_fields();
+                if ui
+                    .add_enabled(
+                        data.sort().is_some(),
+                        egui::Button::new("Restore File Order"),
+                    )
+                    .clicked()
+                {
+                    data.set_sort(None, common_fields);
+                    self.should_scroll = true;
+                } else if sort != original {
+                    data.set_sort(Some(sort), common_fields);
+                    self.should_scroll = true;
+                }
+            });
+    }
+
+    /// Toggles to show or hide rows of each level
+    fn levels_ui(&mut self, ui: &mut egui::Ui) {
+        let Some(data) = self.data.as_mut() else {
+            return;
+        };
+        let field_name = self.data_display_options.level_field_name();
+        let levels = self.data_display_options.level_names();
+        let mut level_filter = data.level_filter().clone();
+        if level_filter.field_name != field_name {
+            // Level field changed so the hidden levels no longer apply
+            level_filter = LevelFilter {
+                field_name: field_name.to_string(),
+                ..Default::default()
+            };
+        }
+        let mut min_level = self.data_display_options.min_level.clone();
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_label("Minimum Level")
+                .selected_text(min_level.as_deref().unwrap_or("All"))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut min_level, None, "All");
+                    for level in levels.iter() {
+                        ui.selectable_value(&mut min_level, Some(level.to_string()), *level);
+                    }
+                });
+            ui.separator();
+            ui.label("Levels:");
+            for level in levels {
+                let mut is_shown = !level_filter.hidden_levels.contains(level);
+                let color = self.data_display_options.field_color(field_name, level);
+                let text = match color {
+                    Some(color) => egui::RichText::new(level).color(color),
+                    None => egui::RichText::new(level),
+                };
+                if ui.toggle_value(&mut is_shown, text).changed() {
+                    if is_shown {
+                        level_filter.hidden_levels.remove(level);
+                    } else {
+                        level_filter.hidden_levels.insert(level.to_string());
+                    }
+                }
+            }
+        });
+        self.data_display_options.min_level = min_level;
+        // Kept in sync every frame so the minimum also applies to newly loaded data
+        level_filter.below_min = self.data_display_options.levels_below_min();
+        if &level_filter != data.level_filter() {
+            data.set_level_filter(level_filter, self.data_display_options.common_fields());
+            self.should_scroll = true;
+        }
+    }
+
+    fn ignore_list_ui(&mut self, ui: &mut egui::Ui) {
+        let Some(data) = self.data.as_mut() else {
+            return;
+        };
+        let mut ignore_list = data.ignore_list().to_vec();
+        let title = if ignore_list.is_empty() {
+            "Ignore List".to_string()
+        } else {
+            format!("Ignore List ({})", ignore_list.len())
+        };
+        egui::CollapsingHeader::new(title)
+            .id_salt("ignore list")
+            .show(ui, |ui| {
+                let mut rule_to_remove = None;
+                for (i, rule) in ignore_list.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        ui.checkbox(&mut rule.is_enabled, "");
+                        ui.label(rule.filter.to_string());
+                        if ui.small_button("🗑").on_hover_text("Remove rule").clicked() {
+                            rule_to_remove = Some(i);
+                        }
+                    });
+                }
+                if let Some(i) = rule_to_remove {
+                    ignore_list.remove(i);
+                }
+                if ui
+                    .add_enabled(data.filter.is_some(), egui::Button::new("Ignore Matching"))
+                    .on_hover_text("Adds the current filter to the ignore list")
+                    .clicked()
+                {
+                    if let Some(filter) = data.filter.clone() {
+                        ignore_list.push(IgnoreRule {
+                            filter,
+                            is_enabled: true,
+                        });
+                    }
+                }
+            });
+        if ignore_list.as_slice() != data.ignore_list() {
+            data.set_ignore_list(ignore_list, self.data_display_options.common_fields());
+            self.should_scroll = true;
+        }
+    }
+
+    /// Folding controls for CI log groups (only shown if the data has groups)
+    fn groups_ui(&mut self, ui: &mut egui::Ui) {
+        let Some(data) = self.data.as_mut() else {
+            return;
+        };
+        if !data.has_groups() {
+            return;
+        }
+        for (label, is_collapsed) in [("Collapse All Groups", true), ("Expand All Groups", false)] {
+            if ui.button(label).clicked() {
+                data.set_all_groups_collapsed(
+                    is_collapsed,
+                    self.data_display_options.common_fields(),
+                );
+                self.should_scroll = true;
+            }
+        }
+        ui.separator();
+    }
+
+    /// Controls for folding runs of rows that repeat the same value
+    fn duplicates_ui(&mut self, ui: &mut egui::Ui) {
+        let Some(data) = self.data.as_mut() else {
+            return;
+        };
+        let common_fields = self.data_display_options.common_fields();
+        let mut is_enabled = data.collapse_duplicates_field().is_some();
+        if ui
+            .checkbox(&mut is_enabled, "Collapse repeats of")
+            .on_hover_text("Folds consecutive rows with the same value into the first of them")
+            .changed()
+        {
+            let field_name = is_enabled.then(|| DEFAULT_DUPLICATES_FIELD.to_string());
+            data.set_collapse_duplicates_field(field_name, common_fields);
+            self.should_scroll = true;
+        }
+        let current = data
+            .collapse_duplicates_field()
+            .unwrap_or(DEFAULT_DUPLICATES_FIELD)
+            .to_string();
+        let mut chosen = None;
+        ui.add_enabled_ui(is_enabled, |ui| {
+            egui::ComboBox::from_id_salt("collapse duplicates field")
+                .selected_text(&current)
+                .show_ui(ui, |ui| {
+                    let field_names = self.field_names.get_or_insert_with(|| data.field_names());
+                    for field_name in field_names.iter() {
+                        if ui
+                            .selectable_label(field_name == &current, field_name)
+                            .clicked()
+                        {
+                            chosen = Some(field_name.clone());
+                        }
+                    }
+                });
+        });
+        if let Some(field_name) = chosen.filter(|x| x != &current) {
+            data.set_collapse_duplicates_field(Some(field_name), common_fields);
+            self.should_scroll = true;
+        }
+        ui.separator();
+    }
+
+    fn parse_failures_ui(&mut self, ui: &mut egui::Ui) {
+        let Some(data) = self.data.as_ref() else {
+            return;
+        };
+        let failure_count = data.parse_failure_count();
+        if failure_count == 0 {
+            return;
+        }
+        ui.colored_label(
+            ui.visuals().warn_fg_color,
+            format!(
+                "Lines that failed to parse: {}",
+                as_string_with_separators(failure_count)
+            ),
+        );
+        ui.separator();
+    }
+
+    fn partial_load_ui(&mut self, ui: &mut egui::Ui) {
+        let Some(load_range) = self.data.as_ref().and_then(|x| x.partial_load()) else {
+            return;
+        };
+        ui.colored_label(
+            ui.visuals().warn_fg_color,
+            format!("Partial view: only the {load_range} were loaded"),
+        );
+        #[cfg(not(target_arch = "wasm32"))]
+        if ui
+            .button("Load All")
+            .on_hover_text("Reloads the file with all lines")
+            .clicked()
+        {
+            self.data_display_options.load_range = LoadRange::All;
+            self.reload_file(ui.ctx().clone());
+        }
+        ui.separator();
+    }
+
+    fn decoding_ui(&mut self, ui: &mut egui::Ui) {
+        let Some(decoding) = self.data.as_ref().and_then(|x| x.decoding()) else {
+            return;
+        };
+        if decoding.encoding != TextEncoding::Utf8 {
+            ui.label(format!("Decoded as {}", decoding.encoding));
+        }
+        if decoding.replaced_bytes > 0 {
+            ui.colored_label(
+                ui.visuals().warn_fg_color,
+                format!(
+                    "{} invalid bytes were replaced with {}",
+                    as_string_with_separators(decoding.replaced_bytes),
+                    char::REPLACEMENT_CHARACTER
+                ),
+            );
+        }
+        ui.separator();
+    }
+
+    fn hidden_rows_ui(&mut self, ui: &mut egui::Ui) {
+        let Some(data) = self.data.as_mut() else {
+            return;
+        };
+        let hidden_count = data.hidden_count();
+        if hidden_count == 0 {
+            return;
+        }
+        ui.label(format!(
+            "Hidden Rows: {}",
+            as_string_with_separators(hidden_count)
+        ));
+        if shortcut_button(
+            ui,
+            "Undo Hide",
+            "Restores the last row hidden",
+            &self.shortcuts.undo_hide,
+        ) {
+            data.undo_hide(self.data_display_options.common_fields());
+            self.should_scroll = true;
+        }
+        if ui.button("Restore All").clicked() {
+            data.restore_all_hidden(self.data_display_options.common_fields());
+            self.should_scroll = true;
         }
-    }
-
-    fn navigation_and_filtering_ui(&mut self, ui: &mut egui::Ui) {
-        ui.horizontal(|ui| {
-            self.navigation_ui(ui);
-            ui.separator();
-            self.filtering_ui(ui);
-        });
-        ui.horizontal(|ui| {
-            self.unfilter_ui(ui);
-        });
+        ui.separator();
     }
 
     fn filtering_ui(&mut self, ui: &mut egui::Ui) {
+        let mut should_start_filter = false;
         if let Some(data) = self.data.as_mut() {
             ui.label("Filter:");
             let mut is_filter_enabled = data.filter.is_some();
@@ -491,16 +2607,52 @@ impl LogViewerApp {
                 (false, false) | (true, true) => {} // Already match
                 (true, false) => data.filter = Some(Default::default()),
                 (false, true) => {
-                    data.unfilter();
+                    self.filter_task = None;
+                    data.unfilter(self.data_display_options.common_fields());
                     data.filter = None;
                     self.should_scroll = true;
                 }
             }
             let mut should_apply_filter = false;
+            let mut recalled_filter = None;
             if is_filter_enabled && shortcut_button(ui, "Apply", "", &self.shortcuts.apply_filter) {
                 should_apply_filter = true;
             }
+            if is_filter_enabled {
+                let mut context_rows = data.context_rows();
+                if ui
+                    .add(
+                        egui::DragValue::new(&mut context_rows)
+                            .range(0..=100)
+                            .prefix("±"),
+                    )
+                    .on_hover_text(
+                        "Context rows: also show this many rows before and after each match",
+                    )
+                    .changed()
+                {
+                    data.set_context_rows(context_rows, self.data_display_options.common_fields());
+                }
+            }
+            if is_filter_enabled {
+                let common_fields = self.data_display_options.common_fields();
+                if shortcut_button(ui, "⬆", "Previous match", &self.shortcuts.prev_match) {
+                    self.should_scroll |= data.move_selected_to_match(false, common_fields);
+                }
+                if shortcut_button(ui, "⬇", "Next match", &self.shortcuts.next_match) {
+                    self.should_scroll |= data.move_selected_to_match(true, common_fields);
+                }
+            }
 
+            let field_names = if data
+                .filter
+                .as_ref()
+                .is_some_and(|x| x.filter_on.field_names().is_some())
+            {
+                self.field_names.get_or_insert_with(|| data.field_names())
+            } else {
+                &Vec::new()
+            };
             if let Some(filter) = data.filter.as_mut() {
                 let FilterConfig {
                     search_key,
@@ -510,7 +2662,13 @@ impl LogViewerApp {
                 } = filter;
 
                 ui.label("Search Key: ");
-                let mut search_key_text_edit = egui::TextEdit::singleline(search_key).show(ui);
+                let mut search_key_text_edit = egui::TextEdit::singleline(search_key)
+                    .hint_text(if comparator.is_presence_check() {
+                        "(not used)"
+                    } else {
+                        ""
+                    })
+                    .show(ui);
                 if self.should_focus_search {
                     self.should_focus_search = false;
 
@@ -536,6 +2694,23 @@ impl LogViewerApp {
                 {
                     should_apply_filter = true;
                 }
+                if self.should_filter_as_you_type && search_key_text_edit.response.changed() {
+                    self.search_key_edit_time = Some(ui.input(|i| i.time));
+                }
+                recalled_filter = self.search_history.menu_ui(ui);
+                if search_key_text_edit.response.has_focus() {
+                    let (is_up, is_down) = ui.input_mut(|i| {
+                        (
+                            i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp),
+                            i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown),
+                        )
+                    });
+                    if is_up {
+                        recalled_filter = self.search_history.older().cloned();
+                    } else if is_down {
+                        recalled_filter = self.search_history.newer().cloned();
+                    }
+                }
 
                 ui.spacing();
                 ui.checkbox(is_case_sensitive, "Case Sensitive");
@@ -560,6 +2735,11 @@ impl LogViewerApp {
                         ui.selectable_value(comparator, Comparator::NotEqual, "Not equal");
                         ui.selectable_value(comparator, Comparator::Contains, "Contains");
                         ui.selectable_value(comparator, Comparator::NotContains, "Not contains");
+                        ui.selectable_value(comparator, Comparator::Fuzzy, "Fuzzy");
+                        ui.selectable_value(comparator, Comparator::IsPresent, "Is present");
+                        ui.selectable_value(comparator, Comparator::IsMissing, "Is missing");
+                        ui.selectable_value(comparator, Comparator::Template, "Template")
+                            .on_hover_text("Numbers and ids in the value are replaced by {} before comparing (eg. took {}ms)");
                     });
 
                 ui.spacing();
@@ -573,20 +2753,57 @@ impl LogViewerApp {
                 let mut is_field = filter_on.is_field();
                 ui.toggle_value(&mut is_field, "Field");
                 if is_field && !filter_on.is_field() {
+                    // Toggled on (keeps the first field if there were multiple)
+                    *filter_on = match std::mem::take(filter_on) {
+                        FilterOn::Fields(fields) => {
+                            FilterOn::Field(fields.into_iter().next().unwrap_or_default())
+                        }
+                        FilterOn::Any | FilterOn::Field(_) => FilterOn::Field(Default::default()),
+                    };
+                }
+
+                let mut is_fields = filter_on.is_fields();
+                ui.toggle_value(&mut is_fields, "Fields")
+                    .on_hover_text("Match if any of the chosen fields match");
+                if is_fields && !filter_on.is_fields() {
                     // Toggled on
-                    *filter_on = FilterOn::Field(Default::default());
+                    *filter_on = match std::mem::take(filter_on) {
+                        FilterOn::Field(field) => FilterOn::Fields(vec![field]),
+                        FilterOn::Any | FilterOn::Fields(_) => {
+                            FilterOn::Fields(vec![Default::default()])
+                        }
+                    };
                 }
 
-                if let FilterOn::Field(FieldSpecifier { name }) = filter_on {
-                    ui.spacing();
-                    if ui
-                        .add(egui::TextEdit::singleline(name).hint_text("Name"))
-                        .lost_focus()
-                        && ui.input(|i| i.key_pressed(egui::Key::Enter))
-                    {
-                        should_apply_filter = true;
+                if comparator.is_presence_check() && filter_on.is_any() {
+                    ui.colored_label(ui.visuals().warn_fg_color, "(Choose a field to check)");
+                }
+                match filter_on {
+                    FilterOn::Any => {}
+                    FilterOn::Field(FieldSpecifier { name }) => {
+                        ui.spacing();
+                        should_apply_filter |= field_name_ui(ui, name, field_names);
                     }
-
+                    FilterOn::Fields(fields) => {
+                        let mut index_to_remove = None;
+                        for (i, FieldSpecifier { name }) in fields.iter_mut().enumerate() {
+                            ui.spacing();
+                            ui.push_id(i, |ui| {
+                                should_apply_filter |= field_name_ui(ui, name, field_names);
+                                if ui.small_button("🗑").on_hover_text("Remove field").clicked() {
+                                    index_to_remove = Some(i);
+                                }
+                            });
+                        }
+                        if let Some(i) = index_to_remove {
+                            fields.remove(i);
+                        }
+                        if ui.small_button("➕").on_hover_text("Add field").clicked() {
+                            fields.push(Default::default());
+                        }
+                    }
+                }
+                if !filter_on.is_any() {
                     let color = if self.should_highlight_field_warning {
                         ui.visuals().warn_fg_color
                     } else {
@@ -607,15 +2824,62 @@ impl LogViewerApp {
                     };
                 }
             }
-            if should_apply_filter {
-                data.apply_filter(self.data_display_options.common_fields());
+            if let Some(filter) = recalled_filter {
+                data.filter = Some(filter);
+            }
+            self.match_counter
+                .ui(ui, data, self.data_display_options.common_fields());
+            if ui
+                .button("Copy Filters")
+                .on_hover_text("Copies all the filters as text to share with others")
+                .clicked()
+            {
+                ui.ctx()
+                    .copy_text(filter_sharing::export_text(&SavedFilters::new(Some(data))));
+            }
+            if ui
+                .button("Import Filters...")
+                .on_hover_text("Use filters copied by someone else")
+                .clicked()
+            {
+                self.filter_sharing.is_import_open = true;
+            }
+            let mut is_debounce_done = false;
+            if let Some(edit_time) = self.search_key_edit_time {
+                let remaining = SEARCH_DEBOUNCE_SECS - (ui.input(|i| i.time) - edit_time);
+                if remaining <= 0.0 {
+                    is_debounce_done = true;
+                } else {
+                    ui.ctx().request_repaint_after_secs(remaining as f32);
+                }
+            }
+            if should_apply_filter || is_debounce_done {
+                self.search_key_edit_time = None;
+                if let (true, Some(filter)) = (should_apply_filter, data.filter.as_ref()) {
+                    // Only explicit applies are kept so the history is not filled with partly typed keys
+                    self.search_history.record(filter);
+                }
+                if data.filter.is_some() {
+                    if !should_apply_filter {
+                        self.filter_undo.mark_typing();
+                    }
+                    should_start_filter = true;
+                }
             }
         }
+        if should_start_filter {
+            self.start_filter(ui.ctx());
+        }
     }
 
     fn navigation_ui(&mut self, ui: &mut egui::Ui) {
         ui.label("Nav:");
-        if shortcut_button(ui, "⏪", "First", &self.shortcuts.first) {
+        if ui
+            .button("⏪")
+            .on_hover_text(shortcut_hint_text(ui, "First", &self.shortcuts.first))
+            .clicked()
+            || self.consume_first_shortcut(ui.ctx())
+        {
             self.move_selected_first();
         }
         if shortcut_button(ui, "⬆", "Previous", &self.shortcuts.prev) {
@@ -627,40 +2891,240 @@ impl LogViewerApp {
         if shortcut_button(ui, "⏩", "Last", &self.shortcuts.last) {
             self.move_selected_last();
         }
+        let mut history_target = None;
+        ui.add_enabled_ui(self.selection_history.can_go_back(), |ui| {
+            if shortcut_button(
+                ui,
+                "⬅",
+                "Back to the row selected before",
+                &self.shortcuts.selection_back,
+            ) {
+                history_target = self.selection_history.back();
+            }
+        });
+        ui.add_enabled_ui(self.selection_history.can_go_forward(), |ui| {
+            if shortcut_button(ui, "➡", "Forward", &self.shortcuts.selection_forward) {
+                history_target = self.selection_history.forward();
+            }
+        });
+        if let (Some(real_index), Some(data)) = (history_target, self.data.as_mut()) {
+            data.select_real_index(real_index, self.data_display_options.common_fields());
+            self.should_scroll = true;
+        }
+        let time_field_name = &self.data_display_options.time_gaps.time_field_name;
+        if let Some(target) = self
+            .time_jump_ui
+            .ui(ui, self.data.as_ref(), time_field_name)
+        {
+            if let Some(data) = self.data.as_mut() {
+                if data.select_nearest_time(target, time_field_name) {
+                    self.is_following = false;
+                    self.should_scroll = true;
+                } else {
+                    self.time_jump_ui.set_not_found(time_field_name);
+                }
+            }
+        }
+        {
+            // Rows of the same request (or whatever is emphasized)
+            let field_name = self
+                .emphasized_field_name()
+                .unwrap_or(&self.copy_key_field_name)
+                .to_string();
+            let hint = format!("row with the same {field_name}");
+            let mut is_last = None;
+            if shortcut_button(
+                ui,
+                "⏪=",
+                &format!("First {hint}"),
+                &self.shortcuts.first_same_value,
+            ) {
+                is_last = Some(false);
+            }
+            let mut direction = None;
+            if shortcut_button(
+                ui,
+                "⬆=",
+                &format!("Previous {hint}"),
+                &self.shortcuts.prev_same_value,
+            ) {
+                direction = Some(false);
+            }
+            if shortcut_button(
+                ui,
+                "⬇=",
+                &format!("Next {hint}"),
+                &self.shortcuts.next_same_value,
+            ) {
+                direction = Some(true);
+            }
+            if shortcut_button(
+                ui,
+                "⏩=",
+                &format!("Last {hint}"),
+                &self.shortcuts.last_same_value,
+            ) {
+                is_last = Some(true);
+            }
+            if let (Some(is_forward), Some(data)) = (direction, self.data.as_mut()) {
+                self.is_following = false;
+                self.should_scroll |= data.move_selected_to_same_value(&field_name, is_forward);
+            }
+            if let (Some(is_last), Some(data)) = (is_last, self.data.as_mut()) {
+                self.is_following = false;
+                self.should_scroll |= data.move_selected_to_same_value_end(&field_name, is_last);
+            }
+        }
+        if shortcut_button(ui, "🚫", "Hide selected row", &self.shortcuts.hide_row) {
+            if let Some(data) = self.data.as_mut() {
+                data.hide_selected();
+                self.should_scroll = true;
+            }
+        }
+        if shortcut_button(
+            ui,
+            "🔀",
+            "Mark selected row to compare with other rows",
+            &self.shortcuts.mark_for_compare,
+        ) {
+            self.row_diff.mark_selected(self.data.as_ref());
+        }
+        if shortcut_button(
+            ui,
+            "🔑",
+            &format!("Copy {} of selected row", self.copy_key_field_name),
+            &self.shortcuts.copy_key_field,
+        ) {
+            self.copy_key_field(ui.ctx());
+        }
+    }
+
+    /// Copies the value of [`Self::copy_key_field_name`] from the selected row to the clipboard
+    fn copy_key_field(&mut self, ctx: &egui::Context) {
+        let Some(row) = self.data.as_ref().and_then(|x| x.selected_row()) else {
+            return;
+        };
+        match row.field_value(&self.copy_key_field_name) {
+            content @ FieldContent::Present(_) => ctx.copy_text(content.display()),
+            FieldContent::Missing => self.task_messages.push(Err(format!(
+                "Copy failed: the selected row has no {} field",
+                self.copy_key_field_name
+            ))),
+        }
+    }
+    /// The field used to emphasize rows related to the selected row
+    fn emphasized_field_name(&self) -> Option<&str> {
+        let idx = (*self.data_display_options.emphasize_if_matching_field_idx())?;
+        self.data_display_options
+            .main_list_fields()
+            .get(idx)
+            .map(String::as_str)
+    }
+
+    /// Keeps moving between rows with the keyboard working while the controls (and their buttons) are hidden
+    fn hidden_controls_shortcuts(&mut self, ctx: &egui::Context) {
+        if self.consume_first_shortcut(ctx) {
+            self.move_selected_first();
+        }
+        if shortcut::consume(ctx, &self.shortcuts.prev) {
+            self.move_selected_prev();
+        }
+        if shortcut::consume(ctx, &self.shortcuts.next) {
+            self.move_selected_next();
+        }
+        if shortcut::consume(ctx, &self.shortcuts.last) {
+            self.move_selected_last();
+        }
+    }
+
+    /// Handles the shortcut for the first row separately as it may need to be pressed twice (see [`Shortcuts::should_double_press_first`])
+    fn consume_first_shortcut(&mut self, ctx: &egui::Context) -> bool {
+        /// Max seconds between presses to count as a double press
+        const DOUBLE_PRESS_WINDOW: f64 = 1.0;
+        if !shortcut::consume(ctx, &self.shortcuts.first) {
+            return false;
+        }
+        if !self.shortcuts.should_double_press_first {
+            return true;
+        }
+        let now = ctx.input(|i| i.time);
+        match self.pending_first_press.take() {
+            Some(previous) if now - previous <= DOUBLE_PRESS_WINDOW => true,
+            _ => {
+                self.pending_first_press = Some(now);
+                false
+            }
+        }
     }
+
     fn data_load_ui(&mut self, ui: &mut egui::Ui) {
         ui.horizontal(|ui| {
             if shortcut_button(ui, "📂 Open log file...", "", &self.shortcuts.open) {
-                self.loading_status = self.initiate_loading(ui.ctx().clone());
+                self.initiate_loading(ui.ctx().clone());
             }
             #[cfg(not(target_arch = "wasm32"))]
             {
                 if shortcut_button(ui, "Reload", "", &self.shortcuts.reload) {
-                    self.loading_status = self.reload_file();
+                    self.reload_file(ui.ctx().clone());
                 }
                 if shortcut_button(ui, "Load Most Recent File", "", &self.shortcuts.load_latest) {
-                    self.loading_status = self.load_most_recent_file();
+                    self.load_most_recent_file(ui.ctx().clone());
                 }
+                let folder = self.start_open_path.lock().unwrap().clone();
+                self.folder_watch.ui(ui, folder.as_deref());
             }
             if ui.button("Clear Data").clicked() {
                 self.data = None;
+                self.invalidate_data_caches();
+            }
+            self.export_ui(ui);
+            if ui
+                .toggle_value(&mut self.is_following, "Follow")
+                .on_hover_text(
+                    "Keep the newest row selected as rows are added (turns off when you scroll up)",
+                )
+                .clicked()
+                && self.is_following
+            {
+                self.move_selected_last();
+            }
+            if self.live_source().is_some() || self.live_pause.is_paused() {
+                let lines = self.live_pause.ui(ui);
+                self.append_live_lines(lines);
+            }
+            if let Some(real_index) = self.watches.badges_ui(
+                ui,
+                self.data.as_mut(),
+                self.data_display_options.common_fields(),
+            ) {
+                if let Some(data) = self.data.as_mut() {
+                    data.select_real_index(real_index, self.data_display_options.common_fields());
+                    self.is_following = false;
+                    self.should_scroll = true;
+                }
             }
 
+            if let Some(name) = self.workspaces.current() {
+                ui.label(format!("Workspace: {name}"));
+            }
             if self.show_last_filename {
                 if let Some(filename) = self.last_filename.lock().unwrap().as_ref() {
                     ui.label(format!("Filename: {}", filename.display()));
                 }
             }
             if let Some(data) = self.data.as_ref() {
-                let row_count_text =
-                    match (data.is_filtered(), data.len(), data.total_len_unfiltered()) {
-                        (true, filtered_len, total_len) => format!(
-                            "{} of {}",
-                            as_string_with_separators(filtered_len),
-                            as_string_with_separators(total_len)
-                        ),
-                        (false, _, total_len) => as_string_with_separators(total_len),
-                    };
+                let row_count_text = match (
+                    data.is_filtered() || data.hidden_count() > 0,
+                    data.len(),
+                    data.total_len_unfiltered(),
+                ) {
+                    (true, filtered_len, total_len) => format!(
+                        "{} of {}",
+                        as_string_with_separators(filtered_len),
+                        as_string_with_separators(total_len)
+                    ),
+                    (false, _, total_len) => as_string_with_separators(total_len),
+                };
                 ui.label(format!("# Rows: {row_count_text}"));
             }
         });
@@ -673,13 +3137,98 @@ impl LogViewerApp {
         }
     }
 
+    /// Exports the visible rows with the columns of the main list
+    fn export_ui(&mut self, ui: &mut egui::Ui) {
+        let title = self
+            .current_file_key()
+            .unwrap_or_else(|| "Log Viewer Report".to_string());
+        let Some(data) = self.data.as_ref() else {
+            return;
+        };
+        ui.menu_button("Export", |ui| {
+            if ui
+                .button("Copy as Markdown Table")
+                .on_hover_text("Copies the visible rows to paste into an issue or document")
+                .clicked()
+            {
+                ui.ctx().copy_text(table_export::markdown_table(
+                    data,
+                    &self.data_display_options,
+                ));
+                self.task_messages.push(Ok(format!(
+                    "Copied {} rows as a Markdown table",
+                    as_string_with_separators(data.len())
+                )));
+                ui.close_menu();
+            }
+            if ui
+                .button("Export HTML Report...")
+                .on_hover_text("Saves the visible rows as an HTML table keeping the colors")
+                .clicked()
+            {
+                let html = table_export::html_report(data, &self.data_display_options, &title);
+                self.tasks
+                    .spawn_save("Export HTML report", "report.html", html, ui.ctx().clone());
+                ui.close_menu();
+            }
+            #[cfg(target_arch = "wasm32")]
+            if ui
+                .button("Copy Link to This View")
+                .on_hover_text(
+                    "Copies a link that opens the same filters, selected row and columns (the log must be loaded the same way)",
+                )
+                .clicked()
+            {
+                let fragment =
+                    SharedView::new(Some(data), &self.data_display_options).to_fragment();
+                self.task_messages.push(copy_share_link(ui.ctx(), &fragment));
+                ui.close_menu();
+            }
+        });
+    }
+
+    fn filter_undo_ui(&mut self, ui: &mut egui::Ui) {
+        if self.data.is_none() {
+            return;
+        }
+        let mut restored = None;
+        ui.add_enabled_ui(self.filter_undo.can_undo(), |ui| {
+            if shortcut_button(ui, "↶", "Undo Filter", &self.shortcuts.undo_filter) {
+                restored = self.filter_undo.undo(1);
+            }
+        });
+        if let Some(steps) = self.filter_undo.menu_ui(ui) {
+            restored = self.filter_undo.undo(steps);
+        }
+        ui.add_enabled_ui(self.filter_undo.can_redo(), |ui| {
+            if shortcut_button(ui, "↷", "Redo Filter", &self.shortcuts.redo_filter) {
+                restored = self.filter_undo.redo();
+            }
+        });
+        if let (Some(filter), Some(data)) = (restored, self.data.as_mut()) {
+            match filter {
+                Some(filter) => {
+                    data.filter = Some(filter);
+                    self.start_filter(ui.ctx());
+                }
+                None => {
+                    self.filter_task = None;
+                    data.unfilter(self.data_display_options.common_fields());
+                    self.should_scroll = true;
+                }
+            }
+        }
+        ui.separator();
+    }
+
     fn unfilter_ui(&mut self, ui: &mut egui::Ui) {
         if let Some(data) = self.data.as_mut() {
             if data.is_filtered() {
                 ui.label(format!("Applied Filter: {}", data.applied_filter_display()));
                 ui.separator();
                 if shortcut_button(ui, "Unfilter", "Clears Filter", &self.shortcuts.unfilter) {
-                    data.unfilter();
+                    self.filter_task = None;
+                    data.unfilter(self.data_display_options.common_fields());
                     self.should_scroll = true;
                 }
             }
@@ -715,29 +3264,163 @@ fn get_most_recent_file(folder: &PathBuf) -> anyhow::Result<PathBuf> {
     }
 }
 
+/// Text box for the name of a field to filter on with a list of the fields in the data to choose from.
+/// Returns true if enter was pressed
+fn field_name_ui(ui: &mut egui::Ui, name: &mut String, field_names: &[String]) -> bool {
+    let result = ui
+        .add(egui::TextEdit::singleline(name).hint_text("Name"))
+        .lost_focus()
+        && ui.input(|i| i.key_pressed(egui::Key::Enter));
+    ui.menu_button("⏷", |ui| {
+        egui::ScrollArea::vertical()
+            .max_height(300.0)
+            .show(ui, |ui| {
+                let typed = name.to_lowercase();
+                for field_name in field_names
+                    .iter()
+                    .filter(|x| x.to_lowercase().contains(&typed))
+                {
+                    if ui.button(field_name).clicked() {
+                        name.clone_from(field_name);
+                        ui.close_menu();
+                    }
+                }
+            });
+    })
+    .response
+    .on_hover_text("Choose from the fields in the data");
+    result
+}
+
+/// The options to parse a file with, those of the first display profile matching the file (with the name of the
+/// profile) or the current options if none match
+fn options_for_file(
+    profiles: &DisplayProfiles,
+    current: DataDisplayOptions,
+    file_name: &str,
+    sample: &[u8],
+) -> (DataDisplayOptions, Option<String>) {
+    match profiles.find_match(file_name, sample) {
+        Some(profile) => {
+            let mut options = profile.options.clone();
+            options.parsers = current.parsers;
+            (options, Some(profile.name.clone()))
+        }
+        None => (current, None),
+    }
+}
+
+/// Lets the app know a display profile was used so it can switch to the profile's options too
+fn with_profile(outcome: TaskOutcome, profile: Option<String>) -> TaskOutcome {
+    match (outcome, profile) {
+        (TaskOutcome::Loaded(data), Some(profile)) => {
+            TaskOutcome::LoadedWithProfile { data, profile }
+        }
+        (outcome, _) => outcome,
+    }
+}
+
 #[cfg(not(target_arch = "wasm32"))]
-fn execute<F: std::future::Future<Output = Box<LoadingStatus>> + 'static + Send>(
-    f: F,
-) -> poll_promise::Promise<Box<LoadingStatus>> {
-    poll_promise::Promise::spawn_async(f)
+/// The start of the file used to pick a display profile (empty if it cannot be read, loading then reports the error)
+fn read_sample(path: &std::path::Path) -> Vec<u8> {
+    use std::io::Read as _;
+    let mut result = Vec::new();
+    if let Ok(file) = std::fs::File::open(path) {
+        let _ = file
+            .take(crate::core::display_profiles::SAMPLE_LEN as u64)
+            .read_to_end(&mut result);
+    }
+    result
 }
 
-#[cfg(target_arch = "wasm32")]
-fn execute<F: std::future::Future<Output = Box<LoadingStatus>> + 'static>(
-    f: F,
-) -> poll_promise::Promise<Box<LoadingStatus>> {
-    poll_promise::Promise::spawn_local(f)
+/// Parses the text into the outcome for a load task (reporting progress through the handle)
+///
+/// On the web parsing runs on the same thread as the UI so control is given back to the browser between chunks
+/// Invalid bytes in the input are replaced instead of failing the load (the number replaced is kept in the data)
+async fn parse_loaded_text(
+    data_display_options: &DataDisplayOptions,
+    bytes: Vec<u8>,
+    handle: &TaskHandle,
+    ctx: &egui::Context,
+) -> TaskOutcome {
+    let (text, decoding) = decode::decode(bytes);
+    let mut parser = DataParser::new(data_display_options, &text);
+    while !parser.is_done() {
+        handle.set_progress(parser.progress());
+        if handle.is_cancel_requested() {
+            return TaskOutcome::Cancelled;
+        }
+        if let Err(e) = parser.parse_chunk(Data::PROGRESS_INTERVAL) {
+            return TaskOutcome::Failed(clean_msg(format!("{e:?}")));
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            ctx.request_repaint();
+            tasks::yield_to_browser().await;
+        }
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    let _ = ctx; // Parsing already runs in the background
+    let mut data = parser.finish();
+    data.set_decoding(decoding);
+    TaskOutcome::Loaded(Box::new(data))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+/// Parses the file as it is read so its text is not kept in memory with the rows.
+/// Falls back to reading the whole file if it is not UTF-8 as it then needs to be decoded as a whole
+async fn load_file(
+    data_display_options: &DataDisplayOptions,
+    path: &std::path::Path,
+    handle: &TaskHandle,
+    ctx: &egui::Context,
+) -> TaskOutcome {
+    let file = match std::fs::File::open(path) {
+        Ok(file) => file,
+        Err(e) => return TaskOutcome::Failed(format!("error loading file: {e:?}")),
+    };
+    let mut parser = match StreamParser::new(data_display_options, std::io::BufReader::new(file)) {
+        Ok(parser) => parser,
+        Err(e) => return TaskOutcome::Failed(clean_msg(format!("{e:?}"))),
+    };
+    while !parser.is_done() {
+        handle.set_progress(parser.progress());
+        if handle.is_cancel_requested() {
+            return TaskOutcome::Cancelled;
+        }
+        if let Err(e) = parser.parse_chunk(Data::PROGRESS_INTERVAL) {
+            return TaskOutcome::Failed(clean_msg(format!("{e:?}")));
+        }
+    }
+    match parser.finish() {
+        Some(data) => TaskOutcome::Loaded(Box::new(data)),
+        None => match std::fs::read(path) {
+            Ok(bytes) => parse_loaded_text(data_display_options, bytes, handle, ctx).await,
+            Err(e) => TaskOutcome::Failed(format!("error loading file: {e:?}")),
+        },
+    }
 }
 
 impl eframe::App for LogViewerApp {
     /// Called by the frame work to save state before shutdown.
     fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        if self.is_safe_mode {
+            info!("Safe mode, not saving");
+            return;
+        }
         info!("Saving data");
         eframe::set_value(storage, eframe::APP_KEY, self);
     }
 
+    fn persist_egui_memory(&self) -> bool {
+        !self.is_safe_mode
+    }
+
     /// Called each time the UI needs repainting, which may be many times per second.
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.shortcut_editor.capture_key(ctx, &mut self.shortcuts);
+        self.step_filter();
+        let mut workspace_action = None;
         egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
             // The top panel is often a good place for a menu bar:
 
@@ -755,44 +3438,257 @@ impl eframe::App for LogViewerApp {
                     ui.add_space(16.0);
                 }
 
+                ui.menu_button("View", |ui| {
+                    ui.checkbox(&mut self.trace_view.is_open, "Trace View");
+                    ui.checkbox(&mut self.filter_diff.is_open, "Filter Diff");
+                    ui.checkbox(&mut self.row_diff.is_open, "Compare Rows");
+                    ui.checkbox(&mut self.annotations.is_open, "Annotations");
+                    ui.checkbox(&mut self.top_values.is_open, "Top Values");
+                    ui.checkbox(&mut self.message_templates.is_open, "Message Templates");
+                    ui.checkbox(&mut self.request_summary.is_open, "Request Summary");
+                    ui.checkbox(&mut self.request_waterfall.is_open, "Request Waterfall");
+                    ui.checkbox(&mut self.field_plot.is_open, "Plot");
+                    ui.checkbox(&mut self.field_schema.is_open, "Schema");
+                    ui.checkbox(&mut self.minimap.is_enabled, "Minimap");
+                    ui.checkbox(&mut self.split_view.is_enabled, "Split View");
+                    ui.checkbox(&mut self.status_bar.is_enabled, "Status Bar");
+                    #[cfg(not(target_arch = "wasm32"))]
+                    ui.checkbox(&mut self.ssh_tail.is_open, "SSH Tail");
+                    ui.checkbox(&mut self.websocket_source.is_open, "WebSocket Source");
+                    #[cfg(all(feature = "cloudwatch", not(target_arch = "wasm32")))]
+                    ui.checkbox(&mut self.cloudwatch.is_open, "CloudWatch Logs");
+                    ui.separator();
+                    ui.checkbox(&mut self.shortcut_editor.is_open, "Keyboard Shortcuts");
+                    ui.checkbox(&mut self.reset_dialog.is_open, "Reset...");
+                });
+                ui.menu_button("Workspaces", |ui| {
+                    workspace_action = self.workspaces.menu_ui(ui);
+                });
+                ui.add_space(16.0);
+
+                ui.toggle_value(&mut self.show_controls, "Controls")
+                    .on_hover_text(shortcut_hint_text(
+                        ui,
+                        "Show the controls above the rows",
+                        &self.shortcuts.toggle_controls,
+                    ));
+                ui.toggle_value(&mut self.show_details, "Details")
+                    .on_hover_text(shortcut_hint_text(
+                        ui,
+                        "Show the details of the selected row",
+                        &self.shortcuts.toggle_details,
+                    ));
+                ui.add_space(16.0);
+
                 egui::widgets::global_theme_preference_buttons(ui);
             });
         });
 
+        self.filter_diff
+            .show(ctx, self.data.as_ref(), &mut self.tasks);
+        self.row_diff.show(ctx, self.data.as_ref());
+        if let Some(real_index) = self.annotations.show(
+            ctx,
+            self.data.as_ref(),
+            self.data_display_options.row_idx_field_name.as_deref(),
+        ) {
+            self.perform_row_command(ctx, RowCommand::Select(real_index));
+        }
+        if self.top_values.is_open {
+            let field_names = match self.data.as_ref() {
+                Some(data) => self.field_names.get_or_insert_with(|| data.field_names()),
+                None => &Vec::new(),
+            };
+            if let Some(command) =
+                self.top_values
+                    .show(ctx, self.data.as_ref(), field_names, &mut self.tasks)
+            {
+                self.perform_row_command(ctx, command);
+            }
+        } else {
+            self.top_values.stop_updating();
+        }
+        if self.message_templates.is_open {
+            let field_names = match self.data.as_ref() {
+                Some(data) => self.field_names.get_or_insert_with(|| data.field_names()),
+                None => &Vec::new(),
+            };
+            if let Some(command) =
+                self.message_templates
+                    .show(ctx, self.data.as_ref(), field_names, &mut self.tasks)
+            {
+                self.perform_row_command(ctx, command);
+            }
+        } else {
+            self.message_templates.stop_updating();
+        }
+        if self.field_plot.is_open {
+            let field_names = match self.data.as_ref() {
+                Some(data) => self.field_names.get_or_insert_with(|| data.field_names()),
+                None => &Vec::new(),
+            };
+            if let Some(real_index) = self.field_plot.show(ctx, self.data.as_ref(), field_names) {
+                self.perform_row_command(ctx, RowCommand::Select(real_index));
+            }
+        }
+        self.detached_details
+            .show(ctx, self.data.as_ref(), &self.data_display_options);
+        if let Some(command) = self.request_summary.show(
+            ctx,
+            self.data.as_ref(),
+            &self.data_display_options,
+            &mut self.tasks,
+        ) {
+            self.perform_row_command(ctx, command);
+        }
+        if self.request_waterfall.is_open {
+            let field_names = match self.data.as_ref() {
+                Some(data) => self.field_names.get_or_insert_with(|| data.field_names()),
+                None => &Vec::new(),
+            };
+            if let Some(command) = self.request_waterfall.show(
+                ctx,
+                self.data.as_ref(),
+                &self.data_display_options,
+                field_names,
+            ) {
+                self.perform_row_command(ctx, command);
+            }
+        }
+        self.field_schema.show(
+            ctx,
+            self.data.as_ref(),
+            &mut self.data_display_options,
+            &mut self.tasks,
+        );
+        self.update_live_source(ctx);
+        #[cfg(all(feature = "cloudwatch", not(target_arch = "wasm32")))]
+        if let Some(query) = self.cloudwatch.show(ctx) {
+            self.spawn_cloudwatch_load(ctx.clone(), query);
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        self.update_folder_watch(ctx);
+        #[cfg(not(target_arch = "wasm32"))]
+        self.update_single_instance(ctx);
+        self.shortcut_editor.show(ctx, &mut self.shortcuts);
+        self.value_viewer.show(ctx);
+        if let Some(import) = self.filter_sharing.show(ctx) {
+            self.import_filters(ctx, import);
+        }
+        if let Some(action) = self.workspaces.show(ctx).or(workspace_action) {
+            self.perform_workspace_action(ctx, action);
+        }
+        if let Some(choices) = self.reset_dialog.show(ctx) {
+            self.reset(ctx, choices);
+        }
+        if let Some(real_index) = self.trace_view.show(ctx, self.data.as_ref()) {
+            if let Some(data) = self.data.as_mut() {
+                data.select_real_index(real_index, self.data_display_options.common_fields());
+                self.should_scroll = true;
+            }
+        }
+
+        if self.status_bar.is_enabled {
+            let is_live_connected = self.live_source().is_some();
+            egui::TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
+                self.status_bar.ui(
+                    ui,
+                    self.data.as_ref(),
+                    StatusInfo {
+                        time_field_name: &self.data_display_options.time_gaps.time_field_name,
+                        is_following: self.is_following,
+                        is_live_connected,
+                        paused_count: self.live_pause.buffered_count(),
+                        paused_dropped_count: self.live_pause.dropped_count(),
+                    },
+                )
+            });
+        }
+
         egui::CentralPanel::default().show(ctx, |ui| {
             // The central panel the region left after adding TopPanel's and SidePanel's
             static HEADING: LazyLock<&'static str> =
                 LazyLock::new(|| format!("Log Viewer {}", env!("CARGO_PKG_VERSION")).leak());
-            ui.heading(*HEADING);
-            ui.separator();
-            self.ui_loading(ui);
-            ui.separator();
-            self.ui_options(ui);
-            ui.separator();
-            self.ui_help(ui);
-            ui.separator();
+            if self.show_controls {
+                ui.heading(*HEADING);
+                ui.separator();
+                self.safe_mode_ui(ui);
+                self.ui_loading(ui);
+                ui.separator();
+                self.ui_options(ui);
+                ui.separator();
+                self.ui_help(ui);
+                ui.separator();
+            } else {
+                // Finished tasks are still collected so loads complete while the controls are hidden
+                self.safe_mode_ui(ui);
+                self.collect_finished_tasks();
+                self.hidden_controls_shortcuts(ui.ctx());
+            }
 
             const MIN_LOG_LINES_SIZE: f32 = 100.0;
             let max_details_height = ui.available_height() - MIN_LOG_LINES_SIZE;
 
-            egui::TopBottomPanel::bottom("details_panel")
-                .resizable(true)
-                .default_height(200.)
-                .max_height(max_details_height)
-                .min_height(60.)
-                .show_inside(ui, |ui| {
-                    ui.vertical_centered(|ui| {
-                        ui.heading("Details");
-                    });
-                    egui::ScrollArea::horizontal()
-                        .id_salt("details area")
-                        .show(ui, |ui| {
-                            ui.push_id("table details", |ui| self.show_log_details(ui));
+            if self.show_details {
+                egui::TopBottomPanel::bottom("details_panel")
+                    .resizable(true)
+                    .default_height(200.)
+                    .max_height(max_details_height)
+                    .min_height(60.)
+                    .show_inside(ui, |ui| {
+                        ui.vertical_centered(|ui| {
+                            ui.heading("Details");
                         });
-                    if ui.available_height() > 0.0 {
-                        ui.allocate_space(ui.available_size());
+                        egui::ScrollArea::horizontal()
+                            .id_salt("details area")
+                            .show(ui, |ui| {
+                                ui.push_id("table details", |ui| self.show_log_details(ui));
+                            });
+                        if ui.available_height() > 0.0 {
+                            ui.allocate_space(ui.available_size());
+                        }
+                    });
+            }
+
+            if self.split_view.is_enabled {
+                let action = egui::SidePanel::right("split view")
+                    .resizable(true)
+                    .default_width(ui.available_width() / 2.0)
+                    .show_inside(ui, |ui| {
+                        egui::ScrollArea::horizontal()
+                            .id_salt("split view area")
+                            .show(ui, |ui| {
+                                self.split_view.ui(
+                                    ui,
+                                    self.data.as_mut(),
+                                    self.first_visible_real_index,
+                                    &self.data_display_options,
+                                )
+                            })
+                            .inner
+                    })
+                    .inner;
+                match action {
+                    Some(SplitViewAction::Row(command)) => {
+                        self.perform_row_command(ui.ctx(), command)
                     }
-                });
+                    Some(SplitViewAction::OpenCompareFile) => {
+                        self.initiate_compare_loading(ui.ctx().clone())
+                    }
+                    Some(SplitViewAction::ScrollMain(real_index)) => {
+                        self.scroll_to_real_index = Some(real_index)
+                    }
+                    None => {}
+                }
+            }
+
+            if self.minimap.is_enabled {
+                egui::SidePanel::right("minimap")
+                    .exact_width(MINIMAP_WIDTH)
+                    .resizable(false)
+                    .frame(egui::Frame::none())
+                    .show_inside(ui, |ui| self.minimap_ui(ui));
+            }
 
             egui::CentralPanel::default().show_inside(ui, |ui| {
                 egui::ScrollArea::horizontal()
@@ -802,15 +3698,77 @@ impl eframe::App for LogViewerApp {
                     });
             });
         });
+
+        self.filter_undo
+            .observe(self.data.as_ref().and_then(|data| data.applied_filter()));
+        self.selection_history.observe(
+            self.data
+                .as_ref()
+                .and_then(|data| Some((data.selected_real_index()?, data.selected_row?))),
+        );
+
+        // Done last so rows used while drawing are not frozen only to be thawed again next frame
+        if let Some(data) = self.data.as_mut() {
+            data.update_cold_storage(&self.data_display_options.cold_storage);
+        }
     }
 }
 
-pub fn calculate_hash<T: Hash + ?Sized>(t: &T) -> u64 {
-    let mut s = DefaultHasher::new();
-    t.hash(&mut s);
-    s.finish()
+/// Safe mode is requested with `--safe-mode` (or the `LOG_VIEWER_SAFE_MODE` environment variable) natively
+/// and with `?safe_mode` in the URL on the web
+#[cfg(not(target_arch = "wasm32"))]
+fn is_safe_mode_requested(_cc: &eframe::CreationContext<'_>) -> bool {
+    std::env::args().any(|arg| arg == "--safe-mode")
+        || std::env::var_os("LOG_VIEWER_SAFE_MODE").is_some()
+}
+
+#[cfg(target_arch = "wasm32")]
+fn is_safe_mode_requested(cc: &eframe::CreationContext<'_>) -> bool {
+    cc.integration_info
+        .web_info
+        .location
+        .query_map
+        .contains_key("safe_mode")
+}
+
+/// Puts the view into the address bar (so reloading keeps it) and copies the resulting link
+#[cfg(target_arch = "wasm32")]
+fn copy_share_link(ctx: &egui::Context, fragment: &str) -> Result<String, String> {
+    let location = web_sys::window()
+        .ok_or("no window to get the address from")?
+        .location();
+    location
+        .set_hash(fragment)
+        .map_err(|e| format!("failed to set the address: {e:?}"))?;
+    let link = location
+        .href()
+        .map_err(|e| format!("failed to read the address: {e:?}"))?;
+    ctx.copy_text(link);
+    Ok("Copied link to this view".to_string())
 }
 
+const MINIMAP_WIDTH: f32 = 14.0;
+
+/// Time to wait after the last edit to the search key before applying it when filtering as you type
+const SEARCH_DEBOUNCE_SECS: f64 = 0.3;
+
+const TIME_GAP_COLOR: egui::Color32 = egui::Color32::from_rgb(230, 140, 0);
+
+/// Background of rows whose message template is rare (see [`MessageTemplates::is_rare`])
+const RARE_MESSAGE_COLOR: egui::Color32 = egui::Color32::from_rgba_premultiplied(60, 0, 60, 60);
+
+/// Field used when collapsing repeated rows is first turned on
+const DEFAULT_DUPLICATES_FIELD: &str = "msg";
+
+/// Field copied from the selected row until the user chooses another one
+const DEFAULT_COPY_KEY_FIELD: &str = "request_id";
+
+/// Number of lines used when a partial load range is first chosen
+const DEFAULT_PARTIAL_LINES: usize = 10_000;
+
+/// Number of lines rows are limited to when a limit is first chosen
+const DEFAULT_MAX_ROW_LINES: usize = 5;
+
 /// Returns true if the button is clicked or the shortcut is pressed
 ///
 /// Note: This makes it the case that the code for both the button and the shortcut press will do the same thing and you cannot use the shortcut to bypass the button not showing
@@ -823,7 +3781,7 @@ fn shortcut_button(
     ui.button(caption)
         .on_hover_text(shortcut_hint_text(ui, hint_msg, shortcut))
         .clicked()
-        || ui.input_mut(|i| i.consume_shortcut(shortcut))
+        || shortcut::consume(ui.ctx(), shortcut)
 }
 
 fn shortcut_hint_text(ui: &mut egui::Ui, hint_msg: &str, shortcut: &KeyboardShortcut) -> String {
@@ -831,6 +3789,18 @@ fn shortcut_hint_text(ui: &mut egui::Ui, hint_msg: &str, shortcut: &KeyboardShor
     format!("{hint_msg}{space}({})", ui.ctx().format_shortcut(shortcut))
 }
 
+/// Shortened to the largest units as gaps are only shown to give a sense of scale
+fn time_gap_text(gap: chrono::TimeDelta) -> String {
+    let seconds = gap.num_seconds();
+    if seconds < 60 {
+        format!("{:.1}s", gap.num_milliseconds() as f64 / 1000.0)
+    } else if seconds < 3600 {
+        format!("{}m {}s", seconds / 60, seconds % 60)
+    } else {
+        format!("{}h {}m", seconds / 3600, seconds % 3600 / 60)
+    }
+}
+
 fn as_string_with_separators(value: usize) -> String {
     value
         .to_string()
@@ -846,3 +3816,42 @@ fn as_string_with_separators(value: usize) -> String {
 fn clean_msg<S: AsRef<str>>(msg: S) -> String {
     msg.as_ref().replace(r"\n", "\n").replace(r#"\""#, "\"")
 }
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use super::*;
+    use data::OwnedLogRow;
+
+    #[test]
+    fn registered_row_action_in_context_menu() {
+        let mut app = LogViewerApp::default();
+        app.register_row_action(SimpleAction {
+            group: ActionGroup::Row,
+            label: |_| "Open in tracker".into(),
+            command: |c| {
+                let id = c.row.field_value("request_id").as_str()?;
+                Some(RowCommand::CopyText(format!("https://tracker/{id}")))
+            },
+        });
+        let row: OwnedLogRow =
+            serde_json::from_value(json!({ "data": {"request_id": "abc"} })).unwrap();
+        let context = MenuContext {
+            real_index: 0,
+            row: row.as_row(),
+            field_name: None,
+            display_options: &app.data_display_options,
+        };
+        let entries = app.context_menu.entries(&context);
+        let entry = entries
+            .iter()
+            .find(|x| x.label == "Open in tracker")
+            .expect("registered action should be listed");
+        assert_eq!(
+            entry.command,
+            RowCommand::CopyText("https://tracker/abc".into())
+        );
+    }
+}
@@ -0,0 +1,246 @@
+use std::collections::BTreeMap;
+
+use super::data::{Data, LogRow};
+
+/// Short notes attached to rows, stored per file so they can be seen again when the file is reloaded
+///
+/// Rows are identified by a hash of their content so notes stay attached even if lines are added to the file.
+/// Rows with identical content share a note.
+#[derive(serde::Deserialize, serde::Serialize, Default, Debug)]
+#[serde(default)]
+pub struct Annotations {
+    pub is_open: bool,
+    /// Notes keyed by the file they belong to then by the row's content hash
+    notes: BTreeMap<String, BTreeMap<u64, String>>,
+    /// Identifies the file currently loaded (None if there is no way to identify it)
+    file_key: Option<String>,
+
+    /// Maps indices into all rows to their note for the loaded data, rebuilt when the data or notes change
+    #[serde(skip)]
+    index: Option<BTreeMap<usize, String>>,
+    /// Row being edited (index into all rows) and the text of the note
+    #[serde(skip)]
+    edit: Option<(usize, String)>,
+}
+
+impl Annotations {
+    /// Should be called when new data is loaded, `file_key` identifies where the data came from
+    pub fn set_file_key(&mut self, file_key: Option<String>) {
+        self.file_key = file_key;
+        self.index = None;
+        self.edit = None;
+    }
+
+    /// Should be called when rows are added so the notes are matched to the new rows
+    pub fn invalidate(&mut self) {
+        self.index = None;
+    }
+
+    /// Should be called when the oldest `count` rows are dropped (notes are found again by content, only the row being edited needs moving)
+    pub fn on_rows_dropped(&mut self, count: usize) {
+        self.index = None;
+        self.edit = self
+            .edit
+            .take()
+            .and_then(|(real_index, text)| Some((real_index.checked_sub(count)?, text)));
+    }
+
+    /// Ensures the notes for the rows are available via [`Self::note`], should be called before drawing
+    ///
+    /// `row_idx_field_name` is excluded from the hash as it changes if lines are added before a row
+    pub fn refresh(&mut self, data: &Data, row_idx_field_name: Option<&str>) {
+        if self.index.is_some() {
+            return;
+        }
+        let notes = self.file_notes();
+        let index = if notes.is_empty() {
+            BTreeMap::new()
+        } else {
+            (0..data.total_len_unfiltered())
+                .filter_map(|i| {
+                    let row = data.row_by_real_index(i)?;
+                    let note = notes.get(&row.content_hash(row_idx_field_name))?;
+                    Some((i, note.clone()))
+                })
+                .collect()
+        };
+        self.index = Some(index);
+    }
+
+    /// The note for the row at `real_index` (index into all rows). Only available after [`Self::refresh`]
+    pub fn note(&self, real_index: usize) -> Option<&str> {
+        self.index.as_ref()?.get(&real_index).map(String::as_str)
+    }
+
+    /// Opens the window with the note of the row at `real_index` ready for editing
+    pub fn start_edit(&mut self, real_index: usize) {
+        let text = self.note(real_index).unwrap_or_default().to_string();
+        self.edit = Some((real_index, text));
+        self.is_open = true;
+    }
+
+    fn file_notes(&self) -> BTreeMap<u64, String> {
+        self.file_key
+            .as_ref()
+            .and_then(|key| self.notes.get(key))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Saves the note for the row (removes it if `text` is empty)
+    fn save_note(&mut self, row: LogRow<'_>, text: &str, row_idx_field_name: Option<&str>) {
+        let Some(file_key) = self.file_key.clone() else {
+            return;
+        };
+        let hash = row.content_hash(row_idx_field_name);
+        let file_notes = self.notes.entry(file_key.clone()).or_default();
+        if text.trim().is_empty() {
+            file_notes.remove(&hash);
+            if file_notes.is_empty() {
+                self.notes.remove(&file_key);
+            }
+        } else {
+            file_notes.insert(hash, text.trim().to_string());
+        }
+        self.index = None;
+    }
+
+    /// Shows the window if it is open. Returns the index into all rows of the row to select if one was clicked
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        data: Option<&Data>,
+        row_idx_field_name: Option<&str>,
+    ) -> Option<usize> {
+        let mut is_open = self.is_open;
+        let mut result = None;
+        egui::Window::new("Annotations")
+            .open(&mut is_open)
+            .show(ctx, |ui| result = self.ui(ui, data, row_idx_field_name));
+        self.is_open = is_open;
+        result
+    }
+
+    fn ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        data: Option<&Data>,
+        row_idx_field_name: Option<&str>,
+    ) -> Option<usize> {
+        let Some(data) = data else {
+            ui.label("No data");
+            return None;
+        };
+        if self.file_key.is_none() {
+            ui.label("Annotations are only available for data loaded from a file");
+            return None;
+        }
+        if let Some((real_index, mut text)) = self.edit.take() {
+            ui.label(format!("Note for row {real_index}"));
+            ui.text_edit_multiline(&mut text);
+            let mut is_done = false;
+            ui.horizontal(|ui| {
+                if ui.button("Save").clicked() {
+                    if let Some(row) = data.row_by_real_index(real_index) {
+                        self.save_note(row, &text, row_idx_field_name);
+                    }
+                    is_done = true;
+                }
+                if ui.button("Cancel").clicked() {
+                    is_done = true;
+                }
+            });
+            if !is_done {
+                self.edit = Some((real_index, text));
+            }
+            ui.separator();
+        }
+
+        self.refresh(data, row_idx_field_name);
+        let mut result = None;
+        let notes = self.index.as_ref().map(|x| x.iter()).into_iter().flatten();
+        let mut row_to_edit = None;
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            egui::Grid::new("annotations").striped(true).show(ui, |ui| {
+                let mut is_empty = true;
+                for (&real_index, note) in notes {
+                    is_empty = false;
+                    if ui
+                        .link(format!("Row {real_index}"))
+                        .on_hover_text("Jump to row")
+                        .clicked()
+                    {
+                        result = Some(real_index);
+                    }
+                    ui.label(note);
+                    if ui.small_button("✏").on_hover_text("Edit note").clicked() {
+                        row_to_edit = Some(real_index);
+                    }
+                    ui.end_row();
+                }
+                if is_empty {
+                    ui.label("No annotations for this file (right click on a row to add one)");
+                }
+            });
+        });
+        if let Some(real_index) = row_to_edit {
+            self.start_edit(real_index);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::super::data::OwnedLogRow;
+    use super::*;
+
+    fn row(fields: serde_json::Value) -> OwnedLogRow {
+        serde_json::from_value(json!({ "data": fields })).unwrap()
+    }
+
+    #[test]
+    fn hash_ignores_row_index() {
+        let a = row(json!({"row#": 1, "msg": "hi"}));
+        let b = row(json!({"row#": 5, "msg": "hi"}));
+        let c = row(json!({"row#": 1, "msg": "bye"}));
+        assert_eq!(
+            a.as_row().content_hash(Some("row#")),
+            b.as_row().content_hash(Some("row#"))
+        );
+        assert_ne!(
+            a.as_row().content_hash(Some("row#")),
+            c.as_row().content_hash(Some("row#"))
+        );
+        assert_ne!(a.as_row().content_hash(None), b.as_row().content_hash(None));
+    }
+
+    #[test]
+    fn notes_follow_row_content_across_reloads() {
+        let display_options = Default::default();
+        let mut annotations = Annotations::default();
+        annotations.set_file_key(Some("app.log".into()));
+        let data =
+            Data::try_from((&display_options, "{\"msg\": \"a\"}\n{\"msg\": \"b\"}")).unwrap();
+        let row = data.row_by_real_index(1).unwrap();
+        annotations.save_note(row, " interesting ", Some("row#"));
+
+        // Line added at the start so the row moves
+        annotations.set_file_key(Some("app.log".into()));
+        let data = Data::try_from((
+            &display_options,
+            "{\"msg\": \"new\"}\n{\"msg\": \"a\"}\n{\"msg\": \"b\"}",
+        ))
+        .unwrap();
+        annotations.refresh(&data, Some("row#"));
+        assert_eq!(annotations.note(2), Some("interesting"));
+        assert_eq!(annotations.note(1), None);
+
+        // Notes are per file
+        annotations.set_file_key(Some("other.log".into()));
+        annotations.refresh(&data, Some("row#"));
+        assert_eq!(annotations.note(2), None);
+    }
+}
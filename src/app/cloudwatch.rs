@@ -0,0 +1,365 @@
+use anyhow::{anyhow, Context};
+use aws_config::{BehaviorVersion, Region};
+use aws_sdk_cloudwatchlogs::{
+    error::DisplayErrorContext,
+    types::{FilteredLogEvent, OrderBy},
+    Client,
+};
+use poll_promise::Promise;
+
+use super::tasks::{execute, TaskHandle};
+
+/// Most recent streams listed to pick from
+const MAX_STREAMS_LISTED: i32 = 50;
+
+/// Loads events from AWS CloudWatch Logs (using the usual AWS config files, environment variables and credentials)
+#[derive(serde::Deserialize, serde::Serialize, Default)]
+#[serde(default)]
+pub struct CloudWatch {
+    pub is_open: bool,
+    pub query: CloudWatchQuery,
+
+    /// Log groups and streams to pick from, refreshed on request
+    #[serde(skip)]
+    log_groups: Vec<String>,
+    #[serde(skip)]
+    log_streams: Vec<String>,
+    #[serde(skip)]
+    pending_listing: Option<Promise<anyhow::Result<Listing>>>,
+    #[serde(skip)]
+    status_msg: Option<String>,
+}
+
+/// The events to load
+#[derive(serde::Deserialize, serde::Serialize, Default, Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct CloudWatchQuery {
+    /// If empty the default profile is used
+    pub profile: String,
+    /// If empty the region of the profile is used
+    pub region: String,
+    pub log_group: String,
+    /// If empty events from all streams in the group are loaded
+    pub log_stream: String,
+    /// RFC 3339 timestamps, an empty end means up to now
+    pub start_time: String,
+    pub end_time: String,
+    /// CloudWatch filter pattern syntax, empty loads all events
+    pub filter_pattern: String,
+}
+
+#[derive(Debug)]
+enum Listing {
+    Groups(Vec<String>),
+    Streams(Vec<String>),
+}
+
+impl CloudWatchQuery {
+    /// Start and end (if any) as milliseconds since the epoch as used by the API
+    fn time_range(&self) -> anyhow::Result<(i64, Option<i64>)> {
+        let start = epoch_millis(&self.start_time).context("invalid start time")?;
+        let end = if self.end_time.trim().is_empty() {
+            None
+        } else {
+            Some(epoch_millis(&self.end_time).context("invalid end time")?)
+        };
+        Ok((start, end))
+    }
+
+    /// Identifies the events loaded (used like a file name)
+    pub fn description(&self) -> String {
+        if self.log_stream.trim().is_empty() {
+            self.log_group.trim().to_string()
+        } else {
+            format!("{}/{}", self.log_group.trim(), self.log_stream.trim())
+        }
+    }
+
+    async fn client(&self) -> Client {
+        let mut loader = aws_config::defaults(BehaviorVersion::latest());
+        if !self.profile.trim().is_empty() {
+            loader = loader.profile_name(self.profile.trim());
+        }
+        if !self.region.trim().is_empty() {
+            loader = loader.region(Region::new(self.region.trim().to_string()));
+        }
+        Client::new(&loader.load().await)
+    }
+}
+
+fn epoch_millis(rfc3339: &str) -> anyhow::Result<i64> {
+    let time = chrono::DateTime::parse_from_rfc3339(rfc3339.trim())
+        .context("expected an RFC 3339 timestamp (eg. 2024-02-10T03:10:25Z)")?;
+    Ok(time.timestamp_millis())
+}
+
+/// The SDK's errors only include the details (eg. the message from AWS) in their sources
+fn sdk_error(e: impl std::error::Error) -> anyhow::Error {
+    anyhow!("{}", DisplayErrorContext(e))
+}
+
+/// One line per event so JSON messages are parsed like lines of a file
+fn events_text(events: &[FilteredLogEvent]) -> String {
+    let mut result = String::new();
+    for message in events.iter().filter_map(|x| x.message()) {
+        // Messages often end with the newline the application logged
+        result.push_str(message.trim_end_matches(['\r', '\n']));
+        result.push('\n');
+    }
+    result
+}
+
+/// Fetches all pages of events matching the query, stops early if the task is cancelled
+pub async fn fetch_events(query: &CloudWatchQuery, handle: &TaskHandle) -> anyhow::Result<String> {
+    let (start, end) = query.time_range()?;
+    let log_stream = query.log_stream.trim();
+    let filter_pattern = query.filter_pattern.trim();
+    let mut pages = query
+        .client()
+        .await
+        .filter_log_events()
+        .log_group_name(query.log_group.trim())
+        .start_time(start)
+        .set_end_time(end)
+        .set_log_stream_names((!log_stream.is_empty()).then(|| vec![log_stream.to_string()]))
+        .set_filter_pattern((!filter_pattern.is_empty()).then(|| filter_pattern.to_string()))
+        .into_paginator()
+        .send();
+    let mut events = Vec::new();
+    while let Some(page) = pages.next().await {
+        if handle.is_cancel_requested() {
+            break;
+        }
+        events.extend(page.map_err(sdk_error)?.events.unwrap_or_default());
+    }
+    Ok(events_text(&events))
+}
+
+async fn list_log_groups(query: &CloudWatchQuery) -> anyhow::Result<Vec<String>> {
+    let mut pages = query
+        .client()
+        .await
+        .describe_log_groups()
+        .into_paginator()
+        .send();
+    let mut result = Vec::new();
+    while let Some(page) = pages.next().await {
+        let page = page.map_err(sdk_error)?;
+        result.extend(
+            page.log_groups()
+                .iter()
+                .filter_map(|x| x.log_group_name().map(String::from)),
+        );
+    }
+    Ok(result)
+}
+
+async fn list_log_streams(query: &CloudWatchQuery) -> anyhow::Result<Vec<String>> {
+    let output = query
+        .client()
+        .await
+        .describe_log_streams()
+        .log_group_name(query.log_group.trim())
+        .order_by(OrderBy::LastEventTime)
+        .descending(true)
+        .limit(MAX_STREAMS_LISTED)
+        .send()
+        .await
+        .map_err(sdk_error)?;
+    Ok(output
+        .log_streams()
+        .iter()
+        .filter_map(|x| x.log_stream_name().map(String::from))
+        .collect())
+}
+
+impl CloudWatch {
+    /// Shows the window if it is open. Returns the query to load when requested
+    pub fn show(&mut self, ctx: &egui::Context) -> Option<CloudWatchQuery> {
+        self.receive_listing();
+        let mut is_open = self.is_open;
+        let mut result = None;
+        egui::Window::new("CloudWatch Logs")
+            .open(&mut is_open)
+            .show(ctx, |ui| result = self.ui(ui));
+        self.is_open = is_open;
+        result
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui) -> Option<CloudWatchQuery> {
+        let is_listing = self.pending_listing.is_some();
+        let mut is_groups_requested = false;
+        let mut is_streams_requested = false;
+        let CloudWatchQuery {
+            profile,
+            region,
+            log_group,
+            log_stream,
+            start_time,
+            end_time,
+            filter_pattern,
+        } = &mut self.query;
+        egui::Grid::new("cloudwatch query").show(ui, |ui| {
+            ui.label("Profile");
+            ui.add(egui::TextEdit::singleline(profile).hint_text("(default)"));
+            ui.end_row();
+            ui.label("Region");
+            ui.add(egui::TextEdit::singleline(region).hint_text("(from profile)"));
+            ui.end_row();
+            ui.label("Log group");
+            ui.horizontal(|ui| {
+                ui.text_edit_singleline(log_group);
+                name_picker_ui(ui, "cloudwatch groups", log_group, &self.log_groups);
+                is_groups_requested = ui
+                    .add_enabled(!is_listing, egui::Button::new("⟳"))
+                    .on_hover_text("List log groups")
+                    .clicked();
+            });
+            ui.end_row();
+            ui.label("Log stream");
+            ui.horizontal(|ui| {
+                ui.add(egui::TextEdit::singleline(log_stream).hint_text("(all streams)"));
+                name_picker_ui(ui, "cloudwatch streams", log_stream, &self.log_streams);
+                is_streams_requested = ui
+                    .add_enabled(
+                        !is_listing && !log_group.trim().is_empty(),
+                        egui::Button::new("⟳"),
+                    )
+                    .on_hover_text("List the most recent streams of the log group")
+                    .clicked();
+            });
+            ui.end_row();
+            ui.label("Start time");
+            ui.add(egui::TextEdit::singleline(start_time).hint_text("2024-02-10T03:00:00Z"));
+            ui.end_row();
+            ui.label("End time");
+            ui.add(egui::TextEdit::singleline(end_time).hint_text("(now)"));
+            ui.end_row();
+            ui.label("Filter pattern");
+            ui.add(egui::TextEdit::singleline(filter_pattern).hint_text("(all events)"));
+            ui.end_row();
+        });
+        if is_groups_requested || is_streams_requested {
+            self.start_listing(is_groups_requested, ui.ctx().clone());
+        }
+
+        let mut result = None;
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(
+                    !self.query.log_group.trim().is_empty()
+                        && !self.query.start_time.trim().is_empty(),
+                    egui::Button::new("Load Events"),
+                )
+                .clicked()
+            {
+                result = Some(self.query.clone());
+            }
+            if self.pending_listing.is_some() {
+                ui.spinner();
+            }
+        });
+        if let Some(msg) = self.status_msg.as_ref() {
+            ui.label(msg);
+        }
+        result
+    }
+
+    /// Lists the log groups (or the streams of the log group) in the background
+    fn start_listing(&mut self, is_groups: bool, ctx: egui::Context) {
+        let query = self.query.clone();
+        self.pending_listing = Some(execute(async move {
+            let result = if is_groups {
+                list_log_groups(&query).await.map(Listing::Groups)
+            } else {
+                list_log_streams(&query).await.map(Listing::Streams)
+            };
+            ctx.request_repaint();
+            result
+        }));
+        self.status_msg = None;
+    }
+
+    fn receive_listing(&mut self) {
+        let Some(promise) = self.pending_listing.take() else {
+            return;
+        };
+        let result = match promise.try_take() {
+            Ok(result) => result,
+            Err(promise) => {
+                self.pending_listing = Some(promise);
+                return;
+            }
+        };
+        match result {
+            Ok(Listing::Groups(groups)) => {
+                self.status_msg = Some(format!("Found {} log groups", groups.len()));
+                self.log_groups = groups;
+            }
+            Ok(Listing::Streams(streams)) => {
+                self.status_msg = Some(format!("Found {} log streams", streams.len()));
+                self.log_streams = streams;
+            }
+            Err(e) => self.status_msg = Some(format!("{e:#}")),
+        }
+    }
+}
+
+/// Drop down to pick one of the listed names (hidden until names are listed)
+fn name_picker_ui(ui: &mut egui::Ui, id: &str, value: &mut String, names: &[String]) {
+    if names.is_empty() {
+        return;
+    }
+    egui::ComboBox::from_id_salt(id)
+        .selected_text("")
+        .width(20.0)
+        .show_ui(ui, |ui| {
+            for name in names {
+                ui.selectable_value(value, name.clone(), name);
+            }
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn time_range_read_from_query() {
+        let query = CloudWatchQuery {
+            start_time: "1970-01-01T00:00:01Z".into(),
+            end_time: "1970-01-01T01:00:00+01:00".into(),
+            ..Default::default()
+        };
+        assert_eq!(query.time_range().unwrap(), (1000, Some(0)));
+        let open_ended = CloudWatchQuery {
+            end_time: String::new(),
+            ..query.clone()
+        };
+        assert_eq!(open_ended.time_range().unwrap(), (1000, None));
+        let invalid = CloudWatchQuery {
+            start_time: "yesterday".into(),
+            ..query
+        };
+        assert!(invalid.time_range().is_err());
+    }
+
+    #[test]
+    fn events_become_lines() {
+        let events = [
+            FilteredLogEvent::builder()
+                .timestamp(1)
+                .message("{\"msg\": \"a\"}\n")
+                .build(),
+            FilteredLogEvent::builder().timestamp(2).build(),
+            FilteredLogEvent::builder()
+                .timestamp(3)
+                .message("plain text")
+                .build(),
+        ];
+        assert_eq!(events_text(&events), "{\"msg\": \"a\"}\nplain text\n");
+        assert_eq!(events_text(&[]), "");
+    }
+}
@@ -0,0 +1,161 @@
+use std::collections::BTreeMap;
+
+use egui::Color32;
+
+use super::data_display_options::{ColorCondition, ColorRule};
+
+/// Color used for a new value until the user picks one
+const DEFAULT_NEW_COLOR: Color32 = Color32::LIGHT_BLUE;
+
+/// Editor for [`super::data_display_options::DataDisplayOptions::colored_fields`] and
+/// [`super::data_display_options::DataDisplayOptions::color_rules`]
+#[derive(Default, Debug)]
+pub struct ColoredFieldsUi {
+    new_field_name: String,
+    new_rule_field_name: String,
+    /// Text typed in for the value to add to each field
+    new_values: BTreeMap<String, String>,
+}
+
+impl ColoredFieldsUi {
+    pub fn ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        colored_fields: &mut BTreeMap<String, BTreeMap<String, Color32>>,
+    ) {
+        let mut field_to_remove = None;
+        for (field_name, value_colors) in colored_fields.iter_mut() {
+            egui::CollapsingHeader::new(field_name)
+                .id_salt(("colored field", field_name))
+                .show(ui, |ui| {
+                    let mut value_to_remove = None;
+                    egui::Grid::new(("colored values", field_name)).show(ui, |ui| {
+                        for (value, color) in value_colors.iter_mut() {
+                            ui.color_edit_button_srgba(color);
+                            ui.colored_label(*color, value);
+                            if ui.small_button("🗑").on_hover_text("Remove value").clicked() {
+                                value_to_remove = Some(value.clone());
+                            }
+                            ui.end_row();
+                        }
+                    });
+                    if let Some(value) = value_to_remove {
+                        value_colors.remove(&value);
+                    }
+                    ui.horizontal(|ui| {
+                        let new_value = self.new_values.entry(field_name.clone()).or_default();
+                        ui.add(egui::TextEdit::singleline(new_value).hint_text("Value"));
+                        if ui.button("Add Value").clicked() && !new_value.is_empty() {
+                            value_colors
+                                .entry(std::mem::take(new_value))
+                                .or_insert(DEFAULT_NEW_COLOR);
+                        }
+                    });
+                    if ui.button("Remove Field").clicked() {
+                        field_to_remove = Some(field_name.clone());
+                    }
+                });
+        }
+        if let Some(field_name) = field_to_remove {
+            colored_fields.remove(&field_name);
+            self.new_values.remove(&field_name);
+        }
+
+        ui.horizontal(|ui| {
+            ui.add(egui::TextEdit::singleline(&mut self.new_field_name).hint_text("Field name"));
+            if ui.button("Add Colored Field").clicked() && !self.new_field_name.is_empty() {
+                colored_fields
+                    .entry(std::mem::take(&mut self.new_field_name))
+                    .or_default();
+            }
+        });
+    }
+
+    pub fn rules_ui(&mut self, ui: &mut egui::Ui, color_rules: &mut Vec<ColorRule>) {
+        let mut rule_to_remove = None;
+        let mut rule_to_raise = None;
+        egui::Grid::new("color rules").show(ui, |ui| {
+            for (i, rule) in color_rules.iter_mut().enumerate() {
+                ui.color_edit_button_srgba(&mut rule.color);
+                ui.add(
+                    egui::TextEdit::singleline(&mut rule.field_name)
+                        .hint_text("Field name")
+                        .desired_width(100.0),
+                );
+                let mut is_regex = matches!(rule.condition, ColorCondition::Regex(_));
+                ui.horizontal(|ui| {
+                    let mut is_changed = false;
+                    if ui.selectable_value(&mut is_regex, true, "Regex").clicked() {
+                        rule.condition = ColorCondition::Regex(String::new());
+                        is_changed = true;
+                    }
+                    if ui.selectable_value(&mut is_regex, false, "Range").clicked() {
+                        rule.condition = ColorCondition::Range {
+                            min: None,
+                            max: None,
+                        };
+                        is_changed = true;
+                    }
+                    match &mut rule.condition {
+                        ColorCondition::Regex(pattern) => {
+                            is_changed |= ui
+                                .add(egui::TextEdit::singleline(pattern).hint_text(r"eg. ^5\d\d$"))
+                                .changed();
+                        }
+                        ColorCondition::Range { min, max } => {
+                            bound_ui(ui, "min", min);
+                            bound_ui(ui, "max", max);
+                        }
+                    }
+                    if is_changed {
+                        rule.invalidate();
+                    }
+                    if let Some(error) = rule.error() {
+                        ui.colored_label(ui.visuals().error_fg_color, "⚠")
+                            .on_hover_text(error);
+                    }
+                });
+                if ui
+                    .add_enabled(i > 0, egui::Button::new("⏶").small())
+                    .on_hover_text("Check this rule earlier")
+                    .clicked()
+                {
+                    rule_to_raise = Some(i);
+                }
+                if ui.small_button("🗑").on_hover_text("Remove rule").clicked() {
+                    rule_to_remove = Some(i);
+                }
+                ui.end_row();
+            }
+        });
+        if let Some(i) = rule_to_raise {
+            color_rules.swap(i - 1, i);
+        }
+        if let Some(i) = rule_to_remove {
+            color_rules.remove(i);
+        }
+
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.new_rule_field_name).hint_text("Field name"),
+            );
+            if ui.button("Add Rule").clicked() && !self.new_rule_field_name.is_empty() {
+                color_rules.push(ColorRule::new(
+                    std::mem::take(&mut self.new_rule_field_name),
+                    ColorCondition::Regex(String::new()),
+                ));
+            }
+        });
+    }
+}
+
+/// An unset bound is shown as a checkbox that can be ticked to set it
+fn bound_ui(ui: &mut egui::Ui, label: &str, bound: &mut Option<i64>) {
+    let mut is_set = bound.is_some();
+    if ui.checkbox(&mut is_set, label).changed() {
+        *bound = is_set.then_some(0);
+    }
+    if let Some(value) = bound {
+        ui.add(egui::DragValue::new(value));
+    }
+}
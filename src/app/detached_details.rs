@@ -0,0 +1,114 @@
+use std::collections::BTreeSet;
+
+use super::{
+    data::{Data, FieldContent},
+    data_display_options::DataDisplayOptions,
+};
+
+/// Details of rows shown in their own viewports so they stay visible while other rows are selected
+#[derive(Default, Debug)]
+pub struct DetachedDetails {
+    /// Indices into all rows
+    rows: BTreeSet<usize>,
+}
+
+impl DetachedDetails {
+    /// Should be called when new data is loaded as the rows shown are no longer valid
+    pub fn invalidate(&mut self) {
+        self.rows.clear();
+    }
+
+    /// Should be called when the oldest `count` rows are dropped, details of dropped rows are closed
+    pub fn on_rows_dropped(&mut self, count: usize) {
+        self.rows = self
+            .rows
+            .iter()
+            .filter_map(|x| x.checked_sub(count))
+            .collect();
+    }
+
+    pub fn open(&mut self, real_index: usize) {
+        self.rows.insert(real_index);
+    }
+
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        data: Option<&Data>,
+        display_options: &DataDisplayOptions,
+    ) {
+        let Some(data) = data else {
+            return;
+        };
+        let mut closed = Vec::new();
+        for &real_index in self.rows.iter() {
+            let title = format!("Row {real_index}");
+            let is_open = ctx.show_viewport_immediate(
+                egui::ViewportId::from_hash_of(("detached details", real_index)),
+                egui::ViewportBuilder::default()
+                    .with_title(&title)
+                    .with_inner_size([450.0, 500.0]),
+                |ctx, class| {
+                    if class == egui::ViewportClass::Embedded {
+                        // Multiple viewports not supported by the backend (eg. web) so shown as a window instead
+                        let mut is_open = true;
+                        egui::Window::new(&title)
+                            .id(egui::Id::new(("detached details", real_index)))
+                            .open(&mut is_open)
+                            .show(ctx, |ui| {
+                                row_details_ui(ui, data, real_index, display_options)
+                            });
+                        is_open
+                    } else {
+                        egui::CentralPanel::default().show(ctx, |ui| {
+                            row_details_ui(ui, data, real_index, display_options)
+                        });
+                        !ctx.input(|i| i.viewport().close_requested())
+                    }
+                },
+            );
+            if !is_open {
+                closed.push(real_index);
+            }
+        }
+        for real_index in closed {
+            self.rows.remove(&real_index);
+        }
+    }
+}
+
+fn row_details_ui(
+    ui: &mut egui::Ui,
+    data: &Data,
+    real_index: usize,
+    display_options: &DataDisplayOptions,
+) {
+    let Some(row) = data.row_by_real_index(real_index) else {
+        ui.label("Row no longer available");
+        return;
+    };
+    let fields: Vec<(&str, String)> = row
+        .fields()
+        .map(|(name, value)| (name, FieldContent::Present(value).display()))
+        .collect();
+    let display_order = display_options.details_display_order(fields.iter().map(|(name, _)| *name));
+    egui::ScrollArea::both().show(ui, |ui| {
+        egui::Grid::new(("detached details grid", real_index))
+            .striped(true)
+            .show(ui, |ui| {
+                for i in display_order {
+                    let (name, value) = &fields[i];
+                    ui.strong(*name);
+                    if ui.small_button("📋").on_hover_text("Copy value").clicked() {
+                        ui.ctx().copy_text(value.clone());
+                    }
+                    let formatted = display_options.formatted_value(name, value);
+                    let response = ui.label(formatted.as_ref());
+                    if formatted != value.as_str() {
+                        response.on_hover_text(value);
+                    }
+                    ui.end_row();
+                }
+            });
+    });
+}
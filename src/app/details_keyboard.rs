@@ -0,0 +1,133 @@
+use egui::{Key, KeyboardShortcut, Modifiers};
+
+use super::shortcut;
+
+/// Keyboard focus inside the details pane so a row can be inspected without the mouse.
+///
+/// While active the arrow keys move between fields instead of rows. Keys are captured before the rest of the UI
+/// (see [`Self::capture_keys`]) and applied when the details are drawn as only then the number of fields is known
+#[derive(Debug, Default)]
+pub struct DetailsKeyboard {
+    /// Position in the displayed fields of the field with focus, None when focus is not in the details
+    focused: Option<usize>,
+    /// Change to the focused position requested since the details were last drawn
+    pending_move: isize,
+    pending_action: Option<DetailsKeyAction>,
+    /// Set when the focused field changed so it can be scrolled into view
+    should_scroll: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DetailsKeyAction {
+    /// Expand or collapse the focused value
+    Expand,
+    Copy,
+}
+
+impl DetailsKeyboard {
+    pub fn is_active(&self) -> bool {
+        self.focused.is_some()
+    }
+
+    /// Enters or leaves the details and, while focus is in the details, takes the keys used to move between fields
+    pub fn capture_keys(&mut self, ctx: &egui::Context, focus_shortcut: &KeyboardShortcut) {
+        let is_tab = |ctx: &egui::Context| {
+            // Only when no widget has focus so Tab still moves between widgets as usual
+            ctx.memory(|mem| mem.focused().is_none())
+                && shortcut::consume(ctx, &KeyboardShortcut::new(Modifiers::NONE, Key::Tab))
+        };
+        if !self.is_active() {
+            if shortcut::consume(ctx, focus_shortcut) || is_tab(ctx) {
+                ctx.memory_mut(|mem| {
+                    if let Some(id) = mem.focused() {
+                        mem.surrender_focus(id);
+                    }
+                });
+                self.focused = Some(0);
+                self.should_scroll = true;
+            }
+            return;
+        }
+        if ctx.wants_keyboard_input() {
+            // Focus was given to a text edit (eg. by clicking on it)
+            self.focused = None;
+            return;
+        }
+        let consume = |key| shortcut::consume(ctx, &KeyboardShortcut::new(Modifiers::NONE, key));
+        if consume(Key::Escape) || shortcut::consume(ctx, focus_shortcut) || is_tab(ctx) {
+            self.focused = None;
+            return;
+        }
+        if consume(Key::ArrowUp) {
+            self.pending_move -= 1;
+        }
+        if consume(Key::ArrowDown) {
+            self.pending_move += 1;
+        }
+        if consume(Key::Enter) {
+            self.pending_action = Some(DetailsKeyAction::Expand);
+        }
+        // Ctrl+C arrives as a copy event instead of a key press
+        let is_copy = ctx.input_mut(|i| {
+            let position = i
+                .events
+                .iter()
+                .position(|event| matches!(event, egui::Event::Copy));
+            position.map(|x| i.events.remove(x)).is_some()
+        });
+        if is_copy {
+            self.pending_action = Some(DetailsKeyAction::Copy);
+        }
+    }
+
+    /// Applies the keys captured since the last call given the number of fields shown.
+    /// Returns the focused position and the action to perform on it if any
+    pub fn update(&mut self, field_count: usize) -> (Option<usize>, Option<DetailsKeyAction>) {
+        let pending_move = std::mem::take(&mut self.pending_move);
+        let action = self.pending_action.take();
+        let Some(focused) = self.focused.as_mut() else {
+            return (None, None);
+        };
+        if field_count == 0 {
+            return (None, None);
+        }
+        let moved = focused
+            .saturating_add_signed(pending_move)
+            .min(field_count - 1);
+        if moved != *focused || pending_move != 0 {
+            self.should_scroll = true;
+        }
+        *focused = moved;
+        (Some(moved), action)
+    }
+
+    /// Returns true once each time the focused field changes
+    pub fn take_should_scroll(&mut self) -> bool {
+        std::mem::take(&mut self.should_scroll)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn moves_stay_within_fields() {
+        let mut keyboard = DetailsKeyboard::default();
+        assert_eq!(keyboard.update(5), (None, None));
+
+        keyboard.focused = Some(0);
+        keyboard.pending_move = -1;
+        assert_eq!(keyboard.update(5), (Some(0), None));
+        keyboard.pending_move = 3;
+        keyboard.pending_action = Some(DetailsKeyAction::Copy);
+        assert_eq!(keyboard.update(5), (Some(3), Some(DetailsKeyAction::Copy)));
+        keyboard.pending_move = 10;
+        assert_eq!(keyboard.update(5), (Some(4), None));
+
+        // Fewer fields shown after the details filter changed
+        assert_eq!(keyboard.update(2), (Some(1), None));
+    }
+}
@@ -0,0 +1,85 @@
+use crate::core::display_profiles::DisplayProfiles;
+
+/// Requests that need the rest of the app's state to carry out
+#[derive(Debug, PartialEq, Eq)]
+pub enum ProfileAction {
+    /// Save the current options under the name
+    Save(String),
+    /// Switch to the options of the profile
+    Use(String),
+}
+
+/// Editor for the display profiles in the options
+#[derive(Default, Debug)]
+pub struct DisplayProfilesUi {
+    new_name: String,
+}
+
+impl DisplayProfilesUi {
+    pub fn ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        profiles: &mut DisplayProfiles,
+        active_profile: Option<&str>,
+    ) -> Option<ProfileAction> {
+        let mut result = None;
+        ui.checkbox(
+            &mut profiles.is_auto_select_enabled,
+            "Pick the profile matching each file loaded",
+        )
+        .on_hover_text(
+            "The first profile whose patterns match the file name and start of the file is used",
+        );
+        if let Some(name) = active_profile {
+            ui.label(format!("Current profile: {name}"));
+        }
+        let mut index_to_remove = None;
+        for (i, profile) in profiles.profiles.iter_mut().enumerate() {
+            egui::CollapsingHeader::new(&profile.name)
+                .id_salt(("display profile", i))
+                .show(ui, |ui| {
+                    egui::Grid::new(("display profile patterns", i)).show(ui, |ui| {
+                        ui.label("File name pattern:")
+                            .on_hover_text("Regex, ignored if empty");
+                        ui.text_edit_singleline(&mut profile.file_name_pattern);
+                        ui.end_row();
+                        ui.label("Content pattern:").on_hover_text(
+                            "Regex matched against the start of the file (eg. \"v\":0 for bunyan), ignored if empty",
+                        );
+                        ui.text_edit_singleline(&mut profile.content_pattern);
+                        ui.end_row();
+                    });
+                    ui.horizontal(|ui| {
+                        if ui.button("Use Now").clicked() {
+                            result = Some(ProfileAction::Use(profile.name.clone()));
+                        }
+                        if ui
+                            .button("Update from Current")
+                            .on_hover_text("Replaces the profile's options with the current options")
+                            .clicked()
+                        {
+                            result = Some(ProfileAction::Save(profile.name.clone()));
+                        }
+                        if ui.button("🗑").on_hover_text("Remove profile").clicked() {
+                            index_to_remove = Some(i);
+                        }
+                    });
+                });
+        }
+        if let Some(i) = index_to_remove {
+            profiles.profiles.remove(i);
+        }
+        ui.horizontal(|ui| {
+            ui.add(egui::TextEdit::singleline(&mut self.new_name).hint_text("Profile name"));
+            let name = self.new_name.trim();
+            if ui
+                .add_enabled(!name.is_empty(), egui::Button::new("Save Current Options"))
+                .clicked()
+            {
+                result = Some(ProfileAction::Save(name.to_string()));
+                self.new_name.clear();
+            }
+        });
+        result
+    }
+}
@@ -0,0 +1,163 @@
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use anyhow::{bail, Context};
+
+use super::{data::LogRow, paths};
+
+/// Placeholder in the command replaced by the path of the file to open
+const FILE_PLACEHOLDER: &str = "{file}";
+/// Placeholder in the command replaced by the line number (starting from 1)
+const LINE_PLACEHOLDER: &str = "{line}";
+
+/// Opens files at a line in an editor chosen by the user (eg. to see the raw line of the selected row)
+#[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct ExternalEditor {
+    /// Program followed by its arguments separated by spaces (placeholders are replaced in each argument)
+    pub command: String,
+    /// Field with the path of the source file that logged the row (eg. from tracing)
+    pub file_field_name: String,
+    pub line_field_name: String,
+    /// Relative source paths are resolved from this folder (the working directory if empty)
+    pub source_root: String,
+}
+
+impl Default for ExternalEditor {
+    fn default() -> Self {
+        Self {
+            command: format!("code -g {FILE_PLACEHOLDER}:{LINE_PLACEHOLDER}"),
+            file_field_name: "file".into(),
+            line_field_name: "line".into(),
+            source_root: Default::default(),
+        }
+    }
+}
+
+impl ExternalEditor {
+    /// Starts the editor without waiting for it to exit
+    pub fn open(&self, file: &Path, line: usize) -> anyhow::Result<()> {
+        let (program, args) = self.program_and_args(file, line)?;
+        Command::new(&program)
+            .args(args)
+            .spawn()
+            .with_context(|| format!("failed to start editor {program:?}"))?;
+        Ok(())
+    }
+
+    /// The source file and line that logged the row, None if the row has no file field.
+    /// Line 1 is used if the row has no valid line
+    pub fn source_location(&self, row: LogRow<'_>) -> Option<(PathBuf, usize)> {
+        let file = row.field_value(&self.file_field_name).as_str()?;
+        let line = row
+            .field_value(&self.line_field_name)
+            .as_u64()
+            .map_or(1, |x| x as usize);
+        let file = Path::new(file);
+        let path = if file.is_absolute() || self.source_root.trim().is_empty() {
+            paths::expand(file)
+        } else {
+            paths::expand(self.source_root.trim().as_ref()).join(file)
+        };
+        Some((path, line))
+    }
+
+    /// The command split into the program and its arguments with the placeholders replaced.
+    /// Splitting happens before replacing so paths with spaces stay a single argument
+    fn program_and_args(&self, file: &Path, line: usize) -> anyhow::Result<(String, Vec<String>)> {
+        let file = file.to_string_lossy();
+        let line = line.to_string();
+        let mut parts = self.command.split_whitespace().map(|part| {
+            part.replace(FILE_PLACEHOLDER, &file)
+                .replace(LINE_PLACEHOLDER, &line)
+        });
+        let Some(program) = parts.next() else {
+            bail!("no editor command set");
+        };
+        Ok((program, parts.collect()))
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Editor command:");
+            ui.text_edit_singleline(&mut self.command).on_hover_text(format!(
+                "Used to open files in an editor. {FILE_PLACEHOLDER} and {LINE_PLACEHOLDER} are replaced by the file and line number"
+            ));
+            if ui.button("Default").clicked() {
+                self.command = Self::default().command;
+            }
+        });
+        egui::Grid::new("source location fields").show(ui, |ui| {
+            ui.label("Source file field");
+            ui.text_edit_singleline(&mut self.file_field_name);
+            ui.end_row();
+            ui.label("Source line field");
+            ui.text_edit_singleline(&mut self.line_field_name);
+            ui.end_row();
+            ui.label("Source root");
+            ui.text_edit_singleline(&mut self.source_root)
+                .on_hover_text("Folder relative source file paths are resolved from (eg. the root of the repository)");
+            ui.end_row();
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use super::super::data::OwnedLogRow;
+    use super::*;
+
+    #[test]
+    fn placeholders_replaced_in_each_argument() {
+        let editor = ExternalEditor::default();
+        let (program, args) = editor
+            .program_and_args(Path::new("/tmp/my logs/a.log"), 12)
+            .unwrap();
+        assert_eq!(program, "code");
+        assert_eq!(args, vec!["-g", "/tmp/my logs/a.log:12"]);
+
+        let editor = ExternalEditor {
+            command: "vim +{line} {file}".into(),
+            ..Default::default()
+        };
+        let (program, args) = editor.program_and_args(Path::new("a.log"), 3).unwrap();
+        assert_eq!(program, "vim");
+        assert_eq!(args, vec!["+3", "a.log"]);
+
+        let editor = ExternalEditor {
+            command: "  ".into(),
+            ..Default::default()
+        };
+        assert!(editor.program_and_args(Path::new("a.log"), 3).is_err());
+    }
+
+    #[test]
+    fn source_location_from_fields() {
+        let editor = ExternalEditor {
+            source_root: "/repo".into(),
+            ..Default::default()
+        };
+        let row = |value| serde_json::from_value::<OwnedLogRow>(json!({ "data": value })).unwrap();
+        assert_eq!(
+            editor.source_location(row(json!({"file": "src/main.rs", "line": 42})).as_row()),
+            Some((PathBuf::from("/repo/src/main.rs"), 42))
+        );
+        assert_eq!(
+            editor.source_location(row(json!({"file": "/abs/lib.rs", "line": "7"})).as_row()),
+            Some((PathBuf::from("/abs/lib.rs"), 7))
+        );
+        assert_eq!(
+            editor.source_location(row(json!({"file": "src/main.rs"})).as_row()),
+            Some((PathBuf::from("/repo/src/main.rs"), 1))
+        );
+        assert_eq!(
+            editor.source_location(row(json!({"line": 3})).as_row()),
+            None
+        );
+    }
+}
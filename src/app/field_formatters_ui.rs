@@ -0,0 +1,66 @@
+use std::collections::BTreeMap;
+
+use super::data_display_options::FieldFormatter;
+
+/// Editor for [`super::data_display_options::DataDisplayOptions::field_formatters`]
+#[derive(Default, Debug)]
+pub struct FieldFormattersUi {
+    new_field_name: String,
+}
+
+impl FieldFormattersUi {
+    pub fn ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        field_formatters: &mut BTreeMap<String, FieldFormatter>,
+    ) {
+        let mut field_to_remove = None;
+        egui::Grid::new("field formatters").show(ui, |ui| {
+            for (field_name, formatter) in field_formatters.iter_mut() {
+                ui.label(field_name);
+                egui::ComboBox::from_id_salt(("field formatter", field_name))
+                    .selected_text(formatter.label())
+                    .show_ui(ui, |ui| {
+                        for option in FieldFormatter::ALL {
+                            let is_selected = std::mem::discriminant(formatter)
+                                == std::mem::discriminant(&option);
+                            if ui.selectable_label(is_selected, option.label()).clicked()
+                                && !is_selected
+                            {
+                                *formatter = option;
+                            }
+                        }
+                    });
+                if let FieldFormatter::Truncate(max_chars) = formatter {
+                    ui.add(
+                        egui::DragValue::new(max_chars)
+                            .range(1..=usize::MAX)
+                            .suffix(" chars"),
+                    );
+                } else {
+                    ui.label("");
+                }
+                if ui
+                    .small_button("🗑")
+                    .on_hover_text("Remove formatter")
+                    .clicked()
+                {
+                    field_to_remove = Some(field_name.clone());
+                }
+                ui.end_row();
+            }
+        });
+        if let Some(field_name) = field_to_remove {
+            field_formatters.remove(&field_name);
+        }
+
+        ui.horizontal(|ui| {
+            ui.add(egui::TextEdit::singleline(&mut self.new_field_name).hint_text("Field name"));
+            if ui.button("Add Formatter").clicked() && !self.new_field_name.is_empty() {
+                field_formatters
+                    .entry(std::mem::take(&mut self.new_field_name))
+                    .or_insert(FieldFormatter::ALL[0]);
+            }
+        });
+    }
+}
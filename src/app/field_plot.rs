@@ -0,0 +1,215 @@
+use egui_plot::{Plot, PlotPoint, Points};
+
+use super::data::Data;
+
+/// Max distance in points from a marker for a click to select its row
+const CLICK_RADIUS: f32 = 10.0;
+
+/// Chart of a numeric field against time for the visible rows
+#[derive(serde::Deserialize, serde::Serialize, Debug)]
+#[serde(default)]
+pub struct FieldPlot {
+    pub is_open: bool,
+    pub field_name: String,
+    /// Expected to hold RFC 3339 timestamps
+    pub time_field_name: String,
+
+    #[serde(skip)]
+    cache: Option<PlotCache>,
+}
+
+#[derive(Debug)]
+struct PlotCache {
+    key: CacheKey,
+    points: Vec<RowPoint>,
+}
+
+#[derive(Debug, PartialEq)]
+struct CacheKey {
+    visible_rows_generation: u64,
+    visible_len: usize,
+    field_name: String,
+    time_field_name: String,
+}
+
+#[derive(Debug, PartialEq)]
+struct RowPoint {
+    /// Index into all rows
+    real_index: usize,
+    /// Seconds since the unix epoch
+    x: f64,
+    y: f64,
+}
+
+impl Default for FieldPlot {
+    fn default() -> Self {
+        Self {
+            is_open: false,
+            field_name: "elapsed_milliseconds".into(),
+            time_field_name: "time".into(),
+            cache: None,
+        }
+    }
+}
+
+/// Visible rows that have both a valid time and a numeric value for the field
+fn row_points(data: &Data, field_name: &str, time_field_name: &str) -> Vec<RowPoint> {
+    data.visible_real_indices()
+        .into_iter()
+        .filter_map(|real_index| {
+            let row = data.row_by_real_index(real_index)?;
+            let time = row.field_value(time_field_name).as_time()?;
+            Some(RowPoint {
+                real_index,
+                x: time.timestamp_millis() as f64 / 1000.0,
+                y: row.field_value(field_name).as_f64()?,
+            })
+        })
+        .collect()
+}
+
+pub fn time_axis_text(seconds: f64) -> String {
+    chrono::DateTime::from_timestamp_millis((seconds * 1000.0) as i64)
+        .map(|x| x.format("%H:%M:%S").to_string())
+        .unwrap_or_default()
+}
+
+impl FieldPlot {
+    pub fn invalidate(&mut self) {
+        self.cache = None;
+    }
+
+    /// Shows the window if it is open. Returns the index into all rows of the row to select if a marker was clicked
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        data: Option<&Data>,
+        field_names: &[String],
+    ) -> Option<usize> {
+        let mut is_open = self.is_open;
+        let mut result = None;
+        egui::Window::new("Plot")
+            .open(&mut is_open)
+            .default_size([500.0, 300.0])
+            .show(ctx, |ui| result = self.ui(ui, data, field_names));
+        self.is_open = is_open;
+        result
+    }
+
+    fn ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        data: Option<&Data>,
+        field_names: &[String],
+    ) -> Option<usize> {
+        ui.horizontal(|ui| {
+            ui.label("Field");
+            super::field_name_ui(ui, &mut self.field_name, field_names);
+            ui.label("Time field");
+            super::field_name_ui(ui, &mut self.time_field_name, field_names);
+        });
+        let Some(data) = data else {
+            ui.label("No data");
+            return None;
+        };
+        self.refresh(data);
+        let points = self
+            .cache
+            .as_ref()
+            .map(|x| x.points.as_slice())
+            .unwrap_or_default();
+        ui.label(format!(
+            "{} of {} visible rows have a time and a numeric value (click a marker to select its row)",
+            points.len(),
+            data.len()
+        ));
+
+        let field_name = self.field_name.as_str();
+        Plot::new("field plot")
+            .x_axis_formatter(|mark, _| time_axis_text(mark.value))
+            .label_formatter(|_, value: &PlotPoint| {
+                match points.iter().find(|p| p.x == value.x && p.y == value.y) {
+                    Some(point) => format!(
+                        "Row {}\n{}\n{field_name}: {}",
+                        point.real_index,
+                        time_axis_text(point.x),
+                        point.y
+                    ),
+                    None => String::new(),
+                }
+            })
+            .show(ui, |plot_ui| {
+                plot_ui.points(
+                    Points::new(points.iter().map(|p| [p.x, p.y]).collect::<Vec<_>>())
+                        .radius(2.0)
+                        .name(field_name),
+                );
+                if !plot_ui.response().clicked() {
+                    return None;
+                }
+                let pointer = plot_ui.response().interact_pointer_pos()?;
+                points
+                    .iter()
+                    .map(|p| {
+                        let distance = plot_ui
+                            .screen_from_plot(PlotPoint::new(p.x, p.y))
+                            .distance(pointer);
+                        (distance, p.real_index)
+                    })
+                    .filter(|(distance, _)| *distance <= CLICK_RADIUS)
+                    .min_by(|a, b| a.0.total_cmp(&b.0))
+                    .map(|(_, real_index)| real_index)
+            })
+            .inner
+    }
+
+    /// Recomputes the points if the visible rows or fields changed
+    fn refresh(&mut self, data: &Data) {
+        let key = CacheKey {
+            visible_rows_generation: data.visible_rows_generation(),
+            visible_len: data.len(),
+            field_name: self.field_name.clone(),
+            time_field_name: self.time_field_name.clone(),
+        };
+        if self.cache.as_ref().is_some_and(|x| x.key == key) {
+            return;
+        }
+        let points = row_points(data, &key.field_name, &key.time_field_name);
+        self.cache = Some(PlotCache { key, points });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn points_need_time_and_number() {
+        let display_options = Default::default();
+        let text = [
+            r#"{"time": "1970-01-01T00:00:01.5Z", "ms": 10}"#,
+            r#"{"time": "1970-01-01T00:00:02Z"}"#,
+            r#"{"ms": 30}"#,
+            r#"{"time": "1970-01-01T00:00:03Z", "ms": "40"}"#,
+        ]
+        .join("\n");
+        let data = Data::try_from((&display_options, text.as_str())).unwrap();
+        assert_eq!(
+            row_points(&data, "ms", "time"),
+            vec![
+                RowPoint {
+                    real_index: 0,
+                    x: 1.5,
+                    y: 10.0
+                },
+                RowPoint {
+                    real_index: 3,
+                    x: 3.0,
+                    y: 40.0
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,259 @@
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BTreeSet},
+};
+
+use serde_json::Value;
+
+use super::{
+    data::Data,
+    data_display_options::DataDisplayOptions,
+    tasks::{ChunkedCache, ChunkedWork, TaskManager},
+};
+
+/// Lists every field in the data with how many rows have it and the JSON types of its values
+/// to help choose which fields to show in the main list and which are common
+#[derive(serde::Deserialize, serde::Serialize, Default, Debug)]
+#[serde(default)]
+pub struct FieldSchema {
+    pub is_open: bool,
+
+    /// Only valid for the data it was computed on (for the number of rows), cleared when the data changes
+    #[serde(skip)]
+    cache: ChunkedCache<usize, FieldStatsWork>,
+}
+
+/// Collects the stats of all rows (ignoring filters) a chunk of rows at a time
+struct FieldStatsWork {
+    total_len: usize,
+    /// Index into all rows of the next row
+    next: usize,
+    fields: BTreeMap<String, FieldStats>,
+}
+
+#[derive(Debug, PartialEq)]
+struct FieldStats {
+    name: String,
+    /// Number of rows with the field
+    count: usize,
+    types: BTreeSet<JsonType>,
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+enum JsonType {
+    Null,
+    Bool,
+    Number,
+    String,
+    Array,
+    Object,
+}
+
+impl JsonType {
+    fn of(value: &Value) -> Self {
+        match value {
+            Value::Null => Self::Null,
+            Value::Bool(_) => Self::Bool,
+            Value::Number(_) => Self::Number,
+            Value::String(_) => Self::String,
+            Value::Array(_) => Self::Array,
+            Value::Object(_) => Self::Object,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Null => "null",
+            Self::Bool => "bool",
+            Self::Number => "number",
+            Self::String => "string",
+            Self::Array => "array",
+            Self::Object => "object",
+        }
+    }
+}
+
+impl FieldStatsWork {
+    fn new(total_len: usize) -> Self {
+        Self {
+            total_len,
+            next: 0,
+            fields: BTreeMap::new(),
+        }
+    }
+}
+
+impl ChunkedWork for FieldStatsWork {
+    /// Stats for each field ordered by the number of rows with the field (most first)
+    type Output = Vec<FieldStats>;
+
+    fn step(&mut self, data: &Data, max_rows: usize) -> Option<Vec<FieldStats>> {
+        let end = self.next.saturating_add(max_rows).min(self.total_len);
+        for real_index in self.next..end {
+            let Some(row) = data.row_by_real_index(real_index) else {
+                continue;
+            };
+            for (name, value) in row.fields() {
+                let json_type = JsonType::of(value);
+                // Looked up before inserting so the name is only copied the first time it is seen
+                match self.fields.get_mut(name) {
+                    Some(stats) => {
+                        stats.count += 1;
+                        stats.types.insert(json_type);
+                    }
+                    None => {
+                        let stats = FieldStats {
+                            name: name.to_string(),
+                            count: 1,
+                            types: [json_type].into(),
+                        };
+                        self.fields.insert(name.to_string(), stats);
+                    }
+                }
+            }
+        }
+        self.next = end;
+        if end < self.total_len {
+            return None;
+        }
+        let mut result: Vec<FieldStats> = std::mem::take(&mut self.fields).into_values().collect();
+        result.sort_by_key(|x| Reverse(x.count)); // Stable so ties stay in name order
+        Some(result)
+    }
+
+    fn progress(&self) -> f32 {
+        self.next as f32 / self.total_len.max(1) as f32
+    }
+}
+
+impl FieldSchema {
+    pub fn invalidate(&mut self) {
+        self.cache.clear();
+    }
+
+    /// Shows the window if it is open, fields can be marked as common from the window
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        data: Option<&Data>,
+        display_options: &mut DataDisplayOptions,
+        tasks: &mut TaskManager,
+    ) {
+        let mut is_open = self.is_open;
+        egui::Window::new("Schema")
+            .open(&mut is_open)
+            .show(ctx, |ui| self.ui(ui, data, display_options, tasks));
+        self.is_open = is_open;
+        if !is_open {
+            self.cache.stop();
+        }
+    }
+
+    fn ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        data: Option<&Data>,
+        display_options: &mut DataDisplayOptions,
+        tasks: &mut TaskManager,
+    ) {
+        let Some(data) = data else {
+            ui.label("No data");
+            return;
+        };
+        let total_len = data.total_len_unfiltered();
+        self.cache.update(
+            ui.ctx(),
+            tasks,
+            "Finding fields",
+            data,
+            total_len,
+            |&total_len| FieldStatsWork::new(total_len),
+        );
+        self.cache.status_ui(ui);
+        let fields = self.cache.output().map(Vec::as_slice).unwrap_or_default();
+        ui.label(format!("{} fields in {total_len} rows", fields.len()));
+
+        let mut toggled_common = None;
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            egui::Grid::new("field schema")
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.strong("Field");
+                    ui.strong("Rows");
+                    ui.strong("Coverage");
+                    ui.strong("Types");
+                    ui.strong("Main List")
+                        .on_hover_text("Shown as a column in the main list");
+                    ui.strong("Common")
+                        .on_hover_text("Not unique to a request so shown last in the details");
+                    ui.end_row();
+                    for field in fields {
+                        ui.label(&field.name);
+                        ui.label(field.count.to_string());
+                        ui.label(format!(
+                            "{:.1}%",
+                            field.count as f64 * 100.0 / total_len.max(1) as f64
+                        ));
+                        let types: Vec<&str> = field.types.iter().map(|x| x.label()).collect();
+                        ui.label(types.join(", "));
+                        let is_main = display_options
+                            .main_list_fields()
+                            .iter()
+                            .any(|x| x == &field.name);
+                        ui.label(if is_main { "✔" } else { "" });
+                        let mut is_common = display_options.common_fields().contains(&field.name);
+                        if ui.checkbox(&mut is_common, "").changed() {
+                            toggled_common = Some(field.name.clone());
+                        }
+                        ui.end_row();
+                    }
+                });
+        });
+        if let Some(field_name) = toggled_common {
+            display_options.toggle_common_field(&field_name);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn fields_counted_with_types() {
+        let mut display_options = DataDisplayOptions::default();
+        display_options.row_idx_field_name = None;
+        display_options.level_conversion = None;
+        let text = [
+            r#"{"msg": "a", "code": 1}"#,
+            r#"{"msg": "b", "code": "E1"}"#,
+            r#"{"msg": "c", "extra": {"x": null}}"#,
+        ]
+        .join("\n");
+        let data = Data::try_from((&display_options, text.as_str())).unwrap();
+        let mut work = FieldStatsWork::new(data.total_len_unfiltered());
+        assert_eq!(work.step(&data, 2), None);
+        assert_eq!(
+            work.step(&data, 2).unwrap(),
+            vec![
+                FieldStats {
+                    name: "msg".into(),
+                    count: 3,
+                    types: [JsonType::String].into(),
+                },
+                FieldStats {
+                    name: "code".into(),
+                    count: 2,
+                    types: [JsonType::Number, JsonType::String].into(),
+                },
+                FieldStats {
+                    name: "extra".into(),
+                    count: 1,
+                    types: [JsonType::Object].into(),
+                },
+            ]
+        );
+    }
+}
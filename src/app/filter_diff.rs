@@ -0,0 +1,152 @@
+use super::{
+    data::{filter::FilterConfig, Data},
+    tasks::TaskManager,
+};
+
+/// Compares the rows matched by two filters to find rows that only one of them matches
+#[derive(serde::Deserialize, serde::Serialize, Debug)]
+#[serde(default)]
+pub struct FilterDiff {
+    pub is_open: bool,
+    /// Field added to exported rows to indicate which filter matched the row
+    pub marker_field_name: String,
+
+    /// Saved results are only valid for the data they were computed on so they are cleared when new data is loaded
+    #[serde(skip)]
+    a: Option<SavedFilterResult>,
+    #[serde(skip)]
+    b: Option<SavedFilterResult>,
+    #[serde(skip)]
+    status_msg: Option<String>,
+}
+
+#[derive(Debug)]
+struct SavedFilterResult {
+    filter: Option<FilterConfig>,
+    /// Sorted indices into all rows
+    rows: Vec<usize>,
+}
+
+impl Default for FilterDiff {
+    fn default() -> Self {
+        Self {
+            is_open: false,
+            marker_field_name: "filter_diff".into(),
+            a: None,
+            b: None,
+            status_msg: None,
+        }
+    }
+}
+
+impl SavedFilterResult {
+    fn description(&self) -> String {
+        match self.filter.as_ref() {
+            Some(filter) => filter.to_string(),
+            None => "No Filter (all visible rows)".to_string(),
+        }
+    }
+}
+
+impl FilterDiff {
+    pub fn invalidate(&mut self) {
+        self.a = None;
+        self.b = None;
+        self.status_msg = None;
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context, data: Option<&Data>, tasks: &mut TaskManager) {
+        let mut is_open = self.is_open;
+        egui::Window::new("Filter Diff")
+            .open(&mut is_open)
+            .show(ctx, |ui| self.ui(ui, data, tasks));
+        self.is_open = is_open;
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui, data: Option<&Data>, tasks: &mut TaskManager) {
+        let Some(data) = data else {
+            ui.label("No data");
+            return;
+        };
+
+        ui.label("Apply a filter then save its result into one of the slots");
+        egui::Grid::new("filter diff slots").show(ui, |ui| {
+            for (name, slot) in [("A", &mut self.a), ("B", &mut self.b)] {
+                if ui
+                    .button(format!("Save as {name}"))
+                    .on_hover_text("Saves the rows currently visible")
+                    .clicked()
+                {
+                    *slot = Some(SavedFilterResult {
+                        filter: data.applied_filter().cloned(),
+                        rows: data.visible_real_indices(),
+                    });
+                }
+                match slot.as_ref() {
+                    Some(saved) => ui.label(format!(
+                        "{} rows | {}",
+                        saved.rows.len(),
+                        saved.description()
+                    )),
+                    None => ui.label("(empty)"),
+                };
+                ui.end_row();
+            }
+        });
+
+        let (Some(a), Some(b)) = (self.a.as_ref(), self.b.as_ref()) else {
+            return;
+        };
+        ui.separator();
+        let only_a = a
+            .rows
+            .iter()
+            .filter(|x| b.rows.binary_search(x).is_err())
+            .count();
+        let only_b = b
+            .rows
+            .iter()
+            .filter(|x| a.rows.binary_search(x).is_err())
+            .count();
+        ui.label(format!(
+            "Only A: {only_a} | Only B: {only_b} | Both: {}",
+            a.rows.len() - only_a
+        ));
+        ui.horizontal(|ui| {
+            ui.label("Marker field:");
+            ui.text_edit_singleline(&mut self.marker_field_name);
+        });
+        ui.horizontal(|ui| {
+            let export = || {
+                data.export_symmetric_difference(
+                    ("A", &a.rows),
+                    ("B", &b.rows),
+                    &self.marker_field_name,
+                )
+            };
+            if ui.button("Copy Difference").clicked() {
+                self.status_msg = Some(match export() {
+                    Ok(text) => {
+                        ui.ctx().copy_text(text);
+                        "Copied to clipboard".to_string()
+                    }
+                    Err(e) => format!("Export failed: {e:?}"),
+                });
+            }
+            if ui.button("Save Difference...").clicked() {
+                match export() {
+                    Ok(text) => tasks.spawn_save(
+                        "Save filter diff",
+                        "filter_diff.log",
+                        text,
+                        ui.ctx().clone(),
+                    ),
+                    Err(e) => self.status_msg = Some(format!("Export failed: {e:?}")),
+                }
+            }
+        });
+        if let Some(msg) = self.status_msg.as_ref() {
+            ui.label(msg);
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use anyhow::Context;
+
+use super::{data::filter::FilterConfig, workspaces::SavedFilters};
+
+/// Filters copied as JSON to share with others (eg. in chat) and imported from JSON pasted by the user
+#[derive(Debug, Default)]
+pub struct FilterSharing {
+    pub is_import_open: bool,
+    import_text: String,
+    error: Option<String>,
+}
+
+/// Filters read from pasted text
+#[derive(Debug, PartialEq)]
+pub enum FilterImport {
+    /// A single filter, replaces only the main filter
+    Filter(FilterConfig),
+    /// All the filters (as exported), replaces every filter
+    All(Box<SavedFilters>),
+}
+
+/// The filters as compact JSON so they fit on one line when pasted
+pub fn export_text(filters: &SavedFilters) -> String {
+    serde_json::to_string(filters).expect("filters should always be serializable")
+}
+
+/// Reads either the JSON of all the filters (see [`export_text`]) or of a single [`FilterConfig`]
+fn parse_import(text: &str) -> anyhow::Result<FilterImport> {
+    let value: serde_json::Value =
+        serde_json::from_str(text.trim()).context("failed to read the text as JSON")?;
+    let Some(object) = value.as_object() else {
+        anyhow::bail!("expected a JSON object");
+    };
+    // Unknown keys are ignored when deserializing so the kind is decided by the keys present
+    if object.contains_key("search_key") {
+        let filter = serde_json::from_value(value).context("failed to read the filter")?;
+        Ok(FilterImport::Filter(filter))
+    } else if object.contains_key("applied_filter") {
+        let filters = serde_json::from_value(value).context("failed to read the filters")?;
+        Ok(FilterImport::All(Box::new(filters)))
+    } else {
+        anyhow::bail!("no filter found (expected the text copied with \"Copy Filters\")")
+    }
+}
+
+impl FilterSharing {
+    /// Shows the window to paste filters into if it is open. Returns the filters to apply when imported
+    pub fn show(&mut self, ctx: &egui::Context) -> Option<FilterImport> {
+        let mut is_open = self.is_import_open;
+        let mut result = None;
+        egui::Window::new("Import Filters")
+            .open(&mut is_open)
+            .default_width(400.0)
+            .show(ctx, |ui| {
+                ui.label(
+                    "Paste filters copied with \"Copy Filters\" (or the JSON of a single filter)",
+                );
+                ui.add(
+                    egui::TextEdit::multiline(&mut self.import_text)
+                        .code_editor()
+                        .desired_rows(4)
+                        .desired_width(f32::INFINITY),
+                );
+                if ui.button("Import").clicked() {
+                    match parse_import(&self.import_text) {
+                        Ok(import) => {
+                            result = Some(import);
+                            self.import_text.clear();
+                            self.error = None;
+                        }
+                        Err(e) => self.error = Some(format!("{e:#}")),
+                    }
+                }
+                if let Some(error) = self.error.as_ref() {
+                    ui.colored_label(ui.visuals().error_fg_color, error);
+                }
+            });
+        self.is_import_open = is_open && result.is_none();
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::core::data_display_options::DataDisplayOptions;
+
+    use super::{super::data::Data, *};
+
+    #[test]
+    fn exported_filters_import() {
+        let display_options = DataDisplayOptions::default();
+        let mut data = Data::try_from((&display_options, "{\"msg\": \"a\"}")).unwrap();
+        data.filter = Some(FilterConfig {
+            search_key: "a".into(),
+            ..Default::default()
+        });
+        data.apply_filter(display_options.common_fields());
+        let filters = SavedFilters::new(Some(&data));
+        assert_eq!(
+            parse_import(&export_text(&filters)).unwrap(),
+            FilterImport::All(Box::new(filters))
+        );
+
+        let filter = FilterConfig {
+            search_key: "timeout".into(),
+            ..Default::default()
+        };
+        let text = serde_json::to_string_pretty(&filter).unwrap();
+        assert_eq!(parse_import(&text).unwrap(), FilterImport::Filter(filter));
+
+        assert!(parse_import("not json").is_err());
+        assert!(parse_import("{\"other\": 1}").is_err());
+    }
+}
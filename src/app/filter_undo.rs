@@ -0,0 +1,150 @@
+use super::data::filter::FilterConfig;
+
+/// Max number of filters that can be undone
+const MAX_UNDO: usize = 50;
+/// Number of earlier filters listed in the dropdown
+const MENU_LEN: usize = 10;
+
+/// Undo and redo for the applied filter (None is no filter applied).
+///
+/// Changes are noticed by comparing the applied filter each frame (see [`Self::observe`]) so every way of applying a
+/// filter is covered
+#[derive(Debug, Default)]
+pub struct FilterUndo {
+    /// The applied filter when last observed
+    current: Option<FilterConfig>,
+    /// Most recent last
+    undo: Vec<Option<FilterConfig>>,
+    /// Most recent last
+    redo: Vec<Option<FilterConfig>>,
+    /// Set if the next change comes from typing (filter as you type)
+    is_next_change_typing: bool,
+    /// Set if the last change came from typing so changes while typing undo as one
+    was_last_change_typing: bool,
+}
+
+impl FilterUndo {
+    /// Marks the next change as being made while typing so it merges with other changes made while typing
+    pub fn mark_typing(&mut self) {
+        self.is_next_change_typing = true;
+    }
+
+    /// Records a change if the applied filter is not the one last observed
+    pub fn observe(&mut self, applied: Option<&FilterConfig>) {
+        let is_typing = std::mem::take(&mut self.is_next_change_typing);
+        if self.current.as_ref() == applied {
+            return;
+        }
+        let previous = std::mem::replace(&mut self.current, applied.cloned());
+        if !(is_typing && self.was_last_change_typing) {
+            self.undo.push(previous);
+            if self.undo.len() > MAX_UNDO {
+                self.undo.remove(0);
+            }
+        }
+        self.was_last_change_typing = is_typing;
+        self.redo.clear();
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Goes back `steps` filters and returns the filter to apply (None to remove the filter)
+    pub fn undo(&mut self, steps: usize) -> Option<Option<FilterConfig>> {
+        if steps == 0 || steps > self.undo.len() {
+            return None;
+        }
+        for _ in 0..steps {
+            let previous = self.undo.pop().expect("length checked above");
+            self.redo
+                .push(std::mem::replace(&mut self.current, previous));
+        }
+        self.was_last_change_typing = false;
+        Some(self.current.clone())
+    }
+
+    /// Reapplies the last undone filter, returns the filter to apply (None to remove the filter)
+    pub fn redo(&mut self) -> Option<Option<FilterConfig>> {
+        let next = self.redo.pop()?;
+        self.undo.push(std::mem::replace(&mut self.current, next));
+        self.was_last_change_typing = false;
+        Some(self.current.clone())
+    }
+
+    /// Dropdown listing the filters that can be undone, returns how many steps back the one chosen is
+    pub fn menu_ui(&self, ui: &mut egui::Ui) -> Option<usize> {
+        let mut result = None;
+        ui.add_enabled_ui(self.can_undo(), |ui| {
+            ui.menu_button("⏷", |ui| {
+                for (i, filter) in self.undo.iter().rev().take(MENU_LEN).enumerate() {
+                    let text = match filter {
+                        Some(filter) => filter.to_string(),
+                        None => "(No filter)".to_string(),
+                    };
+                    if ui.button(text).clicked() {
+                        result = Some(i + 1);
+                        ui.close_menu();
+                    }
+                }
+            })
+            .response
+            .on_hover_text("Earlier filters");
+        });
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn filter(search_key: &str) -> FilterConfig {
+        FilterConfig {
+            search_key: search_key.into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn undo_and_redo_applied_filters() {
+        let mut history = FilterUndo::default();
+        history.observe(None);
+        assert!(!history.can_undo());
+
+        history.observe(Some(&filter("a")));
+        history.observe(Some(&filter("b")));
+        history.observe(None);
+
+        assert_eq!(history.undo(1), Some(Some(filter("b"))));
+        assert_eq!(history.undo(2), Some(None));
+        assert_eq!(history.undo(1), None);
+        assert_eq!(history.redo(), Some(Some(filter("a"))));
+
+        // Already at the filter so no new undo entry is added and redo is kept
+        history.observe(Some(&filter("a")));
+        assert!(history.can_redo());
+
+        // A new filter clears redo
+        history.observe(Some(&filter("c")));
+        assert!(!history.can_redo());
+        assert_eq!(history.undo(1), Some(Some(filter("a"))));
+    }
+
+    #[test]
+    fn changes_while_typing_undo_together() {
+        let mut history = FilterUndo::default();
+        history.observe(Some(&filter("start")));
+        for key in ["e", "er", "err"] {
+            history.mark_typing();
+            history.observe(Some(&filter(key)));
+        }
+        assert_eq!(history.undo(1), Some(Some(filter("start"))));
+    }
+}
@@ -0,0 +1,120 @@
+use std::path::{Path, PathBuf};
+
+/// Seconds between checks of the folder
+const CHECK_INTERVAL: f64 = 2.0;
+
+/// Checks the starting folder for a newer file (eg. after log rotation) so it can be opened automatically
+#[derive(serde::Deserialize, serde::Serialize, Debug)]
+#[serde(default)]
+pub struct FolderWatch {
+    pub is_enabled: bool,
+    /// Keep the filters of the data shown when switching to the newer file
+    pub should_keep_filters: bool,
+
+    /// Time of the last check (egui time)
+    #[serde(skip)]
+    last_check: Option<f64>,
+    /// Most recent file found by the last check, a newer file is one that is different from this
+    #[serde(skip)]
+    known_newest: Option<PathBuf>,
+    /// Set when a switch is started without keeping filters, cleared when the data is loaded
+    #[serde(skip)]
+    should_discard_filters: bool,
+}
+
+impl Default for FolderWatch {
+    fn default() -> Self {
+        Self {
+            is_enabled: false,
+            should_keep_filters: true,
+            last_check: None,
+            known_newest: None,
+            should_discard_filters: false,
+        }
+    }
+}
+
+impl FolderWatch {
+    /// Returns the newer file to switch to if it is time to check and one was found.
+    /// `find_newest` is only called when it is time to check
+    pub fn poll(
+        &mut self,
+        ctx: &egui::Context,
+        find_newest: impl FnOnce() -> anyhow::Result<PathBuf>,
+    ) -> Option<PathBuf> {
+        if !self.is_enabled {
+            self.last_check = None;
+            self.known_newest = None;
+            return None;
+        }
+        ctx.request_repaint_after_secs(CHECK_INTERVAL as f32);
+        let now = ctx.input(|i| i.time);
+        if self.last_check.is_some_and(|x| now - x < CHECK_INTERVAL) {
+            return None;
+        }
+        self.last_check = Some(now);
+        // Errors are ignored as the folder may be briefly unavailable during rotation
+        let newest = find_newest().ok()?;
+        self.check(newest)
+    }
+
+    /// The first file seen is the baseline, only files found after that are returned
+    fn check(&mut self, newest: PathBuf) -> Option<PathBuf> {
+        let is_first_check = self.known_newest.is_none();
+        if self.known_newest.as_deref() == Some(newest.as_path()) {
+            return None;
+        }
+        self.known_newest = Some(newest.clone());
+        if is_first_check {
+            return None;
+        }
+        self.should_discard_filters = !self.should_keep_filters;
+        Some(newest)
+    }
+
+    /// True if the filters should not be carried over to the data being loaded (only returns true once per switch)
+    pub fn take_should_discard_filters(&mut self) -> bool {
+        std::mem::take(&mut self.should_discard_filters)
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui, folder: Option<&Path>) {
+        let hint = match folder {
+            Some(folder) => format!(
+                "Opens newer files that appear in {} (eg. after log rotation)",
+                folder.display()
+            ),
+            None => "Open a file first so there is a folder to watch".to_string(),
+        };
+        ui.add_enabled(
+            folder.is_some(),
+            egui::Checkbox::new(&mut self.is_enabled, "Watch Folder"),
+        )
+        .on_hover_text(hint);
+        if self.is_enabled {
+            ui.checkbox(&mut self.should_keep_filters, "Keep filters")
+                .on_hover_text("Apply the current filters to the newer file when switching");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn only_files_after_first_check_returned() {
+        let mut watch = FolderWatch {
+            is_enabled: true,
+            should_keep_filters: false,
+            ..Default::default()
+        };
+        assert_eq!(watch.check("a.log".into()), None);
+        assert_eq!(watch.check("a.log".into()), None);
+        assert!(!watch.take_should_discard_filters());
+        assert_eq!(watch.check("b.log".into()), Some("b.log".into()));
+        assert!(watch.take_should_discard_filters());
+        assert!(!watch.take_should_discard_filters());
+    }
+}
@@ -0,0 +1,140 @@
+use std::collections::BTreeSet;
+
+use serde_json::Value;
+
+/// Tracks which nodes of nested values in the details pane are expanded
+///
+/// Nodes are identified by a path made of the field name followed by the keys/indices leading to the node
+#[derive(Default, Debug)]
+pub struct JsonTree {
+    expanded: BTreeSet<String>,
+}
+
+impl JsonTree {
+    /// Returns true if the value should be shown as a tree instead of as text
+    pub fn is_nested(value: &Value) -> bool {
+        match value {
+            Value::Array(x) => !x.is_empty(),
+            Value::Object(x) => !x.is_empty(),
+            _ => false,
+        }
+    }
+
+    /// Number of lines the value takes up given the nodes currently expanded
+    pub fn line_count(&self, path: &str, value: &Value) -> usize {
+        if !self.expanded.contains(path) {
+            return 1;
+        }
+        1 + children(value)
+            .map(|(key, child)| self.line_count(&child_path(path, &key), child))
+            .sum::<usize>()
+    }
+
+    /// Expands the node if it is collapsed and collapses it otherwise
+    pub fn toggle(&mut self, path: &str) {
+        if !self.expanded.remove(path) {
+            self.expanded.insert(path.to_string());
+        }
+    }
+
+    /// Shows the value as a tree (root line does not include a key as it is expected to be shown elsewhere)
+    pub fn show(&mut self, ui: &mut egui::Ui, path: &str, value: &Value, color: egui::Color32) {
+        ui.vertical(|ui| {
+            ui.spacing_mut().item_spacing.y = 0.0;
+            self.show_node(ui, path, None, value, 0, color);
+        });
+    }
+
+    fn show_node(
+        &mut self,
+        ui: &mut egui::Ui,
+        path: &str,
+        key: Option<&str>,
+        value: &Value,
+        depth: usize,
+        color: egui::Color32,
+    ) {
+        let is_nested = Self::is_nested(value);
+        let is_expanded = is_nested && self.expanded.contains(path);
+        ui.horizontal(|ui| {
+            ui.add_space(depth as f32 * ui.spacing().indent);
+            if is_nested {
+                let icon = if is_expanded { "⏷" } else { "⏵" };
+                if ui.small_button(icon).clicked() {
+                    self.toggle(path);
+                }
+            }
+            if let Some(key) = key {
+                ui.colored_label(color, format!("{key}:"));
+            }
+            let text = match value {
+                Value::Array(x) if is_expanded => format!("[{}]", x.len()),
+                Value::Object(x) if is_expanded => format!("{{{}}}", x.len()),
+                Value::String(s) => s.clone(),
+                _ => value.to_string(),
+            };
+            ui.colored_label(color, text);
+            if ui
+                .small_button("📋")
+                .on_hover_text("Copy value as JSON")
+                .clicked()
+            {
+                let text = match value {
+                    Value::String(s) => s.clone(),
+                    _ => serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string()),
+                };
+                ui.ctx().copy_text(text);
+            }
+        });
+        if is_expanded {
+            for (child_key, child) in children(value) {
+                self.show_node(
+                    ui,
+                    &child_path(path, &child_key),
+                    Some(&child_key),
+                    child,
+                    depth + 1,
+                    color,
+                );
+            }
+        }
+    }
+}
+
+fn children(value: &Value) -> Box<dyn Iterator<Item = (String, &Value)> + '_> {
+    match value {
+        Value::Array(x) => Box::new(x.iter().enumerate().map(|(i, v)| (i.to_string(), v))),
+        Value::Object(x) => Box::new(x.iter().map(|(k, v)| (k.clone(), v))),
+        _ => Box::new(std::iter::empty()),
+    }
+}
+
+fn child_path(parent: &str, key: &str) -> String {
+    format!("{parent}/{key}")
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn line_count_follows_expanded_nodes() {
+        let value =
+            json!({"headers": {"host": "x", "accept": "y"}, "method": "GET", "list": [1, 2]});
+        let mut tree = JsonTree::default();
+        assert_eq!(tree.line_count("req", &value), 1);
+
+        tree.expanded.insert("req".into());
+        assert_eq!(tree.line_count("req", &value), 4);
+
+        tree.expanded.insert("req/headers".into());
+        tree.expanded.insert("req/list".into());
+        assert_eq!(tree.line_count("req", &value), 8);
+
+        // Children are not shown when the parent is collapsed
+        tree.expanded.remove("req");
+        assert_eq!(tree.line_count("req", &value), 1);
+    }
+}
@@ -0,0 +1,91 @@
+use super::data_display_options::LevelConversion;
+
+/// Editor for [`super::data_display_options::DataDisplayOptions::level_conversion`]
+#[derive(Default, Debug)]
+pub struct LevelConversionUi {
+    new_raw_value: i64,
+    new_display_value: String,
+}
+
+impl LevelConversionUi {
+    /// Returns true when the changes should be applied to the loaded rows (text is only applied once it is no longer
+    /// being edited so the rows are not converted again on each key press)
+    pub fn ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        level_conversion: &mut Option<LevelConversion>,
+    ) -> bool {
+        let mut is_enabled = level_conversion.is_some();
+        let mut should_apply = ui
+            .checkbox(&mut is_enabled, "Convert numeric levels")
+            .changed();
+        match (is_enabled, level_conversion.is_some()) {
+            (true, false) => *level_conversion = Some(Default::default()),
+            (false, true) => *level_conversion = None,
+            _ => {}
+        }
+        let Some(settings) = level_conversion.as_mut() else {
+            return should_apply;
+        };
+
+        ui.horizontal(|ui| {
+            ui.label("Presets:");
+            if ui.button("Bunyan").clicked() {
+                *settings = LevelConversion::default();
+                should_apply = true;
+            }
+            if ui.button("Syslog").clicked() {
+                *settings = LevelConversion::syslog();
+                should_apply = true;
+            }
+        });
+        egui::Grid::new("level conversion fields").show(ui, |ui| {
+            ui.label("Source field");
+            should_apply |= ui
+                .text_edit_singleline(&mut settings.source_field_name)
+                .lost_focus();
+            ui.end_row();
+            ui.label("Display field");
+            should_apply |= ui
+                .text_edit_singleline(&mut settings.display_field_name)
+                .lost_focus();
+            ui.end_row();
+        });
+
+        ui.separator();
+        let mut value_to_remove = None;
+        egui::Grid::new("level conversion map").show(ui, |ui| {
+            for (raw_value, display_value) in settings.convert_map.iter_mut() {
+                ui.label(raw_value.to_string());
+                should_apply |= ui.text_edit_singleline(display_value).lost_focus();
+                if ui.small_button("🗑").on_hover_text("Remove level").clicked() {
+                    value_to_remove = Some(*raw_value);
+                }
+                ui.end_row();
+            }
+        });
+        if let Some(raw_value) = value_to_remove {
+            settings.convert_map.remove(&raw_value);
+            should_apply = true;
+        }
+        ui.horizontal(|ui| {
+            ui.add(egui::DragValue::new(&mut self.new_raw_value));
+            ui.add(egui::TextEdit::singleline(&mut self.new_display_value).hint_text("Level name"));
+            let is_new = !settings.convert_map.contains_key(&self.new_raw_value);
+            if ui
+                .add_enabled(
+                    is_new && !self.new_display_value.is_empty(),
+                    egui::Button::new("Add Level"),
+                )
+                .clicked()
+            {
+                settings.convert_map.insert(
+                    self.new_raw_value,
+                    std::mem::take(&mut self.new_display_value),
+                );
+                should_apply = true;
+            }
+        });
+        should_apply
+    }
+}
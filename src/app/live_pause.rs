@@ -0,0 +1,146 @@
+use std::collections::VecDeque;
+
+use crate::core::data_display_options::LiveRowLimit;
+
+/// Holds back lines from a live source while paused so the rows do not move while the user inspects them
+#[derive(Debug, Default)]
+pub struct LivePause {
+    /// Lines received while paused, None when not paused
+    buffered: Option<VecDeque<String>>,
+    /// Number of the oldest lines dropped while paused to stay within the row limit
+    dropped_count: usize,
+}
+
+impl LivePause {
+    pub fn is_paused(&self) -> bool {
+        self.buffered.is_some()
+    }
+
+    /// Number of lines waiting to be added, None when not paused
+    pub fn buffered_count(&self) -> Option<usize> {
+        self.buffered.as_ref().map(VecDeque::len)
+    }
+
+    /// Number of lines received while paused that were dropped as there were more than the row limit
+    pub fn dropped_count(&self) -> usize {
+        self.dropped_count
+    }
+
+    pub fn pause(&mut self) {
+        if self.buffered.is_none() {
+            self.buffered = Some(VecDeque::new());
+            self.dropped_count = 0;
+        }
+    }
+
+    /// Returns the lines received while paused
+    #[must_use]
+    pub fn resume(&mut self) -> Vec<String> {
+        self.dropped_count = 0;
+        self.buffered.take().map(Vec::from).unwrap_or_default()
+    }
+
+    /// Returns the lines to add now, while paused they are kept until [`Self::resume`] is called instead.
+    ///
+    /// Only the newest lines up to the row limit are kept while paused as older ones would be dropped when added anyway
+    #[must_use]
+    pub fn pass_through(&mut self, lines: Vec<String>, limit: &LiveRowLimit) -> Vec<String> {
+        match self.buffered.as_mut() {
+            Some(buffered) => {
+                buffered.extend(lines);
+                let excess = limit.excess(buffered.len());
+                buffered.drain(..excess);
+                self.dropped_count += excess;
+                Vec::new()
+            }
+            None => lines,
+        }
+    }
+
+    /// Should be called when a new session starts as the lines held belong to the previous one
+    pub fn clear(&mut self) {
+        self.buffered = None;
+        self.dropped_count = 0;
+    }
+
+    /// Button to pause and resume, returns the held lines when resumed
+    pub fn ui(&mut self, ui: &mut egui::Ui) -> Vec<String> {
+        match self.buffered_count() {
+            Some(count) => {
+                let text = match self.dropped_count {
+                    0 => format!("▶ Resume ({count})"),
+                    dropped => format!("▶ Resume ({count}, {dropped} dropped)"),
+                };
+                if ui
+                    .button(text)
+                    .on_hover_text(
+                        "Add the rows received while paused and keep adding new rows \
+                        (the oldest are dropped if there are more than the live row limit)",
+                    )
+                    .clicked()
+                {
+                    return self.resume();
+                }
+            }
+            None => {
+                if ui
+                    .button("⏸ Pause")
+                    .on_hover_text(
+                        "Stop adding rows from the live source (they are kept until resumed)",
+                    )
+                    .clicked()
+                {
+                    self.pause();
+                }
+            }
+        }
+        Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn lines_held_until_resumed() {
+        let mut pause = LivePause::default();
+        let no_limit = LiveRowLimit {
+            is_enabled: false,
+            max_rows: 1,
+        };
+        let lines = |x: &[&str]| x.iter().map(|x| x.to_string()).collect::<Vec<_>>();
+        assert_eq!(pause.pass_through(lines(&["a"]), &no_limit), lines(&["a"]));
+
+        pause.pause();
+        assert!(pause.pass_through(lines(&["b", "c"]), &no_limit).is_empty());
+        assert!(pause.pass_through(lines(&["d"]), &no_limit).is_empty());
+        assert_eq!(pause.buffered_count(), Some(3));
+
+        assert_eq!(pause.resume(), lines(&["b", "c", "d"]));
+        assert!(!pause.is_paused());
+        assert_eq!(pause.pass_through(lines(&["e"]), &no_limit), lines(&["e"]));
+    }
+
+    #[test]
+    fn oldest_lines_dropped_over_limit() {
+        let mut pause = LivePause::default();
+        let limit = LiveRowLimit {
+            is_enabled: true,
+            max_rows: 2,
+        };
+        let lines = |x: &[&str]| x.iter().map(|x| x.to_string()).collect::<Vec<_>>();
+        pause.pause();
+        assert!(pause
+            .pass_through(lines(&["a", "b", "c"]), &limit)
+            .is_empty());
+        assert!(pause.pass_through(lines(&["d"]), &limit).is_empty());
+        assert_eq!(pause.buffered_count(), Some(2));
+        assert_eq!(pause.dropped_count(), 2);
+
+        assert_eq!(pause.resume(), lines(&["c", "d"]));
+        assert_eq!(pause.dropped_count(), 0);
+    }
+}
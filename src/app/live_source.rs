@@ -0,0 +1,17 @@
+/// A source that streams lines to append to the data as they arrive (eg. SSH tail or WebSocket)
+pub trait LiveSource {
+    /// Shows the window for the source if it is open. Returns true if a new session was started (existing data should be replaced)
+    fn show(&mut self, ctx: &egui::Context) -> bool;
+
+    /// Returns the lines received since the last call
+    fn take_new_lines(&mut self) -> Vec<String>;
+
+    /// Called if the received lines could not be used to stop receiving more
+    fn disconnect_with_error(&mut self, msg: String);
+
+    /// True while lines may still arrive
+    fn is_connected(&self) -> bool;
+
+    /// Identifies where the lines come from (used like a file name)
+    fn source_description(&self) -> String;
+}
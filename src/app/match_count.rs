@@ -0,0 +1,114 @@
+use std::collections::BTreeSet;
+
+use super::data::{filter::FilterConfig, Data};
+
+/// Number of rows checked each frame so the UI stays responsive on large files
+const ROWS_PER_FRAME: usize = 20_000;
+
+/// Counts the rows matching the filter being edited (before it is applied) a chunk of rows each frame
+#[derive(Debug, Default)]
+pub struct MatchCounter {
+    /// The filter the count is for, counting restarts when it changes
+    filter: Option<FilterConfig>,
+    /// Real index of the next row to check
+    next_index: usize,
+    count: usize,
+}
+
+impl MatchCounter {
+    pub fn invalidate(&mut self) {
+        *self = Default::default();
+    }
+
+    /// Checks the next chunk of rows against the filter being edited.
+    /// Returns the count so far and if all rows have been checked or None if there is nothing to count
+    fn update(
+        &mut self,
+        data: &mut Data,
+        common_fields: &BTreeSet<String>,
+        rows_per_frame: usize,
+    ) -> Option<(usize, bool)> {
+        let filter = data
+            .filter
+            .as_ref()
+            .filter(|x| !x.search_key.is_empty() || x.comparator.is_presence_check())?;
+        if self.filter.as_ref() != Some(filter) {
+            self.filter = Some(filter.clone());
+            self.next_index = 0;
+            self.count = 0;
+        }
+        let filter = self.filter.as_ref().expect("set just above");
+        let end = data
+            .total_len_unfiltered()
+            .min(self.next_index + rows_per_frame);
+        self.count += (self.next_index..end)
+            .filter(|&i| data.row_matches(i, filter, common_fields))
+            .count();
+        self.next_index = end;
+        Some((self.count, end == data.total_len_unfiltered()))
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui, data: &mut Data, common_fields: &BTreeSet<String>) {
+        let Some((count, is_done)) = self.update(data, common_fields, ROWS_PER_FRAME) else {
+            return;
+        };
+        let text = format!(
+            "{}{count} row{} match",
+            if is_done { "" } else { "≥ " },
+            if count == 1 { "" } else { "s" }
+        );
+        let color = if is_done && count == 0 {
+            ui.visuals().warn_fg_color
+        } else {
+            ui.visuals().weak_text_color()
+        };
+        ui.colored_label(color, text).on_hover_text(
+            "Rows in the file matching the search key (ignoring other filters) even if the filter is not applied yet",
+        );
+        if !is_done {
+            ui.ctx().request_repaint();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::core::data_display_options::DataDisplayOptions;
+
+    use super::*;
+
+    #[test]
+    fn counts_in_chunks_and_restarts_on_change() {
+        let display_options = DataDisplayOptions::default();
+        let text = [
+            r#"{"msg": "find me"}"#,
+            r#"{"msg": "other"}"#,
+            r#"{"msg": "find me too"}"#,
+        ]
+        .join("\n");
+        let mut data = Data::try_from((&display_options, text.as_str())).unwrap();
+        let common_fields = display_options.common_fields();
+        let mut counter = MatchCounter::default();
+        assert_eq!(counter.update(&mut data, common_fields, 2), None);
+
+        data.filter = Some(FilterConfig {
+            search_key: "find".into(),
+            ..Default::default()
+        });
+        assert_eq!(
+            counter.update(&mut data, common_fields, 2),
+            Some((1, false))
+        );
+        assert_eq!(counter.update(&mut data, common_fields, 2), Some((2, true)));
+        assert_eq!(counter.update(&mut data, common_fields, 2), Some((2, true)));
+
+        data.filter.as_mut().unwrap().search_key = "other".into();
+        assert_eq!(
+            counter.update(&mut data, common_fields, 2),
+            Some((1, false))
+        );
+        assert_eq!(counter.update(&mut data, common_fields, 2), Some((1, true)));
+    }
+}
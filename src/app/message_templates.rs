@@ -0,0 +1,340 @@
+use std::collections::{BTreeMap, HashMap};
+
+use super::{
+    data::{
+        filter::{Comparator, FieldSpecifier, FilterConfig, FilterOn},
+        template::message_template,
+        Data, FieldContent,
+    },
+    row_actions::RowCommand,
+    tasks::{ChunkedCache, ChunkedWork, TaskManager},
+};
+
+/// Groups the messages of the visible rows by template (numbers and ids masked out) to see what a noisy log is made of
+#[derive(serde::Deserialize, serde::Serialize, Debug)]
+#[serde(default)]
+pub struct MessageTemplates {
+    pub is_open: bool,
+    pub field_name: String,
+    /// Max number of templates listed
+    pub max_shown: usize,
+    /// Rows are highlighted if their template occurs fewer than [`Self::rare_below`] times in all rows
+    pub is_rare_highlight_enabled: bool,
+    pub rare_below: usize,
+
+    #[serde(skip)]
+    cache: ChunkedCache<CacheKey, TemplateCountsWork>,
+    #[serde(skip)]
+    rare_index: TemplateIndex,
+}
+
+/// Template of every row (ignoring filters) built up as rows are added so rare rows can be found while drawing
+#[derive(Debug, Default)]
+struct TemplateIndex {
+    field_name: String,
+    /// Id of each template
+    templates: HashMap<String, usize>,
+    /// Number of rows with each template by id
+    counts: Vec<usize>,
+    /// Template id of each row (None if the row does not have the field)
+    row_templates: Vec<Option<usize>>,
+}
+
+#[derive(Debug, PartialEq)]
+struct CacheKey {
+    visible_rows_generation: u64,
+    visible_len: usize,
+    field_name: String,
+}
+
+/// Groups the messages of the rows visible when it was started a chunk of rows at a time
+struct TemplateCountsWork {
+    field_name: String,
+    rows: Vec<usize>,
+    /// Position in `rows` of the next row
+    next: usize,
+    /// Number of rows and an example message of each template
+    counts: BTreeMap<String, (usize, String)>,
+    total: usize,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct TemplateCounts {
+    /// Template, number of rows and an example message. Ordered by count (highest first) then by template
+    templates: Vec<(String, usize, String)>,
+    /// Number of visible rows with the field
+    total: usize,
+}
+
+impl Default for MessageTemplates {
+    fn default() -> Self {
+        Self {
+            is_open: false,
+            field_name: "msg".into(),
+            max_shown: 50,
+            is_rare_highlight_enabled: false,
+            rare_below: 3,
+            cache: Default::default(),
+            rare_index: Default::default(),
+        }
+    }
+}
+
+impl TemplateCountsWork {
+    fn new(data: &Data, field_name: &str) -> Self {
+        Self {
+            field_name: field_name.to_string(),
+            rows: data.visible_real_indices(),
+            next: 0,
+            counts: BTreeMap::new(),
+            total: 0,
+        }
+    }
+}
+
+impl ChunkedWork for TemplateCountsWork {
+    type Output = TemplateCounts;
+
+    fn step(&mut self, data: &Data, max_rows: usize) -> Option<TemplateCounts> {
+        let end = self.next.saturating_add(max_rows).min(self.rows.len());
+        for &real_index in &self.rows[self.next..end] {
+            let Some(row) = data.row_by_real_index(real_index) else {
+                continue;
+            };
+            let content @ FieldContent::Present(_) = row.field_value(&self.field_name) else {
+                continue;
+            };
+            self.total += 1;
+            let message = content.display();
+            self.counts
+                .entry(message_template(&message))
+                .or_insert_with(|| (0, message))
+                .0 += 1;
+        }
+        self.next = end;
+        if end < self.rows.len() {
+            return None;
+        }
+        let mut templates: Vec<(String, usize, String)> = std::mem::take(&mut self.counts)
+            .into_iter()
+            .map(|(template, (count, example))| (template, count, example))
+            .collect();
+        // Stable so templates with the same count stay in alphabetical order
+        templates.sort_by_key(|(_, count, _)| std::cmp::Reverse(*count));
+        Some(TemplateCounts {
+            templates,
+            total: self.total,
+        })
+    }
+
+    fn progress(&self) -> f32 {
+        self.next as f32 / self.rows.len().max(1) as f32
+    }
+}
+
+impl TemplateIndex {
+    /// Adds the rows not seen yet, starts over if the field changed or rows were removed
+    fn update(&mut self, data: &Data, field_name: &str) {
+        if self.field_name != field_name || data.total_len_unfiltered() < self.row_templates.len() {
+            *self = Self {
+                field_name: field_name.to_string(),
+                ..Default::default()
+            };
+        }
+        for real_index in self.row_templates.len()..data.total_len_unfiltered() {
+            let template = data
+                .row_by_real_index(real_index)
+                .map(|row| row.field_value(field_name))
+                .and_then(|content| match content {
+                    FieldContent::Present(_) => Some(message_template(&content.display())),
+                    FieldContent::Missing => None,
+                });
+            let id = template.map(|template| {
+                let next_id = self.counts.len();
+                let id = *self.templates.entry(template).or_insert(next_id);
+                if id == next_id {
+                    self.counts.push(0);
+                }
+                self.counts[id] += 1;
+                id
+            });
+            self.row_templates.push(id);
+        }
+    }
+
+    /// Number of rows with the same template as the row (None if the row does not have the field)
+    fn template_count(&self, real_index: usize) -> Option<usize> {
+        let id = (*self.row_templates.get(real_index)?)?;
+        Some(self.counts[id])
+    }
+}
+
+impl MessageTemplates {
+    /// Stops updating the counts, used when the window is closed without calling [`Self::show`]
+    pub fn stop_updating(&mut self) {
+        self.cache.stop();
+    }
+
+    pub fn invalidate(&mut self) {
+        self.cache.clear();
+        self.rare_index = Default::default();
+    }
+
+    /// Brings the templates used to find rare rows up to date with the rows, should be called before [`Self::is_rare`]
+    pub fn update_rare(&mut self, data: &Data) {
+        if self.is_rare_highlight_enabled {
+            self.rare_index.update(data, &self.field_name);
+        }
+    }
+
+    /// True if highlighting rare rows is enabled and the template of the row at `real_index` is rare
+    pub fn is_rare(&self, real_index: usize) -> bool {
+        self.is_rare_highlight_enabled
+            && self
+                .rare_index
+                .template_count(real_index)
+                .is_some_and(|count| count < self.rare_below)
+    }
+
+    /// Shows the window if it is open. Returns the filter to apply if a template was clicked
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        data: Option<&Data>,
+        field_names: &[String],
+        tasks: &mut TaskManager,
+    ) -> Option<RowCommand> {
+        let mut is_open = self.is_open;
+        let mut result = None;
+        egui::Window::new("Message Templates")
+            .open(&mut is_open)
+            .show(ctx, |ui| result = self.ui(ui, data, field_names, tasks));
+        self.is_open = is_open;
+        if !is_open {
+            self.cache.stop();
+        }
+        result
+    }
+
+    fn ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        data: Option<&Data>,
+        field_names: &[String],
+        tasks: &mut TaskManager,
+    ) -> Option<RowCommand> {
+        ui.horizontal(|ui| {
+            ui.label("Field");
+            super::field_name_ui(ui, &mut self.field_name, field_names);
+            ui.label("Show");
+            ui.add(egui::DragValue::new(&mut self.max_shown).range(1..=1000));
+        });
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.is_rare_highlight_enabled, "Highlight rows with templates seen fewer than")
+                .on_hover_text("One-off messages among routine ones are often the ones worth reading. Counts are over all rows, ignoring filters");
+            ui.add_enabled(
+                self.is_rare_highlight_enabled,
+                egui::DragValue::new(&mut self.rare_below)
+                    .range(2..=1000)
+                    .suffix(" times"),
+            );
+        });
+        let Some(data) = data else {
+            ui.label("No data");
+            return None;
+        };
+        let field_name = self.field_name.clone();
+        let max_shown = self.max_shown;
+        self.update_counts(ui.ctx(), data, tasks);
+        self.cache.status_ui(ui);
+        let counts = self.cache.output()?;
+        ui.label(format!(
+            "{} templates in {} visible rows with the field",
+            counts.templates.len(),
+            counts.total,
+        ));
+        ui.separator();
+
+        let mut result = None;
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            egui::Grid::new("message templates")
+                .striped(true)
+                .show(ui, |ui| {
+                    for (template, count, example) in counts.templates.iter().take(max_shown) {
+                        ui.label(count.to_string());
+                        if ui
+                            .link(template)
+                            .on_hover_text(format!("Filter to this template\n\nExample: {example}"))
+                            .clicked()
+                        {
+                            result = Some(RowCommand::Filter(FilterConfig {
+                                search_key: template.clone(),
+                                filter_on: FilterOn::Field(FieldSpecifier {
+                                    name: field_name.clone(),
+                                }),
+                                is_case_sensitive: true,
+                                comparator: Comparator::Template,
+                            }));
+                        }
+                        ui.end_row();
+                    }
+                });
+        });
+        result
+    }
+
+    /// Counts are cached as they require checking every visible row
+    fn update_counts(&mut self, ctx: &egui::Context, data: &Data, tasks: &mut TaskManager) {
+        let key = CacheKey {
+            visible_rows_generation: data.visible_rows_generation(),
+            visible_len: data.len(),
+            field_name: self.field_name.clone(),
+        };
+        self.cache
+            .update(ctx, tasks, "Grouping messages", data, key, |key| {
+                TemplateCountsWork::new(data, &key.field_name)
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn messages_grouped_by_template() {
+        let display_options = Default::default();
+        let text = [
+            r#"{"msg": "took 5ms"}"#,
+            r#"{"msg": "started"}"#,
+            r#"{"msg": "took 12ms"}"#,
+            r#"{"other": 1}"#,
+        ]
+        .join("\n");
+        let data = Data::try_from((&display_options, text.as_str())).unwrap();
+        let mut templates = MessageTemplates {
+            is_rare_highlight_enabled: true,
+            rare_below: 2,
+            ..Default::default()
+        };
+        templates.update_rare(&data);
+        assert_eq!(
+            (0..4).map(|i| templates.is_rare(i)).collect::<Vec<_>>(),
+            [false, true, false, false]
+        );
+        let mut work = TemplateCountsWork::new(&data, "msg");
+        assert_eq!(work.step(&data, 2), None);
+        assert_eq!(
+            work.step(&data, 2).unwrap(),
+            TemplateCounts {
+                templates: vec![
+                    ("took {}ms".into(), 2, "took 5ms".into()),
+                    ("started".into(), 1, "started".into()),
+                ],
+                total: 3,
+            }
+        );
+    }
+}
@@ -0,0 +1,198 @@
+use std::collections::BTreeSet;
+
+use egui::{Color32, Rect, Sense};
+
+use super::data::{filter::FilterConfig, Data};
+
+/// Level values that are marked as errors
+const ERROR_LEVELS: [&str; 5] = ["Fatal", "Error", "Emergency", "Alert", "Critical"];
+/// Max number of markers drawn, rows are grouped when there are more rows than this
+const MAX_BUCKETS: usize = 1000;
+const ERROR_COLOR: Color32 = Color32::from_rgb(230, 60, 60);
+
+/// Strip beside the rows with markers for errors and rows matching the search so they can be found at a glance
+#[derive(serde::Deserialize, serde::Serialize, Debug)]
+#[serde(default)]
+pub struct Minimap {
+    pub is_enabled: bool,
+
+    #[serde(skip)]
+    cache: Option<MinimapCache>,
+}
+
+#[derive(Debug)]
+struct MinimapCache {
+    key: CacheKey,
+    /// Each covers an equal share of the visible rows
+    buckets: Vec<Bucket>,
+}
+
+#[derive(Debug, PartialEq)]
+struct CacheKey {
+    visible_rows_generation: u64,
+    visible_len: usize,
+    search: Option<FilterConfig>,
+    level_field_name: String,
+}
+
+/// Positions are indices into the visible rows
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct Bucket {
+    first_error: Option<usize>,
+    first_match: Option<usize>,
+}
+
+impl Default for Minimap {
+    fn default() -> Self {
+        Self {
+            is_enabled: true,
+            cache: None,
+        }
+    }
+}
+
+impl Bucket {
+    /// Row to jump to when clicked, errors are preferred
+    fn target(&self) -> Option<usize> {
+        self.first_error.or(self.first_match)
+    }
+}
+
+impl Minimap {
+    pub fn invalidate(&mut self) {
+        self.cache = None;
+    }
+
+    /// Draws the markers filling the available space. Returns the index into all rows of the row clicked if any
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        data: &mut Data,
+        level_field_name: &str,
+        common_fields: &BTreeSet<String>,
+    ) -> Option<usize> {
+        let key = CacheKey {
+            visible_rows_generation: data.visible_rows_generation(),
+            visible_len: data.len(),
+            // The search only shows extra information if it is not already applied
+            search: data
+                .filter
+                .clone()
+                .filter(|x| !x.search_key.is_empty() && Some(x) != data.applied_filter()),
+            level_field_name: level_field_name.to_string(),
+        };
+        if self.cache.as_ref().is_none_or(|x| x.key != key) {
+            let buckets = buckets(data, &key, common_fields);
+            self.cache = Some(MinimapCache { key, buckets });
+        }
+        let buckets = &self.cache.as_ref()?.buckets;
+
+        let (rect, response) = ui.allocate_exact_size(ui.available_size(), Sense::click());
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 0.0, ui.visuals().extreme_bg_color);
+        if buckets.is_empty() {
+            return None;
+        }
+        let bucket_height = (rect.height() / buckets.len() as f32).max(2.0);
+        let match_color = ui.visuals().selection.bg_fill;
+        for (i, bucket) in buckets.iter().enumerate() {
+            let top = rect.top() + rect.height() * i as f32 / buckets.len() as f32;
+            let marker = |left: f32, right: f32| {
+                Rect::from_min_max(
+                    egui::pos2(left, top),
+                    egui::pos2(right, top + bucket_height),
+                )
+            };
+            if bucket.first_error.is_some() {
+                painter.rect_filled(marker(rect.left(), rect.center().x), 0.0, ERROR_COLOR);
+            }
+            if bucket.first_match.is_some() {
+                painter.rect_filled(marker(rect.center().x, rect.right()), 0.0, match_color);
+            }
+        }
+
+        let response = response.on_hover_text("Red: errors, other: search matches. Click to jump");
+        let pointer = response
+            .interact_pointer_pos()
+            .filter(|_| response.clicked())?;
+        let fraction = ((pointer.y - rect.top()) / rect.height()).clamp(0.0, 1.0);
+        let clicked = ((fraction * buckets.len() as f32) as usize).min(buckets.len() - 1);
+        let position = buckets[clicked].target().unwrap_or_else(|| {
+            // No marker there so go to the proportional row
+            (fraction * (data.len().saturating_sub(1)) as f32) as usize
+        });
+        Some(data.get_real_index(position))
+    }
+}
+
+fn buckets(data: &mut Data, key: &CacheKey, common_fields: &BTreeSet<String>) -> Vec<Bucket> {
+    let n = data.len();
+    let bucket_count = n.min(MAX_BUCKETS);
+    let mut result = vec![Bucket::default(); bucket_count];
+    for position in 0..n {
+        let real_index = data.get_real_index(position);
+        let bucket = &mut result[position * bucket_count / n];
+        let is_error = data.row_by_real_index(real_index).is_some_and(|row| {
+            let level = row.field_value(&key.level_field_name).display();
+            ERROR_LEVELS.contains(&level.as_str())
+        });
+        if is_error && bucket.first_error.is_none() {
+            bucket.first_error = Some(position);
+        }
+        if let Some(search) = key.search.as_ref() {
+            if bucket.first_match.is_none() && data.row_matches(real_index, search, common_fields) {
+                bucket.first_match = Some(position);
+            }
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::core::data_display_options::DataDisplayOptions;
+
+    #[test]
+    fn buckets_mark_first_error_and_match() {
+        let display_options = DataDisplayOptions::default();
+        let text = [
+            r#"{"level": 30, "msg": "a"}"#,
+            r#"{"level": 50, "msg": "b"}"#,
+            r#"{"level": 30, "msg": "find me"}"#,
+            r#"{"level": 60, "msg": "find me"}"#,
+        ]
+        .join("\n");
+        let mut data = Data::try_from((&display_options, text.as_str())).unwrap();
+        let key = CacheKey {
+            visible_rows_generation: 0,
+            visible_len: 4,
+            search: Some(FilterConfig {
+                search_key: "find".into(),
+                ..Default::default()
+            }),
+            level_field_name: "level_str".into(),
+        };
+        let none = Bucket::default();
+        assert_eq!(
+            buckets(&mut data, &key, display_options.common_fields()),
+            vec![
+                none,
+                Bucket {
+                    first_error: Some(1),
+                    first_match: None
+                },
+                Bucket {
+                    first_error: None,
+                    first_match: Some(2)
+                },
+                Bucket {
+                    first_error: Some(3),
+                    first_match: Some(3)
+                },
+            ]
+        );
+    }
+}
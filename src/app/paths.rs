@@ -0,0 +1,131 @@
+use std::path::{Path, PathBuf};
+
+/// Expands a leading `~`, environment variables (`$VAR` or `${VAR}`) and makes relative paths absolute
+/// (relative to the current working directory)
+///
+/// Variables that are not set are left unchanged
+pub fn expand(path: &Path) -> PathBuf {
+    let result = expand_with(path, home_dir().as_deref(), |name| std::env::var(name).ok());
+    if result.is_relative() {
+        if let Ok(cwd) = std::env::current_dir() {
+            return cwd.join(result);
+        }
+    }
+    result
+}
+
+/// Replaces the home directory prefix with `~` so the path is not specific to one user
+pub fn contract_home(path: &Path) -> PathBuf {
+    match home_dir() {
+        Some(home) => contract_home_with(path, &home),
+        None => path.to_path_buf(),
+    }
+}
+
+fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .filter(|x| !x.is_empty())
+        .map(PathBuf::from)
+}
+
+fn contract_home_with(path: &Path, home: &Path) -> PathBuf {
+    match path.strip_prefix(home) {
+        Ok(rest) if rest.as_os_str().is_empty() => PathBuf::from("~"),
+        Ok(rest) => Path::new("~").join(rest),
+        Err(_) => path.to_path_buf(),
+    }
+}
+
+fn expand_with(
+    path: &Path,
+    home: Option<&Path>,
+    lookup_var: impl Fn(&str) -> Option<String>,
+) -> PathBuf {
+    let Some(s) = path.to_str() else {
+        // Not valid unicode so we cannot do any substitution
+        return path.to_path_buf();
+    };
+    let s = expand_vars(s, lookup_var);
+
+    if let Some(home) = home {
+        if s == "~" {
+            return home.to_path_buf();
+        }
+        if let Some(rest) = s.strip_prefix("~/").or_else(|| s.strip_prefix("~\\")) {
+            return home.join(rest);
+        }
+    }
+    PathBuf::from(s)
+}
+
+fn expand_vars(s: &str, lookup_var: impl Fn(&str) -> Option<String>) -> String {
+    let mut result = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(idx) = rest.find('$') {
+        result.push_str(&rest[..idx]);
+        rest = &rest[idx + 1..];
+        let (name, raw, remaining) = if let Some(braced) = rest.strip_prefix('{') {
+            match braced.find('}') {
+                Some(end) => (&braced[..end], &rest[..end + 2], &braced[end + 1..]),
+                None => ("", "", rest), // Unterminated, leave as is
+            }
+        } else {
+            let end = rest
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(rest.len());
+            (&rest[..end], &rest[..end], &rest[end..])
+        };
+        match (name.is_empty(), lookup_var(name)) {
+            (false, Some(value)) => result.push_str(&value),
+            _ => {
+                result.push('$');
+                result.push_str(raw);
+            }
+        }
+        rest = remaining;
+    }
+    result.push_str(rest);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+
+    fn lookup(name: &str) -> Option<String> {
+        match name {
+            "LOGS" => Some("/var/log".into()),
+            "APP" => Some("my_app".into()),
+            _ => None,
+        }
+    }
+
+    #[rstest]
+    #[case::home_only("~", "/home/user")]
+    #[case::home_prefix("~/logs", "/home/user/logs")]
+    #[case::tilde_not_prefix("/tmp/~/logs", "/tmp/~/logs")]
+    #[case::var("$LOGS/app", "/var/log/app")]
+    #[case::braced_var("${LOGS}/${APP}_old", "/var/log/my_app_old")]
+    #[case::unknown_var("$MISSING/app", "$MISSING/app")]
+    #[case::unterminated("${LOGS/app", "${LOGS/app")]
+    #[case::lone_dollar("/tmp/$/x", "/tmp/$/x")]
+    #[case::relative("logs/app", "logs/app")]
+    fn expansion(#[case] input: &str, #[case] expected: &str) {
+        let actual = expand_with(Path::new(input), Some(Path::new("/home/user")), lookup);
+        assert_eq!(actual, PathBuf::from(expected));
+    }
+
+    #[rstest]
+    #[case::inside_home("/home/user/logs", "~/logs")]
+    #[case::home_itself("/home/user", "~")]
+    #[case::outside_home("/var/log", "/var/log")]
+    #[case::similar_prefix("/home/user2/logs", "/home/user2/logs")]
+    fn contraction(#[case] input: &str, #[case] expected: &str) {
+        let actual = contract_home_with(Path::new(input), Path::new("/home/user"));
+        assert_eq!(actual, PathBuf::from(expected));
+    }
+}
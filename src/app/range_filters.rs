@@ -0,0 +1,190 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use egui::{Rect, Sense, Vec2};
+
+use super::data::{filter::RangeFilter, Data};
+
+const HISTOGRAM_BINS: usize = 40;
+const HISTOGRAM_SIZE: Vec2 = Vec2::new(200.0, 20.0);
+
+/// Min/max sliders for numeric fields with a histogram showing the distribution of values
+#[derive(Default, Debug)]
+pub struct RangeFiltersUi {
+    /// Computed from the data on first use and cleared when new data is loaded
+    stats: BTreeMap<String, Option<FieldStats>>,
+    /// Current slider values which may not be applied yet (eg. during a drag or when disabled)
+    pending: BTreeMap<String, (f64, f64)>,
+    new_field_name: String,
+}
+
+#[derive(Debug)]
+struct FieldStats {
+    min: f64,
+    max: f64,
+    histogram: Vec<usize>,
+}
+
+impl FieldStats {
+    /// Returns None if there are no numeric values
+    fn new(values: &[f64]) -> Option<Self> {
+        let min = values.iter().copied().reduce(f64::min)?;
+        let max = values.iter().copied().reduce(f64::max)?;
+        let mut histogram = vec![0; HISTOGRAM_BINS];
+        for value in values {
+            histogram[Self::bin(min, max, *value)] += 1;
+        }
+        Some(Self {
+            min,
+            max,
+            histogram,
+        })
+    }
+
+    fn bin(min: f64, max: f64, value: f64) -> usize {
+        if max <= min {
+            return 0;
+        }
+        (((value - min) / (max - min)) * HISTOGRAM_BINS as f64)
+            .floor()
+            .clamp(0.0, (HISTOGRAM_BINS - 1) as f64) as usize
+    }
+
+    /// Draws the histogram highlighting the bins inside the selected range
+    fn show_histogram(&self, ui: &mut egui::Ui, (lo, hi): (f64, f64)) {
+        let (rect, _) = ui.allocate_exact_size(HISTOGRAM_SIZE, Sense::hover());
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 2.0, ui.visuals().extreme_bg_color);
+        let tallest = self
+            .histogram
+            .iter()
+            .copied()
+            .max()
+            .unwrap_or_default()
+            .max(1) as f32;
+        let bin_width = rect.width() / HISTOGRAM_BINS as f32;
+        let (lo_bin, hi_bin) = (
+            Self::bin(self.min, self.max, lo),
+            Self::bin(self.min, self.max, hi),
+        );
+        for (i, count) in self.histogram.iter().enumerate() {
+            let height = rect.height() * (*count as f32 / tallest);
+            let left = rect.left() + i as f32 * bin_width;
+            let bar = Rect::from_min_max(
+                egui::pos2(left, rect.bottom() - height),
+                egui::pos2(left + bin_width, rect.bottom()),
+            );
+            let color = if (lo_bin..=hi_bin).contains(&i) {
+                ui.visuals().selection.bg_fill
+            } else {
+                ui.visuals().weak_text_color()
+            };
+            painter.rect_filled(bar, 0.0, color);
+        }
+    }
+}
+
+impl RangeFiltersUi {
+    pub fn invalidate(&mut self) {
+        self.stats.clear();
+        self.pending.clear();
+    }
+
+    pub fn ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        data: &mut Data,
+        field_names: &mut Vec<String>,
+        common_fields: &BTreeSet<String>,
+    ) {
+        let mut range_filters = data.range_filters().to_vec();
+        let mut should_apply = false;
+        let mut field_to_remove = None;
+
+        egui::Grid::new("range filters").show(ui, |ui| {
+            for field_name in field_names.iter() {
+                let stats = self
+                    .stats
+                    .entry(field_name.clone())
+                    .or_insert_with(|| FieldStats::new(&data.numeric_values(field_name)));
+                let existing = range_filters
+                    .iter()
+                    .position(|x| &x.field_name == field_name);
+
+                let mut is_enabled = existing.is_some();
+                ui.checkbox(&mut is_enabled, field_name);
+                let Some(stats) = stats else {
+                    ui.label("(no numeric values)");
+                    ui.end_row();
+                    continue;
+                };
+                let (mut lo, mut hi) =
+                    *self.pending.entry(field_name.clone()).or_insert_with(|| {
+                        existing.map_or((stats.min, stats.max), |i| {
+                            (range_filters[i].min, range_filters[i].max)
+                        })
+                    });
+
+                stats.show_histogram(ui, (lo, hi));
+                let lo_response = ui.add(egui::Slider::new(&mut lo, stats.min..=stats.max));
+                let hi_response = ui.add(egui::Slider::new(&mut hi, stats.min..=stats.max));
+                if lo > hi {
+                    std::mem::swap(&mut lo, &mut hi);
+                }
+                // Only apply when the user lets go to avoid filtering on every frame of a drag
+                let is_edit_done = [lo_response, hi_response]
+                    .iter()
+                    .any(|x| x.drag_stopped() || (x.changed() && !x.dragged()));
+
+                self.pending.insert(field_name.clone(), (lo, hi));
+                match (is_enabled, existing) {
+                    (true, Some(i)) => {
+                        if is_edit_done {
+                            range_filters[i].min = lo;
+                            range_filters[i].max = hi;
+                            should_apply = true;
+                        }
+                    }
+                    (true, None) => {
+                        range_filters.push(RangeFilter {
+                            field_name: field_name.clone(),
+                            min: lo,
+                            max: hi,
+                        });
+                        should_apply = true;
+                    }
+                    (false, Some(i)) => {
+                        range_filters.remove(i);
+                        should_apply = true;
+                    }
+                    (false, None) => {}
+                }
+                if ui.small_button("🗑").on_hover_text("Remove field").clicked() {
+                    field_to_remove = Some(field_name.clone());
+                }
+                ui.end_row();
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.add(egui::TextEdit::singleline(&mut self.new_field_name).hint_text("Field name"));
+            if ui.button("Add Range Filter Field").clicked()
+                && !self.new_field_name.is_empty()
+                && !field_names.contains(&self.new_field_name)
+            {
+                field_names.push(std::mem::take(&mut self.new_field_name));
+            }
+        });
+
+        if let Some(field_name) = field_to_remove {
+            field_names.retain(|x| x != &field_name);
+            self.pending.remove(&field_name);
+            let before = range_filters.len();
+            range_filters.retain(|x| x.field_name != field_name);
+            should_apply |= before != range_filters.len();
+        }
+
+        if should_apply {
+            data.set_range_filters(range_filters, common_fields);
+        }
+    }
+}
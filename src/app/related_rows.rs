@@ -0,0 +1,96 @@
+use std::collections::BTreeMap;
+
+use super::data::Data;
+
+/// Rows sharing the value of a correlation key (eg. request_id) with the selected row
+#[derive(Debug, Clone)]
+pub struct RelatedRows {
+    /// Number of rows with the same value excluding the selected row
+    pub other_count: usize,
+    /// The next row (wrapping around) with the same value as an index into all rows
+    pub next: Option<usize>,
+}
+
+/// Caches the related rows for the selected row as finding them requires checking every row
+#[derive(Default, Debug)]
+pub struct RelatedRowsCache {
+    /// Real index of the row and the fields that the cache was computed for
+    key: Option<(usize, Vec<String>)>,
+    related: BTreeMap<String, RelatedRows>,
+}
+
+impl RelatedRows {
+    fn new(real_index: usize, same_value: &[usize]) -> Self {
+        let next = same_value
+            .iter()
+            .find(|&&i| i > real_index)
+            .or_else(|| same_value.first())
+            .copied()
+            .filter(|&i| i != real_index);
+        Self {
+            other_count: same_value.len().saturating_sub(1),
+            next,
+        }
+    }
+}
+
+impl RelatedRowsCache {
+    pub fn invalidate(&mut self) {
+        self.key = None;
+        self.related.clear();
+    }
+
+    /// Returns the related rows for each of `correlation_fields` that the selected row has
+    pub fn get(
+        &mut self,
+        data: &Data,
+        correlation_fields: &[String],
+    ) -> &BTreeMap<String, RelatedRows> {
+        let Some(real_index) = data.selected_real_index() else {
+            self.invalidate();
+            return &self.related;
+        };
+        let is_valid = self
+            .key
+            .as_ref()
+            .is_some_and(|(i, fields)| *i == real_index && fields == correlation_fields);
+        if !is_valid {
+            self.related = correlation_fields
+                .iter()
+                .filter_map(|field_name| {
+                    let same_value = data.real_indices_with_same_value(real_index, field_name);
+                    (!same_value.is_empty()).then(|| {
+                        (
+                            field_name.clone(),
+                            RelatedRows::new(real_index, &same_value),
+                        )
+                    })
+                })
+                .collect();
+            self.key = Some((real_index, correlation_fields.to_vec()));
+        }
+        &self.related
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::only_self(1, &[1], 0, None)]
+    #[case::next_after(1, &[0, 1, 3], 2, Some(3))]
+    #[case::wraps_around(3, &[0, 1, 3], 2, Some(0))]
+    fn next_related_row(
+        #[case] real_index: usize,
+        #[case] same_value: &[usize],
+        #[case] expected_count: usize,
+        #[case] expected_next: Option<usize>,
+    ) {
+        let actual = RelatedRows::new(real_index, same_value);
+        assert_eq!(actual.other_count, expected_count);
+        assert_eq!(actual.next, expected_next);
+    }
+}
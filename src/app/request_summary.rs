@@ -0,0 +1,366 @@
+use std::{cmp::Ordering, collections::BTreeMap};
+
+use super::{
+    data::{Data, FieldContent},
+    data_display_options::DataDisplayOptions,
+    row_actions::{equal_to_value_filter, RowCommand},
+    tasks::{ChunkedCache, ChunkedWork, TaskManager},
+};
+
+/// Table of the requests in the data (rows grouped by a request id) to find the slowest ones quickly
+#[derive(serde::Deserialize, serde::Serialize, Debug)]
+#[serde(default)]
+pub struct RequestSummary {
+    pub is_open: bool,
+    pub id_field_name: String,
+    /// Numeric field with the time taken, the largest value of a request is used as its duration
+    pub duration_field_name: String,
+    sort_column: SortColumn,
+    is_descending: bool,
+
+    /// Only valid for the data it was computed on, cleared when the data changes
+    #[serde(skip)]
+    cache: ChunkedCache<CacheKey, SummaryWork>,
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum SortColumn {
+    Id,
+    #[default]
+    Duration,
+    Rows,
+    Level,
+}
+
+#[derive(Debug, PartialEq)]
+struct CacheKey {
+    total_len: usize,
+    id_field_name: String,
+    duration_field_name: String,
+    level_field_name: String,
+}
+
+/// Groups all rows (ignoring filters) by the value of the id field a chunk of rows at a time, rows without it are skipped
+struct SummaryWork {
+    id_field_name: String,
+    duration_field_name: String,
+    level_field_name: String,
+    /// In order of increasing severity
+    level_names: Vec<String>,
+    total_len: usize,
+    /// Index into all rows of the next row
+    next: usize,
+    requests: BTreeMap<String, RequestStats>,
+}
+
+#[derive(Debug, PartialEq)]
+struct RequestStats {
+    id: String,
+    duration_ms: Option<f64>,
+    row_count: usize,
+    /// Index into the level names (higher is more severe)
+    max_level: Option<usize>,
+}
+
+impl Default for RequestSummary {
+    fn default() -> Self {
+        Self {
+            is_open: false,
+            id_field_name: "request_id".into(),
+            duration_field_name: "elapsed_milliseconds".into(),
+            sort_column: Default::default(),
+            is_descending: true,
+            cache: Default::default(),
+        }
+    }
+}
+
+impl SortColumn {
+    fn label(self) -> &'static str {
+        match self {
+            SortColumn::Id => "Request",
+            SortColumn::Duration => "Duration (ms)",
+            SortColumn::Rows => "Rows",
+            SortColumn::Level => "Max Level",
+        }
+    }
+
+    fn compare(self, a: &RequestStats, b: &RequestStats) -> Ordering {
+        match self {
+            SortColumn::Id => a.id.cmp(&b.id),
+            // None is treated as less than any duration
+            SortColumn::Duration => a
+                .duration_ms
+                .partial_cmp(&b.duration_ms)
+                .unwrap_or(Ordering::Equal),
+            SortColumn::Rows => a.row_count.cmp(&b.row_count),
+            SortColumn::Level => a.max_level.cmp(&b.max_level),
+        }
+    }
+}
+
+impl SummaryWork {
+    fn new(key: &CacheKey, level_names: &[&str]) -> Self {
+        Self {
+            id_field_name: key.id_field_name.clone(),
+            duration_field_name: key.duration_field_name.clone(),
+            level_field_name: key.level_field_name.clone(),
+            level_names: level_names.iter().map(|x| x.to_string()).collect(),
+            total_len: key.total_len,
+            next: 0,
+            requests: BTreeMap::new(),
+        }
+    }
+}
+
+impl ChunkedWork for SummaryWork {
+    /// Ordered by id
+    type Output = Vec<RequestStats>;
+
+    fn step(&mut self, data: &Data, max_rows: usize) -> Option<Vec<RequestStats>> {
+        let end = self.next.saturating_add(max_rows).min(self.total_len);
+        for real_index in self.next..end {
+            let Some(row) = data.row_by_real_index(real_index) else {
+                continue;
+            };
+            let id = match row.field_value(&self.id_field_name) {
+                content @ FieldContent::Present(_) => content.display(),
+                FieldContent::Missing => continue,
+            };
+            let stats = self
+                .requests
+                .entry(id.clone())
+                .or_insert_with(|| RequestStats {
+                    id,
+                    duration_ms: None,
+                    row_count: 0,
+                    max_level: None,
+                });
+            stats.row_count += 1;
+            if let Some(duration) = row.field_value(&self.duration_field_name).as_f64() {
+                stats.duration_ms = Some(stats.duration_ms.map_or(duration, |x| x.max(duration)));
+            }
+            let level = row.field_value(&self.level_field_name).display();
+            let level = self.level_names.iter().position(|x| *x == level);
+            stats.max_level = stats.max_level.max(level);
+        }
+        self.next = end;
+        (end == self.total_len).then(|| std::mem::take(&mut self.requests).into_values().collect())
+    }
+
+    fn progress(&self) -> f32 {
+        self.next as f32 / self.total_len.max(1) as f32
+    }
+}
+
+impl RequestSummary {
+    pub fn invalidate(&mut self) {
+        self.cache.clear();
+    }
+
+    /// Shows the window if it is open. Returns the filter to apply if a request was clicked
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        data: Option<&Data>,
+        display_options: &DataDisplayOptions,
+        tasks: &mut TaskManager,
+    ) -> Option<RowCommand> {
+        let mut is_open = self.is_open;
+        let mut result = None;
+        egui::Window::new("Request Summary")
+            .open(&mut is_open)
+            .show(ctx, |ui| result = self.ui(ui, data, display_options, tasks));
+        self.is_open = is_open;
+        if !is_open {
+            self.cache.stop();
+        }
+        result
+    }
+
+    fn ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        data: Option<&Data>,
+        display_options: &DataDisplayOptions,
+        tasks: &mut TaskManager,
+    ) -> Option<RowCommand> {
+        egui::Grid::new("request summary fields").show(ui, |ui| {
+            ui.label("Request id field");
+            ui.text_edit_singleline(&mut self.id_field_name);
+            ui.end_row();
+            ui.label("Duration field");
+            ui.text_edit_singleline(&mut self.duration_field_name);
+            ui.end_row();
+        });
+        let Some(data) = data else {
+            ui.label("No data");
+            return None;
+        };
+        ui.separator();
+
+        let level_names = display_options.level_names();
+        self.refresh(
+            ui.ctx(),
+            tasks,
+            data,
+            display_options.level_field_name(),
+            &level_names,
+        );
+        self.cache.status_ui(ui);
+        let requests = self.cache.output().map(Vec::as_slice).unwrap_or_default();
+        ui.label(format!("{} requests", requests.len()));
+
+        let mut result = None;
+        let mut clicked_column = None;
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            egui::Grid::new("request summary")
+                .striped(true)
+                .show(ui, |ui| {
+                    for column in [
+                        SortColumn::Id,
+                        SortColumn::Duration,
+                        SortColumn::Rows,
+                        SortColumn::Level,
+                    ] {
+                        let text = match (column == self.sort_column, self.is_descending) {
+                            (true, true) => format!("{} ⏷", column.label()),
+                            (true, false) => format!("{} ⏶", column.label()),
+                            (false, _) => column.label().to_string(),
+                        };
+                        if ui.button(text).on_hover_text("Sort").clicked() {
+                            clicked_column = Some(column);
+                        }
+                    }
+                    ui.end_row();
+                    for request in requests {
+                        if ui
+                            .link(&request.id)
+                            .on_hover_text("Filter to this request")
+                            .clicked()
+                        {
+                            result = Some(RowCommand::Filter(equal_to_value_filter(
+                                self.id_field_name.clone(),
+                                request.id.clone(),
+                            )));
+                        }
+                        match request.duration_ms {
+                            Some(duration) => ui.label(duration.to_string()),
+                            None => ui.weak("-"),
+                        };
+                        ui.label(request.row_count.to_string());
+                        match request.max_level.and_then(|x| level_names.get(x)) {
+                            Some(level) => {
+                                let text = egui::RichText::new(*level);
+                                let text = match display_options
+                                    .field_color(display_options.level_field_name(), level)
+                                {
+                                    Some(color) => text.color(color),
+                                    None => text,
+                                };
+                                ui.label(text)
+                            }
+                            None => ui.weak("-"),
+                        };
+                        ui.end_row();
+                    }
+                });
+        });
+        if let Some(column) = clicked_column {
+            if column == self.sort_column {
+                self.is_descending = !self.is_descending;
+            } else {
+                self.sort_column = column;
+                self.is_descending = column != SortColumn::Id;
+            }
+            self.sort();
+        }
+        result
+    }
+
+    /// Recomputes the summary if the data or fields changed
+    fn refresh(
+        &mut self,
+        ctx: &egui::Context,
+        tasks: &mut TaskManager,
+        data: &Data,
+        level_field_name: &str,
+        level_names: &[&str],
+    ) {
+        let key = CacheKey {
+            total_len: data.total_len_unfiltered(),
+            id_field_name: self.id_field_name.clone(),
+            duration_field_name: self.duration_field_name.clone(),
+            level_field_name: level_field_name.to_string(),
+        };
+        if self
+            .cache
+            .update(ctx, tasks, "Summarizing requests", data, key, |key| {
+                SummaryWork::new(key, level_names)
+            })
+        {
+            self.sort();
+        }
+    }
+
+    fn sort(&mut self) {
+        let Some(requests) = self.cache.output_mut() else {
+            return;
+        };
+        let column = self.sort_column;
+        if self.is_descending {
+            requests.sort_by(|a, b| column.compare(b, a));
+        } else {
+            requests.sort_by(|a, b| column.compare(a, b));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn requests_grouped_by_id() {
+        let display_options = DataDisplayOptions::default();
+        let text = [
+            r#"{"request_id": "a", "level": 30}"#,
+            r#"{"request_id": "b", "level": 50, "elapsed_milliseconds": 7}"#,
+            r#"{"msg": "no request"}"#,
+            r#"{"request_id": "a", "level": 40, "elapsed_milliseconds": 12.5}"#,
+            r#"{"request_id": "a", "level": 30, "elapsed_milliseconds": 3}"#,
+        ]
+        .join("\n");
+        let data = Data::try_from((&display_options, text.as_str())).unwrap();
+        let level_names = display_options.level_names();
+        let key = CacheKey {
+            total_len: data.total_len_unfiltered(),
+            id_field_name: "request_id".into(),
+            duration_field_name: "elapsed_milliseconds".into(),
+            level_field_name: display_options.level_field_name().to_string(),
+        };
+        let mut work = SummaryWork::new(&key, &level_names);
+        assert_eq!(work.step(&data, 3), None);
+        let actual = work.step(&data, 3).unwrap();
+        let level = |name| level_names.iter().position(|x| *x == name);
+        assert_eq!(
+            actual,
+            vec![
+                RequestStats {
+                    id: "a".into(),
+                    duration_ms: Some(12.5),
+                    row_count: 3,
+                    max_level: level("Warn"),
+                },
+                RequestStats {
+                    id: "b".into(),
+                    duration_ms: Some(7.0),
+                    row_count: 1,
+                    max_level: level("Error"),
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,304 @@
+use std::collections::BTreeMap;
+
+use egui_plot::{Bar, BarChart, Plot};
+
+use super::{
+    data::{Data, FieldContent},
+    data_display_options::DataDisplayOptions,
+    field_plot::time_axis_text,
+    row_actions::{equal_to_value_filter, RowCommand},
+};
+
+/// Most requests drawn, the earliest ones are kept
+const MAX_BARS: usize = 2_000;
+
+/// Fraction of the space between requests taken by a bar
+const BAR_THICKNESS: f64 = 0.8;
+
+/// Timeline with a bar for each request (rows grouped by a request id) from its first to its last row
+#[derive(serde::Deserialize, serde::Serialize, Debug)]
+#[serde(default)]
+pub struct RequestWaterfall {
+    pub is_open: bool,
+    pub id_field_name: String,
+    /// Expected to hold RFC 3339 timestamps
+    pub time_field_name: String,
+
+    /// Only valid for the data it was computed on, cleared when the data changes
+    #[serde(skip)]
+    cache: Option<WaterfallCache>,
+}
+
+#[derive(Debug)]
+struct WaterfallCache {
+    key: CacheKey,
+    requests: Vec<RequestSpan>,
+}
+
+#[derive(Debug, PartialEq)]
+struct CacheKey {
+    total_len: usize,
+    id_field_name: String,
+    time_field_name: String,
+    level_field_name: String,
+}
+
+#[derive(Debug, PartialEq)]
+struct RequestSpan {
+    id: String,
+    /// Seconds since the unix epoch
+    start: f64,
+    end: f64,
+    /// Index into the level names (higher is more severe)
+    max_level: Option<usize>,
+}
+
+impl Default for RequestWaterfall {
+    fn default() -> Self {
+        Self {
+            is_open: false,
+            id_field_name: "request_id".into(),
+            time_field_name: "time".into(),
+            cache: None,
+        }
+    }
+}
+
+/// Groups all rows (ignoring filters) with a time by the value of `id_field_name`. Ordered by start time
+fn request_spans(
+    data: &Data,
+    id_field_name: &str,
+    time_field_name: &str,
+    level_field_name: &str,
+    level_names: &[&str],
+) -> Vec<RequestSpan> {
+    let mut requests: BTreeMap<String, RequestSpan> = BTreeMap::new();
+    for real_index in 0..data.total_len_unfiltered() {
+        let Some(row) = data.row_by_real_index(real_index) else {
+            continue;
+        };
+        let id = match row.field_value(id_field_name) {
+            content @ FieldContent::Present(_) => content.display(),
+            FieldContent::Missing => continue,
+        };
+        let Some(time) = row.field_value(time_field_name).as_time() else {
+            continue;
+        };
+        let time = time.timestamp_millis() as f64 / 1000.0;
+        let span = requests.entry(id.clone()).or_insert_with(|| RequestSpan {
+            id,
+            start: time,
+            end: time,
+            max_level: None,
+        });
+        span.start = span.start.min(time);
+        span.end = span.end.max(time);
+        let level = row.field_value(level_field_name).display();
+        let level = level_names.iter().position(|x| *x == level);
+        span.max_level = span.max_level.max(level);
+    }
+    let mut result: Vec<RequestSpan> = requests.into_values().collect();
+    // Stable so requests that start at the same time stay ordered by id
+    result.sort_by(|a, b| a.start.total_cmp(&b.start));
+    result
+}
+
+impl RequestWaterfall {
+    pub fn invalidate(&mut self) {
+        self.cache = None;
+    }
+
+    /// Shows the window if it is open. Returns the filter to apply if a request was clicked
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        data: Option<&Data>,
+        display_options: &DataDisplayOptions,
+        field_names: &[String],
+    ) -> Option<RowCommand> {
+        let mut is_open = self.is_open;
+        let mut result = None;
+        egui::Window::new("Request Waterfall")
+            .open(&mut is_open)
+            .default_size([600.0, 400.0])
+            .show(ctx, |ui| {
+                result = self.ui(ui, data, display_options, field_names)
+            });
+        self.is_open = is_open;
+        result
+    }
+
+    fn ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        data: Option<&Data>,
+        display_options: &DataDisplayOptions,
+        field_names: &[String],
+    ) -> Option<RowCommand> {
+        ui.horizontal(|ui| {
+            ui.label("Request id field");
+            super::field_name_ui(ui, &mut self.id_field_name, field_names);
+            ui.label("Time field");
+            super::field_name_ui(ui, &mut self.time_field_name, field_names);
+        });
+        let Some(data) = data else {
+            ui.label("No data");
+            return None;
+        };
+
+        let level_names = display_options.level_names();
+        self.refresh(data, display_options.level_field_name(), &level_names);
+        let requests = self
+            .cache
+            .as_ref()
+            .map(|x| x.requests.as_slice())
+            .unwrap_or_default();
+        let shown = &requests[..requests.len().min(MAX_BARS)];
+        if shown.len() < requests.len() {
+            ui.label(format!(
+                "Showing the first {} of {} requests (click a bar to filter to its request)",
+                shown.len(),
+                requests.len()
+            ));
+        } else {
+            ui.label(format!(
+                "{} requests (click a bar to filter to its request, scroll and drag to zoom and pan)",
+                requests.len()
+            ));
+        }
+
+        let default_color = ui.visuals().selection.bg_fill;
+        let bars = shown
+            .iter()
+            .enumerate()
+            .map(|(i, request)| {
+                let color = request
+                    .max_level
+                    .and_then(|x| level_names.get(x))
+                    .and_then(|level| {
+                        display_options.field_color(display_options.level_field_name(), level)
+                    })
+                    .unwrap_or(default_color);
+                // Later requests are lower down like rows in the table
+                Bar::new(-(i as f64), request.end - request.start)
+                    .base_offset(request.start)
+                    .width(BAR_THICKNESS)
+                    .fill(color)
+                    .stroke(egui::Stroke::new(1.0, color))
+                    .name(&request.id)
+            })
+            .collect();
+        let chart = BarChart::new(bars)
+            .horizontal()
+            .element_formatter(Box::new(|bar, _| {
+                format!(
+                    "{}\n{} + {:.3} s",
+                    bar.name,
+                    time_axis_text(bar.base_offset.unwrap_or_default()),
+                    bar.value
+                )
+            }));
+
+        let clicked_index = Plot::new("request waterfall")
+            .x_axis_formatter(|mark, _| time_axis_text(mark.value))
+            .y_axis_formatter(|mark, _| {
+                let index = -mark.value;
+                let is_whole = index.fract() == 0.0 && index >= 0.0;
+                is_whole
+                    .then(|| shown.get(index as usize))
+                    .flatten()
+                    .map(|x| x.id.clone())
+                    .unwrap_or_default()
+            })
+            .show(ui, |plot_ui| {
+                plot_ui.bar_chart(chart);
+                if !plot_ui.response().clicked() {
+                    return None;
+                }
+                let pointer = plot_ui.pointer_coordinate()?;
+                let index = (-pointer.y).round();
+                let is_on_bar = index >= 0.0 && (index + pointer.y).abs() <= BAR_THICKNESS / 2.0;
+                is_on_bar.then_some(index as usize)
+            })
+            .inner;
+        let request = shown.get(clicked_index?)?;
+        Some(RowCommand::Filter(equal_to_value_filter(
+            self.id_field_name.clone(),
+            request.id.clone(),
+        )))
+    }
+
+    /// Recomputes the requests if the data or fields changed
+    fn refresh(&mut self, data: &Data, level_field_name: &str, level_names: &[&str]) {
+        let key = CacheKey {
+            total_len: data.total_len_unfiltered(),
+            id_field_name: self.id_field_name.clone(),
+            time_field_name: self.time_field_name.clone(),
+            level_field_name: level_field_name.to_string(),
+        };
+        if self.cache.as_ref().is_some_and(|x| x.key == key) {
+            return;
+        }
+        let requests = request_spans(
+            data,
+            &key.id_field_name,
+            &key.time_field_name,
+            &key.level_field_name,
+            level_names,
+        );
+        self.cache = Some(WaterfallCache { key, requests });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn requests_span_first_to_last_row() {
+        let display_options = DataDisplayOptions::default();
+        let text = [
+            r#"{"request_id": "b", "time": "1970-01-01T00:00:02Z", "level": 30}"#,
+            r#"{"request_id": "a", "time": "1970-01-01T00:00:03Z", "level": 50}"#,
+            r#"{"request_id": "b", "time": "1970-01-01T00:00:05.5Z", "level": 40}"#,
+            r#"{"request_id": "a", "level": 60}"#,
+            r#"{"time": "1970-01-01T00:00:01Z"}"#,
+            r#"{"request_id": "c", "time": "1970-01-01T00:00:03Z"}"#,
+        ]
+        .join("\n");
+        let data = Data::try_from((&display_options, text.as_str())).unwrap();
+        let level_names = display_options.level_names();
+        let level = |name| level_names.iter().position(|x| *x == name);
+        assert_eq!(
+            request_spans(
+                &data,
+                "request_id",
+                "time",
+                display_options.level_field_name(),
+                &level_names
+            ),
+            vec![
+                RequestSpan {
+                    id: "b".into(),
+                    start: 2.0,
+                    end: 5.5,
+                    max_level: level("Warn"),
+                },
+                RequestSpan {
+                    id: "a".into(),
+                    start: 3.0,
+                    end: 3.0,
+                    max_level: level("Error"),
+                },
+                RequestSpan {
+                    id: "c".into(),
+                    start: 3.0,
+                    end: 3.0,
+                    max_level: None,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,73 @@
+/// Lets the user choose which parts of the saved state to reset to recover from a bad state
+#[derive(serde::Deserialize, serde::Serialize, Default, Debug)]
+#[serde(default)]
+pub struct ResetDialog {
+    pub is_open: bool,
+    #[serde(skip)]
+    choices: ResetChoices,
+}
+
+/// The parts of the state to reset
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResetChoices {
+    /// Display options and profiles, watches, shortcuts and general options (not the settings of tool windows)
+    pub should_reset_settings: bool,
+    /// Window positions, panel sizes and other UI state kept by egui
+    pub should_reset_layout: bool,
+    /// The loaded data including its filters
+    pub should_reset_data: bool,
+    pub should_reset_annotations: bool,
+}
+
+impl ResetChoices {
+    fn is_any(&self) -> bool {
+        *self != Self::default()
+    }
+}
+
+impl ResetDialog {
+    /// Shows the dialog if it is open. Returns the choices when the user confirms
+    pub fn show(&mut self, ctx: &egui::Context) -> Option<ResetChoices> {
+        let mut is_open = self.is_open;
+        let mut result = None;
+        egui::Window::new("Reset")
+            .open(&mut is_open)
+            .collapsible(false)
+            .show(ctx, |ui| result = self.ui(ui));
+        self.is_open = is_open && result.is_none();
+        result
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui) -> Option<ResetChoices> {
+        let ResetChoices {
+            should_reset_settings,
+            should_reset_layout,
+            should_reset_data,
+            should_reset_annotations,
+        } = &mut self.choices;
+        ui.label("Choose what to restore to the defaults:");
+        ui.checkbox(should_reset_settings, "Settings")
+            .on_hover_text(
+                "Display options and profiles, watches, shortcuts and general options \
+                (tool windows like the trace view keep their settings)",
+            );
+        ui.checkbox(should_reset_layout, "Layout")
+            .on_hover_text("Window positions, panel sizes and theme");
+        ui.checkbox(should_reset_data, "Loaded data")
+            .on_hover_text("The loaded rows and their filters");
+        ui.checkbox(should_reset_annotations, "Annotations")
+            .on_hover_text("Notes for all files");
+        ui.separator();
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(self.choices.is_any(), egui::Button::new("Reset Selected"))
+                .clicked()
+            {
+                Some(std::mem::take(&mut self.choices))
+            } else {
+                None
+            }
+        })
+        .inner
+    }
+}
@@ -0,0 +1,361 @@
+use super::{
+    data::{
+        filter::{Comparator, FieldSpecifier, FilterConfig, FilterOn},
+        FieldContent, LogRow,
+    },
+    data_display_options::DataDisplayOptions,
+};
+
+/// What the context menu was opened on
+pub struct MenuContext<'a> {
+    /// Index into all rows (ignoring filters)
+    pub real_index: usize,
+    pub row: LogRow<'a>,
+    /// The field of the cell that was clicked, None if not opened on a specific field
+    pub field_name: Option<&'a str>,
+    pub display_options: &'a DataDisplayOptions,
+}
+
+/// The result of choosing an action. Performed by the app after drawing is completed
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum RowCommand {
+    CopyText(String),
+    Filter(FilterConfig),
+    Ignore(FilterConfig),
+    /// Index into all rows
+    Select(usize),
+    /// Index into all rows
+    Hide(usize),
+    /// Index into all rows
+    MarkForCompare(usize),
+    /// Index into all rows
+    Annotate(usize),
+    /// Index into all rows
+    #[cfg(not(target_arch = "wasm32"))]
+    OpenInEditor(usize),
+    /// Index into all rows, opens the source location logged in the row
+    #[cfg(not(target_arch = "wasm32"))]
+    OpenSource(usize),
+    /// Field name and value to show in full
+    ViewValue(String, String),
+    TogglePinned(String),
+    ToggleCorrelation(String),
+}
+
+/// Actions are shown grouped in this order with a separator between groups
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum ActionGroup {
+    Copy,
+    Filter,
+    Row,
+    Field,
+}
+
+/// An entry in the context menu for rows/cells
+pub trait RowAction {
+    fn group(&self) -> ActionGroup;
+    fn label(&self, context: &MenuContext<'_>) -> String;
+    /// Returns None if the action does not apply to what was clicked
+    fn command(&self, context: &MenuContext<'_>) -> Option<RowCommand>;
+}
+
+/// Action made from plain functions, used for the built-in actions
+pub struct SimpleAction {
+    pub group: ActionGroup,
+    pub label: fn(&MenuContext<'_>) -> String,
+    pub command: fn(&MenuContext<'_>) -> Option<RowCommand>,
+}
+
+impl RowAction for SimpleAction {
+    fn group(&self) -> ActionGroup {
+        self.group
+    }
+
+    fn label(&self, context: &MenuContext<'_>) -> String {
+        (self.label)(context)
+    }
+
+    fn command(&self, context: &MenuContext<'_>) -> Option<RowCommand> {
+        (self.command)(context)
+    }
+}
+
+/// An action that applies to the target
+#[derive(Debug, PartialEq, Eq)]
+pub struct MenuEntry {
+    pub group: ActionGroup,
+    pub label: String,
+    pub command: RowCommand,
+}
+
+/// Holds the actions available in row and cell context menus
+pub struct ContextMenuRegistry {
+    actions: Vec<Box<dyn RowAction>>,
+}
+
+impl Default for ContextMenuRegistry {
+    fn default() -> Self {
+        let mut result = Self {
+            actions: Vec::new(),
+        };
+        for action in built_in_actions() {
+            result.register(action);
+        }
+        result
+    }
+}
+
+impl ContextMenuRegistry {
+    pub fn register(&mut self, action: impl RowAction + 'static) {
+        self.actions.push(Box::new(action));
+    }
+
+    /// The actions that apply to the context ordered by group (and by registration order within a group)
+    pub fn entries(&self, context: &MenuContext<'_>) -> Vec<MenuEntry> {
+        let mut result: Vec<MenuEntry> = self
+            .actions
+            .iter()
+            .filter_map(|action| {
+                Some(MenuEntry {
+                    command: action.command(context)?,
+                    group: action.group(),
+                    label: action.label(context),
+                })
+            })
+            .collect();
+        result.sort_by_key(|x| x.group); // Stable so registration order is kept
+        result
+    }
+
+    /// Shows the menu contents, returns the command chosen if any
+    pub fn show(&self, ui: &mut egui::Ui, context: &MenuContext<'_>) -> Option<RowCommand> {
+        let mut result = None;
+        let mut previous_group = None;
+        for entry in self.entries(context) {
+            if previous_group.is_some_and(|x| x != entry.group) {
+                ui.separator();
+            }
+            previous_group = Some(entry.group);
+            if ui.button(entry.label).clicked() {
+                result = Some(entry.command);
+                ui.close_menu();
+            }
+        }
+        result
+    }
+}
+
+impl MenuContext<'_> {
+    /// The name of the field clicked and its display value if it is present in the row
+    fn field(&self) -> Option<(&str, String)> {
+        let field_name = self.field_name?;
+        match self.row.field_value(field_name) {
+            content @ FieldContent::Present(_) => Some((field_name, content.display())),
+            FieldContent::Missing => None,
+        }
+    }
+}
+
+/// Matches rows where the field has exactly the value (as displayed)
+pub fn equal_to_value_filter(field_name: String, value: String) -> FilterConfig {
+    FilterConfig {
+        search_key: value,
+        filter_on: FilterOn::Field(FieldSpecifier { name: field_name }),
+        is_case_sensitive: true,
+        comparator: Comparator::Equal,
+    }
+}
+
+fn built_in_actions() -> Vec<SimpleAction> {
+    vec![
+        SimpleAction {
+            group: ActionGroup::Copy,
+            label: |_| "Copy value".into(),
+            command: |c| c.field().map(|(_, value)| RowCommand::CopyText(value)),
+        },
+        SimpleAction {
+            group: ActionGroup::Copy,
+            label: |_| "Copy key=value".into(),
+            command: |c| {
+                c.field()
+                    .map(|(name, value)| RowCommand::CopyText(format!("{name}={value}")))
+            },
+        },
+        SimpleAction {
+            group: ActionGroup::Copy,
+            label: |_| "Copy row as JSON".into(),
+            command: |c| Some(RowCommand::CopyText(c.row.to_json_string())),
+        },
+        SimpleAction {
+            group: ActionGroup::Filter,
+            label: |_| "Filter to this value".into(),
+            command: |c| {
+                c.field().map(|(name, value)| {
+                    RowCommand::Filter(equal_to_value_filter(name.to_string(), value))
+                })
+            },
+        },
+        SimpleAction {
+            group: ActionGroup::Filter,
+            label: |_| "Ignore rows with this value".into(),
+            command: |c| {
+                c.field().map(|(name, value)| {
+                    RowCommand::Ignore(equal_to_value_filter(name.to_string(), value))
+                })
+            },
+        },
+        SimpleAction {
+            group: ActionGroup::Row,
+            label: |_| "Hide row".into(),
+            command: |c| Some(RowCommand::Hide(c.real_index)),
+        },
+        SimpleAction {
+            group: ActionGroup::Row,
+            label: |_| "Mark for compare".into(),
+            command: |c| Some(RowCommand::MarkForCompare(c.real_index)),
+        },
+        SimpleAction {
+            group: ActionGroup::Row,
+            label: |_| "Annotate row...".into(),
+            command: |c| Some(RowCommand::Annotate(c.real_index)),
+        },
+        SimpleAction {
+            group: ActionGroup::Field,
+            label: |_| "View full value...".into(),
+            command: |c| {
+                c.field()
+                    .map(|(name, value)| RowCommand::ViewValue(name.to_string(), value))
+            },
+        },
+        SimpleAction {
+            group: ActionGroup::Field,
+            label: |c| match c.field_name {
+                Some(name) if c.display_options.is_pinned(name) => "Unpin field".into(),
+                _ => "Pin field".into(),
+            },
+            command: |c| {
+                c.field_name
+                    .map(|x| RowCommand::TogglePinned(x.to_string()))
+            },
+        },
+        SimpleAction {
+            group: ActionGroup::Field,
+            label: |c| match c.field_name {
+                Some(name) if c.display_options.is_correlation_field(name) => {
+                    "Stop using as correlation key".into()
+                }
+                _ => "Use as correlation key".into(),
+            },
+            command: |c| {
+                c.field_name
+                    .map(|x| RowCommand::ToggleCorrelation(x.to_string()))
+            },
+        },
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use super::super::data::OwnedLogRow;
+    use super::*;
+
+    fn row() -> OwnedLogRow {
+        serde_json::from_value(json!({ "data": {"msg": "hi", "request_id": "abc"} })).unwrap()
+    }
+
+    fn labels(entries: &[MenuEntry]) -> Vec<&str> {
+        entries.iter().map(|x| x.label.as_str()).collect()
+    }
+
+    #[test]
+    fn field_actions_only_for_present_fields() {
+        let registry = ContextMenuRegistry::default();
+        let row = row();
+        let display_options = DataDisplayOptions::default();
+        let mut context = MenuContext {
+            real_index: 3,
+            row: row.as_row(),
+            field_name: None,
+            display_options: &display_options,
+        };
+        assert_eq!(
+            labels(&registry.entries(&context)),
+            vec![
+                "Copy row as JSON",
+                "Hide row",
+                "Mark for compare",
+                "Annotate row..."
+            ]
+        );
+
+        context.field_name = Some("request_id");
+        let entries = registry.entries(&context);
+        assert_eq!(
+            labels(&entries),
+            vec![
+                "Copy value",
+                "Copy key=value",
+                "Copy row as JSON",
+                "Filter to this value",
+                "Ignore rows with this value",
+                "Hide row",
+                "Mark for compare",
+                "Annotate row...",
+                "View full value...",
+                "Pin field",
+                "Stop using as correlation key",
+            ]
+        );
+        assert_eq!(
+            entries[3].command,
+            RowCommand::Filter(equal_to_value_filter("request_id".into(), "abc".into()))
+        );
+
+        // Missing values cannot be copied or filtered on
+        context.field_name = Some("missing");
+        assert_eq!(
+            labels(&registry.entries(&context)),
+            vec![
+                "Copy row as JSON",
+                "Hide row",
+                "Mark for compare",
+                "Annotate row...",
+                "Pin field",
+                "Use as correlation key",
+            ]
+        );
+    }
+
+    #[test]
+    fn registered_actions_are_grouped() {
+        let mut registry = ContextMenuRegistry::default();
+        registry.register(SimpleAction {
+            group: ActionGroup::Copy,
+            label: |_| "Copy row number".into(),
+            command: |c| Some(RowCommand::CopyText(c.real_index.to_string())),
+        });
+        let row = row();
+        let display_options = DataDisplayOptions::default();
+        let context = MenuContext {
+            real_index: 7,
+            row: row.as_row(),
+            field_name: None,
+            display_options: &display_options,
+        };
+        let entries = registry.entries(&context);
+        assert_eq!(
+            labels(&entries),
+            vec![
+                "Copy row as JSON",
+                "Copy row number",
+                "Hide row",
+                "Mark for compare",
+                "Annotate row..."
+            ]
+        );
+        assert_eq!(entries[1].command, RowCommand::CopyText("7".into()));
+    }
+}
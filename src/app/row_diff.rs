@@ -0,0 +1,197 @@
+use std::collections::BTreeSet;
+
+use egui::Color32;
+
+use super::data::{Data, FieldContent, LogRow};
+
+/// Side by side comparison of the fields of a marked row and the selected row
+#[derive(serde::Deserialize, serde::Serialize, Default, Debug)]
+#[serde(default)]
+pub struct RowDiff {
+    pub is_open: bool,
+    pub should_show_unchanged: bool,
+
+    /// Index into all rows of the row to compare against, cleared when new data is loaded
+    #[serde(skip)]
+    marked: Option<usize>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum DiffKind {
+    Unchanged,
+    /// Only present in the selected row
+    Added,
+    /// Only present in the marked row
+    Removed,
+    Changed,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct FieldDiff {
+    field_name: String,
+    kind: DiffKind,
+    marked: Option<String>,
+    selected: Option<String>,
+}
+
+impl RowDiff {
+    pub fn invalidate(&mut self) {
+        self.marked = None;
+    }
+
+    /// Should be called when the oldest `count` rows are dropped, the mark is cleared if the marked row was dropped
+    pub fn on_rows_dropped(&mut self, count: usize) {
+        self.marked = self.marked.and_then(|x| x.checked_sub(count));
+    }
+
+    /// Marks the selected row as the one to compare against and opens the window
+    pub fn mark_selected(&mut self, data: Option<&Data>) {
+        if let Some(real_index) = data.and_then(|x| x.selected_real_index()) {
+            self.mark(real_index);
+        }
+    }
+
+    /// Marks the row at `real_index` (index into all rows) as the one to compare against and opens the window
+    pub fn mark(&mut self, real_index: usize) {
+        self.marked = Some(real_index);
+        self.is_open = true;
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context, data: Option<&Data>) {
+        let mut is_open = self.is_open;
+        egui::Window::new("Compare Rows")
+            .open(&mut is_open)
+            .default_width(600.0)
+            .show(ctx, |ui| self.ui(ui, data));
+        self.is_open = is_open;
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui, data: Option<&Data>) {
+        let Some(data) = data else {
+            ui.label("No data");
+            return;
+        };
+        let marked = self.marked.and_then(|x| data.row_by_real_index(x));
+        let (Some(marked_index), Some(marked)) = (self.marked, marked) else {
+            ui.label("Select a row and mark it to compare against another row");
+            return;
+        };
+        let (Some(selected_index), Some(selected)) =
+            (data.selected_real_index(), data.selected_row())
+        else {
+            ui.label("Select another row to compare with the marked row");
+            return;
+        };
+
+        ui.checkbox(&mut self.should_show_unchanged, "Show unchanged fields");
+        let diffs = diff_rows(marked, selected);
+        let changed_count = diffs
+            .iter()
+            .filter(|x| x.kind != DiffKind::Unchanged)
+            .count();
+        ui.label(format!("{changed_count} field(s) differ"));
+        ui.separator();
+
+        egui::ScrollArea::both().show(ui, |ui| {
+            egui::Grid::new("row diff")
+                .striped(true)
+                .num_columns(3)
+                .show(ui, |ui| {
+                    ui.strong("Field");
+                    ui.strong(format!("Marked (row {marked_index})"));
+                    ui.strong(format!("Selected (row {selected_index})"));
+                    ui.end_row();
+
+                    for diff in diffs.iter() {
+                        if diff.kind == DiffKind::Unchanged && !self.should_show_unchanged {
+                            continue;
+                        }
+                        let color = diff.kind.color(ui.visuals());
+                        ui.colored_label(color, &diff.field_name);
+                        for value in [&diff.marked, &diff.selected] {
+                            ui.colored_label(
+                                color,
+                                value.as_deref().unwrap_or(FieldContent::TEXT_FOR_EMPTY),
+                            );
+                        }
+                        ui.end_row();
+                    }
+                });
+        });
+    }
+}
+
+impl DiffKind {
+    fn color(&self, visuals: &egui::Visuals) -> Color32 {
+        match self {
+            DiffKind::Unchanged => visuals.weak_text_color(),
+            DiffKind::Added => Color32::from_rgb(0, 160, 0),
+            DiffKind::Removed => visuals.error_fg_color,
+            DiffKind::Changed => visuals.warn_fg_color,
+        }
+    }
+}
+
+/// Compares all fields of the rows, result is sorted by field name
+fn diff_rows(marked: LogRow<'_>, selected: LogRow<'_>) -> Vec<FieldDiff> {
+    let field_names: BTreeSet<&str> = marked
+        .fields()
+        .chain(selected.fields())
+        .map(|(k, _)| k)
+        .collect();
+    field_names
+        .into_iter()
+        .map(|field_name| {
+            let value_of = |row: LogRow<'_>| match row.field_value(field_name) {
+                content @ FieldContent::Present(_) => Some(content.display()),
+                FieldContent::Missing => None,
+            };
+            let (marked, selected) = (value_of(marked), value_of(selected));
+            let kind = match (&marked, &selected) {
+                (Some(a), Some(b)) if a == b => DiffKind::Unchanged,
+                (Some(_), Some(_)) => DiffKind::Changed,
+                (None, _) => DiffKind::Added,
+                (_, None) => DiffKind::Removed,
+            };
+            FieldDiff {
+                field_name: field_name.to_string(),
+                kind,
+                marked,
+                selected,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use super::super::data::OwnedLogRow;
+    use super::*;
+
+    fn row(fields: serde_json::Value) -> OwnedLogRow {
+        serde_json::from_value(json!({ "data": fields })).unwrap()
+    }
+
+    #[test]
+    fn diff_highlights_each_kind() {
+        let marked = row(json!({"msg": "ok", "status": 200, "only_marked": true}));
+        let selected = row(json!({"msg": "ok", "status": 500, "only_selected": "x"}));
+        let diffs = diff_rows(marked.as_row(), selected.as_row());
+        let actual: Vec<(&str, DiffKind)> = diffs
+            .iter()
+            .map(|x| (x.field_name.as_str(), x.kind))
+            .collect();
+        assert_eq!(
+            actual,
+            vec![
+                ("msg", DiffKind::Unchanged),
+                ("only_marked", DiffKind::Removed),
+                ("only_selected", DiffKind::Added),
+                ("status", DiffKind::Changed),
+            ]
+        );
+    }
+}
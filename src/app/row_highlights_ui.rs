@@ -0,0 +1,71 @@
+use egui::Color32;
+
+use super::{data::filter::Comparator, data_display_options::RowHighlightRule};
+
+/// Editor for [`super::data_display_options::DataDisplayOptions::row_highlights`]
+pub fn ui(ui: &mut egui::Ui, row_highlights: &mut Vec<RowHighlightRule>) {
+    let mut rule_to_remove = None;
+    let mut rule_to_raise = None;
+    egui::Grid::new("row highlights").show(ui, |ui| {
+        for (i, rule) in row_highlights.iter_mut().enumerate() {
+            ui.checkbox(&mut rule.is_enabled, "");
+            ui.add(
+                egui::TextEdit::singleline(&mut rule.field_name)
+                    .hint_text("Field name")
+                    .desired_width(120.0),
+            );
+            egui::ComboBox::from_id_salt(("row highlight comparator", i))
+                .selected_text(rule.comparator.to_string())
+                .show_ui(ui, |ui| {
+                    for comparator in Comparator::ALL {
+                        ui.selectable_value(
+                            &mut rule.comparator,
+                            comparator,
+                            comparator.to_string(),
+                        );
+                    }
+                });
+            ui.add_enabled(
+                !rule.comparator.is_presence_check(),
+                egui::TextEdit::singleline(&mut rule.value)
+                    .hint_text("Value")
+                    .desired_width(80.0),
+            );
+            optional_color_ui(ui, "Background", &mut rule.background);
+            optional_color_ui(ui, "Text", &mut rule.text_color);
+            if ui
+                .add_enabled(i > 0, egui::Button::new("⏶").small())
+                .on_hover_text("Check this rule earlier")
+                .clicked()
+            {
+                rule_to_raise = Some(i);
+            }
+            if ui.small_button("🗑").on_hover_text("Remove rule").clicked() {
+                rule_to_remove = Some(i);
+            }
+            ui.end_row();
+        }
+    });
+    if let Some(i) = rule_to_raise {
+        row_highlights.swap(i - 1, i);
+    }
+    if let Some(i) = rule_to_remove {
+        row_highlights.remove(i);
+    }
+    if ui.button("Add Rule").clicked() {
+        row_highlights.push(Default::default());
+    }
+}
+
+/// Checkbox to use the color with a color picker when it is used
+fn optional_color_ui(ui: &mut egui::Ui, label: &str, color: &mut Option<Color32>) {
+    ui.horizontal(|ui| {
+        let mut is_set = color.is_some();
+        if ui.checkbox(&mut is_set, label).changed() {
+            *color = is_set.then_some(Color32::LIGHT_BLUE);
+        }
+        if let Some(color) = color {
+            ui.color_edit_button_srgba(color);
+        }
+    });
+}
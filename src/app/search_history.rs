@@ -0,0 +1,134 @@
+use super::data::filter::FilterConfig;
+
+/// The filters applied most recently so they can be used again without retyping them
+#[derive(serde::Deserialize, serde::Serialize, Debug)]
+#[serde(default)]
+pub struct SearchHistory {
+    /// Most recent first
+    entries: Vec<FilterConfig>,
+    max_len: usize,
+
+    /// Index of the entry last recalled with [`Self::older`] or [`Self::newer`]
+    #[serde(skip)]
+    browse_position: Option<usize>,
+}
+
+impl Default for SearchHistory {
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+            max_len: 20,
+            browse_position: None,
+        }
+    }
+}
+
+impl SearchHistory {
+    /// Adds the filter as the most recent (moved to the front if it was already in the history)
+    pub fn record(&mut self, filter: &FilterConfig) {
+        self.entries.retain(|x| x != filter);
+        self.entries.insert(0, filter.clone());
+        self.entries.truncate(self.max_len);
+        self.browse_position = None;
+    }
+
+    /// Steps back through the history, stays on the oldest entry once it is reached
+    pub fn older(&mut self) -> Option<&FilterConfig> {
+        if self.entries.is_empty() {
+            return None;
+        }
+        let position = self
+            .browse_position
+            .map_or(0, |x| (x + 1).min(self.entries.len() - 1));
+        self.browse_position = Some(position);
+        self.entries.get(position)
+    }
+
+    /// Steps forward through the history, returns None when moving past the most recent entry
+    pub fn newer(&mut self) -> Option<&FilterConfig> {
+        let position = self.browse_position?.checked_sub(1);
+        self.browse_position = position;
+        self.entries.get(position?)
+    }
+
+    /// Dropdown listing the history, returns the filter chosen if any
+    pub fn menu_ui(&mut self, ui: &mut egui::Ui) -> Option<FilterConfig> {
+        let mut result = None;
+        ui.menu_button("🕘", |ui| {
+            if self.entries.is_empty() {
+                ui.label("No searches yet");
+            }
+            for filter in self.entries.iter() {
+                if ui
+                    .button(&filter.search_key)
+                    .on_hover_text(filter.to_string())
+                    .clicked()
+                {
+                    result = Some(filter.clone());
+                    ui.close_menu();
+                }
+            }
+            if !self.entries.is_empty() {
+                ui.separator();
+                if ui.button("Clear History").clicked() {
+                    self.entries.clear();
+                    self.browse_position = None;
+                    ui.close_menu();
+                }
+            }
+        })
+        .response
+        .on_hover_text("Recent searches (Up/Down in the search box)");
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn filter(search_key: &str) -> FilterConfig {
+        FilterConfig {
+            search_key: search_key.into(),
+            ..Default::default()
+        }
+    }
+
+    fn keys(history: &SearchHistory) -> Vec<&str> {
+        history
+            .entries
+            .iter()
+            .map(|x| x.search_key.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn record_moves_repeats_to_front_and_truncates() {
+        let mut history = SearchHistory {
+            max_len: 3,
+            ..Default::default()
+        };
+        for key in ["a", "b", "c", "a", "d"] {
+            history.record(&filter(key));
+        }
+        assert_eq!(keys(&history), vec!["d", "a", "c"]);
+    }
+
+    #[test]
+    fn browse_older_and_newer() {
+        let mut history = SearchHistory::default();
+        assert_eq!(history.older(), None);
+        for key in ["a", "b"] {
+            history.record(&filter(key));
+        }
+        assert_eq!(history.older(), Some(&filter("b")));
+        assert_eq!(history.older(), Some(&filter("a")));
+        assert_eq!(history.older(), Some(&filter("a")));
+        assert_eq!(history.newer(), Some(&filter("b")));
+        assert_eq!(history.newer(), None);
+        assert_eq!(history.newer(), None);
+        assert_eq!(history.older(), Some(&filter("b")));
+    }
+}
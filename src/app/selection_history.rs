@@ -0,0 +1,110 @@
+/// Max number of rows kept to go back to
+const MAX_BACK: usize = 100;
+
+/// Rows selected earlier so the user can go back and forward between them like in a browser.
+///
+/// Changes are noticed by comparing the selection each frame (see [`Self::observe`]). Moving to a neighbouring row
+/// is not recorded so stepping through rows does not fill the history
+#[derive(Debug, Default)]
+pub struct SelectionHistory {
+    /// Real index and position among the visible rows of the selection when last observed
+    current: Option<(usize, usize)>,
+    /// Real indices, most recent last
+    back: Vec<usize>,
+    /// Real indices, most recent last
+    forward: Vec<usize>,
+}
+
+impl SelectionHistory {
+    pub fn invalidate(&mut self) {
+        *self = Default::default();
+    }
+
+    /// Should be called when the oldest `count` rows are dropped so the history still refers to the same rows
+    pub fn on_rows_dropped(&mut self, count: usize) {
+        // Positions changed as well so the current selection is observed again
+        self.current = None;
+        for rows in [&mut self.back, &mut self.forward] {
+            *rows = rows.iter().filter_map(|x| x.checked_sub(count)).collect();
+        }
+    }
+
+    /// Records the selection if it jumped from the one last observed. `selected` is the real index and position among
+    /// the visible rows
+    pub fn observe(&mut self, selected: Option<(usize, usize)>) {
+        let Some((real_index, position)) = selected else {
+            return;
+        };
+        let Some((current_real, current_position)) = self.current else {
+            self.current = selected;
+            return;
+        };
+        if current_real != real_index && current_position.abs_diff(position) > 1 {
+            self.back.push(current_real);
+            if self.back.len() > MAX_BACK {
+                self.back.remove(0);
+            }
+            self.forward.clear();
+        }
+        self.current = selected;
+    }
+
+    pub fn can_go_back(&self) -> bool {
+        !self.back.is_empty()
+    }
+
+    pub fn can_go_forward(&self) -> bool {
+        !self.forward.is_empty()
+    }
+
+    /// Returns the real index of the row to select
+    pub fn back(&mut self) -> Option<usize> {
+        let target = self.back.pop()?;
+        if let Some((current_real, _)) = self.current.take() {
+            self.forward.push(current_real);
+        }
+        Some(target)
+    }
+
+    /// Returns the real index of the row to select
+    pub fn forward(&mut self) -> Option<usize> {
+        let target = self.forward.pop()?;
+        if let Some((current_real, _)) = self.current.take() {
+            self.back.push(current_real);
+        }
+        Some(target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn jumps_recorded_but_not_steps() {
+        let mut history = SelectionHistory::default();
+        history.observe(Some((10, 10)));
+        history.observe(Some((11, 11)));
+        history.observe(Some((12, 12)));
+        assert!(!history.can_go_back());
+
+        history.observe(Some((500, 500)));
+        history.observe(None);
+        history.observe(Some((40, 2)));
+        assert_eq!(history.back(), Some(500));
+        history.observe(Some((500, 500)));
+        assert_eq!(history.back(), Some(12));
+        history.observe(Some((12, 12)));
+        assert!(!history.can_go_back());
+
+        assert_eq!(history.forward(), Some(500));
+        history.observe(Some((500, 500)));
+
+        // A new jump clears forward
+        history.observe(Some((0, 0)));
+        assert!(!history.can_go_forward());
+        assert_eq!(history.back(), Some(500));
+    }
+}
@@ -0,0 +1,146 @@
+use std::collections::BTreeSet;
+
+use super::{data::Data, data_display_options::DataDisplayOptions, workspaces::SavedFilters};
+
+/// Key of the view in the URL fragment (eg. `#view=...`)
+const FRAGMENT_KEY: &str = "view=";
+
+/// What is needed to show a teammate the same view of the same log, encoded into the URL fragment on the web
+#[derive(serde::Deserialize, serde::Serialize, Default, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct SharedView {
+    filters: SavedFilters,
+    /// Index into all rows (ignoring filters) so it does not depend on the filters being applied in the same order
+    selected_real_index: Option<usize>,
+    main_list_fields: Vec<String>,
+}
+
+impl SharedView {
+    pub fn new(data: Option<&Data>, options: &DataDisplayOptions) -> Self {
+        Self {
+            filters: SavedFilters::new(data),
+            selected_real_index: data.and_then(|x| x.selected_real_index()),
+            main_list_fields: options.main_list_fields().to_vec(),
+        }
+    }
+
+    /// The fragment (without the leading `#`) that [`Self::from_fragment`] reads back
+    pub fn to_fragment(&self) -> String {
+        let json = serde_json::to_string(self).expect("view should always serialize");
+        format!("{FRAGMENT_KEY}{}", percent_encode(&json))
+    }
+
+    /// Reads the view from a URL fragment (a leading `#` is ignored), None if it does not hold a view
+    pub fn from_fragment(fragment: &str) -> Option<Self> {
+        let encoded = fragment
+            .strip_prefix('#')
+            .unwrap_or(fragment)
+            .strip_prefix(FRAGMENT_KEY)?;
+        let json = percent_decode(encoded)?;
+        match serde_json::from_str(&json) {
+            Ok(view) => Some(view),
+            Err(e) => {
+                log::warn!("Ignored view in the URL that could not be read: {e}");
+                None
+            }
+        }
+    }
+
+    /// Sets the parts of the view that do not depend on the data
+    pub fn apply_to_options(&self, options: &mut DataDisplayOptions) {
+        if !self.main_list_fields.is_empty() {
+            options.set_main_list_fields(self.main_list_fields.clone());
+        }
+    }
+
+    /// Restores the filters and selected row (ignored if the data does not have that row)
+    pub fn apply_to_data(self, data: &mut Data, common_fields: &BTreeSet<String>) {
+        self.filters.apply_to(data, common_fields);
+        if let Some(real_index) = self
+            .selected_real_index
+            .filter(|&x| x < data.total_len_unfiltered())
+        {
+            data.select_real_index(real_index, common_fields);
+        }
+    }
+}
+
+/// Escapes everything except the characters that are never changed in a URL
+fn percent_encode(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) {
+            result.push(byte as char);
+        } else {
+            result.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    result
+}
+
+fn percent_decode(text: &str) -> Option<String> {
+    let bytes = text.as_bytes();
+    let mut result = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = std::str::from_utf8(bytes.get(i + 1..i + 3)?).ok()?;
+            result.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            result.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(result).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::core::data::filter::FilterConfig;
+
+    #[test]
+    fn view_round_trips_through_fragment() {
+        let mut options = DataDisplayOptions::default();
+        options.set_main_list_fields(vec!["time".into(), "msg".into()]);
+        let text = [
+            r#"{"msg": "first"}"#,
+            r#"{"msg": "second #1 & more"}"#,
+            r#"{"msg": "third"}"#,
+        ]
+        .join("\n");
+        let mut data = Data::try_from((&options, text.as_str())).unwrap();
+        data.filter = Some(FilterConfig {
+            search_key: "#1 &".into(),
+            ..Default::default()
+        });
+        data.apply_filter(options.common_fields());
+        data.select_real_index(1, options.common_fields());
+        let view = SharedView::new(Some(&data), &options);
+
+        let fragment = view.to_fragment();
+        assert!(!fragment.contains(['#', '&', ' ']));
+        let read_back = SharedView::from_fragment(&format!("#{fragment}")).unwrap();
+        assert_eq!(read_back, view);
+
+        let mut other_options = DataDisplayOptions::default();
+        read_back.apply_to_options(&mut other_options);
+        assert_eq!(other_options.main_list_fields(), ["time", "msg"]);
+        assert_eq!(*other_options.emphasize_if_matching_field_idx(), None);
+
+        let mut other = Data::try_from((&other_options, text.as_str())).unwrap();
+        read_back.apply_to_data(&mut other, other_options.common_fields());
+        assert_eq!(other.applied_filter(), data.applied_filter());
+        assert_eq!(other.selected_real_index(), Some(1));
+    }
+
+    #[test]
+    fn other_fragments_ignored() {
+        assert_eq!(SharedView::from_fragment(""), None);
+        assert_eq!(SharedView::from_fragment("#section"), None);
+        assert_eq!(SharedView::from_fragment("view=%7"), None);
+    }
+}
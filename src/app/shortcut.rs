@@ -1,6 +1,8 @@
+use std::collections::BTreeSet;
+
 use egui::{KeyboardShortcut, Modifiers};
 
-#[derive(Debug, serde::Deserialize, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
 #[serde(default)]
 pub struct Shortcuts {
     pub prev: KeyboardShortcut,
@@ -14,6 +16,35 @@ pub struct Shortcuts {
     pub apply_filter: KeyboardShortcut,
     pub search: KeyboardShortcut,
     pub auto_scroll: KeyboardShortcut,
+    pub hide_row: KeyboardShortcut,
+    pub undo_hide: KeyboardShortcut,
+    pub mark_for_compare: KeyboardShortcut,
+    pub next_match: KeyboardShortcut,
+    pub prev_match: KeyboardShortcut,
+    /// Moves to the next row with the same value as the selected row in the emphasized field (or the copy key field if none)
+    pub next_same_value: KeyboardShortcut,
+    pub prev_same_value: KeyboardShortcut,
+    /// Moves to the first row with the same value in that field (eg. the start of the selected row's request)
+    pub first_same_value: KeyboardShortcut,
+    pub last_same_value: KeyboardShortcut,
+    /// Goes back to the filter applied before the current one
+    pub undo_filter: KeyboardShortcut,
+    pub redo_filter: KeyboardShortcut,
+    /// Goes back to the row selected before the last jump (like back in a browser)
+    pub selection_back: KeyboardShortcut,
+    pub selection_forward: KeyboardShortcut,
+    /// Moves keyboard focus into the details pane (Tab also works when no widget has focus)
+    pub focus_details: KeyboardShortcut,
+    /// Hides or shows the controls above the rows
+    pub toggle_controls: KeyboardShortcut,
+    /// Hides or shows the details panel
+    pub toggle_details: KeyboardShortcut,
+    /// Shows the full value of the cell under the mouse
+    pub view_value: KeyboardShortcut,
+    /// Copies the value of the key field (eg. `request_id`) of the selected row
+    pub copy_key_field: KeyboardShortcut,
+    /// When set `first` must be pressed twice in a row to trigger (like `gg` in vim)
+    pub should_double_press_first: bool,
 }
 
 impl Default for Shortcuts {
@@ -30,6 +61,267 @@ impl Default for Shortcuts {
             apply_filter: KeyboardShortcut::new(Modifiers::NONE, egui::Key::F7),
             search: KeyboardShortcut::new(Modifiers::CTRL, egui::Key::F),
             auto_scroll: KeyboardShortcut::new(Modifiers::NONE, egui::Key::F8),
+            hide_row: KeyboardShortcut::new(Modifiers::NONE, egui::Key::Delete),
+            undo_hide: KeyboardShortcut::new(Modifiers::NONE, egui::Key::Insert),
+            mark_for_compare: KeyboardShortcut::new(Modifiers::CTRL, egui::Key::M),
+            next_match: KeyboardShortcut::new(Modifiers::NONE, egui::Key::F3),
+            prev_match: KeyboardShortcut::new(Modifiers::SHIFT, egui::Key::F3),
+            next_same_value: KeyboardShortcut::new(Modifiers::CTRL, egui::Key::ArrowDown),
+            prev_same_value: KeyboardShortcut::new(Modifiers::CTRL, egui::Key::ArrowUp),
+            first_same_value: KeyboardShortcut::new(Modifiers::CTRL, egui::Key::Home),
+            last_same_value: KeyboardShortcut::new(Modifiers::CTRL, egui::Key::End),
+            undo_filter: KeyboardShortcut::new(Modifiers::ALT, egui::Key::Z),
+            redo_filter: KeyboardShortcut::new(Modifiers::ALT.plus(Modifiers::SHIFT), egui::Key::Z),
+            selection_back: KeyboardShortcut::new(Modifiers::ALT, egui::Key::ArrowLeft),
+            selection_forward: KeyboardShortcut::new(Modifiers::ALT, egui::Key::ArrowRight),
+            focus_details: KeyboardShortcut::new(Modifiers::ALT, egui::Key::D),
+            toggle_controls: KeyboardShortcut::new(Modifiers::NONE, egui::Key::F9),
+            toggle_details: KeyboardShortcut::new(Modifiers::SHIFT, egui::Key::F9),
+            view_value: KeyboardShortcut::new(Modifiers::ALT, egui::Key::V),
+            copy_key_field: KeyboardShortcut::new(Modifiers::ALT, egui::Key::C),
+            should_double_press_first: false,
+        }
+    }
+}
+
+impl Shortcuts {
+    /// Navigation keys like in vim (j/k, gg/G, / and n/N), other shortcuts are the same as the defaults
+    pub fn vim() -> Self {
+        Self {
+            prev: KeyboardShortcut::new(Modifiers::NONE, egui::Key::K),
+            next: KeyboardShortcut::new(Modifiers::NONE, egui::Key::J),
+            first: KeyboardShortcut::new(Modifiers::NONE, egui::Key::G),
+            last: KeyboardShortcut::new(Modifiers::SHIFT, egui::Key::G),
+            search: KeyboardShortcut::new(Modifiers::NONE, egui::Key::Slash),
+            next_match: KeyboardShortcut::new(Modifiers::NONE, egui::Key::N),
+            prev_match: KeyboardShortcut::new(Modifiers::SHIFT, egui::Key::N),
+            should_double_press_first: true,
+            ..Default::default()
+        }
+    }
+
+    /// Uses the navigation keys of `preset` (the ones [`Self::vim`] changes), other shortcuts are kept so changes made
+    /// to them are not lost when switching presets
+    pub fn use_navigation_of(&mut self, preset: Self) {
+        self.prev = preset.prev;
+        self.next = preset.next;
+        self.first = preset.first;
+        self.last = preset.last;
+        self.search = preset.search;
+        self.next_match = preset.next_match;
+        self.prev_match = preset.prev_match;
+        self.should_double_press_first = preset.should_double_press_first;
+    }
+
+    /// Every shortcut with the name of its action, used for editing
+    pub fn entries_mut(&mut self) -> Vec<(&'static str, &mut KeyboardShortcut)> {
+        // Destructured so adding a shortcut without listing it here fails to compile
+        let Self {
+            prev,
+            next,
+            first,
+            last,
+            unfilter,
+            open,
+            reload,
+            load_latest,
+            apply_filter,
+            search,
+            auto_scroll,
+            hide_row,
+            undo_hide,
+            mark_for_compare,
+            next_match,
+            prev_match,
+            next_same_value,
+            prev_same_value,
+            first_same_value,
+            last_same_value,
+            undo_filter,
+            redo_filter,
+            selection_back,
+            selection_forward,
+            focus_details,
+            toggle_controls,
+            toggle_details,
+            view_value,
+            copy_key_field,
+            should_double_press_first: _,
+        } = self;
+        vec![
+            ("Previous row", prev),
+            ("Next row", next),
+            ("First row", first),
+            ("Last row", last),
+            ("Unfilter", unfilter),
+            ("Open file", open),
+            ("Reload file", reload),
+            ("Load most recent file", load_latest),
+            ("Apply filter", apply_filter),
+            ("Focus search", search),
+            ("Toggle scroll to end on load", auto_scroll),
+            ("Hide row", hide_row),
+            ("Undo hide", undo_hide),
+            ("Mark for compare", mark_for_compare),
+            ("Next match", next_match),
+            ("Previous match", prev_match),
+            ("Next row with same value", next_same_value),
+            ("Previous row with same value", prev_same_value),
+            ("First row with same value", first_same_value),
+            ("Last row with same value", last_same_value),
+            ("Undo filter", undo_filter),
+            ("Redo filter", redo_filter),
+            ("Back to previous selection", selection_back),
+            ("Forward to next selection", selection_forward),
+            ("Focus details", focus_details),
+            ("Toggle controls", toggle_controls),
+            ("Toggle details panel", toggle_details),
+            ("View full value", view_value),
+            ("Copy key field", copy_key_field),
+        ]
+    }
+
+    /// Names of the actions whose shortcut would also trigger another action's shortcut
+    pub fn conflicts(&self) -> BTreeSet<&'static str> {
+        let mut copy = self.clone();
+        let entries: Vec<(&str, KeyboardShortcut)> = copy
+            .entries_mut()
+            .into_iter()
+            .map(|(name, shortcut)| (name, *shortcut))
+            .collect();
+        let mut result = BTreeSet::new();
+        for (i, (name_a, a)) in entries.iter().enumerate() {
+            for (name_b, b) in entries.iter().skip(i + 1) {
+                if overlaps(a, b) {
+                    result.insert(*name_a);
+                    result.insert(*name_b);
+                }
+            }
         }
+        result
+    }
+}
+
+/// True if pressing one of the shortcuts would also trigger the other (see [`consume`])
+fn overlaps(a: &KeyboardShortcut, b: &KeyboardShortcut) -> bool {
+    a.logical_key == b.logical_key && a.modifiers.matches_exact(b.modifiers)
+}
+
+/// Returns true if the shortcut was pressed and removes the key press so it only triggers once.
+///
+/// Unlike [`egui::InputState::consume_shortcut`] the modifiers must match exactly so that shortcuts that only differ
+/// by shift (eg. `n` and `N`) do not trigger each other. Shortcuts that type or edit text are ignored while typing.
+pub fn consume(ctx: &egui::Context, shortcut: &KeyboardShortcut) -> bool {
+    if ctx.wants_keyboard_input() && is_typing_key(shortcut) {
+        return false;
+    }
+    ctx.input_mut(|i| {
+        let position = i.events.iter().position(|event| {
+            matches!(
+                event,
+                egui::Event::Key {
+                    key,
+                    pressed: true,
+                    modifiers,
+                    ..
+                } if *key == shortcut.logical_key && modifiers.matches_exact(shortcut.modifiers)
+            )
+        });
+        position.map(|x| i.events.remove(x)).is_some()
+    })
+}
+
+/// Keys a text edit uses to edit or move the cursor, also with ctrl or shift (eg. ctrl+delete deletes a word)
+const EDITING_KEYS: [egui::Key; 5] = [
+    egui::Key::Delete,
+    egui::Key::Insert,
+    egui::Key::Backspace,
+    egui::Key::Home,
+    egui::Key::End,
+];
+
+/// True if pressing the shortcut would also type into (or edit) a text edit
+fn is_typing_key(shortcut: &KeyboardShortcut) -> bool {
+    let Modifiers {
+        alt, ctrl, mac_cmd, ..
+    } = shortcut.modifiers;
+    if EDITING_KEYS.contains(&shortcut.logical_key) {
+        return !alt;
+    }
+    !(alt || ctrl || mac_cmd)
+        && (shortcut.logical_key == egui::Key::Space
+            || shortcut.logical_key.symbol_or_name().chars().count() == 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn conflicts_detected() {
+        let mut shortcuts = Shortcuts::default();
+        assert_eq!(shortcuts.conflicts(), BTreeSet::new());
+
+        shortcuts.load_latest = shortcuts.reload;
+        assert_eq!(
+            shortcuts.conflicts(),
+            ["Load most recent file", "Reload file"]
+                .into_iter()
+                .collect()
+        );
+
+        // Modifiers must match exactly
+        shortcuts.load_latest = KeyboardShortcut::new(Modifiers::SHIFT, egui::Key::F5);
+        assert_eq!(shortcuts.conflicts(), BTreeSet::new());
+
+        assert_eq!(Shortcuts::vim().conflicts(), BTreeSet::new());
+    }
+
+    #[test]
+    fn presets_only_change_navigation() {
+        let custom_open = KeyboardShortcut::new(Modifiers::CTRL, egui::Key::P);
+        let mut shortcuts = Shortcuts {
+            open: custom_open,
+            ..Default::default()
+        };
+        shortcuts.use_navigation_of(Shortcuts::vim());
+        assert_eq!(
+            shortcuts,
+            Shortcuts {
+                open: custom_open,
+                ..Shortcuts::vim()
+            }
+        );
+
+        shortcuts.use_navigation_of(Shortcuts::default());
+        assert_eq!(
+            shortcuts,
+            Shortcuts {
+                open: custom_open,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn typing_keys() {
+        let shortcuts = Shortcuts::vim();
+        assert!(is_typing_key(&shortcuts.next));
+        assert!(is_typing_key(&shortcuts.last));
+        assert!(is_typing_key(&shortcuts.search));
+        assert!(!is_typing_key(&shortcuts.open));
+        assert!(!is_typing_key(&shortcuts.unfilter));
+        assert!(!is_typing_key(&Shortcuts::default().reload));
+
+        let shortcuts = Shortcuts::default();
+        assert!(is_typing_key(&shortcuts.hide_row));
+        assert!(is_typing_key(&shortcuts.undo_hide));
+        assert!(is_typing_key(&shortcuts.last_same_value));
+        assert!(!is_typing_key(&KeyboardShortcut::new(
+            Modifiers::ALT,
+            egui::Key::Delete
+        )));
     }
 }
@@ -0,0 +1,122 @@
+use egui::{KeyboardShortcut, Modifiers};
+
+use super::shortcut::Shortcuts;
+
+/// Lists the shortcuts and allows rebinding them by pressing the new key combination
+#[derive(serde::Deserialize, serde::Serialize, Default, Debug)]
+#[serde(default)]
+pub struct ShortcutEditor {
+    pub is_open: bool,
+    /// Name of the action waiting for a key press to set its shortcut
+    #[serde(skip)]
+    capturing: Option<&'static str>,
+}
+
+impl ShortcutEditor {
+    /// Uses the first key pressed this frame as the new shortcut if waiting for one.
+    /// Must be called before shortcuts are checked as the key press is removed so it does not trigger anything else.
+    pub fn capture_key(&mut self, ctx: &egui::Context, shortcuts: &mut Shortcuts) {
+        let Some(name) = self.capturing else {
+            return;
+        };
+        let pressed = ctx.input_mut(|i| {
+            let position = i.events.iter().position(|event| {
+                matches!(
+                    event,
+                    egui::Event::Key {
+                        pressed: true,
+                        repeat: false,
+                        ..
+                    }
+                )
+            })?;
+            match i.events.remove(position) {
+                egui::Event::Key { key, modifiers, .. } => Some(KeyboardShortcut::new(
+                    // Command is only a logical alias of ctrl/mac_cmd and would not show when formatted
+                    Modifiers {
+                        command: false,
+                        ..modifiers
+                    },
+                    key,
+                )),
+                _ => unreachable!("only key events are matched above"),
+            }
+        });
+        if let Some(pressed) = pressed {
+            if let Some((_, shortcut)) = shortcuts
+                .entries_mut()
+                .into_iter()
+                .find(|(entry_name, _)| *entry_name == name)
+            {
+                *shortcut = pressed;
+            }
+            self.capturing = None;
+        }
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context, shortcuts: &mut Shortcuts) {
+        let mut is_open = self.is_open;
+        egui::Window::new("Keyboard Shortcuts")
+            .open(&mut is_open)
+            .show(ctx, |ui| self.ui(ui, shortcuts));
+        self.is_open = is_open;
+        if !self.is_open {
+            self.capturing = None;
+        }
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui, shortcuts: &mut Shortcuts) {
+        let conflicts = shortcuts.conflicts();
+        let defaults = Shortcuts::default();
+        let mut default_entries = defaults.clone();
+        let default_entries = default_entries.entries_mut();
+        egui::Grid::new("shortcuts").striped(true).show(ui, |ui| {
+            for ((name, shortcut), (_, default)) in
+                shortcuts.entries_mut().into_iter().zip(default_entries)
+            {
+                ui.label(name);
+                if self.capturing == Some(name) {
+                    ui.label("Press keys...");
+                    if ui.button("Cancel").clicked() {
+                        self.capturing = None;
+                    }
+                } else {
+                    let text = ui.ctx().format_shortcut(shortcut);
+                    let mut response = ui.button(text).on_hover_text("Click to change");
+                    if conflicts.contains(name) {
+                        response = response.highlight();
+                        ui.colored_label(ui.visuals().error_fg_color, "⚠")
+                            .on_hover_text("Conflicts with another shortcut");
+                    } else {
+                        ui.label("");
+                    }
+                    if response.clicked() {
+                        self.capturing = Some(name);
+                    }
+                }
+                if ui
+                    .add_enabled(*shortcut != *default, egui::Button::new("⟲"))
+                    .on_hover_text("Reset to default")
+                    .clicked()
+                {
+                    *shortcut = *default;
+                }
+                ui.end_row();
+            }
+        });
+        ui.checkbox(
+            &mut shortcuts.should_double_press_first,
+            "Press the shortcut for the first row twice (like gg in vim)",
+        );
+        if ui
+            .add_enabled(
+                *shortcuts != defaults,
+                egui::Button::new("Reset All to Defaults"),
+            )
+            .clicked()
+        {
+            *shortcuts = defaults;
+            self.capturing = None;
+        }
+    }
+}
@@ -0,0 +1,253 @@
+use std::{
+    ffi::OsString,
+    io::{BufReader, Read, Write},
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver},
+    time::Duration,
+};
+
+use anyhow::{bail, Context};
+use interprocess::local_socket::{prelude::*, ListenerOptions, Name, Stream};
+use log::{info, warn};
+
+/// Sent before the path so connections from other programs (or other versions) are ignored
+const GREETING: &[u8] = b"log_viewer open v1\n";
+const ACCEPTED: u8 = 1;
+
+/// Longest path accepted, anything longer is not from another instance
+const MAX_PATH_LEN: u64 = 64 * 1024;
+
+/// How long a new instance waits on the running one before starting its own window
+const TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Skips handing the file to the running instance (to open a second window on purpose)
+pub const NEW_INSTANCE_ARG: &str = "--new-instance";
+
+/// Receives files to open from instances started after this one (eg. by opening a file from a file manager).
+///
+/// Uses a local socket only the current user can connect to, a Unix domain socket in the user's runtime
+/// directory or a named pipe with the user name in it on Windows
+#[derive(Debug)]
+pub struct SingleInstance {
+    opened: Receiver<PathBuf>,
+}
+
+/// Directory only the current user can access to hold the socket
+#[cfg(unix)]
+fn socket_dir() -> anyhow::Result<PathBuf> {
+    use std::os::unix::fs::{DirBuilderExt as _, PermissionsExt as _};
+
+    if let Some(dir) = std::env::var_os("XDG_RUNTIME_DIR").filter(|x| !x.is_empty()) {
+        return Ok(PathBuf::from(dir));
+    }
+    let user = std::env::var("USER").unwrap_or_default();
+    let dir = std::env::temp_dir().join(format!("log_viewer-{user}"));
+    if let Err(e) = std::fs::DirBuilder::new().mode(0o700).create(&dir) {
+        if e.kind() != std::io::ErrorKind::AlreadyExists {
+            return Err(e).context("failed to create the socket directory");
+        }
+    }
+    let mode = std::fs::metadata(&dir)?.permissions().mode();
+    if mode & 0o077 != 0 {
+        bail!("{} is accessible by other users", dir.display());
+    }
+    Ok(dir)
+}
+
+#[cfg(unix)]
+fn socket_name() -> anyhow::Result<Name<'static>> {
+    use interprocess::local_socket::GenericFilePath;
+    Ok(socket_dir()?
+        .join("log_viewer.sock")
+        .to_fs_name::<GenericFilePath>()?
+        .into_owned())
+}
+
+/// Named pipes are not files so the user name keeps the instances of different users apart
+#[cfg(windows)]
+fn socket_name() -> anyhow::Result<Name<'static>> {
+    use interprocess::local_socket::GenericNamespaced;
+    let user = std::env::var("USERNAME").unwrap_or_default();
+    Ok(format!("log_viewer-{user}")
+        .to_ns_name::<GenericNamespaced>()?
+        .into_owned())
+}
+
+/// The file passed on the command line (the first argument that is not a flag)
+pub fn file_argument() -> Option<PathBuf> {
+    std::env::args_os()
+        .skip(1)
+        .find(|x| !x.to_string_lossy().starts_with("--"))
+        .map(PathBuf::from)
+}
+
+/// Sends the file to the instance already running. Returns true if it accepted the file
+pub fn send_to_running_instance(path: &Path) -> bool {
+    if std::env::args().any(|x| x == NEW_INSTANCE_ARG) {
+        return false;
+    }
+    // Made absolute as the running instance may have a different working directory
+    let path = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    let result = socket_name().and_then(|name| {
+        let stream = Stream::connect(name).context("no running instance")?;
+        stream.set_recv_timeout(Some(TIMEOUT))?;
+        stream.set_send_timeout(Some(TIMEOUT))?;
+        send_path(stream, &path)
+    });
+    match result {
+        Ok(()) => true,
+        Err(e) => {
+            info!("Not opening in a running instance: {e:#}");
+            false
+        }
+    }
+}
+
+/// Raw bytes of the path so any path the OS allows makes it through unchanged
+#[cfg(unix)]
+fn path_bytes(path: &Path) -> Vec<u8> {
+    use std::os::unix::ffi::OsStrExt as _;
+    path.as_os_str().as_bytes().to_vec()
+}
+
+#[cfg(unix)]
+fn path_from_bytes(bytes: Vec<u8>) -> PathBuf {
+    use std::os::unix::ffi::OsStringExt as _;
+    PathBuf::from(OsString::from_vec(bytes))
+}
+
+/// Windows paths are sequences of u16 (which may not be valid UTF-16)
+#[cfg(windows)]
+fn path_bytes(path: &Path) -> Vec<u8> {
+    use std::os::windows::ffi::OsStrExt as _;
+    path.as_os_str()
+        .encode_wide()
+        .flat_map(u16::to_le_bytes)
+        .collect()
+}
+
+#[cfg(windows)]
+fn path_from_bytes(bytes: Vec<u8>) -> PathBuf {
+    use std::os::windows::ffi::OsStringExt as _;
+    let wide: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|x| u16::from_le_bytes([x[0], x[1]]))
+        .collect();
+    PathBuf::from(OsString::from_wide(&wide))
+}
+
+/// Writes the greeting then the path prefixed by its length and waits for the other end to accept it
+fn send_path(mut stream: impl Read + Write, path: &Path) -> anyhow::Result<()> {
+    let bytes = path_bytes(path);
+    let mut message = GREETING.to_vec();
+    message.extend((bytes.len() as u64).to_le_bytes());
+    message.extend(bytes);
+    stream.write_all(&message)?;
+    stream.flush()?;
+    let mut reply = [0u8];
+    stream
+        .read_exact(&mut reply)
+        .context("no reply from the running instance")?;
+    if reply[0] != ACCEPTED {
+        bail!("the running instance did not accept the file");
+    }
+    Ok(())
+}
+
+/// Reads the path sent by [`send_path`] and acknowledges it
+fn receive_path(stream: impl Read + Write) -> anyhow::Result<PathBuf> {
+    let mut reader = BufReader::new(stream);
+    let mut greeting = vec![0u8; GREETING.len()];
+    reader.read_exact(&mut greeting)?;
+    if greeting != GREETING {
+        bail!("connection was not from another instance");
+    }
+    let mut len = [0u8; 8];
+    reader.read_exact(&mut len)?;
+    let len = u64::from_le_bytes(len);
+    if len == 0 || len > MAX_PATH_LEN {
+        bail!("invalid path length {len}");
+    }
+    let mut bytes = vec![0u8; len as usize];
+    reader.read_exact(&mut bytes)?;
+    let mut stream = reader.into_inner();
+    stream.write_all(&[ACCEPTED])?;
+    stream.flush()?;
+    Ok(path_from_bytes(bytes))
+}
+
+impl SingleInstance {
+    /// Starts listening for files to open. None if the socket could not be created (eg. another instance has it)
+    pub fn listen(ctx: egui::Context) -> Option<Self> {
+        let result = socket_name().and_then(|name| {
+            // Only reached when no instance answered so a socket left behind by one that crashed can be replaced
+            let is_running = Stream::connect(name.borrow()).is_ok();
+            if is_running {
+                bail!("another instance is already listening");
+            }
+            Ok(ListenerOptions::new()
+                .name(name)
+                .try_overwrite(true)
+                .create_sync()?)
+        });
+        let listener = match result {
+            Ok(listener) => listener,
+            Err(e) => {
+                warn!("Not listening for files from other instances: {e:#}");
+                return None;
+            }
+        };
+        let (sender, opened) = mpsc::channel();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(stream) = stream else { continue };
+                let sender = sender.clone();
+                let ctx = ctx.clone();
+                // Each on its own thread so a client that stops responding does not hold up the others
+                std::thread::spawn(move || {
+                    let _ = stream.set_recv_timeout(Some(TIMEOUT));
+                    let _ = stream.set_send_timeout(Some(TIMEOUT));
+                    match receive_path(stream) {
+                        Ok(path) => {
+                            let _ = sender.send(path);
+                            ctx.request_repaint();
+                        }
+                        Err(e) => info!("Ignored connection on the single instance socket: {e:#}"),
+                    }
+                });
+            }
+        });
+        Some(Self { opened })
+    }
+
+    /// Files sent by other instances since the last call
+    pub fn take_opened(&self) -> Vec<PathBuf> {
+        self.opened.try_iter().collect()
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::os::unix::net::UnixStream;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn path_sent_between_instances() {
+        use std::os::unix::ffi::OsStrExt as _;
+
+        // Not valid UTF-8 and with a newline, both need to arrive unchanged
+        let path = PathBuf::from(std::ffi::OsStr::from_bytes(b"/var/log/a\nb\xff.log"));
+        let (client, server) = UnixStream::pair().unwrap();
+        let receiver = std::thread::spawn(move || receive_path(server));
+        send_path(client, &path).unwrap();
+        assert_eq!(receiver.join().unwrap().unwrap(), path);
+
+        let (mut client, server) = UnixStream::pair().unwrap();
+        client.write_all(b"GET / HTTP/1.1\r\n\r\n").unwrap();
+        drop(client);
+        assert!(receive_path(server).is_err());
+    }
+}
@@ -0,0 +1,547 @@
+use egui_extras::{Column, TableBuilder};
+
+use super::{
+    data::{filter::FilterConfig, Data},
+    data_display_options::{DataDisplayOptions, RowHeight},
+    row_actions::RowCommand,
+};
+
+/// A second table with its own filter, selection and scroll position so different parts of the data
+/// (eg. the start and end of a request) can be viewed side by side.
+/// It shows the main data unless another file is loaded into it for comparison
+#[derive(serde::Deserialize, serde::Serialize, Debug)]
+#[serde(default)]
+pub struct SplitView {
+    pub is_enabled: bool,
+    /// The filter being edited, only the search key can be changed (other options keep their defaults)
+    filter: FilterConfig,
+    /// When comparing files selecting a row in either table selects the row closest in time in the other and
+    /// scrolling either table scrolls the other to the row closest in time to its first visible row
+    is_time_synced: bool,
+    /// Expected to hold RFC 3339 timestamps
+    time_field_name: String,
+
+    #[serde(skip)]
+    applied_filter: Option<FilterConfig>,
+    #[serde(skip)]
+    compare: Option<CompareFile>,
+    /// Indices into all rows of the rows shown, None if they need to be found again
+    #[serde(skip)]
+    rows: Option<Vec<usize>>,
+    /// Number of rows in the data when the rows shown were found, used to detect rows being added
+    #[serde(skip)]
+    rows_total_len: usize,
+    /// Heights of the rows shown with the settings and text height they were computed for, cleared when the rows change
+    #[serde(skip)]
+    row_heights: Option<(RowHeight, f32, Vec<f32>)>,
+    /// Index into all rows
+    #[serde(skip)]
+    selected: Option<usize>,
+    /// Selected rows (indices into all rows) of the main table and this one when last synced
+    #[serde(skip)]
+    last_synced: (Option<usize>, Option<usize>),
+    #[serde(skip)]
+    should_scroll: bool,
+    /// Row (index into all rows) to scroll to the top of the table
+    #[serde(skip)]
+    scroll_to: Option<usize>,
+    /// Index into all rows of the first row shown when the table was last drawn
+    #[serde(skip)]
+    first_visible: Option<usize>,
+    /// If the pointer was over the table when it was last drawn (scrolling then comes from this table)
+    #[serde(skip)]
+    is_hovered: bool,
+    /// First visible rows (indices into all rows) of the main table and this one when scrolling was last synced
+    #[serde(skip)]
+    last_scroll_synced: (Option<usize>, Option<usize>),
+}
+
+/// Another file shown instead of the main data
+#[derive(Debug)]
+struct CompareFile {
+    name: String,
+    data: Data,
+}
+
+/// Requests that need the rest of the app's state to carry out
+#[derive(Debug, PartialEq, Eq)]
+pub enum SplitViewAction {
+    /// Performed on the main table
+    Row(RowCommand),
+    /// Pick a file to load into the split view, see [`SplitView::set_compare_data`]
+    OpenCompareFile,
+    /// Scroll the main table so the row (index into all rows) is at the top
+    ScrollMain(usize),
+}
+
+impl Default for SplitView {
+    fn default() -> Self {
+        Self {
+            is_enabled: false,
+            filter: Default::default(),
+            is_time_synced: true,
+            time_field_name: "time".into(),
+            applied_filter: None,
+            compare: None,
+            rows: None,
+            rows_total_len: 0,
+            row_heights: None,
+            selected: None,
+            last_synced: (None, None),
+            should_scroll: false,
+            scroll_to: None,
+            first_visible: None,
+            is_hovered: false,
+            last_scroll_synced: (None, None),
+        }
+    }
+}
+
+/// Of the rows (indices into all rows) the one with the time closest to `target` (in milliseconds since the epoch)
+fn nearest_by_time(
+    data: &Data,
+    rows: impl Iterator<Item = usize>,
+    time_field_name: &str,
+    target: i64,
+) -> Option<usize> {
+    rows.filter_map(|real_index| {
+        let time = time_of(data, real_index, time_field_name)?;
+        Some(((time - target).abs(), real_index))
+    })
+    .min()
+    .map(|(_, real_index)| real_index)
+}
+
+/// Milliseconds since the epoch
+fn time_of(data: &Data, real_index: usize, time_field_name: &str) -> Option<i64> {
+    let time = data
+        .row_by_real_index(real_index)?
+        .field_value(time_field_name)
+        .as_time()?;
+    Some(time.timestamp_millis())
+}
+
+impl SplitView {
+    /// Should be called when new data is loaded
+    pub fn invalidate(&mut self) {
+        if self.compare.is_none() {
+            self.rows = None;
+            self.selected = None;
+        }
+        self.last_synced = (None, None);
+        self.last_scroll_synced = (None, None);
+    }
+
+    /// Shows `data` (loaded from the file called `name`) instead of the main data
+    pub fn set_compare_data(&mut self, data: Data, name: String) {
+        self.compare = Some(CompareFile { name, data });
+        self.is_enabled = true;
+        self.rows = None;
+        self.selected = None;
+        self.last_synced = (None, None);
+        self.last_scroll_synced = (None, None);
+    }
+
+    /// `main_first_visible` is the first row (index into all rows) shown in the main table
+    pub fn ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        main_data: Option<&mut Data>,
+        main_first_visible: Option<usize>,
+        display_options: &DataDisplayOptions,
+    ) -> Option<SplitViewAction> {
+        let mut result = None;
+        ui.horizontal(|ui| match self.compare.as_ref() {
+            Some(compare) => {
+                ui.label(format!("Comparing: {}", compare.name));
+                if ui.button("Close File").clicked() {
+                    self.compare = None;
+                    self.rows = None;
+                    self.selected = None;
+                }
+            }
+            None => {
+                if ui
+                    .button("Compare File...")
+                    .on_hover_text("Load another file into this pane")
+                    .clicked()
+                {
+                    result = Some(SplitViewAction::OpenCompareFile);
+                }
+            }
+        });
+        let Some(main_data) = main_data else {
+            ui.label("No data");
+            return result;
+        };
+
+        ui.horizontal(|ui| {
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut self.filter.search_key)
+                    .hint_text("Filter for this pane")
+                    .desired_width(150.0),
+            );
+            let is_enter_pressed =
+                response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            if ui.button("Apply").clicked() || is_enter_pressed {
+                self.applied_filter =
+                    (!self.filter.search_key.is_empty()).then(|| self.filter.clone());
+                self.rows = None;
+            }
+            if self.applied_filter.is_some() && ui.button("Unfilter").clicked() {
+                self.applied_filter = None;
+                self.rows = None;
+            }
+            if self.compare.is_some() {
+                ui.checkbox(&mut self.is_time_synced, "Sync by")
+                    .on_hover_text(
+                        "Selecting or scrolling to a row selects or scrolls to the row closest \
+                        in time in the other table",
+                    );
+                ui.add(egui::TextEdit::singleline(&mut self.time_field_name).desired_width(60.0));
+                return;
+            }
+            if ui
+                .button("⬅ Show in main")
+                .on_hover_text("Select this pane's row in the main table")
+                .clicked()
+            {
+                result = self
+                    .selected
+                    .map(|x| SplitViewAction::Row(RowCommand::Select(x)));
+            }
+            if ui
+                .button("➡ From main")
+                .on_hover_text("Select the main table's row in this pane")
+                .clicked()
+            {
+                self.selected = main_data.selected_real_index();
+                self.should_scroll = true;
+            }
+        });
+
+        // Taken so the data shown and the main data can be borrowed at the same time
+        let mut compare = self.compare.take();
+        match compare.as_mut() {
+            Some(compare) => {
+                self.refresh(&mut compare.data, display_options);
+                if self.is_time_synced {
+                    if let Some(command) = self.sync_by_time(main_data, &compare.data) {
+                        result = Some(SplitViewAction::Row(command));
+                    } else if let Some(real_index) =
+                        self.sync_scroll_by_time(main_first_visible, main_data, &compare.data)
+                    {
+                        result = Some(SplitViewAction::ScrollMain(real_index));
+                    }
+                }
+                self.table_ui(ui, &compare.data, display_options);
+            }
+            None => {
+                self.refresh(main_data, display_options);
+                self.table_ui(ui, main_data, display_options);
+            }
+        }
+        self.compare = compare;
+        result
+    }
+
+    /// Selects the row closest in time to the row newly selected in the other table.
+    /// Returns the command to select the row in the main table if the selection in this table changed
+    fn sync_by_time(&mut self, main_data: &Data, data: &Data) -> Option<RowCommand> {
+        let main_selected = main_data.selected_real_index();
+        let (last_main, last_selected) = self.last_synced;
+        let mut result = None;
+        if main_selected != last_main {
+            if let Some(target) =
+                main_selected.and_then(|x| time_of(main_data, x, &self.time_field_name))
+            {
+                let rows = self.rows.iter().flatten().copied();
+                if let Some(nearest) = nearest_by_time(data, rows, &self.time_field_name, target) {
+                    self.selected = Some(nearest);
+                    self.should_scroll = true;
+                }
+            }
+        } else if self.selected != last_selected {
+            if let Some(target) = self
+                .selected
+                .and_then(|x| time_of(data, x, &self.time_field_name))
+            {
+                let rows = main_data.visible_real_indices().into_iter();
+                result = nearest_by_time(main_data, rows, &self.time_field_name, target)
+                    .map(RowCommand::Select);
+            }
+        }
+        // The main selection only changes after the command is performed
+        let main_selected = match result {
+            Some(RowCommand::Select(x)) => Some(x),
+            _ => main_selected,
+        };
+        self.last_synced = (main_selected, self.selected);
+        result
+    }
+
+    /// Scrolls the table not being scrolled by the user so its first visible row is the one closest in time to the
+    /// first visible row of the table being scrolled. The pointer is over the table being scrolled so the other
+    /// table scrolling in response does not sync back.
+    /// Returns the row (index into all rows) to scroll the main table to if this table was scrolled
+    fn sync_scroll_by_time(
+        &mut self,
+        main_first_visible: Option<usize>,
+        main_data: &Data,
+        data: &Data,
+    ) -> Option<usize> {
+        let (last_main, last_first) = self.last_scroll_synced;
+        self.last_scroll_synced = (main_first_visible, self.first_visible);
+        if self.is_hovered {
+            if self.first_visible == last_first {
+                return None;
+            }
+            let target = time_of(data, self.first_visible?, &self.time_field_name)?;
+            let rows = main_data.visible_real_indices().into_iter();
+            nearest_by_time(main_data, rows, &self.time_field_name, target)
+        } else {
+            if main_first_visible == last_main {
+                return None;
+            }
+            let target = time_of(main_data, main_first_visible?, &self.time_field_name)?;
+            let rows = self.rows.iter().flatten().copied();
+            self.scroll_to = nearest_by_time(data, rows, &self.time_field_name, target);
+            None
+        }
+    }
+
+    /// Heights of the rows shown using the same settings as the main table, only recomputed when something changed
+    fn update_row_heights(&mut self, data: &Data, settings: &RowHeight, text_height: f32) {
+        let is_current = self
+            .row_heights
+            .as_ref()
+            .is_some_and(|(cached_settings, height, _)| {
+                cached_settings == settings && *height == text_height
+            });
+        if is_current {
+            return;
+        }
+        let heights = self
+            .rows
+            .iter()
+            .flatten()
+            .map(|&real_index| {
+                data.row_by_real_index(real_index)
+                    .map_or(1, |row| settings.line_count(row)) as f32
+                    * text_height
+            })
+            .collect();
+        self.row_heights = Some((settings.clone(), text_height, heights));
+    }
+
+    fn table_ui(&mut self, ui: &mut egui::Ui, data: &Data, display_options: &DataDisplayOptions) {
+        let text_height = egui::TextStyle::Body
+            .resolve(ui.style())
+            .size
+            .max(ui.spacing().interact_size.y);
+        self.update_row_heights(data, &display_options.row_height, text_height);
+        let rows = self.rows.as_deref().unwrap_or_default();
+        let heights = self
+            .row_heights
+            .as_ref()
+            .map(|(_, _, heights)| heights.as_slice())
+            .unwrap_or_default();
+        let fields = display_options.main_list_fields();
+        self.is_hovered = ui.rect_contains_pointer(ui.clip_rect());
+
+        let mut table_builder = TableBuilder::new(ui)
+            .id_salt("split view table")
+            .striped(true)
+            .resizable(true)
+            .cell_layout(egui::Layout::left_to_right(egui::Align::LEFT))
+            .sense(egui::Sense::click());
+        for _ in 0..fields.len().saturating_sub(1) {
+            table_builder = table_builder.column(Column::auto());
+        }
+        table_builder = table_builder
+            .column(Column::remainder())
+            .min_scrolled_height(0.0);
+        // Scrolling to the selected row takes priority over scrolling to keep in sync with the main table
+        let scroll_to_top = self.scroll_to.take();
+        if std::mem::take(&mut self.should_scroll) {
+            if let Some(position) = self.selected.and_then(|x| rows.binary_search(&x).ok()) {
+                table_builder = table_builder.scroll_to_row(position, Some(egui::Align::Center));
+            }
+        } else if let Some(position) = scroll_to_top.and_then(|x| rows.binary_search(&x).ok()) {
+            table_builder = table_builder.scroll_to_row(position, Some(egui::Align::TOP));
+        }
+
+        let mut clicked = None;
+        let mut first_visible = None;
+        table_builder
+            .header(text_height, |mut header| {
+                for field_name in fields {
+                    header.col(|ui| {
+                        ui.strong(field_name);
+                    });
+                }
+            })
+            .body(|body| {
+                body.heterogeneous_rows(heights.iter().copied(), |mut row| {
+                    let real_index = rows[row.index()];
+                    // Only the rows in view are drawn so the first drawn is the first visible
+                    first_visible.get_or_insert(real_index);
+                    let Some(log_row) = data.row_by_real_index(real_index) else {
+                        return;
+                    };
+                    row.set_selected(self.selected == Some(real_index));
+                    for field_name in fields {
+                        row.col(|ui| {
+                            let text = log_row.field_value(field_name).display();
+                            ui.add(
+                                egui::Label::new(
+                                    display_options.formatted_value(field_name, &text),
+                                )
+                                .selectable(false),
+                            );
+                        });
+                    }
+                    if row.response().clicked() {
+                        clicked = Some(real_index);
+                    }
+                });
+            });
+        self.first_visible = first_visible;
+        if clicked.is_some() {
+            self.selected = clicked;
+        }
+    }
+
+    /// Finds the rows to show again if they are not valid anymore
+    fn refresh(&mut self, data: &mut Data, display_options: &DataDisplayOptions) {
+        let total_len = data.total_len_unfiltered();
+        if self.rows.is_some() && self.rows_total_len == total_len {
+            return;
+        }
+        let rows = match self.applied_filter.as_ref() {
+            Some(filter) => (0..total_len)
+                .filter(|&i| data.row_matches(i, filter, display_options.common_fields()))
+                .collect(),
+            None => (0..total_len).collect(),
+        };
+        self.rows = Some(rows);
+        self.rows_total_len = total_len;
+        self.row_heights = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn rows_independent_of_main_filter() {
+        let display_options = DataDisplayOptions::default();
+        let common_fields = display_options.common_fields();
+        let text = "{\"msg\": \"start\"}\n{\"msg\": \"middle\"}\n{\"msg\": \"end\"}";
+        let mut data = Data::try_from((&display_options, text)).unwrap();
+        data.filter = Some(FilterConfig {
+            search_key: "start".into(),
+            ..Default::default()
+        });
+        data.apply_filter(common_fields);
+
+        let mut split_view = SplitView {
+            applied_filter: Some(FilterConfig {
+                search_key: "end".into(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        split_view.refresh(&mut data, &display_options);
+        assert_eq!(split_view.rows, Some(vec![2]));
+        assert_eq!(data.len(), 1);
+
+        split_view.applied_filter = None;
+        split_view.rows = None;
+        split_view.refresh(&mut data, &display_options);
+        assert_eq!(split_view.rows, Some(vec![0, 1, 2]));
+    }
+
+    #[test]
+    fn selection_synced_by_time() {
+        let display_options = DataDisplayOptions::default();
+        let parse = |seconds: &[u32]| {
+            let text = seconds
+                .iter()
+                .map(|s| format!(r#"{{"time": "2024-01-01T00:00:{s:02}Z"}}"#))
+                .collect::<Vec<_>>()
+                .join("\n");
+            Data::try_from((&display_options, text.as_str())).unwrap()
+        };
+        let mut main_data = parse(&[0, 10, 20, 30]);
+        let mut other = parse(&[1, 12, 29]);
+        let mut split_view = SplitView::default();
+        split_view.refresh(&mut other, &display_options);
+
+        main_data.selected_row = Some(2);
+        assert_eq!(split_view.sync_by_time(&main_data, &other), None);
+        assert_eq!(split_view.selected, Some(1));
+
+        // Nothing changed so nothing to sync
+        assert_eq!(split_view.sync_by_time(&main_data, &other), None);
+
+        split_view.selected = Some(2);
+        assert_eq!(
+            split_view.sync_by_time(&main_data, &other),
+            Some(RowCommand::Select(3))
+        );
+        main_data.selected_row = Some(3);
+        assert_eq!(split_view.sync_by_time(&main_data, &other), None);
+        assert_eq!(split_view.selected, Some(2));
+    }
+
+    #[test]
+    fn scrolling_synced_by_time() {
+        let display_options = DataDisplayOptions::default();
+        let parse = |seconds: &[u32]| {
+            let text = seconds
+                .iter()
+                .map(|s| format!(r#"{{"time": "2024-01-01T00:00:{s:02}Z"}}"#))
+                .collect::<Vec<_>>()
+                .join("\n");
+            Data::try_from((&display_options, text.as_str())).unwrap()
+        };
+        let main_data = parse(&[0, 10, 20, 30]);
+        let mut other = parse(&[1, 12, 29]);
+        let mut split_view = SplitView::default();
+        split_view.refresh(&mut other, &display_options);
+
+        // Main table scrolled
+        assert_eq!(
+            split_view.sync_scroll_by_time(Some(2), &main_data, &other),
+            None
+        );
+        assert_eq!(split_view.scroll_to, Some(1));
+
+        // This table scrolling in response does not scroll the main table back
+        split_view.scroll_to = None;
+        split_view.first_visible = Some(1);
+        assert_eq!(
+            split_view.sync_scroll_by_time(Some(2), &main_data, &other),
+            None
+        );
+        assert_eq!(split_view.scroll_to, None);
+
+        // This table scrolled by the user
+        split_view.is_hovered = true;
+        split_view.first_visible = Some(2);
+        assert_eq!(
+            split_view.sync_scroll_by_time(Some(2), &main_data, &other),
+            Some(3)
+        );
+        // The main table scrolling in response does not scroll this table back
+        assert_eq!(
+            split_view.sync_scroll_by_time(Some(3), &main_data, &other),
+            None
+        );
+        assert_eq!(split_view.scroll_to, None);
+    }
+}
@@ -0,0 +1,553 @@
+use std::sync::{
+    mpsc::{self, Receiver, Sender, TryRecvError},
+    Arc, Mutex,
+};
+
+use anyhow::{bail, Context};
+use russh::{
+    client::{self, Handle},
+    keys::{
+        self, agent::AgentIdentity, HashAlg, PrivateKey, PrivateKeyWithHashAlg,
+        PublicKeyOrCertificate,
+    },
+    ChannelMsg,
+};
+
+use super::live_source::LiveSource;
+
+/// Streams lines from a file on a remote host by running `tail` over SSH, optionally through a jump host
+#[derive(serde::Deserialize, serde::Serialize, Default, Debug)]
+#[serde(default)]
+pub struct SshTail {
+    pub is_open: bool,
+    pub settings: SshSettings,
+
+    #[serde(skip)]
+    session: Option<SshSession>,
+    #[serde(skip)]
+    status_msg: Option<String>,
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct SshSettings {
+    /// May include the user (eg. `user@host`), defaults to the local user
+    pub host: String,
+    pub port: u16,
+    /// Host to connect through (eg. `user@jump:22`), empty to connect directly
+    pub jump_host: String,
+    pub remote_path: String,
+    /// Used for the jump host too. If empty the agent's keys then the default keys (`~/.ssh/id_*`) are tried
+    pub identity_file: String,
+    /// Number of existing lines to load before following new ones
+    pub initial_lines: usize,
+}
+
+#[derive(Debug)]
+struct SshSession {
+    lines: Receiver<String>,
+    /// Set before `lines` disconnects to explain why the session ended
+    end_reason: Arc<Mutex<Option<String>>>,
+    /// Runs the session, aborted to disconnect when the session is dropped
+    task: tokio::task::JoinHandle<()>,
+    received_count: usize,
+}
+
+/// Host to connect to and the user to log in as, parsed from `[user@]host[:port]`
+#[derive(Debug, PartialEq, Eq)]
+struct Destination {
+    user: String,
+    host: String,
+    port: u16,
+}
+
+/// Only accepts servers whose key matches the one recorded in the user's known hosts file
+struct KnownHostsCheck {
+    host: String,
+    port: u16,
+}
+
+/// Number of lines of stderr kept to show the user
+const STDERR_LINES_KEPT: usize = 5;
+
+/// Tried when no identity file is set and none of the agent's keys are accepted, missing files are skipped
+const DEFAULT_KEY_FILES: [&str; 3] = ["~/.ssh/id_ed25519", "~/.ssh/id_ecdsa", "~/.ssh/id_rsa"];
+
+/// Port used for the jump host if it does not include one
+const DEFAULT_PORT: u16 = 22;
+
+impl Default for SshSettings {
+    fn default() -> Self {
+        Self {
+            host: Default::default(),
+            port: DEFAULT_PORT,
+            jump_host: Default::default(),
+            remote_path: Default::default(),
+            identity_file: Default::default(),
+            initial_lines: 1000,
+        }
+    }
+}
+
+impl SshSettings {
+    fn remote_command(&self) -> String {
+        format!(
+            "tail -n {} -F {}",
+            self.initial_lines,
+            shell_quote(&self.remote_path)
+        )
+    }
+}
+
+impl Destination {
+    fn parse(value: &str, default_port: u16) -> anyhow::Result<Self> {
+        let value = value.trim();
+        let (user, host_and_port) = match value.rsplit_once('@') {
+            Some((user, rest)) => (user.to_string(), rest),
+            None => (
+                std::env::var("USER")
+                    .or_else(|_| std::env::var("USERNAME"))
+                    .with_context(|| {
+                        format!("no user given in {value:?} and the local user is unknown")
+                    })?,
+                value,
+            ),
+        };
+        let (host, port) = match host_and_port.split_once(':') {
+            Some((host, port)) => (
+                host,
+                port.parse()
+                    .with_context(|| format!("invalid port in {value:?}"))?,
+            ),
+            None => (host_and_port, default_port),
+        };
+        if host.is_empty() {
+            bail!("no host given in {value:?}");
+        }
+        Ok(Self {
+            user,
+            host: host.to_string(),
+            port,
+        })
+    }
+}
+
+impl client::Handler for KnownHostsCheck {
+    type Error = anyhow::Error;
+
+    /// For a host certificate the key it certifies is checked
+    async fn check_server_key(
+        &mut self,
+        server_public_key: &PublicKeyOrCertificate,
+    ) -> anyhow::Result<bool> {
+        let is_known =
+            keys::check_known_hosts(&self.host, self.port, &server_public_key.public_key())
+                .with_context(|| format!("failed to check the key of {}", self.host))?;
+        if !is_known {
+            bail!(
+                "{} is not a known host, connect to it once with ssh to add its key",
+                self.host
+            );
+        }
+        Ok(true)
+    }
+}
+
+impl SshSession {
+    fn start(settings: &SshSettings, ctx: egui::Context) -> anyhow::Result<Self> {
+        let destination = Destination::parse(&settings.host, settings.port)?;
+        let jump = match settings.jump_host.trim() {
+            "" => None,
+            jump_host => Some(Destination::parse(jump_host, DEFAULT_PORT)?),
+        };
+        let identity_file = settings.identity_file.trim().to_string();
+        let command = settings.remote_command();
+
+        let (sender, lines) = mpsc::channel();
+        let end_reason = Arc::new(Mutex::new(None));
+        let end_reason_clone = Arc::clone(&end_reason);
+        let task = tokio::spawn(async move {
+            let reason = run(destination, jump, &identity_file, command, &sender, &ctx)
+                .await
+                .unwrap_or_else(|e| format!("{e:?}"));
+            *end_reason_clone.lock().unwrap() = Some(reason);
+            // Disconnects the channel to signal the end of the stream
+            drop(sender);
+            ctx.request_repaint();
+        });
+
+        Ok(Self {
+            lines,
+            end_reason,
+            task,
+            received_count: 0,
+        })
+    }
+
+    /// Description of why the session ended
+    fn end_reason(&self) -> String {
+        self.end_reason
+            .lock()
+            .unwrap()
+            .take()
+            .unwrap_or_else(|| "SSH session ended".to_string())
+    }
+}
+
+impl Drop for SshSession {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Runs `command` on `destination` sending each line it outputs, returns why the session ended
+async fn run(
+    destination: Destination,
+    jump: Option<Destination>,
+    identity_file: &str,
+    command: String,
+    sender: &Sender<String>,
+    ctx: &egui::Context,
+) -> anyhow::Result<String> {
+    let config = Arc::new(client::Config::default());
+    // Kept for as long as the session runs through it
+    let jump_session = match jump.as_ref() {
+        Some(jump) => Some(connect(Arc::clone(&config), jump, identity_file, None).await?),
+        None => None,
+    };
+    let session = connect(config, &destination, identity_file, jump_session.as_ref()).await?;
+    let mut channel = session.channel_open_session().await?;
+    channel.exec(true, command).await?;
+
+    let mut partial_line = Vec::new();
+    let mut stderr = Vec::new();
+    let mut exit_status = None;
+    while let Some(msg) = channel.wait().await {
+        match msg {
+            ChannelMsg::Data { data } => {
+                partial_line.extend_from_slice(&data);
+                while let Some(end) = partial_line.iter().position(|&x| x == b'\n') {
+                    let line: Vec<u8> = partial_line.drain(..=end).collect();
+                    if sender.send(decode_line(&line)).is_err() {
+                        return Ok("Disconnected".to_string()); // Session was dropped
+                    }
+                }
+                ctx.request_repaint();
+            }
+            // Extended data of type 1 is stderr
+            ChannelMsg::ExtendedData { data, ext: 1 } => {
+                for line in String::from_utf8_lossy(&data).lines() {
+                    stderr.push(line.to_string());
+                    if stderr.len() > STDERR_LINES_KEPT {
+                        stderr.remove(0);
+                    }
+                }
+            }
+            ChannelMsg::ExitStatus {
+                exit_status: status,
+            } => exit_status = Some(status),
+            _ => {}
+        }
+    }
+    if !partial_line.is_empty() {
+        let _ = sender.send(decode_line(&partial_line));
+    }
+
+    let status = match exit_status {
+        Some(status) => format!("exit status: {status}"),
+        None => "no exit status".to_string(),
+    };
+    let stderr = stderr.join("\n");
+    Ok(if stderr.is_empty() {
+        format!("SSH session ended ({status})")
+    } else {
+        format!("SSH session ended ({status}): {stderr}")
+    })
+}
+
+/// Connects directly or through a channel opened on `jump_session` then authenticates
+async fn connect(
+    config: Arc<client::Config>,
+    destination: &Destination,
+    identity_file: &str,
+    jump_session: Option<&Handle<KnownHostsCheck>>,
+) -> anyhow::Result<Handle<KnownHostsCheck>> {
+    let handler = KnownHostsCheck {
+        host: destination.host.clone(),
+        port: destination.port,
+    };
+    let session = match jump_session {
+        Some(jump_session) => {
+            let channel = jump_session
+                .channel_open_direct_tcpip(
+                    destination.host.as_str(),
+                    destination.port.into(),
+                    "127.0.0.1",
+                    0,
+                )
+                .await
+                .with_context(|| format!("jump host failed to connect to {}", destination.host))?;
+            client::connect_stream(config, channel.into_stream(), handler).await
+        }
+        None => {
+            client::connect(
+                config,
+                (destination.host.as_str(), destination.port),
+                handler,
+            )
+            .await
+        }
+    };
+    let mut session =
+        session.with_context(|| format!("failed to connect to {}", destination.host))?;
+    authenticate(&mut session, &destination.user, identity_file)
+        .await
+        .with_context(|| {
+            format!(
+                "failed to authenticate to {} as {}",
+                destination.host, destination.user
+            )
+        })?;
+    Ok(session)
+}
+
+/// Uses the identity file if set otherwise tries the agent's keys then the default keys
+async fn authenticate(
+    session: &mut Handle<KnownHostsCheck>,
+    user: &str,
+    identity_file: &str,
+) -> anyhow::Result<()> {
+    let rsa_hash = session.best_supported_rsa_hash().await?.flatten();
+    if !identity_file.is_empty() {
+        let path = super::paths::expand(identity_file.as_ref());
+        let key = keys::load_secret_key(&path, None)
+            .with_context(|| format!("failed to load {}", path.display()))?;
+        if !authenticate_with_key(session, user, key, rsa_hash).await? {
+            bail!("{} was not accepted", path.display());
+        }
+        return Ok(());
+    }
+
+    #[cfg(unix)]
+    if let Ok(mut agent) = keys::agent::client::AgentClient::connect_env().await {
+        for identity in agent.request_identities().await.unwrap_or_default() {
+            let result = match identity {
+                AgentIdentity::PublicKey { key, .. } => {
+                    session
+                        .authenticate_publickey_with(user, key, rsa_hash, &mut agent)
+                        .await?
+                }
+                AgentIdentity::Certificate { certificate, .. } => {
+                    session
+                        .authenticate_certificate_with(user, certificate, rsa_hash, &mut agent)
+                        .await?
+                }
+            };
+            if result.success() {
+                return Ok(());
+            }
+        }
+    }
+    for path in DEFAULT_KEY_FILES {
+        // Keys protected by a passphrase can only be used through the agent
+        let Ok(key) = keys::load_secret_key(super::paths::expand(path.as_ref()), None) else {
+            continue;
+        };
+        if authenticate_with_key(session, user, key, rsa_hash).await? {
+            return Ok(());
+        }
+    }
+    bail!("none of the agent's keys or the default keys were accepted")
+}
+
+async fn authenticate_with_key(
+    session: &mut Handle<KnownHostsCheck>,
+    user: &str,
+    key: PrivateKey,
+    rsa_hash: Option<HashAlg>,
+) -> anyhow::Result<bool> {
+    let key = PrivateKeyWithHashAlg::new(Arc::new(key), rsa_hash);
+    Ok(session.authenticate_publickey(user, key).await?.success())
+}
+
+/// Drops the line ending (`\n` or `\r\n`), invalid UTF-8 is replaced
+fn decode_line(line: &[u8]) -> String {
+    let line = line.strip_suffix(b"\n").unwrap_or(line);
+    let line = line.strip_suffix(b"\r").unwrap_or(line);
+    String::from_utf8_lossy(line).into_owned()
+}
+
+/// Quotes the value so the remote shell treats it as a single literal argument
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+impl SshTail {
+    fn ui(&mut self, ui: &mut egui::Ui) -> bool {
+        let is_connected = self.is_connected();
+        ui.add_enabled_ui(!is_connected, |ui| {
+            let SshSettings {
+                host,
+                port,
+                jump_host,
+                remote_path,
+                identity_file,
+                initial_lines,
+            } = &mut self.settings;
+            egui::Grid::new("ssh settings").show(ui, |ui| {
+                ui.label("Host");
+                ui.add(egui::TextEdit::singleline(host).hint_text("user@host"));
+                ui.end_row();
+                ui.label("Port");
+                ui.add(egui::DragValue::new(port));
+                ui.end_row();
+                ui.label("Jump host");
+                ui.add(egui::TextEdit::singleline(jump_host).hint_text("(connect directly)"));
+                ui.end_row();
+                ui.label("Remote path");
+                ui.add(egui::TextEdit::singleline(remote_path).hint_text("/var/log/app.log"));
+                ui.end_row();
+                ui.label("Identity file");
+                ui.add(
+                    egui::TextEdit::singleline(identity_file).hint_text("(agent or default keys)"),
+                );
+                ui.end_row();
+                ui.label("Initial lines");
+                ui.add(egui::DragValue::new(initial_lines));
+                ui.end_row();
+            });
+        });
+
+        let mut result = false;
+        ui.horizontal(|ui| {
+            if is_connected {
+                if ui.button("Disconnect").clicked() {
+                    self.session = None;
+                    self.status_msg = Some("Disconnected".to_string());
+                }
+            } else if ui
+                .add_enabled(
+                    !self.settings.host.trim().is_empty()
+                        && !self.settings.remote_path.trim().is_empty(),
+                    egui::Button::new("Connect"),
+                )
+                .clicked()
+            {
+                match SshSession::start(&self.settings, ui.ctx().clone()) {
+                    Ok(session) => {
+                        self.session = Some(session);
+                        self.status_msg = None;
+                        result = true;
+                    }
+                    Err(e) => self.status_msg = Some(format!("{e:?}")),
+                }
+            }
+        });
+        if let Some(session) = self.session.as_ref() {
+            ui.label(format!(
+                "Streaming {} ({} lines received)",
+                self.source_description(),
+                session.received_count
+            ));
+        }
+        if let Some(msg) = self.status_msg.as_ref() {
+            ui.label(msg);
+        }
+        result
+    }
+}
+
+impl LiveSource for SshTail {
+    fn show(&mut self, ctx: &egui::Context) -> bool {
+        let mut is_open = self.is_open;
+        let mut result = false;
+        egui::Window::new("SSH Tail")
+            .open(&mut is_open)
+            .show(ctx, |ui| result = self.ui(ui));
+        self.is_open = is_open;
+        result
+    }
+
+    /// Also closes the session if the stream ended
+    fn take_new_lines(&mut self) -> Vec<String> {
+        let Some(session) = self.session.as_mut() else {
+            return Vec::new();
+        };
+        let mut result = Vec::new();
+        loop {
+            match session.lines.try_recv() {
+                Ok(line) => result.push(line),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.status_msg = Some(session.end_reason());
+                    self.session = None;
+                    break;
+                }
+            }
+        }
+        if let Some(session) = self.session.as_mut() {
+            session.received_count += result.len();
+        }
+        result
+    }
+
+    fn disconnect_with_error(&mut self, msg: String) {
+        self.session = None;
+        self.status_msg = Some(msg);
+    }
+
+    fn is_connected(&self) -> bool {
+        self.session.is_some()
+    }
+
+    /// Same form scp uses (`host:path`)
+    fn source_description(&self) -> String {
+        format!(
+            "{}:{}",
+            self.settings.host.trim(),
+            self.settings.remote_path.trim()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn remote_command_quotes_path() {
+        let settings = SshSettings {
+            remote_path: "/var/log/it's here.log".into(),
+            initial_lines: 10,
+            ..Default::default()
+        };
+        assert_eq!(
+            settings.remote_command(),
+            r"tail -n 10 -F '/var/log/it'\''s here.log'"
+        );
+    }
+
+    #[test]
+    fn destination_parsed() {
+        assert_eq!(
+            Destination::parse(" admin@bastion:2200 ", DEFAULT_PORT).unwrap(),
+            Destination {
+                user: "admin".into(),
+                host: "bastion".into(),
+                port: 2200,
+            }
+        );
+        assert_eq!(
+            Destination::parse("user@box", 2222).unwrap(),
+            Destination {
+                user: "user".into(),
+                host: "box".into(),
+                port: 2222,
+            }
+        );
+        assert!(Destination::parse("user@box:ssh", DEFAULT_PORT).is_err());
+        assert!(Destination::parse("user@", DEFAULT_PORT).is_err());
+    }
+}
@@ -0,0 +1,193 @@
+/// Shows values holding a multi-line stack trace in the details pane one frame per line,
+/// optionally hiding frames from dependencies
+#[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct StackTraceView {
+    pub collapse_dependencies: bool,
+    /// A frame containing any of these is from a dependency (eg. `node_modules`)
+    pub dependency_patterns: Vec<String>,
+}
+
+impl Default for StackTraceView {
+    fn default() -> Self {
+        Self {
+            collapse_dependencies: true,
+            dependency_patterns: [
+                "node_modules",
+                "node:internal",
+                "site-packages",
+                "<frozen ",
+                "/.cargo/registry/",
+                "/rustc/",
+                "at java.",
+                "at jdk.",
+                "at sun.",
+            ]
+            .into_iter()
+            .map(String::from)
+            .collect(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum TraceLine<'a> {
+    /// A frame or other text (eg. the error message)
+    Text { text: &'a str, is_frame: bool },
+    /// Frames from dependencies that are collapsed
+    Hidden(usize),
+}
+
+/// Returns true if the text looks like a stack trace and should be shown as one
+pub fn is_stack_trace(text: &str) -> bool {
+    text.lines().filter(|line| is_frame(line)).take(2).count() == 2
+}
+
+/// Lines of the stack traces of common languages that refer to a location in the code
+fn is_frame(line: &str) -> bool {
+    let line = line.trim_start();
+    line.starts_with("at ") // JavaScript, Java, C#
+        || line.starts_with("File \"") // Python
+        || line.starts_with("from ") // Ruby
+        || line
+            .split_once(": ") // Rust (`  0: crate::function`)
+            .is_some_and(|(prefix, _)| !prefix.is_empty() && prefix.chars().all(|c| c.is_ascii_digit()))
+}
+
+impl StackTraceView {
+    fn is_dependency(&self, line: &str) -> bool {
+        self.dependency_patterns
+            .iter()
+            .any(|pattern| !pattern.is_empty() && line.contains(pattern.as_str()))
+    }
+
+    fn lines<'a>(&self, text: &'a str) -> Vec<TraceLine<'a>> {
+        let mut result = Vec::new();
+        for line in text.lines() {
+            let is_frame = is_frame(line);
+            if self.collapse_dependencies && is_frame && self.is_dependency(line) {
+                match result.last_mut() {
+                    Some(TraceLine::Hidden(count)) => *count += 1,
+                    _ => result.push(TraceLine::Hidden(1)),
+                }
+            } else {
+                result.push(TraceLine::Text {
+                    text: line,
+                    is_frame,
+                });
+            }
+        }
+        result
+    }
+
+    /// Number of lines the trace takes up when shown
+    pub fn line_count(&self, text: &str) -> usize {
+        self.lines(text).len()
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, text: &str, color: egui::Color32) {
+        let mut should_expand = false;
+        ui.vertical(|ui| {
+            ui.spacing_mut().item_spacing.y = 0.0;
+            for line in self.lines(text) {
+                match line {
+                    TraceLine::Text { text, is_frame } => {
+                        let mut text = egui::RichText::new(text).monospace().color(color);
+                        if !is_frame {
+                            text = text.strong();
+                        }
+                        ui.label(text);
+                    }
+                    TraceLine::Hidden(count) => {
+                        let text = format!(
+                            "    … {count} dependency frame{}",
+                            if count == 1 { "" } else { "s" }
+                        );
+                        if ui
+                            .add(
+                                egui::Label::new(egui::RichText::new(text).monospace().weak())
+                                    .sense(egui::Sense::click()),
+                            )
+                            .on_hover_text("Click to show frames from dependencies")
+                            .clicked()
+                        {
+                            should_expand = true;
+                        }
+                    }
+                }
+            }
+        });
+        if should_expand {
+            self.collapse_dependencies = false;
+        }
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.checkbox(
+            &mut self.collapse_dependencies,
+            "Collapse frames from dependencies",
+        );
+        ui.label("A frame containing any of these (one per line) is from a dependency");
+        let mut text = self.dependency_patterns.join("\n");
+        if ui
+            .add(egui::TextEdit::multiline(&mut text).desired_rows(4))
+            .changed()
+        {
+            self.dependency_patterns = text.split('\n').map(String::from).collect();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+
+    const NODE_TRACE: &str = "TypeError: x is undefined
+    at handler (/srv/app/src/routes.js:10:5)
+    at Layer.handle (/srv/app/node_modules/express/lib/router/layer.js:95:5)
+    at next (/srv/app/node_modules/express/lib/router/route.js:137:13)
+    at main (/srv/app/src/index.js:3:1)";
+
+    #[rstest]
+    #[case::node(NODE_TRACE, true)]
+    #[case::python(
+        "Traceback (most recent call last):\n  File \"a.py\", line 3, in <module>\n    f()\n  File \"a.py\", line 2, in f\nValueError",
+        true
+    )]
+    #[case::rust("panicked at src/main.rs:2:5\n   0: app::main\n   1: core::ops::function::FnOnce::call_once", true)]
+    #[case::one_frame("Error\n    at main (index.js:1:1)", false)]
+    #[case::plain_text("meet at noon\nthen at 3", false)]
+    fn detection(#[case] text: &str, #[case] expected: bool) {
+        assert_eq!(is_stack_trace(text), expected);
+    }
+
+    #[test]
+    fn dependency_frames_collapsed() {
+        let mut view = StackTraceView::default();
+        let lines: Vec<&str> = NODE_TRACE.lines().collect();
+        assert_eq!(
+            view.lines(NODE_TRACE),
+            vec![
+                TraceLine::Text {
+                    text: lines[0],
+                    is_frame: false
+                },
+                TraceLine::Text {
+                    text: lines[1],
+                    is_frame: true
+                },
+                TraceLine::Hidden(2),
+                TraceLine::Text {
+                    text: lines[4],
+                    is_frame: true
+                },
+            ]
+        );
+
+        view.collapse_dependencies = false;
+        assert_eq!(view.line_count(NODE_TRACE), 5);
+    }
+}
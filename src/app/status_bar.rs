@@ -0,0 +1,196 @@
+use super::data::{Data, FieldContent};
+
+/// Seconds between estimates of the memory used while the number of rows stays the same
+const MEMORY_REFRESH_SECS: f64 = 5.0;
+
+/// Bar along the bottom of the window summarizing the selection, filters and state of the loaded data
+#[derive(serde::Deserialize, serde::Serialize, Debug)]
+#[serde(default)]
+pub struct StatusBar {
+    pub is_enabled: bool,
+
+    /// Walking all rows is too slow to do each frame so the estimate is kept until the rows change
+    #[serde(skip)]
+    memory: Option<MemoryEstimate>,
+}
+
+#[derive(Debug)]
+struct MemoryEstimate {
+    total_len: usize,
+    /// Time from [`egui::InputState::time`] when the estimate was made
+    computed_at: f64,
+    bytes: usize,
+}
+
+/// State owned by the app that is shown in the bar
+pub struct StatusInfo<'a> {
+    pub time_field_name: &'a str,
+    pub is_following: bool,
+    pub is_live_connected: bool,
+    /// Lines held back from the live source, None when not paused
+    pub paused_count: Option<usize>,
+    /// Lines dropped while paused to stay within the live row limit
+    pub paused_dropped_count: usize,
+}
+
+impl Default for StatusBar {
+    fn default() -> Self {
+        Self {
+            is_enabled: true,
+            memory: None,
+        }
+    }
+}
+
+impl StatusBar {
+    pub fn invalidate(&mut self) {
+        self.memory = None;
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui, data: Option<&Data>, info: StatusInfo<'_>) {
+        ui.horizontal(|ui| {
+            let Some(data) = data else {
+                ui.weak("No data loaded");
+                self.memory = None;
+                return;
+            };
+
+            match (data.selected_row, data.selected_real_index()) {
+                (Some(position), Some(real_index)) => {
+                    ui.label(format!(
+                        "Row {} of {}",
+                        super::as_string_with_separators(position + 1),
+                        super::as_string_with_separators(data.len())
+                    ))
+                    .on_hover_text("Position among the visible rows");
+                    ui.weak(format!(
+                        "(row# {})",
+                        super::as_string_with_separators(real_index + 1)
+                    ))
+                    .on_hover_text("Position in the file ignoring any filter");
+                }
+                _ => {
+                    ui.weak(format!(
+                        "No row selected ({} rows)",
+                        super::as_string_with_separators(data.len())
+                    ));
+                }
+            }
+            let time = data
+                .selected_row()
+                .map(|row| row.field_value(info.time_field_name));
+            if let Some(time @ FieldContent::Present(_)) = time {
+                ui.separator();
+                ui.label(time.display_str())
+                    .on_hover_text(format!("Value of \"{}\"", info.time_field_name));
+            }
+
+            ui.separator();
+            let filter_text = match data.applied_filter() {
+                Some(filter) => format!("Filter: {filter}"),
+                None => "No filter".to_string(),
+            };
+            if data.is_filtered() {
+                ui.label(filter_text);
+            } else {
+                ui.weak(filter_text);
+            }
+            let hidden_count = data.hidden_count();
+            if hidden_count > 0 {
+                ui.weak(format!(
+                    "{} hidden",
+                    super::as_string_with_separators(hidden_count)
+                ));
+            }
+
+            if info.is_following || info.is_live_connected || info.paused_count.is_some() {
+                ui.separator();
+                if info.is_live_connected {
+                    ui.label("● Live")
+                        .on_hover_text("Rows are being received from a live source");
+                }
+                if let Some(count) = info.paused_count {
+                    let dropped = match info.paused_dropped_count {
+                        0 => String::new(),
+                        dropped => format!(
+                            ", {} oldest dropped",
+                            super::as_string_with_separators(dropped)
+                        ),
+                    };
+                    ui.label(format!(
+                        "⏸ Paused ({} buffered{dropped})",
+                        super::as_string_with_separators(count)
+                    ))
+                    .on_hover_text(
+                        "Rows received are held until resumed (up to the live row limit)",
+                    );
+                }
+                if info.is_following {
+                    ui.label("Following")
+                        .on_hover_text("The newest row is kept selected as rows are added");
+                }
+            }
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                let bytes = self.memory_estimate(ui, data);
+                ui.weak(format!("~{}", format_bytes(bytes)))
+                    .on_hover_text("Rough estimate of the memory used by the rows");
+            });
+        });
+    }
+
+    fn memory_estimate(&mut self, ui: &egui::Ui, data: &Data) -> usize {
+        let now = ui.input(|i| i.time);
+        let total_len = data.total_len_unfiltered();
+        match self.memory.as_ref() {
+            Some(estimate)
+                if estimate.total_len == total_len
+                    && now - estimate.computed_at < MEMORY_REFRESH_SECS =>
+            {
+                estimate.bytes
+            }
+            _ => {
+                let bytes = data.approximate_memory_size();
+                self.memory = Some(MemoryEstimate {
+                    total_len,
+                    computed_at: now,
+                    bytes,
+                });
+                bytes
+            }
+        }
+    }
+}
+
+fn format_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} {}", UNITS[0])
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case(0, "0 B")]
+    #[case(1023, "1023 B")]
+    #[case(1024, "1.0 KB")]
+    #[case(5 * 1024 * 1024 + 512 * 1024, "5.5 MB")]
+    #[case(3 * 1024 * 1024 * 1024, "3.0 GB")]
+    fn bytes_formatted_with_units(#[case] bytes: usize, #[case] expected: &str) {
+        assert_eq!(format_bytes(bytes), expected);
+    }
+}
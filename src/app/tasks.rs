@@ -0,0 +1,394 @@
+use std::sync::{
+    atomic::{AtomicBool, AtomicU32, Ordering},
+    Arc,
+};
+
+use super::data::Data;
+
+/// Shared between a background task and the UI so the task can report progress and the UI can request cancellation
+#[derive(Debug, Clone)]
+pub struct TaskHandle {
+    /// Bits of an f32 in the range [0, 1] or [`NO_PROGRESS`] if the task has not reported any progress
+    progress: Arc<AtomicU32>,
+    is_cancel_requested: Arc<AtomicBool>,
+    /// Only used by work done on the UI thread (see [`ChunkedTask`]), other tasks are finished when their promise is ready
+    is_finished: Arc<AtomicBool>,
+}
+
+/// Sentinel used before a task reports progress (Not a valid progress value as it is a NaN)
+const NO_PROGRESS: u32 = u32::MAX;
+
+pub enum TaskOutcome {
+    Loaded(Box<Data>),
+    /// Loaded with the options of the display profile named `profile` as it matched the file
+    LoadedWithProfile {
+        data: Box<Data>,
+        profile: String,
+    },
+    /// Loaded to compare with the main data, `name` is the name of the file
+    LoadedForCompare {
+        data: Box<Data>,
+        name: String,
+    },
+    /// Completed with a message to show the user
+    Done(String),
+    Failed(String),
+    Cancelled,
+}
+
+struct Task {
+    name: String,
+    handle: TaskHandle,
+    /// None for work done on the UI thread, it hands its result over itself
+    promise: Option<poll_promise::Promise<TaskOutcome>>,
+}
+
+/// Keeps track of long running operations: loading files or CloudWatch events and saving exports in the background,
+/// filtering and the stats windows (top values, schema, templates, summary) a chunk of rows per frame on the UI thread
+/// (see [`ChunkedTask`])
+#[derive(Default)]
+pub struct TaskManager {
+    tasks: Vec<Task>,
+}
+
+/// Rows processed per frame by work done on the UI thread, small enough to keep the UI responsive
+pub const ROWS_PER_CHUNK: usize = 20_000;
+
+/// Tracks work done on the UI thread a chunk of rows per frame. Used for work on the loaded data as the UI keeps using
+/// the data between chunks so the work cannot be moved to another thread.
+/// Only listed with the other tasks once it takes more than one chunk and removed from the list when dropped
+pub struct ChunkedTask {
+    name: String,
+    ctx: egui::Context,
+    handle: Option<TaskHandle>,
+}
+
+/// Work over the rows of the loaded data done a chunk at a time, see [`ChunkedCache`]
+pub trait ChunkedWork {
+    type Output;
+
+    /// Processes up to `max_rows` more rows, returns the output once all rows are processed
+    fn step(&mut self, data: &Data, max_rows: usize) -> Option<Self::Output>;
+
+    /// Fraction of the rows processed
+    fn progress(&self) -> f32;
+}
+
+/// Output of [`ChunkedWork`] for the key it was computed for (eg. the visible rows and the field). When the key
+/// changes the work is done again as a task that can be cancelled, the previous output is kept until the new one is
+/// ready. If the key changes while the work runs the work completes before it is started again for the new key
+pub struct ChunkedCache<K, W: ChunkedWork> {
+    done: Option<(K, W::Output)>,
+    pending: Option<(K, W, ChunkedTask)>,
+    /// Key the work was cancelled for, the work only starts again once the key changes or it is retried from
+    /// [`Self::status_ui`]
+    cancelled: Option<K>,
+}
+
+impl TaskHandle {
+    fn new() -> Self {
+        Self {
+            progress: Arc::new(AtomicU32::new(NO_PROGRESS)),
+            is_cancel_requested: Default::default(),
+            is_finished: Default::default(),
+        }
+    }
+
+    pub fn set_progress(&self, fraction: f32) {
+        self.progress
+            .store(fraction.clamp(0.0, 1.0).to_bits(), Ordering::Relaxed);
+    }
+
+    pub fn progress(&self) -> Option<f32> {
+        let bits = self.progress.load(Ordering::Relaxed);
+        (bits != NO_PROGRESS).then(|| f32::from_bits(bits))
+    }
+
+    pub fn cancel(&self) {
+        self.is_cancel_requested.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancel_requested(&self) -> bool {
+        self.is_cancel_requested.load(Ordering::Relaxed)
+    }
+
+    fn finish(&self) {
+        self.is_finished.store(true, Ordering::Relaxed);
+    }
+
+    fn is_finished(&self) -> bool {
+        self.is_finished.load(Ordering::Relaxed)
+    }
+}
+
+impl TaskManager {
+    /// Starts a task, `start` is passed the handle the task should use to report progress and check for cancellation
+    pub fn spawn(
+        &mut self,
+        name: impl Into<String>,
+        start: impl FnOnce(TaskHandle) -> poll_promise::Promise<TaskOutcome>,
+    ) {
+        let handle = TaskHandle::new();
+        let promise = start(handle.clone());
+        self.tasks.push(Task {
+            name: name.into(),
+            handle,
+            promise: Some(promise),
+        });
+    }
+
+    /// Lists work the caller does on the UI thread, see [`ChunkedTask`]
+    fn track(&mut self, name: String) -> TaskHandle {
+        let handle = TaskHandle::new();
+        self.tasks.push(Task {
+            name,
+            handle: handle.clone(),
+            promise: None,
+        });
+        handle
+    }
+
+    /// Asks the user where to save `text` then writes it out in the background
+    pub fn spawn_save(
+        &mut self,
+        name: impl Into<String>,
+        default_file_name: &str,
+        text: String,
+        ctx: egui::Context,
+    ) {
+        let dialog = rfd::AsyncFileDialog::new().set_file_name(default_file_name);
+        self.spawn(name, |_| {
+            execute(async move {
+                let Some(file) = dialog.save_file().await else {
+                    // user canceled saving
+                    return TaskOutcome::Cancelled;
+                };
+                let result = match file.write(text.as_bytes()).await {
+                    Ok(()) => TaskOutcome::Done(format!("Saved {}", file.file_name())),
+                    Err(e) => TaskOutcome::Failed(format!("{e:?}")),
+                };
+                ctx.request_repaint();
+                result
+            })
+        });
+    }
+
+    pub fn is_busy(&self) -> bool {
+        !self.tasks.is_empty()
+    }
+
+    /// Removes and returns the tasks that have completed. Tasks that were cancelled always report as cancelled even if they completed.
+    /// Work done on the UI thread is removed without being returned as it hands its result over itself
+    pub fn take_finished(&mut self) -> Vec<(String, TaskOutcome)> {
+        let (finished, running): (Vec<Task>, Vec<Task>) = std::mem::take(&mut self.tasks)
+            .into_iter()
+            .partition(|task| match task.promise.as_ref() {
+                Some(promise) => promise.ready().is_some(),
+                None => task.handle.is_finished(),
+            });
+        self.tasks = running;
+        finished
+            .into_iter()
+            .filter_map(|task| {
+                let promise = task.promise?;
+                let outcome = if task.handle.is_cancel_requested() {
+                    TaskOutcome::Cancelled
+                } else {
+                    promise.block_and_take() // We know the promise is ready at this point
+                };
+                Some((task.name, outcome))
+            })
+            .collect()
+    }
+
+    /// Lists the running tasks with their progress and a button to cancel each
+    pub fn ui(&self, ui: &mut egui::Ui) {
+        if !self.is_busy() {
+            return;
+        }
+        for task in self.tasks.iter() {
+            ui.horizontal(|ui| {
+                match task.handle.progress() {
+                    Some(progress) => {
+                        ui.add(
+                            egui::ProgressBar::new(progress)
+                                .desired_width(150.0)
+                                .show_percentage(),
+                        );
+                    }
+                    None => {
+                        ui.spinner();
+                    }
+                }
+                ui.label(&task.name);
+                if task.handle.is_cancel_requested() {
+                    ui.label("(Cancelling...)");
+                } else if ui.button("Cancel").clicked() {
+                    task.handle.cancel();
+                }
+            });
+        }
+        // Keep progress updating while tasks are running
+        ui.ctx()
+            .request_repaint_after(std::time::Duration::from_millis(100));
+    }
+}
+
+impl ChunkedTask {
+    pub fn new(name: impl Into<String>, ctx: egui::Context) -> Self {
+        Self {
+            name: name.into(),
+            ctx,
+            handle: None,
+        }
+    }
+
+    /// Should be called after each chunk that did not complete the work
+    pub fn report_progress(&mut self, tasks: &mut TaskManager, fraction: f32) {
+        let handle = self
+            .handle
+            .get_or_insert_with(|| tasks.track(self.name.clone()));
+        handle.set_progress(fraction);
+        // The next chunk is done in the next frame
+        self.ctx.request_repaint();
+    }
+
+    pub fn is_cancel_requested(&self) -> bool {
+        self.handle
+            .as_ref()
+            .is_some_and(TaskHandle::is_cancel_requested)
+    }
+}
+
+impl Drop for ChunkedTask {
+    fn drop(&mut self) {
+        if let Some(handle) = self.handle.as_ref() {
+            handle.finish();
+        }
+    }
+}
+
+impl<K: PartialEq, W: ChunkedWork> ChunkedCache<K, W> {
+    /// Brings the output up to date with `key` doing the next chunk of work if needed, `start` creates the work for
+    /// the key. Returns true if a new output is ready
+    pub fn update(
+        &mut self,
+        ctx: &egui::Context,
+        tasks: &mut TaskManager,
+        name: &str,
+        data: &Data,
+        key: K,
+        start: impl FnOnce(&K) -> W,
+    ) -> bool {
+        if self
+            .done
+            .as_ref()
+            .is_some_and(|(done_key, _)| *done_key == key)
+        {
+            return false;
+        }
+        if self.pending.is_none() {
+            if self.cancelled.as_ref() == Some(&key) {
+                return false;
+            }
+            self.cancelled = None;
+            let work = start(&key);
+            self.pending = Some((key, work, ChunkedTask::new(name, ctx.clone())));
+        }
+        let (_, work, task) = self.pending.as_mut().expect("set above if it was empty");
+        if task.is_cancel_requested() {
+            self.cancelled = self.pending.take().map(|(key, ..)| key);
+            return false;
+        }
+        match work.step(data, ROWS_PER_CHUNK) {
+            Some(output) => {
+                self.done = self.pending.take().map(|(key, ..)| (key, output));
+                true
+            }
+            None => {
+                task.report_progress(tasks, work.progress());
+                false
+            }
+        }
+    }
+
+    /// The last output completed (may be for an earlier key while the work is done again)
+    pub fn output(&self) -> Option<&W::Output> {
+        self.done.as_ref().map(|(_, output)| output)
+    }
+
+    pub fn output_mut(&mut self) -> Option<&mut W::Output> {
+        self.done.as_mut().map(|(_, output)| output)
+    }
+
+    /// Shows if the work is running or was cancelled (with a button to start it again)
+    pub fn status_ui(&mut self, ui: &mut egui::Ui) {
+        if self.pending.is_some() {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label("Updating...");
+            });
+        } else if self.cancelled.is_some() {
+            ui.horizontal(|ui| {
+                ui.label("Update cancelled");
+                if ui.button("Retry").clicked() {
+                    self.cancelled = None;
+                }
+            });
+        }
+    }
+
+    /// Drops the work in progress (eg. when its window is closed), the last output is kept
+    pub fn stop(&mut self) {
+        self.pending = None;
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
+impl<K, W: ChunkedWork> Default for ChunkedCache<K, W> {
+    fn default() -> Self {
+        Self {
+            done: None,
+            pending: None,
+            cancelled: None,
+        }
+    }
+}
+
+impl<K: std::fmt::Debug, W: ChunkedWork> std::fmt::Debug for ChunkedCache<K, W> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ChunkedCache")
+            .field("done", &self.done.as_ref().map(|(key, _)| key))
+            .field("pending", &self.pending.as_ref().map(|(key, ..)| key))
+            .field("cancelled", &self.cancelled)
+            .finish()
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn execute<T: Send + 'static, F: std::future::Future<Output = T> + 'static + Send>(
+    f: F,
+) -> poll_promise::Promise<T> {
+    poll_promise::Promise::spawn_async(f)
+}
+
+/// Lets the browser handle events and draw frames before continuing (tasks share the thread with the UI on the web)
+#[cfg(target_arch = "wasm32")]
+pub async fn yield_to_browser() {
+    let promise = wasm_bindgen_futures::js_sys::Promise::new(&mut |resolve, _reject| {
+        if let Some(window) = web_sys::window() {
+            let _ = window.set_timeout_with_callback(&resolve);
+        }
+    });
+    let _ = wasm_bindgen_futures::JsFuture::from(promise).await;
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn execute<T: 'static, F: std::future::Future<Output = T> + 'static>(
+    f: F,
+) -> poll_promise::Promise<T> {
+    poll_promise::Promise::spawn_local(f)
+}
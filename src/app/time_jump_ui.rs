@@ -0,0 +1,69 @@
+use chrono::{DateTime, FixedOffset};
+
+use super::data::{time_jump, Data};
+
+/// Input to select the row closest to a time typed by the user (eg. `14:32`)
+#[derive(Default, Debug)]
+pub struct TimeJumpUi {
+    text: String,
+    /// Why the last jump failed
+    error: Option<String>,
+}
+
+impl TimeJumpUi {
+    /// Returns the time to jump to when the user asks to jump
+    pub fn ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        data: Option<&Data>,
+        time_field_name: &str,
+    ) -> Option<DateTime<FixedOffset>> {
+        let response = ui.add(
+            egui::TextEdit::singleline(&mut self.text)
+                .hint_text("Jump to time")
+                .desired_width(120.0),
+        );
+        let response = response.on_hover_text(format!(
+            "Selects the row with the {time_field_name:?} closest to eg. 14:32, 2024-05-01 14:32:05 or an RFC 3339 time.\n\
+             A time without a date uses the date of the selected row"
+        ));
+        let is_enter_pressed =
+            response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+        let is_clicked = ui
+            .add_enabled(!self.text.trim().is_empty(), egui::Button::new("🕐"))
+            .on_hover_text("Jump to time")
+            .clicked();
+        if let Some(error) = self.error.as_ref() {
+            ui.colored_label(ui.visuals().error_fg_color, "⚠")
+                .on_hover_text(error);
+        }
+        if !(is_enter_pressed || is_clicked) || self.text.trim().is_empty() {
+            return None;
+        }
+        let reference = data.and_then(|data| reference_time(data, time_field_name));
+        let result = time_jump::parse_target(&self.text, reference);
+        self.error = result.is_none().then(|| {
+            if reference.is_none() {
+                format!("Could not read the time or no row has a valid {time_field_name:?} to take the date from")
+            } else {
+                "Could not read the time".to_string()
+            }
+        });
+        result
+    }
+
+    /// Called when no row could be selected for the time
+    pub fn set_not_found(&mut self, time_field_name: &str) {
+        self.error = Some(format!("No visible row has a valid {time_field_name:?}"));
+    }
+}
+
+/// The time of the selected row or if it does not have one the first visible row with a time
+fn reference_time(data: &Data, time_field_name: &str) -> Option<DateTime<FixedOffset>> {
+    data.selected_row()
+        .and_then(|row| row.field_value(time_field_name).as_time())
+        .or_else(|| {
+            data.rows_iter()
+                .find_map(|row| row.field_value(time_field_name).as_time())
+        })
+}
@@ -0,0 +1,246 @@
+use std::collections::BTreeMap;
+
+use super::{
+    data::{Data, FieldContent},
+    row_actions::{equal_to_value_filter, RowCommand},
+    tasks::{ChunkedCache, ChunkedWork, TaskManager},
+};
+
+/// Lists the most frequent values of a field in the visible rows to get a feel for unfamiliar logs
+#[derive(serde::Deserialize, serde::Serialize, Debug)]
+#[serde(default)]
+pub struct TopValues {
+    pub is_open: bool,
+    pub field_name: String,
+    /// Max number of values listed
+    pub max_shown: usize,
+
+    #[serde(skip)]
+    cache: ChunkedCache<CacheKey, ValueCountsWork>,
+}
+
+#[derive(Debug, PartialEq)]
+struct CacheKey {
+    visible_rows_generation: u64,
+    visible_len: usize,
+    field_name: String,
+}
+
+/// Counts the values of the rows visible when it was started a chunk of rows at a time
+struct ValueCountsWork {
+    field_name: String,
+    rows: Vec<usize>,
+    /// Position in `rows` of the next row to count
+    next: usize,
+    counts: BTreeMap<String, usize>,
+    result: ValueCounts,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct ValueCounts {
+    /// Ordered by count (highest first) then by value
+    values: Vec<(String, usize)>,
+    /// Number of rows that do not have the field
+    missing: usize,
+    total: usize,
+}
+
+impl Default for TopValues {
+    fn default() -> Self {
+        Self {
+            is_open: false,
+            field_name: "msg".into(),
+            max_shown: 20,
+            cache: Default::default(),
+        }
+    }
+}
+
+impl ValueCountsWork {
+    fn new(data: &Data, field_name: &str) -> Self {
+        Self {
+            field_name: field_name.to_string(),
+            rows: data.visible_real_indices(),
+            next: 0,
+            counts: BTreeMap::new(),
+            result: ValueCounts::default(),
+        }
+    }
+}
+
+impl ChunkedWork for ValueCountsWork {
+    type Output = ValueCounts;
+
+    fn step(&mut self, data: &Data, max_rows: usize) -> Option<ValueCounts> {
+        let end = self.next.saturating_add(max_rows).min(self.rows.len());
+        for &real_index in &self.rows[self.next..end] {
+            let Some(row) = data.row_by_real_index(real_index) else {
+                continue;
+            };
+            self.result.total += 1;
+            match row.field_value(&self.field_name) {
+                content @ FieldContent::Present(_) => {
+                    *self.counts.entry(content.display()).or_default() += 1
+                }
+                FieldContent::Missing => self.result.missing += 1,
+            }
+        }
+        self.next = end;
+        if end < self.rows.len() {
+            return None;
+        }
+        let mut result = std::mem::take(&mut self.result);
+        result.values = std::mem::take(&mut self.counts).into_iter().collect();
+        // Stable so values with the same count stay in alphabetical order
+        result
+            .values
+            .sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+        Some(result)
+    }
+
+    fn progress(&self) -> f32 {
+        self.next as f32 / self.rows.len().max(1) as f32
+    }
+}
+
+impl ValueCounts {
+    fn percent(&self, count: usize) -> f64 {
+        if self.total == 0 {
+            0.0
+        } else {
+            count as f64 * 100.0 / self.total as f64
+        }
+    }
+}
+
+impl TopValues {
+    /// Stops updating the counts, used when the window is closed without calling [`Self::show`]
+    pub fn stop_updating(&mut self) {
+        self.cache.stop();
+    }
+
+    pub fn invalidate(&mut self) {
+        self.cache.clear();
+    }
+
+    /// Shows the window if it is open. Returns the filter to apply if a value was clicked
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        data: Option<&Data>,
+        field_names: &[String],
+        tasks: &mut TaskManager,
+    ) -> Option<RowCommand> {
+        let mut is_open = self.is_open;
+        let mut result = None;
+        egui::Window::new("Top Values")
+            .open(&mut is_open)
+            .show(ctx, |ui| result = self.ui(ui, data, field_names, tasks));
+        self.is_open = is_open;
+        if !is_open {
+            self.cache.stop();
+        }
+        result
+    }
+
+    fn ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        data: Option<&Data>,
+        field_names: &[String],
+        tasks: &mut TaskManager,
+    ) -> Option<RowCommand> {
+        ui.horizontal(|ui| {
+            ui.label("Field");
+            super::field_name_ui(ui, &mut self.field_name, field_names);
+            ui.label("Show");
+            ui.add(egui::DragValue::new(&mut self.max_shown).range(1..=1000));
+        });
+        let Some(data) = data else {
+            ui.label("No data");
+            return None;
+        };
+        let field_name = self.field_name.clone();
+        let max_shown = self.max_shown;
+        self.update_counts(ui.ctx(), data, tasks);
+        self.cache.status_ui(ui);
+        let counts = self.cache.output()?;
+        ui.label(format!(
+            "{} distinct values in {} visible rows ({} without the field)",
+            counts.values.len(),
+            counts.total,
+            counts.missing
+        ));
+        ui.separator();
+
+        let mut result = None;
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            egui::Grid::new("top values").striped(true).show(ui, |ui| {
+                for (value, count) in counts.values.iter().take(max_shown) {
+                    if ui
+                        .link(value)
+                        .on_hover_text("Filter to this value")
+                        .clicked()
+                    {
+                        result = Some(RowCommand::Filter(equal_to_value_filter(
+                            field_name.clone(),
+                            value.clone(),
+                        )));
+                    }
+                    ui.label(count.to_string());
+                    ui.label(format!("{:.1}%", counts.percent(*count)));
+                    ui.end_row();
+                }
+            });
+        });
+        result
+    }
+
+    /// Counts are cached as they require checking every visible row
+    fn update_counts(&mut self, ctx: &egui::Context, data: &Data, tasks: &mut TaskManager) {
+        let key = CacheKey {
+            visible_rows_generation: data.visible_rows_generation(),
+            visible_len: data.len(),
+            field_name: self.field_name.clone(),
+        };
+        self.cache
+            .update(ctx, tasks, "Counting values", data, key, |key| {
+                ValueCountsWork::new(data, &key.field_name)
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn counts_ordered_by_frequency() {
+        let display_options = Default::default();
+        let text = ["b", "a", "c", "a", "", "c", "a"]
+            .map(|msg| {
+                if msg.is_empty() {
+                    r#"{"other": 1}"#.to_string()
+                } else {
+                    format!(r#"{{"msg": "{msg}"}}"#)
+                }
+            })
+            .join("\n");
+        let data = Data::try_from((&display_options, text.as_str())).unwrap();
+        let mut work = ValueCountsWork::new(&data, "msg");
+        assert_eq!(work.step(&data, 4), None);
+        assert_eq!(work.progress(), 4.0 / 7.0);
+        let actual = work.step(&data, 4).unwrap();
+        assert_eq!(
+            actual,
+            ValueCounts {
+                values: vec![("a".into(), 3), ("c".into(), 2), ("b".into(), 1)],
+                missing: 1,
+                total: 7,
+            }
+        );
+        assert_eq!(actual.percent(7), 100.0);
+    }
+}
@@ -0,0 +1,209 @@
+use egui::{Color32, Sense, Vec2};
+
+use super::data::{
+    trace::{RequestTrace, SpanNode, TraceNode, TraceSettings},
+    Data,
+};
+
+#[derive(serde::Deserialize, serde::Serialize, Default, Debug)]
+#[serde(default)]
+pub struct TraceView {
+    pub is_open: bool,
+    pub settings: TraceSettings,
+
+    /// Built on demand and cleared when the data changes
+    #[serde(skip)]
+    traces: Option<Vec<RequestTrace>>,
+}
+
+const BAR_WIDTH: f32 = 200.0;
+
+impl TraceView {
+    /// Discards the computed traces so they are rebuilt next time they are shown
+    pub fn invalidate(&mut self) {
+        self.traces = None;
+    }
+
+    /// Shows the trace view window if it is open. Returns the (unfiltered) index of the row to select if one was clicked
+    pub fn show(&mut self, ctx: &egui::Context, data: Option<&Data>) -> Option<usize> {
+        let mut is_open = self.is_open;
+        let mut result = None;
+        egui::Window::new("Trace View")
+            .open(&mut is_open)
+            .default_width(600.0)
+            .show(ctx, |ui| result = self.ui(ui, data));
+        self.is_open = is_open;
+        result
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui, data: Option<&Data>) -> Option<usize> {
+        let Some(data) = data else {
+            ui.label("No data");
+            return None;
+        };
+
+        ui.collapsing("Settings", |ui| {
+            let TraceSettings {
+                request_id_field_name,
+                message_field_name,
+                span_name_field_name,
+                time_field_name,
+                elapsed_field_name,
+            } = &mut self.settings;
+            egui::Grid::new("trace settings").show(ui, |ui| {
+                for (label, value) in [
+                    ("Request field", request_id_field_name),
+                    ("Message field", message_field_name),
+                    ("Span name field", span_name_field_name),
+                    ("Time field", time_field_name),
+                    ("Elapsed field", elapsed_field_name),
+                ] {
+                    ui.label(label);
+                    ui.text_edit_singleline(value);
+                    ui.end_row();
+                }
+            });
+        });
+        if ui.button("Rebuild").clicked() {
+            self.invalidate();
+        }
+        ui.separator();
+
+        let traces = self
+            .traces
+            .get_or_insert_with(|| data.build_traces(&self.settings));
+        if traces.is_empty() {
+            ui.label(format!(
+                "No rows with a {:?} field found",
+                self.settings.request_id_field_name
+            ));
+            return None;
+        }
+
+        let mut result = None;
+        egui::ScrollArea::vertical()
+            .id_salt("trace view")
+            .show(ui, |ui| {
+                for trace in traces.iter() {
+                    let header = format!(
+                        "{} ({} rows{})",
+                        trace.request_id,
+                        trace.rows.len(),
+                        duration_text(trace.duration_ms())
+                    );
+                    egui::CollapsingHeader::new(header)
+                        .id_salt(&trace.request_id)
+                        .show(ui, |ui| {
+                            for node in trace.nodes.iter() {
+                                show_node(ui, data, trace, node, &self.settings, &mut result);
+                            }
+                        });
+                }
+            });
+        result
+    }
+}
+
+fn show_node(
+    ui: &mut egui::Ui,
+    data: &Data,
+    trace: &RequestTrace,
+    node: &TraceNode,
+    settings: &TraceSettings,
+    clicked_row: &mut Option<usize>,
+) {
+    match node {
+        TraceNode::Span(span) => {
+            let SpanNode {
+                name,
+                start_row,
+                end_row,
+                start_time,
+                duration_ms,
+                children,
+                ..
+            } = span;
+            ui.horizontal(|ui| {
+                waterfall_bar(ui, trace, trace.offset_ms(*start_time), *duration_ms);
+                if ui
+                    .link(format!("{name}{}", duration_text(*duration_ms)))
+                    .on_hover_text("Select start row")
+                    .clicked()
+                {
+                    *clicked_row = Some(*start_row);
+                }
+                if end_row.is_none() {
+                    ui.colored_label(ui.visuals().warn_fg_color, "(no end found)");
+                }
+            });
+            if !children.is_empty() {
+                ui.indent(("span", start_row), |ui| {
+                    for child in children.iter() {
+                        show_node(ui, data, trace, child, settings, clicked_row);
+                    }
+                });
+            }
+            if let Some(end_row) = end_row {
+                ui.horizontal(|ui| {
+                    ui.add_space(BAR_WIDTH + ui.spacing().item_spacing.x);
+                    if ui
+                        .small_button("end")
+                        .on_hover_text("Select end row")
+                        .clicked()
+                    {
+                        *clicked_row = Some(*end_row);
+                    }
+                });
+            }
+        }
+        TraceNode::Event { row, time } => {
+            ui.horizontal(|ui| {
+                waterfall_bar(ui, trace, trace.offset_ms(*time), None);
+                let msg = data
+                    .row_by_real_index(*row)
+                    .map(|x| x.field_value(&settings.message_field_name).display())
+                    .unwrap_or_default();
+                if ui.link(msg).clicked() {
+                    *clicked_row = Some(*row);
+                }
+            });
+        }
+    }
+}
+
+/// Draws the position of the span within the request (a tick mark is drawn if there is no duration)
+fn waterfall_bar(
+    ui: &mut egui::Ui,
+    trace: &RequestTrace,
+    offset_ms: Option<f64>,
+    duration_ms: Option<f64>,
+) {
+    let (rect, _) = ui.allocate_exact_size(
+        Vec2::new(BAR_WIDTH, ui.spacing().interact_size.y * 0.6),
+        Sense::hover(),
+    );
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 2.0, ui.visuals().extreme_bg_color);
+    let (Some(total), Some(offset)) = (trace.duration_ms(), offset_ms) else {
+        return;
+    };
+    let to_x = |ms: f64| {
+        let fraction = if total > 0.0 { ms / total } else { 0.0 };
+        rect.left() + rect.width() * fraction.clamp(0.0, 1.0) as f32
+    };
+    let start = to_x(offset);
+    let end = to_x(offset + duration_ms.unwrap_or_default()).max(start + 2.0);
+    let bar = egui::Rect::from_x_y_ranges(start..=end, rect.y_range());
+    let color = if duration_ms.is_some() {
+        ui.visuals().selection.bg_fill
+    } else {
+        Color32::GRAY
+    };
+    painter.rect_filled(bar, 2.0, color);
+}
+
+fn duration_text(duration_ms: Option<f64>) -> String {
+    duration_ms
+        .map(|x| format!(" - {x:.1} ms"))
+        .unwrap_or_default()
+}
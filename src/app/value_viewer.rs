@@ -0,0 +1,192 @@
+use std::{borrow::Cow, ops::Range};
+
+use egui::text::{LayoutJob, TextFormat};
+
+/// Window showing the complete value of a single field (eg. a cell too long for its column)
+#[derive(serde::Deserialize, serde::Serialize, Debug)]
+#[serde(default)]
+pub struct ValueViewer {
+    pub is_wrapped: bool,
+    pub is_monospace: bool,
+    /// Field name and value shown, None when the window is closed
+    #[serde(skip)]
+    value: Option<(String, String)>,
+    #[serde(skip)]
+    find_text: String,
+}
+
+impl Default for ValueViewer {
+    fn default() -> Self {
+        Self {
+            is_wrapped: true,
+            is_monospace: false,
+            value: None,
+            find_text: String::new(),
+        }
+    }
+}
+
+/// Longest value shown in a tooltip, the rest can be seen in the [`ValueViewer`]
+const TOOLTIP_MAX_CHARS: usize = 2_000;
+const TOOLTIP_MAX_LINES: usize = 30;
+const TOOLTIP_MAX_WIDTH: f32 = 600.0;
+
+/// Contents of the tooltip of a truncated cell, very long values are shortened so the tooltip fits on the screen
+pub fn full_value_tooltip_ui(ui: &mut egui::Ui, value: &str) {
+    ui.set_max_width(TOOLTIP_MAX_WIDTH);
+    ui.add(egui::Label::new(shortened_for_tooltip(value)).wrap());
+}
+
+/// The value limited to [`TOOLTIP_MAX_CHARS`] and [`TOOLTIP_MAX_LINES`] with "…" added if it was shortened
+fn shortened_for_tooltip(value: &str) -> Cow<'_, str> {
+    let char_end = value
+        .char_indices()
+        .nth(TOOLTIP_MAX_CHARS)
+        .map_or(value.len(), |(i, _)| i);
+    let line_end = value
+        .match_indices('\n')
+        .nth(TOOLTIP_MAX_LINES - 1)
+        .map_or(value.len(), |(i, _)| i);
+    let end = char_end.min(line_end);
+    if end == value.len() {
+        Cow::Borrowed(value)
+    } else {
+        Cow::Owned(format!("{}…", &value[..end]))
+    }
+}
+
+/// Byte ranges of the non-overlapping occurrences of `find_text` in `value` ignoring ASCII case
+fn match_ranges(value: &str, find_text: &str) -> Vec<Range<usize>> {
+    if find_text.is_empty() {
+        return Vec::new();
+    }
+    // ASCII lowercasing keeps byte offsets the same as in the original value
+    let value = value.to_ascii_lowercase();
+    let find_text = find_text.to_ascii_lowercase();
+    value
+        .match_indices(&find_text)
+        .map(|(start, matched)| start..start + matched.len())
+        .collect()
+}
+
+/// The value with the matches of the find text highlighted
+fn layout_job(
+    ui: &egui::Ui,
+    value: &str,
+    matches: &[Range<usize>],
+    is_wrapped: bool,
+    is_monospace: bool,
+) -> LayoutJob {
+    let text_style = if is_monospace {
+        egui::TextStyle::Monospace
+    } else {
+        egui::TextStyle::Body
+    };
+    let normal = TextFormat {
+        font_id: text_style.resolve(ui.style()),
+        color: ui.visuals().text_color(),
+        ..Default::default()
+    };
+    let highlighted = TextFormat {
+        background: ui.visuals().selection.bg_fill,
+        color: ui.visuals().strong_text_color(),
+        ..normal.clone()
+    };
+    let mut result = LayoutJob::default();
+    let mut position = 0;
+    for range in matches {
+        result.append(&value[position..range.start], 0.0, normal.clone());
+        result.append(&value[range.clone()], 0.0, highlighted.clone());
+        position = range.end;
+    }
+    result.append(&value[position..], 0.0, normal);
+    result.wrap.max_width = if is_wrapped {
+        ui.available_width()
+    } else {
+        f32::INFINITY
+    };
+    result
+}
+
+impl ValueViewer {
+    pub fn open(&mut self, field_name: &str, value: &str) {
+        self.value = Some((field_name.to_string(), value.to_string()));
+    }
+
+    /// Shows the window if a value is open
+    pub fn show(&mut self, ctx: &egui::Context) {
+        let Self {
+            is_wrapped,
+            is_monospace,
+            value: Some((field_name, value)),
+            find_text,
+        } = self
+        else {
+            return;
+        };
+        let mut is_open = true;
+        egui::Window::new(format!("Value: {field_name}"))
+            .id(egui::Id::new("value viewer"))
+            .open(&mut is_open)
+            .default_size([500.0, 300.0])
+            .show(ctx, |ui| {
+                let matches = match_ranges(value, find_text);
+                ui.horizontal(|ui| {
+                    ui.checkbox(is_wrapped, "Wrap");
+                    ui.checkbox(is_monospace, "Monospace");
+                    ui.add(
+                        egui::TextEdit::singleline(find_text)
+                            .hint_text("Find in value")
+                            .desired_width(150.0),
+                    );
+                    if !find_text.is_empty() {
+                        ui.label(format!("{} matches", matches.len()));
+                    }
+                    if ui.button("Copy").clicked() {
+                        ui.ctx().copy_text(value.clone());
+                    }
+                });
+                ui.separator();
+                let job = layout_job(ui, value, &matches, *is_wrapped, *is_monospace);
+                egui::ScrollArea::both()
+                    .auto_shrink(false)
+                    .show(ui, |ui| ui.add(egui::Label::new(job).selectable(true)));
+            });
+        if !is_open {
+            self.value = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn matches_ignore_case() {
+        assert_eq!(
+            match_ranges("Error: error ERR", "err"),
+            [0..3, 7..10, 13..16]
+        );
+        assert_eq!(match_ranges("é then É THEN", "then"), [3..7, 11..15]);
+        assert_eq!(match_ranges("aaaa", "aa"), [0..2, 2..4]);
+        assert!(match_ranges("value", "").is_empty());
+    }
+
+    #[test]
+    fn long_tooltips_shortened() {
+        assert_eq!(shortened_for_tooltip("short"), "short");
+        let long = "é".repeat(TOOLTIP_MAX_CHARS + 1);
+        assert_eq!(
+            shortened_for_tooltip(&long),
+            format!("{}…", "é".repeat(TOOLTIP_MAX_CHARS))
+        );
+        let many_lines = "line\n".repeat(TOOLTIP_MAX_LINES + 5);
+        assert_eq!(
+            shortened_for_tooltip(&many_lines).lines().count(),
+            TOOLTIP_MAX_LINES
+        );
+    }
+}
@@ -0,0 +1,283 @@
+use std::collections::BTreeSet;
+
+use super::data::{filter::FilterConfig, Data};
+
+/// Number of rows checked each frame so the UI stays responsive on large files
+const ROWS_PER_FRAME: usize = 20_000;
+
+/// Seconds a badge flashes after new matching rows arrive
+const FLASH_SECONDS: f64 = 3.0;
+
+/// Conditions checked against every row with a count of the matches shown as a badge.
+///
+/// Rows added after the rows already loaded have been checked (eg. from a live source) are new and make the badge
+/// show how many arrived since it was last clicked, watches set to notify also flash and request the user's attention
+#[derive(serde::Deserialize, serde::Serialize, Default, Debug)]
+#[serde(default)]
+pub struct Watches {
+    watches: Vec<Watch>,
+    /// Real index of the next row to check
+    #[serde(skip)]
+    next_index: usize,
+    /// Set once the rows present when checking started have been checked, matches after that are new
+    #[serde(skip)]
+    is_caught_up: bool,
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Default, Debug)]
+#[serde(default)]
+struct Watch {
+    name: String,
+    filter: FilterConfig,
+    should_notify: bool,
+    #[serde(skip)]
+    count: usize,
+    /// Matches that arrived since the badge was last clicked
+    #[serde(skip)]
+    new_count: usize,
+    /// Real index of the last matching row
+    #[serde(skip)]
+    last_match: Option<usize>,
+    /// Time (from egui's input) until which the badge flashes
+    #[serde(skip)]
+    flash_until: f64,
+}
+
+impl Watch {
+    fn is_enabled(&self) -> bool {
+        !self.filter.search_key.is_empty() || self.filter.comparator.is_presence_check()
+    }
+}
+
+impl Watches {
+    /// Starts counting again from the first row (without treating the rows as new)
+    pub fn invalidate(&mut self) {
+        self.next_index = 0;
+        self.is_caught_up = false;
+        for watch in self.watches.iter_mut() {
+            watch.count = 0;
+            watch.new_count = 0;
+            watch.last_match = None;
+            watch.flash_until = 0.0;
+        }
+    }
+
+    /// Should be called when the oldest `count` rows are dropped so counting continues with the rows not yet checked.
+    /// Counts include the dropped rows
+    pub fn on_rows_dropped(&mut self, count: usize) {
+        self.next_index = self.next_index.saturating_sub(count);
+        for watch in self.watches.iter_mut() {
+            watch.last_match = watch.last_match.and_then(|x| x.checked_sub(count));
+        }
+    }
+
+    /// Checks the next chunk of rows. Returns true if new rows matched a watch set to notify
+    fn update(
+        &mut self,
+        data: &mut Data,
+        common_fields: &BTreeSet<String>,
+        rows_per_frame: usize,
+        now: f64,
+    ) -> bool {
+        if data.total_len_unfiltered() < self.next_index {
+            // Rows were replaced
+            self.invalidate();
+        }
+        if self.watches.is_empty() {
+            self.next_index = data.total_len_unfiltered();
+            self.is_caught_up = true;
+            return false;
+        }
+        let mut should_notify = false;
+        let end = data
+            .total_len_unfiltered()
+            .min(self.next_index + rows_per_frame);
+        for real_index in self.next_index..end {
+            for watch in self.watches.iter_mut().filter(|x| x.is_enabled()) {
+                if !data.row_matches(real_index, &watch.filter, common_fields) {
+                    continue;
+                }
+                watch.count += 1;
+                watch.last_match = Some(real_index);
+                if self.is_caught_up {
+                    watch.new_count += 1;
+                    if watch.should_notify {
+                        watch.flash_until = now + FLASH_SECONDS;
+                        should_notify = true;
+                    }
+                }
+            }
+        }
+        self.next_index = end;
+        self.is_caught_up |= end == data.total_len_unfiltered();
+        should_notify
+    }
+
+    /// Shows a badge for each watch. Returns the real index of the row to select if a badge was clicked
+    pub fn badges_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        data: Option<&mut Data>,
+        common_fields: &BTreeSet<String>,
+    ) -> Option<usize> {
+        let now = ui.input(|i| i.time);
+        if let Some(data) = data {
+            if self.update(data, common_fields, ROWS_PER_FRAME, now) {
+                ui.ctx()
+                    .send_viewport_cmd(egui::ViewportCommand::RequestUserAttention(
+                        egui::UserAttentionType::Informational,
+                    ));
+            }
+            if !self.is_caught_up {
+                ui.ctx().request_repaint();
+            }
+        }
+        let mut result = None;
+        for watch in self.watches.iter_mut().filter(|x| x.is_enabled()) {
+            let mut text = format!("👁 {}: {}", watch.name, watch.count);
+            if watch.new_count > 0 {
+                text.push_str(&format!(" (+{})", watch.new_count));
+            }
+            let mut button = egui::Button::new(text);
+            if now < watch.flash_until {
+                // Alternate the fill a few times a second while flashing
+                if (now * 4.0) as i64 % 2 == 0 {
+                    button = button.fill(ui.visuals().warn_fg_color);
+                }
+                ui.ctx().request_repaint();
+            } else if watch.new_count > 0 {
+                button = button.fill(ui.visuals().selection.bg_fill);
+            }
+            if ui
+                .add(button)
+                .on_hover_text(format!(
+                    "Rows matching {}\nClick to select the last match",
+                    watch.filter
+                ))
+                .clicked()
+            {
+                watch.new_count = 0;
+                watch.flash_until = 0.0;
+                result = watch.last_match;
+            }
+        }
+        result
+    }
+
+    /// Editor for the watches
+    pub fn ui(&mut self, ui: &mut egui::Ui, current_filter: Option<&FilterConfig>) {
+        let mut is_changed = false;
+        let mut index_to_remove = None;
+        egui::Grid::new("watches").show(ui, |ui| {
+            for (i, watch) in self.watches.iter_mut().enumerate() {
+                ui.add(
+                    egui::TextEdit::singleline(&mut watch.name)
+                        .hint_text("Name")
+                        .desired_width(80.0),
+                );
+                is_changed |= ui
+                    .add(
+                        egui::TextEdit::singleline(&mut watch.filter.search_key)
+                            .hint_text("Search key"),
+                    )
+                    .on_hover_text(format!("Matches {}", watch.filter))
+                    .changed();
+                ui.checkbox(&mut watch.should_notify, "Notify")
+                    .on_hover_text("Flash the badge and request attention when new rows match");
+                if ui.button("🗑").on_hover_text("Remove watch").clicked() {
+                    index_to_remove = Some(i);
+                }
+                ui.end_row();
+            }
+        });
+        if let Some(i) = index_to_remove {
+            self.watches.remove(i);
+            is_changed = true;
+        }
+        ui.horizontal(|ui| {
+            let name = format!("Watch {}", self.watches.len() + 1);
+            if ui.button("Add Watch").clicked() {
+                self.watches.push(Watch {
+                    name,
+                    ..Default::default()
+                });
+                is_changed = true;
+            } else if ui
+                .add_enabled(
+                    current_filter.is_some(),
+                    egui::Button::new("Watch Current Filter"),
+                )
+                .on_hover_text("Adds a watch with the filter being edited")
+                .clicked()
+            {
+                self.watches.push(Watch {
+                    name,
+                    filter: current_filter.cloned().unwrap_or_default(),
+                    ..Default::default()
+                });
+                is_changed = true;
+            }
+        });
+        if is_changed {
+            self.invalidate();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::core::data_display_options::DataDisplayOptions;
+
+    use super::*;
+
+    #[test]
+    fn only_rows_added_after_catching_up_are_new() {
+        let options = DataDisplayOptions::default();
+        let common_fields = options.common_fields();
+        let text = [
+            r#"{"msg": "Error 1"}"#,
+            r#"{"msg": "ok"}"#,
+            r#"{"msg": "Error 2"}"#,
+        ]
+        .join("\n");
+        let mut data = Data::try_from((&options, text.as_str())).unwrap();
+        let mut watches = Watches {
+            watches: vec![Watch {
+                name: "errors".into(),
+                filter: FilterConfig {
+                    search_key: "error".into(),
+                    ..Default::default()
+                },
+                should_notify: true,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        assert!(!watches.update(&mut data, common_fields, 2, 0.0));
+        assert!(!watches.is_caught_up);
+        assert!(!watches.update(&mut data, common_fields, 2, 0.0));
+        assert!(watches.is_caught_up);
+        let watch = &watches.watches[0];
+        assert_eq!(
+            (watch.count, watch.new_count, watch.last_match),
+            (2, 0, Some(2))
+        );
+
+        data.append_lines(
+            &options,
+            [r#"{"msg": "Error 3"}"#, r#"{"msg": "ok"}"#],
+            common_fields,
+        )
+        .unwrap();
+        assert!(watches.update(&mut data, common_fields, 2, 10.0));
+        let watch = &watches.watches[0];
+        assert_eq!(
+            (watch.count, watch.new_count, watch.last_match),
+            (3, 1, Some(3))
+        );
+        assert!(watch.flash_until > 10.0);
+    }
+}
@@ -0,0 +1,237 @@
+use std::{
+    net::{TcpStream, ToSocketAddrs},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use anyhow::{anyhow, Context};
+use tungstenite::{
+    client::IntoClientRequest, handshake::client::Request, protocol::WebSocketConfig,
+    stream::MaybeTlsStream, Connector, Message, WebSocket,
+};
+
+use super::websocket_source::Shared;
+
+/// Largest message accepted, larger ones end the connection instead of using up the memory
+const MAX_MESSAGE_LEN: usize = 64 * 1024 * 1024;
+
+/// Limit for connecting and for each read or write during the handshake
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How often the thread reading messages checks if the connection was dropped
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// WebSocket client for the native build (`ws://` and `wss://`).
+///
+/// Messages are read on a separate thread and the lines added to the shared state
+#[derive(Debug)]
+pub struct Connection {
+    /// Set when dropped so the thread closes the socket and stops
+    is_closed: Arc<AtomicBool>,
+    shared: Arc<Mutex<Shared>>,
+    pub received_count: usize,
+}
+
+/// Uses ring explicitly so the TLS setup does not depend on which crypto provider other crates enable
+fn tls_connector() -> anyhow::Result<Connector> {
+    let mut roots = rustls::RootCertStore::empty();
+    roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+    let config = rustls::ClientConfig::builder_with_provider(Arc::new(
+        rustls::crypto::ring::default_provider(),
+    ))
+    .with_safe_default_protocol_versions()?
+    .with_root_certificates(roots)
+    .with_no_client_auth();
+    Ok(Connector::Rustls(Arc::new(config)))
+}
+
+/// Connects (trying each address the host resolves to) and completes the WebSocket handshake
+fn connect(request: Request) -> anyhow::Result<(WebSocket<MaybeTlsStream<TcpStream>>, TcpStream)> {
+    let uri = request.uri();
+    let host = uri.host().context("no host in URL")?;
+    // Brackets are only part of the URL syntax for IPv6 addresses
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    let port = uri
+        .port_u16()
+        .unwrap_or(if uri.scheme_str() == Some("wss") {
+            443
+        } else {
+            80
+        });
+    let addresses = (host, port)
+        .to_socket_addrs()
+        .with_context(|| format!("failed to resolve {host}"))?;
+    let mut last_error = None;
+    let stream = addresses.into_iter().find_map(|address| {
+        match TcpStream::connect_timeout(&address, CONNECT_TIMEOUT) {
+            Ok(stream) => Some(stream),
+            Err(e) => {
+                last_error = Some(e);
+                None
+            }
+        }
+    });
+    let stream = match (stream, last_error) {
+        (Some(stream), _) => stream,
+        (None, Some(e)) => {
+            return Err(e).with_context(|| format!("failed to connect to {host}:{port}"))
+        }
+        (None, None) => return Err(anyhow!("{host} did not resolve to any address")),
+    };
+    stream.set_read_timeout(Some(CONNECT_TIMEOUT))?;
+    stream.set_write_timeout(Some(CONNECT_TIMEOUT))?;
+    // Shares the socket so the timeout can be changed after TLS wraps the stream
+    let stream_handle = stream.try_clone()?;
+
+    let config = WebSocketConfig::default()
+        .max_message_size(Some(MAX_MESSAGE_LEN))
+        .max_frame_size(Some(MAX_MESSAGE_LEN));
+    let (socket, _response) =
+        tungstenite::client_tls_with_config(request, stream, Some(config), Some(tls_connector()?))
+            .map_err(|e| anyhow!("handshake failed: {e}"))?;
+    Ok((socket, stream_handle))
+}
+
+/// Connects and reads messages until the connection ends, returns why it ended
+fn receive(
+    request: Request,
+    is_closed: &AtomicBool,
+    shared: &Mutex<Shared>,
+    ctx: &egui::Context,
+) -> anyhow::Result<String> {
+    let (mut socket, stream_handle) = connect(request)?;
+    // Reads time out so the thread notices when the connection is dropped
+    stream_handle.set_read_timeout(Some(POLL_INTERVAL))?;
+    loop {
+        if is_closed.load(Ordering::Relaxed) {
+            let _ = socket.close(None);
+            let _ = socket.flush();
+            return Ok("Disconnected".to_string());
+        }
+        match socket.read() {
+            Ok(Message::Text(text)) => {
+                shared
+                    .lock()
+                    .unwrap()
+                    .lines
+                    .extend(text.as_str().lines().map(String::from));
+                ctx.request_repaint();
+            }
+            Ok(Message::Close(frame)) => {
+                return Ok(match frame {
+                    Some(frame) => format!(
+                        "Connection closed (code: {}) {}",
+                        u16::from(frame.code),
+                        frame.reason
+                    ),
+                    None => "Connection closed".to_string(),
+                });
+            }
+            // Binary messages are ignored like in the browser build, pings are answered by tungstenite
+            Ok(_) => {}
+            Err(tungstenite::Error::Io(e))
+                if matches!(
+                    e.kind(),
+                    std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut
+                ) => {}
+            Err(tungstenite::Error::ConnectionClosed) => return Ok("Connection closed".to_string()),
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
+
+impl Connection {
+    pub fn open(url: &str, ctx: egui::Context) -> Result<Self, String> {
+        let request = url
+            .into_client_request()
+            .map_err(|e| format!("invalid URL: {e}"))?;
+        let is_closed = Arc::new(AtomicBool::new(false));
+        let shared = Arc::new(Mutex::new(Shared::default()));
+        let thread_is_closed = Arc::clone(&is_closed);
+        let thread_shared = Arc::clone(&shared);
+        std::thread::spawn(move || {
+            let end_reason = match receive(request, &thread_is_closed, &thread_shared, &ctx) {
+                Ok(reason) => reason,
+                Err(e) => format!("Connection error: {e:#}"),
+            };
+            thread_shared
+                .lock()
+                .unwrap()
+                .end_reason
+                .get_or_insert(end_reason);
+            ctx.request_repaint();
+        });
+        Ok(Self {
+            is_closed,
+            shared,
+            received_count: 0,
+        })
+    }
+
+    /// Lines received since the last call and the reason the connection ended (if it did)
+    pub fn take_received(&self) -> (Vec<String>, Option<String>) {
+        let mut shared = self.shared.lock().unwrap();
+        (std::mem::take(&mut shared.lines), shared.end_reason.take())
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        self.is_closed.store(true, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+
+    use pretty_assertions::assert_eq;
+    use tungstenite::protocol::{frame::coding::CloseCode, CloseFrame};
+
+    use super::*;
+
+    #[test]
+    fn lines_received_until_closed() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = std::thread::spawn(move || {
+            let mut socket = tungstenite::accept(listener.accept().unwrap().0).unwrap();
+            socket.send(Message::text("{\"msg\": \"a\"}\nb")).unwrap();
+            socket.send(Message::binary(vec![1, 2])).unwrap();
+            socket.send(Message::text("c")).unwrap();
+            socket
+                .close(Some(CloseFrame {
+                    code: CloseCode::Away,
+                    reason: "bye".into(),
+                }))
+                .unwrap();
+            // Keep reading so the close handshake completes
+            while socket.read().is_ok() {}
+        });
+
+        let connection = Connection::open(
+            &format!("ws://127.0.0.1:{port}/logs"),
+            egui::Context::default(),
+        )
+        .unwrap();
+        let mut lines = Vec::new();
+        let deadline = std::time::Instant::now() + Duration::from_secs(10);
+        let end_reason = loop {
+            let (new_lines, end_reason) = connection.take_received();
+            lines.extend(new_lines);
+            if let Some(end_reason) = end_reason {
+                break end_reason;
+            }
+            assert!(std::time::Instant::now() < deadline, "timed out");
+            std::thread::sleep(Duration::from_millis(10));
+        };
+        server.join().unwrap();
+
+        assert_eq!(lines, ["{\"msg\": \"a\"}", "b", "c"]);
+        assert_eq!(end_reason, "Connection closed (code: 1001) bye");
+        assert!(Connection::open("not a url", egui::Context::default()).is_err());
+    }
+}
@@ -0,0 +1,202 @@
+#[cfg(target_arch = "wasm32")]
+use std::{cell::RefCell, rc::Rc};
+
+#[cfg(target_arch = "wasm32")]
+use eframe::wasm_bindgen::{closure::Closure, JsCast as _};
+#[cfg(target_arch = "wasm32")]
+use web_sys::{CloseEvent, Event, MessageEvent, WebSocket};
+
+use super::live_source::LiveSource;
+#[cfg(not(target_arch = "wasm32"))]
+use super::websocket_client::Connection;
+
+/// Receives NDJSON over a WebSocket, uses the browser's WebSocket on the web and [`super::websocket_client`] natively
+///
+/// Only text messages are supported, each may contain one or more lines
+#[derive(serde::Deserialize, serde::Serialize, Default, Debug)]
+#[serde(default)]
+pub struct WebSocketSource {
+    pub is_open: bool,
+    pub url: String,
+
+    #[serde(skip)]
+    connection: Option<Connection>,
+    #[serde(skip)]
+    status_msg: Option<String>,
+}
+
+#[cfg(target_arch = "wasm32")]
+#[derive(Debug)]
+struct Connection {
+    socket: WebSocket,
+    /// Filled by the socket callbacks and emptied each frame
+    shared: Rc<RefCell<Shared>>,
+    received_count: usize,
+    // Kept so the callbacks stay alive as long as the socket is in use
+    _on_message: Closure<dyn FnMut(MessageEvent)>,
+    _on_close: Closure<dyn FnMut(CloseEvent)>,
+    _on_error: Closure<dyn FnMut(Event)>,
+}
+
+/// Filled as messages arrive and emptied each frame
+#[derive(Debug, Default)]
+pub struct Shared {
+    pub lines: Vec<String>,
+    /// Set when the socket closes with the reason
+    pub end_reason: Option<String>,
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Connection {
+    fn open(url: &str, ctx: egui::Context) -> Result<Self, String> {
+        let socket = WebSocket::new(url).map_err(|e| format!("failed to connect: {e:?}"))?;
+        let shared = Rc::new(RefCell::new(Shared::default()));
+
+        let on_message = {
+            let shared = Rc::clone(&shared);
+            let ctx = ctx.clone();
+            Closure::<dyn FnMut(MessageEvent)>::new(move |event: MessageEvent| {
+                if let Some(text) = event.data().as_string() {
+                    shared
+                        .borrow_mut()
+                        .lines
+                        .extend(text.lines().map(String::from));
+                    ctx.request_repaint();
+                }
+            })
+        };
+        let on_close = {
+            let shared = Rc::clone(&shared);
+            let ctx = ctx.clone();
+            Closure::<dyn FnMut(CloseEvent)>::new(move |event: CloseEvent| {
+                shared.borrow_mut().end_reason = Some(format!(
+                    "Connection closed (code: {}) {}",
+                    event.code(),
+                    event.reason()
+                ));
+                ctx.request_repaint();
+            })
+        };
+        let on_error = {
+            let shared = Rc::clone(&shared);
+            Closure::<dyn FnMut(Event)>::new(move |_: Event| {
+                // The browser does not provide details about the error
+                shared
+                    .borrow_mut()
+                    .end_reason
+                    .get_or_insert_with(|| "Connection error".to_string());
+                ctx.request_repaint();
+            })
+        };
+        socket.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+        socket.set_onclose(Some(on_close.as_ref().unchecked_ref()));
+        socket.set_onerror(Some(on_error.as_ref().unchecked_ref()));
+
+        Ok(Self {
+            socket,
+            shared,
+            received_count: 0,
+            _on_message: on_message,
+            _on_close: on_close,
+            _on_error: on_error,
+        })
+    }
+
+    /// Lines received since the last call and the reason the connection ended (if it did)
+    fn take_received(&self) -> (Vec<String>, Option<String>) {
+        let mut shared = self.shared.borrow_mut();
+        (std::mem::take(&mut shared.lines), shared.end_reason.take())
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Drop for Connection {
+    fn drop(&mut self) {
+        self.socket.set_onmessage(None);
+        self.socket.set_onclose(None);
+        self.socket.set_onerror(None);
+        let _ = self.socket.close();
+    }
+}
+
+impl WebSocketSource {
+    fn ui(&mut self, ui: &mut egui::Ui) -> bool {
+        let is_connected = self.connection.is_some();
+        ui.add_enabled_ui(!is_connected, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("URL");
+                ui.add(egui::TextEdit::singleline(&mut self.url).hint_text("wss://host/logs"));
+            });
+        });
+
+        let mut result = false;
+        ui.horizontal(|ui| {
+            if is_connected {
+                if ui.button("Disconnect").clicked() {
+                    self.connection = None;
+                    self.status_msg = Some("Disconnected".to_string());
+                }
+            } else if ui
+                .add_enabled(!self.url.trim().is_empty(), egui::Button::new("Connect"))
+                .clicked()
+            {
+                match Connection::open(self.url.trim(), ui.ctx().clone()) {
+                    Ok(connection) => {
+                        self.connection = Some(connection);
+                        self.status_msg = None;
+                        result = true;
+                    }
+                    Err(e) => self.status_msg = Some(e),
+                }
+            }
+        });
+        if let Some(connection) = self.connection.as_ref() {
+            ui.label(format!(
+                "Streaming {} ({} lines received)",
+                self.url, connection.received_count
+            ));
+        }
+        if let Some(msg) = self.status_msg.as_ref() {
+            ui.label(msg);
+        }
+        result
+    }
+}
+
+impl LiveSource for WebSocketSource {
+    fn show(&mut self, ctx: &egui::Context) -> bool {
+        let mut is_open = self.is_open;
+        let mut result = false;
+        egui::Window::new("WebSocket Source")
+            .open(&mut is_open)
+            .show(ctx, |ui| result = self.ui(ui));
+        self.is_open = is_open;
+        result
+    }
+
+    fn take_new_lines(&mut self) -> Vec<String> {
+        let Some(connection) = self.connection.as_mut() else {
+            return Vec::new();
+        };
+        let (result, end_reason) = connection.take_received();
+        connection.received_count += result.len();
+        if let Some(reason) = end_reason {
+            self.status_msg = Some(reason);
+            self.connection = None;
+        }
+        result
+    }
+
+    fn disconnect_with_error(&mut self, msg: String) {
+        self.connection = None;
+        self.status_msg = Some(msg);
+    }
+
+    fn is_connected(&self) -> bool {
+        self.connection.is_some()
+    }
+
+    fn source_description(&self) -> String {
+        self.url.trim().to_string()
+    }
+}
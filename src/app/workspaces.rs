@@ -0,0 +1,237 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::PathBuf,
+};
+
+use super::{
+    data::{
+        filter::{FilterConfig, IgnoreRule, LevelFilter, RangeFilter},
+        Data,
+    },
+    data_display_options::DataDisplayOptions,
+    shortcut::Shortcuts,
+};
+
+/// Named snapshots of the setup so the user can switch between the setups needed for different services
+#[derive(serde::Deserialize, serde::Serialize, Default, Debug)]
+#[serde(default)]
+pub struct Workspaces {
+    pub is_open: bool,
+    saved: BTreeMap<String, Workspace>,
+    /// Name of the workspace last saved or loaded
+    current: Option<String>,
+
+    /// Filters of the workspace loaded when there was no data, applied to the next data loaded
+    #[serde(skip)]
+    pending_filters: Option<SavedFilters>,
+    #[serde(skip)]
+    new_name: String,
+}
+
+/// Everything restored when switching to a workspace
+#[derive(serde::Deserialize, serde::Serialize, Default, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct Workspace {
+    pub data_display_options: DataDisplayOptions,
+    pub shortcuts: Shortcuts,
+    pub filters: SavedFilters,
+    pub start_open_path: Option<PathBuf>,
+    pub last_filename: Option<PathBuf>,
+}
+
+/// The filters of the data (the rows themselves are not saved)
+#[derive(serde::Deserialize, serde::Serialize, Default, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct SavedFilters {
+    /// The filter being edited
+    filter: Option<FilterConfig>,
+    applied_filter: Option<FilterConfig>,
+    range_filters: Vec<RangeFilter>,
+    ignore_list: Vec<IgnoreRule>,
+    level_filter: LevelFilter,
+    column_filters: BTreeMap<String, String>,
+    context_rows: usize,
+    collapse_duplicates_field: Option<String>,
+}
+
+/// Requests that need the rest of the app's state to carry out
+#[derive(Debug, PartialEq, Eq)]
+pub enum WorkspaceAction {
+    /// Save the current setup under the name
+    Save(String),
+    Load(String),
+}
+
+impl SavedFilters {
+    pub fn new(data: Option<&Data>) -> Self {
+        let Some(data) = data else {
+            return Self::default();
+        };
+        Self {
+            filter: data.filter.clone(),
+            applied_filter: data.applied_filter().cloned(),
+            range_filters: data.range_filters().to_vec(),
+            ignore_list: data.ignore_list().to_vec(),
+            level_filter: data.level_filter().clone(),
+            column_filters: data.column_filters().clone(),
+            context_rows: data.context_rows(),
+            collapse_duplicates_field: data.collapse_duplicates_field().map(str::to_string),
+        }
+    }
+
+    pub fn apply_to(self, data: &mut Data, common_fields: &BTreeSet<String>) {
+        data.set_range_filters(self.range_filters, common_fields);
+        data.set_ignore_list(self.ignore_list, common_fields);
+        data.set_level_filter(self.level_filter, common_fields);
+        data.set_column_filters(self.column_filters, common_fields);
+        data.set_context_rows(self.context_rows, common_fields);
+        data.set_collapse_duplicates_field(self.collapse_duplicates_field, common_fields);
+        data.filter = self.applied_filter;
+        if data.filter.is_some() {
+            data.apply_filter(common_fields);
+        } else {
+            data.unfilter(common_fields);
+        }
+        data.filter = self.filter;
+    }
+}
+
+impl Workspaces {
+    pub fn current(&self) -> Option<&str> {
+        self.current.as_deref()
+    }
+
+    /// Saves (or overwrites) the workspace and makes it the current one
+    pub fn insert(&mut self, name: String, workspace: Workspace) {
+        self.saved.insert(name.clone(), workspace);
+        self.current = Some(name);
+    }
+
+    /// Returns the workspace to switch to and makes it the current one
+    pub fn load(&mut self, name: &str) -> Option<Workspace> {
+        let result = self.saved.get(name).cloned()?;
+        self.current = Some(name.to_string());
+        Some(result)
+    }
+
+    pub fn set_pending_filters(&mut self, filters: SavedFilters) {
+        self.pending_filters = Some(filters);
+    }
+
+    pub fn take_pending_filters(&mut self) -> Option<SavedFilters> {
+        self.pending_filters.take()
+    }
+
+    /// Entries for the menu bar to switch between workspaces
+    pub fn menu_ui(&mut self, ui: &mut egui::Ui) -> Option<WorkspaceAction> {
+        let mut result = None;
+        for name in self.saved.keys() {
+            if ui
+                .selectable_label(self.current.as_ref() == Some(name), name)
+                .clicked()
+            {
+                result = Some(WorkspaceAction::Load(name.clone()));
+                ui.close_menu();
+            }
+        }
+        if !self.saved.is_empty() {
+            ui.separator();
+        }
+        ui.checkbox(&mut self.is_open, "Manage Workspaces...");
+        result
+    }
+
+    /// Shows the window if it is open
+    pub fn show(&mut self, ctx: &egui::Context) -> Option<WorkspaceAction> {
+        let mut is_open = self.is_open;
+        let mut result = None;
+        egui::Window::new("Workspaces")
+            .open(&mut is_open)
+            .show(ctx, |ui| result = self.ui(ui));
+        self.is_open = is_open;
+        result
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui) -> Option<WorkspaceAction> {
+        let mut result = None;
+        ui.label("Saves the display options, filters, shortcuts and last file");
+        ui.horizontal(|ui| {
+            ui.add(egui::TextEdit::singleline(&mut self.new_name).hint_text("eg. backend-prod"));
+            let name = self.new_name.trim();
+            if ui
+                .add_enabled(!name.is_empty(), egui::Button::new("Save Current As"))
+                .on_hover_text("Overwrites the workspace if the name is already used")
+                .clicked()
+            {
+                result = Some(WorkspaceAction::Save(name.to_string()));
+                self.new_name.clear();
+            }
+        });
+        ui.separator();
+
+        let mut name_to_delete = None;
+        egui::Grid::new("workspaces").striped(true).show(ui, |ui| {
+            for name in self.saved.keys() {
+                if self.current.as_ref() == Some(name) {
+                    ui.strong(name);
+                } else {
+                    ui.label(name);
+                }
+                if ui.button("Load").clicked() {
+                    result = Some(WorkspaceAction::Load(name.clone()));
+                }
+                if ui
+                    .button("Update")
+                    .on_hover_text("Replace with the current setup")
+                    .clicked()
+                {
+                    result = Some(WorkspaceAction::Save(name.clone()));
+                }
+                if ui.small_button("🗑").on_hover_text("Delete").clicked() {
+                    name_to_delete = Some(name.clone());
+                }
+                ui.end_row();
+            }
+            if self.saved.is_empty() {
+                ui.label("No saved workspaces");
+            }
+        });
+        if let Some(name) = name_to_delete {
+            self.saved.remove(&name);
+            if self.current.as_ref() == Some(&name) {
+                self.current = None;
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn filters_restored_to_other_data() {
+        let display_options = DataDisplayOptions::default();
+        let common_fields = display_options.common_fields();
+        let text = "{\"msg\": \"a\"}\n{\"msg\": \"b\"}\n{\"msg\": \"ab\"}";
+        let mut data = Data::try_from((&display_options, text)).unwrap();
+        data.filter = Some(FilterConfig {
+            search_key: "a".into(),
+            ..Default::default()
+        });
+        data.apply_filter(common_fields);
+        data.filter = Some(FilterConfig {
+            search_key: "not applied".into(),
+            ..Default::default()
+        });
+        let filters = SavedFilters::new(Some(&data));
+
+        let mut other = Data::try_from((&display_options, text)).unwrap();
+        filters.clone().apply_to(&mut other, common_fields);
+        assert_eq!(SavedFilters::new(Some(&other)), filters);
+        assert_eq!(other.len(), 2);
+    }
+}
@@ -0,0 +1,16 @@
+//! Loading, parsing and filtering of logs without any GUI.
+//!
+//! Available without the default `gui` feature so other programs (and scripts) can process logs the same way the viewer does
+
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+pub mod data;
+pub mod data_display_options;
+pub mod display_profiles;
+pub mod table_export;
+
+pub fn calculate_hash<T: Hash + ?Sized>(t: &T) -> u64 {
+    let mut s = DefaultHasher::new();
+    t.hash(&mut s);
+    s.finish()
+}
@@ -0,0 +1,1982 @@
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
+
+use anyhow::Context;
+use columns::Columns;
+use data_iter::DataIter;
+use filter::{Comparator, FilterConfig, IgnoreRule, LevelFilter, RangeFilter};
+use line_parser::{new_line_parser, LineParser};
+use log::warn;
+use row_fields::FieldMap;
+use row_script::RowScript;
+use serde_json::Value;
+
+use super::{
+    calculate_hash,
+    data_display_options::{
+        ColdStorageSettings, DataDisplayOptions, LevelConversion, LoadRange, RowHeight,
+        RowParseErrorHandling,
+    },
+};
+pub mod ci_log;
+mod columns;
+mod data_iter;
+pub mod decode;
+pub mod field_path;
+pub mod filter;
+pub mod line_parser;
+mod row_fields;
+pub mod row_script;
+pub mod sort;
+pub mod stream;
+pub mod template;
+pub mod time_jump;
+pub mod trace;
+pub mod tracing_json;
+
+type RowSlice<'a> = &'a [(String, String)];
+
+#[derive(serde::Deserialize, serde::Serialize, Default, Debug, PartialEq)]
+#[serde(default)]
+pub struct Data {
+    pub selected_row: Option<usize>,
+    pub filter: Option<FilterConfig>,
+    rows: Columns,
+    filtered_rows: Option<Vec<usize>>,
+    applied_filter: Option<FilterConfig>,
+    /// Rows manually hidden by the user (indices into all rows) in the order they were hidden so they can be restored in reverse
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    hidden_rows: Vec<usize>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    range_filters: Vec<RangeFilter>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    ignore_list: Vec<IgnoreRule>,
+    #[serde(skip_serializing_if = "LevelFilter::is_empty")]
+    level_filter: LevelFilter,
+    /// Text each field must contain (ignoring case), keyed by field name
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    column_filters: BTreeMap<String, String>,
+    /// Number of rows before and after each row matching the applied filter that are also shown (like `grep -C`)
+    #[serde(skip_serializing_if = "is_zero")]
+    context_rows: usize,
+    /// Rows (indices into all rows) that are only visible as context for a match
+    #[serde(skip)]
+    context_only_rows: BTreeSet<usize>,
+    /// When set visible rows with the same value for this field as the row before them are folded into that row
+    #[serde(skip_serializing_if = "Option::is_none")]
+    collapse_duplicates_field: Option<String>,
+    /// Runs of duplicates the user expanded (identified by the index into all rows of the first row)
+    #[serde(skip_serializing_if = "BTreeSet::is_empty")]
+    expanded_runs: BTreeSet<usize>,
+    /// Number of rows in each run of duplicates keyed by the index into all rows of the first row
+    #[serde(skip)]
+    run_lengths: BTreeMap<usize, usize>,
+    /// When set the visible rows are shown in this order instead of file order
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sort: Option<sort::SortConfig>,
+    /// Ids of the CI log groups that are folded (only the row starting the group is shown)
+    #[serde(skip_serializing_if = "BTreeSet::is_empty")]
+    collapsed_groups: BTreeSet<u64>,
+    /// Number of rows that start a CI log group, counted as rows are added so checking for groups does not read every row
+    #[serde(skip_serializing_if = "is_zero")]
+    group_start_count: usize,
+    /// The level conversion the rows were loaded with, so the levels it added can be replaced when it changes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    level_conversion: Option<LevelConversion>,
+    /// Number of lines that were not valid JSON and were converted (see [`RowParseErrorHandling`])
+    #[serde(skip_serializing_if = "is_zero")]
+    parse_failure_count: usize,
+    /// Set when only part of the input was loaded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    partial_load: Option<LoadRange>,
+    /// Number of the oldest rows removed by [`Self::drop_oldest_rows`]
+    #[serde(skip_serializing_if = "is_zero")]
+    dropped_count: usize,
+    /// Set when the input was not valid UTF-8 (see [`decode::decode`])
+    #[serde(skip_serializing_if = "Option::is_none")]
+    decoding: Option<decode::Decoding>,
+    /// Created when the first line is parsed and kept for [`Self::append_lines`] as parsers may keep state between lines
+    #[serde(skip)]
+    line_parser: LineParserSlot,
+    /// Compiled from the settings when the first line is parsed, kept for [`Self::append_lines`]
+    #[serde(skip)]
+    row_script: Option<RowScript>,
+    /// Index of the line after the last one parsed (differs from the number of rows if the row script dropped rows)
+    #[serde(skip_serializing_if = "is_zero")]
+    next_line_index: usize,
+    /// False when the visible rows changed since rows were last moved into cold storage
+    #[serde(skip)]
+    is_cold_storage_current: bool,
+    /// Incremented whenever the visible rows may have changed so views can tell when to recompute
+    #[serde(skip)]
+    visible_rows_generation: u64,
+    #[serde(skip)]
+    row_heights: RowHeightsCache,
+    #[serde(skip)]
+    display_cache: DisplayCache,
+}
+
+/// A row of the data. Only refers to the fields as they are kept by field for all the rows (see [`Columns`])
+#[derive(Clone, Copy)]
+pub struct LogRow<'a>(RowSource<'a>);
+
+#[derive(Clone, Copy)]
+enum RowSource<'a> {
+    Stored(&'a Columns, usize),
+    /// A row that is not part of any data (see [`OwnedLogRow`])
+    Owned(&'a FieldMap),
+}
+
+/// A row with its own fields, used for rows that are not part of any data (eg. a single line parsed on its own)
+#[derive(serde::Deserialize, serde::Serialize, Default, Debug, PartialEq, Eq, Clone)]
+#[serde(rename = "LogRow")]
+pub struct OwnedLogRow {
+    data: FieldMap,
+}
+
+#[derive(Default, Debug, PartialEq, Eq, Clone)]
+struct CachedDisplayInfo {
+    data: Vec<(String, String)>,
+    common_fields_hash: u64,
+}
+
+/// The fields of each row as shown in the details (see [`Data::selected_row_data_as_slice`]) by index into all rows,
+/// built when first needed. Ignored when comparing data as it can be recomputed
+#[derive(Debug, Default)]
+struct DisplayCache(Vec<Option<CachedDisplayInfo>>);
+
+impl DisplayCache {
+    fn get(
+        &mut self,
+        real_index: usize,
+        row: LogRow<'_>,
+        common_fields: &BTreeSet<String>,
+    ) -> RowSlice<'_> {
+        let common_fields_hash = calculate_hash(common_fields);
+        if self.0.len() <= real_index {
+            self.0.resize(real_index + 1, None);
+        }
+        let cache = &mut self.0[real_index];
+        if cache
+            .as_ref()
+            .is_none_or(|x| x.common_fields_hash != common_fields_hash)
+        {
+            *cache = Some(CachedDisplayInfo {
+                data: row.display_list(common_fields),
+                common_fields_hash,
+            });
+        }
+        &cache.as_ref().expect("set above if it was missing").data
+    }
+
+    /// Drops the entry of a row that changed (or was moved into cold storage)
+    fn invalidate(&mut self, real_index: usize) {
+        if let Some(cache) = self.0.get_mut(real_index) {
+            *cache = None;
+        }
+    }
+
+    fn drain_front(&mut self, count: usize) {
+        self.0.drain(..count.min(self.0.len()));
+    }
+}
+
+impl PartialEq for DisplayCache {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum FieldContent<'a> {
+    Present(&'a serde_json::Value),
+    Missing,
+}
+
+impl<'a> FieldContent<'a> {
+    pub const TEXT_FOR_EMPTY: &'static str = "[ --- ]";
+
+    pub fn display(&self) -> String {
+        self.display_str().into_owned()
+    }
+
+    /// Same as [`Self::display`] but only allocates for values that are not strings (used when drawing every frame)
+    pub fn display_str(&self) -> Cow<'a, str> {
+        match self {
+            FieldContent::Present(val) => match val.as_str() {
+                Some(s) => Cow::Borrowed(s),
+                None => Cow::Owned(val.to_string()),
+            },
+            FieldContent::Missing => Cow::Borrowed(Self::TEXT_FOR_EMPTY),
+        }
+    }
+
+    /// Returns the value as a number if it is a number or a string that can be parsed as one
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            FieldContent::Present(serde_json::Value::String(s)) => s.trim().parse().ok(),
+            FieldContent::Present(val) => val.as_f64(),
+            FieldContent::Missing => None,
+        }
+    }
+
+    /// Returns the value if it is a string
+    pub fn as_str(&self) -> Option<&'a str> {
+        match self {
+            FieldContent::Present(val) => val.as_str(),
+            FieldContent::Missing => None,
+        }
+    }
+
+    /// Returns the value as a non-negative integer if it is one or a string that can be parsed as one
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            FieldContent::Present(serde_json::Value::String(s)) => s.trim().parse().ok(),
+            FieldContent::Present(val) => val.as_u64(),
+            FieldContent::Missing => None,
+        }
+    }
+}
+
+impl<'a> LogRow<'a> {
+    fn stored(columns: &'a Columns, real_index: usize) -> Self {
+        Self(RowSource::Stored(columns, real_index))
+    }
+
+    /// The value of the field with exactly this name
+    fn get(&self, field_name: &str) -> Option<&'a Value> {
+        match self.0 {
+            RowSource::Stored(columns, real_index) => columns.get(real_index, field_name),
+            RowSource::Owned(fields) => fields.get(field_name),
+        }
+    }
+
+    /// If there is no field with the name it is tried as a path into nested values (see [`field_path::resolve`])
+    pub fn field_value(&self, field_name: &str) -> FieldContent<'a> {
+        match self
+            .get(field_name)
+            .or_else(|| field_path::resolve(*self, field_name))
+        {
+            Some(value) => FieldContent::Present(value),
+            None => FieldContent::Missing,
+        }
+    }
+
+    /// The fields as shown in the details with the common fields at the bottom after a separator
+    fn display_list(&self, common_fields: &BTreeSet<String>) -> Vec<(String, String)> {
+        // Build data for sorting
+        let mut data: Vec<(bool, (String, String))> = self
+            .fields()
+            .map(|(k, v)| {
+                (
+                    common_fields.contains(k),
+                    (k.to_string(), FieldContent::Present(v).display()),
+                )
+            }) // Use display to keep formatting consistent
+            .collect();
+
+        // Add separator for common fields
+        data.push((
+            true,
+            (
+                format!(" {}", FieldContent::TEXT_FOR_EMPTY), // prefixed with a leading space so it should end up at top of the common section
+                FieldContent::TEXT_FOR_EMPTY.to_string(),
+            ),
+        ));
+
+        // Sort data based on common fields (to group them at the bottom)
+        data.sort_unstable();
+
+        // Remove extra info that was used for sorting
+        data.into_iter().map(|x| x.1).collect()
+    }
+
+    /// The fields in order of their names
+    pub fn fields(&self) -> impl Iterator<Item = (&'a str, &'a Value)> {
+        let (stored, owned) = match self.0 {
+            RowSource::Stored(columns, real_index) => (Some(columns.fields(real_index)), None),
+            RowSource::Owned(fields) => (None, Some(fields.iter())),
+        };
+        stored
+            .into_iter()
+            .flatten()
+            .chain(owned.into_iter().flatten())
+    }
+
+    /// Hash of the row's fields (excluding `excluded_field`) that is stable between runs so it can be persisted
+    pub fn content_hash(&self, excluded_field: Option<&str>) -> u64 {
+        // FNV-1a
+        const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+        const PRIME: u64 = 0x0100_0000_01b3;
+        let mut result = OFFSET_BASIS;
+        let mut add = |bytes: &[u8]| {
+            for &byte in bytes {
+                result ^= u64::from(byte);
+                result = result.wrapping_mul(PRIME);
+            }
+        };
+        for (key, value) in self.fields() {
+            if Some(key) == excluded_field {
+                continue;
+            }
+            add(key.as_bytes());
+            add(&[0]);
+            add(value.to_string().as_bytes());
+            add(&[0]);
+        }
+        result
+    }
+
+    /// The row's fields as a single line JSON object
+    pub fn to_json_string(&self) -> String {
+        Value::Object(
+            self.fields()
+                .map(|(k, v)| (k.to_string(), v.clone()))
+                .collect(),
+        )
+        .to_string()
+    }
+
+    /// Fields whose values are objects or arrays
+    pub fn nested_fields(&self) -> impl Iterator<Item = (&'a str, &'a Value)> {
+        self.fields().filter(|(_, v)| v.is_object() || v.is_array())
+    }
+
+    /// A copy of the row that does not borrow the data
+    pub fn to_owned_row(&self) -> OwnedLogRow {
+        OwnedLogRow {
+            data: self.fields().map(|(k, v)| (k, v.clone())).collect(),
+        }
+    }
+
+    /// The id of the CI log group the row belongs to if any
+    fn group_id(&self) -> Option<u64> {
+        self.get(ci_log::GROUP_ID_FIELD_NAME)?.as_u64()
+    }
+
+    fn is_group_start(&self) -> bool {
+        self.get(ci_log::GROUP_START_FIELD_NAME).is_some()
+    }
+}
+
+/// Same as a map of the fields so how the row is stored does not show
+struct RowFieldsView<'a>(LogRow<'a>);
+
+impl std::fmt::Debug for RowFieldsView<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map().entries(self.0.fields()).finish()
+    }
+}
+
+impl serde::Serialize for RowFieldsView<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.0.fields())
+    }
+}
+
+impl std::fmt::Debug for LogRow<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LogRow")
+            .field("data", &RowFieldsView(*self))
+            .finish()
+    }
+}
+
+/// Same as [`OwnedLogRow`] so rows read back as one
+impl serde::Serialize for LogRow<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct as _;
+        let mut result = serializer.serialize_struct("LogRow", 1)?;
+        result.serialize_field("data", &RowFieldsView(*self))?;
+        result.end()
+    }
+}
+
+impl PartialEq for LogRow<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.fields().eq(other.fields())
+    }
+}
+
+impl OwnedLogRow {
+    pub fn as_row(&self) -> LogRow<'_> {
+        LogRow(RowSource::Owned(&self.data))
+    }
+
+    /// Adds `value` at `key` if the row does not have the field
+    fn or_insert(&mut self, key: &str, value: Value) {
+        self.data.or_insert(key, value);
+    }
+}
+
+impl Data {
+    pub fn rows_iter(&self) -> impl Iterator<Item = LogRow<'_>> {
+        DataIter::new(self)
+    }
+
+    pub fn len(&self) -> usize {
+        if let Some(filtered) = self.filtered_rows.as_ref() {
+            filtered.len()
+        } else {
+            self.rows.len()
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn total_len_unfiltered(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// If the points are not filtered returns the input otherwise translates it from the filtered array
+    pub fn get_real_index(&self, index: usize) -> usize {
+        if let Some(filtered) = self.filtered_rows.as_ref() {
+            filtered[index]
+        } else {
+            index
+        }
+    }
+
+    /// Time between the visible row at `index` and the visible row before it (None if either has no valid time)
+    pub fn time_gap_before(
+        &self,
+        index: usize,
+        time_field_name: &str,
+    ) -> Option<chrono::TimeDelta> {
+        let previous = self.row_by_real_index(self.get_real_index(index.checked_sub(1)?))?;
+        let current = self.row_by_real_index(self.get_real_index(index))?;
+        let previous_time = previous.field_value(time_field_name).as_time()?;
+        let current_time = current.field_value(time_field_name).as_time()?;
+        Some(current_time - previous_time)
+    }
+
+    /// Selects the visible row with the time closest to `target`, returns false if no visible row has a valid time
+    pub fn select_nearest_time(
+        &mut self,
+        target: chrono::DateTime<chrono::FixedOffset>,
+        time_field_name: &str,
+    ) -> bool {
+        let time_at = |position: usize| {
+            self.row(self.get_real_index(position))
+                .field_value(time_field_name)
+                .as_time()
+        };
+        match time_jump::nearest_position(self.len(), time_at, target) {
+            Some(position) => {
+                self.selected_row = Some(position);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the row at `real_index` (index into all rows ignoring any filter)
+    pub fn row_by_real_index(&self, real_index: usize) -> Option<LogRow<'_>> {
+        self.rows.row(real_index)
+    }
+
+    /// Same as [`Self::row_by_real_index`] but panics if there is no such row (like indexing)
+    fn row(&self, real_index: usize) -> LogRow<'_> {
+        self.rows
+            .row(real_index)
+            .unwrap_or_else(|| panic!("row index out of bounds: {real_index}"))
+    }
+
+    /// Selects the row at `real_index` (index into all rows ignoring any filter), removes the filter if that row is currently hidden by it
+    pub fn select_real_index(&mut self, real_index: usize, common_fields: &BTreeSet<String>) {
+        if real_index >= self.rows.len() {
+            debug_assert!(false, "index out of bounds: {real_index}");
+            return;
+        }
+        if let Some(position) = self.position_of_real(real_index) {
+            self.selected_row = Some(position);
+        } else {
+            self.unfilter(common_fields);
+            // May still not be visible if hidden by something other than the filter
+            self.selected_row = self.position_of_real(real_index);
+        }
+    }
+
+    pub fn selected_row(&self) -> Option<LogRow<'_>> {
+        let selected_row_index = self.selected_row?;
+        self.rows.row(self.get_real_index(selected_row_index))
+    }
+
+    /// Index into all rows of the selected row (ignoring any filter)
+    pub fn selected_real_index(&self) -> Option<usize> {
+        self.selected_row.map(|x| self.get_real_index(x))
+    }
+
+    /// Indices into all rows (ignoring filters) of the rows with the same value for `field_name` as the row at `real_index` (includes `real_index`)
+    ///
+    /// Empty if the row does not have the field
+    pub fn real_indices_with_same_value(&self, real_index: usize, field_name: &str) -> Vec<usize> {
+        if real_index >= self.rows.len() {
+            return Vec::new();
+        }
+        let Some(target) = self.rows.get(real_index, field_name) else {
+            return Vec::new();
+        };
+        (0..self.rows.len())
+            .filter(|&i| self.rows.get(i, field_name) == Some(target))
+            .collect()
+    }
+
+    pub fn selected_row_data_as_slice(
+        &mut self,
+        common_fields: &BTreeSet<String>,
+    ) -> Option<RowSlice<'_>> {
+        let selected_row_index = self.selected_row?;
+        let real_index = self.get_real_index(selected_row_index);
+        let row = self.rows.row(real_index)?;
+        Some(self.display_cache.get(real_index, row, common_fields))
+    }
+
+    pub fn selected_row_data_as_slice_with_filter_matching_fields(
+        &mut self,
+        common_fields: &BTreeSet<String>,
+    ) -> Option<(RowSlice<'_>, Vec<usize>)> {
+        // Collect other needed info before taking mutable borrow to appease the borrow checker (couldn't find another readable way)
+        let is_filtered = self.is_filtered();
+        let filter = if is_filtered {
+            self.applied_filter.clone()
+        } else {
+            None
+        };
+        let real_index = self.get_real_index(self.selected_row?);
+        let matching_fields = if is_filtered {
+            if let Some(filter) = filter.as_ref() {
+                let row = self.rows.row(real_index)?;
+                let fields_and_values = self.display_cache.get(real_index, row, common_fields);
+                matching_fields(row, fields_and_values, filter).unwrap_or_default()
+            } else {
+                debug_assert!(false, "No filter but is_filtered is true?");
+                Vec::new()
+            }
+        } else {
+            Vec::new()
+        };
+        let row_slice = self.selected_row_data_as_slice(common_fields)?;
+        Some((row_slice, matching_fields))
+    }
+
+    pub fn move_selected_to_next(&mut self) {
+        let n = self.len();
+        if let Some(selected) = self.selected_row.as_mut() {
+            if *selected < n - 1 {
+                *selected += 1;
+            } else {
+                // Do nothing already on last row
+            }
+        } else {
+            self.move_selected_to_first();
+        }
+    }
+
+    pub fn move_selected_to_prev(&mut self) {
+        if let Some(selected) = self.selected_row.as_mut() {
+            if *selected > 0 {
+                *selected -= 1;
+            } else {
+                // Do nothing already on first row
+            }
+        } else {
+            self.move_selected_to_last()
+        }
+    }
+
+    /// True if the row at `real_index` matches `filter` (whether or not it is applied)
+    pub fn row_matches(
+        &mut self,
+        real_index: usize,
+        filter: &FilterConfig,
+        common_fields: &BTreeSet<String>,
+    ) -> bool {
+        self.rows.row(real_index).is_some_and(|row| {
+            let fields_and_values = self.display_cache.get(real_index, row, common_fields);
+            matching_fields(row, fields_and_values, filter).is_some()
+        })
+    }
+
+    /// Selects the next (or previous) visible row that matches the filter being edited ([`Self::filter`]) wrapping around at the ends.
+    /// Returns false if no visible row matches
+    pub fn move_selected_to_match(
+        &mut self,
+        is_forward: bool,
+        common_fields: &BTreeSet<String>,
+    ) -> bool {
+        let Some(filter) = self.filter.clone() else {
+            return false;
+        };
+        let n = self.len();
+        for step in 1..=n {
+            let position = match (self.selected_row, is_forward) {
+                (Some(selected), true) => (selected + step) % n,
+                (Some(selected), false) => (selected + n - step) % n,
+                (None, true) => step - 1,
+                (None, false) => n - step,
+            };
+            if self.row_matches(self.get_real_index(position), &filter, common_fields) {
+                self.selected_row = Some(position);
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Selects the next (or previous) visible row that has the same value for the field as the selected row wrapping around at the ends.
+    /// Returns false if there is no other such row (or no row is selected or it does not have the field)
+    pub fn move_selected_to_same_value(&mut self, field_name: &str, is_forward: bool) -> bool {
+        let Some(selected) = self.selected_row else {
+            return false;
+        };
+        let target = match self
+            .row(self.get_real_index(selected))
+            .field_value(field_name)
+        {
+            FieldContent::Present(value) => value.clone(),
+            FieldContent::Missing => return false,
+        };
+        let n = self.len();
+        for step in 1..n {
+            let position = if is_forward {
+                (selected + step) % n
+            } else {
+                (selected + n - step) % n
+            };
+            if matches!(
+                self.row(self.get_real_index(position)).field_value(field_name),
+                FieldContent::Present(value) if *value == target
+            ) {
+                self.selected_row = Some(position);
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Selects the first (or last) visible row with the same value in the field as the selected row.
+    /// Returns false if no row is selected or it does not have the field
+    pub fn move_selected_to_same_value_end(&mut self, field_name: &str, is_last: bool) -> bool {
+        let Some(selected) = self.selected_row else {
+            return false;
+        };
+        let target = match self
+            .row(self.get_real_index(selected))
+            .field_value(field_name)
+        {
+            FieldContent::Present(value) => value.clone(),
+            FieldContent::Missing => return false,
+        };
+        let is_match = |position: &usize| {
+            matches!(
+                self.row(self.get_real_index(*position)).field_value(field_name),
+                FieldContent::Present(value) if *value == target
+            )
+        };
+        let found = if is_last {
+            (0..self.len()).rev().find(is_match)
+        } else {
+            (0..self.len()).find(is_match)
+        };
+        // The selected row always matches
+        self.selected_row = found.or(self.selected_row);
+        true
+    }
+
+    pub fn move_selected_to_first(&mut self) {
+        if !self.is_empty() {
+            self.selected_row = Some(0)
+        } else {
+            // No rows to select
+        }
+    }
+
+    pub fn move_selected_to_last(&mut self) {
+        let n = self.len();
+        if n > 0 {
+            self.selected_row = Some(n - 1);
+        } else {
+            // No rows to select
+        }
+    }
+
+    /// Returns true if a filter is applied (rows may still be hidden without a filter see [`Self::hidden_count`])
+    pub fn is_filtered(&self) -> bool {
+        debug_assert!(self.applied_filter.is_none() || self.filtered_rows.is_some());
+        self.applied_filter.is_some()
+    }
+
+    pub fn unfilter(&mut self, common_fields: &BTreeSet<String>) {
+        self.applied_filter = None;
+        self.update_visible_rows(common_fields);
+    }
+
+    /// Recomputes which rows are visible based on the applied filter, range filters, ignore list and hidden rows.
+    /// The selected row stays selected if it is still visible.
+    fn update_visible_rows(&mut self, common_fields: &BTreeSet<String>) {
+        let is_unfiltered = self.applied_filter.is_none()
+            && self.range_filters.is_empty()
+            && !self.ignore_list.iter().any(|x| x.is_enabled)
+            && self.level_filter.is_empty()
+            && self.column_filters.is_empty()
+            && self.hidden_rows.is_empty()
+            && self.collapsed_groups.is_empty();
+        let candidates = if is_unfiltered {
+            None
+        } else {
+            // Taken so the filter can be borrowed while the rows are checked
+            let applied_filter = self.applied_filter.take();
+            let candidates =
+                self.visible_candidates(0..self.rows.len(), applied_filter.as_ref(), common_fields);
+            self.applied_filter = applied_filter;
+            Some(candidates)
+        };
+        self.set_visible_rows(candidates);
+    }
+
+    /// Of `real_indices` the rows that can be visible (not hidden, folded or excluded by the filters other than the
+    /// applied filter) with if they match `filter` (always true without a filter)
+    fn visible_candidates(
+        &mut self,
+        real_indices: impl Iterator<Item = usize>,
+        filter: Option<&FilterConfig>,
+        common_fields: &BTreeSet<String>,
+    ) -> Vec<(usize, bool)> {
+        let Self {
+            rows,
+            display_cache,
+            range_filters,
+            ignore_list,
+            level_filter,
+            column_filters,
+            hidden_rows,
+            collapsed_groups,
+            ..
+        } = self;
+        let column_filters: Vec<(&String, String)> = column_filters
+            .iter()
+            .map(|(field_name, text)| (field_name, text.to_lowercase()))
+            .collect();
+        let active_ignore_rules: Vec<&FilterConfig> = ignore_list
+            .iter()
+            .filter_map(|x| x.is_enabled.then_some(&x.filter))
+            .collect();
+        let hidden: BTreeSet<usize> = hidden_rows.iter().copied().collect();
+        real_indices
+            .filter_map(|i| {
+                let row = rows.row(i)?;
+                let is_folded = !row.is_group_start()
+                    && row
+                        .group_id()
+                        .is_some_and(|id| collapsed_groups.contains(&id));
+                let is_visible = !hidden.contains(&i)
+                    && !is_folded
+                    && level_filter.matches(row.field_value(&level_filter.field_name))
+                    && column_filters.iter().all(|(field_name, text)| {
+                        match row.field_value(field_name) {
+                            content @ FieldContent::Present(_) => {
+                                content.display().to_lowercase().contains(text)
+                            }
+                            FieldContent::Missing => false,
+                        }
+                    })
+                    && range_filters
+                        .iter()
+                        .all(|x| x.matches(row.field_value(&x.field_name).as_f64()))
+                    && !active_ignore_rules.iter().any(|filter| {
+                        let fields_and_values = display_cache.get(i, row, common_fields);
+                        matching_fields(row, fields_and_values, filter).is_some()
+                    });
+                is_visible.then(|| {
+                    let is_match = filter.is_none_or(|filter| {
+                        let fields_and_values = display_cache.get(i, row, common_fields);
+                        matching_fields(row, fields_and_values, filter).is_some()
+                    });
+                    (i, is_match)
+                })
+            })
+            .collect()
+    }
+
+    /// Shows the candidates that match (see [`Self::visible_candidates`]) with the context rows around them, None
+    /// shows all rows. The selected row stays selected if it is still visible.
+    fn set_visible_rows(&mut self, candidates: Option<Vec<(usize, bool)>>) {
+        let previous_real_index_selected = self.selected_row.map(|x| self.get_real_index(x));
+        let context_rows = self.context_rows;
+        let context_only_rows = &mut self.context_only_rows;
+        context_only_rows.clear();
+        self.filtered_rows = candidates.map(|candidates| {
+            if self.applied_filter.is_none() || context_rows == 0 {
+                candidates
+                    .into_iter()
+                    .filter_map(|(i, is_match)| is_match.then_some(i))
+                    .collect()
+            } else {
+                let mut is_included = vec![false; candidates.len()];
+                for (position, _) in candidates.iter().enumerate().filter(|(_, x)| x.1) {
+                    let last = (position + context_rows).min(candidates.len() - 1);
+                    is_included[position.saturating_sub(context_rows)..=last].fill(true);
+                }
+                candidates
+                    .into_iter()
+                    .zip(is_included)
+                    .filter_map(|((i, is_match), is_included)| {
+                        if is_included && !is_match {
+                            context_only_rows.insert(i);
+                        }
+                        is_included.then_some(i)
+                    })
+                    .collect()
+            }
+        });
+        self.collapse_duplicate_runs();
+        self.apply_sort();
+        self.selected_row = previous_real_index_selected.and_then(|x| self.position_of_real(x));
+        self.on_visible_rows_changed();
+    }
+
+    /// Puts the visible rows in the order of [`Self::sort`] (must be called after the visible rows are recomputed as that is in file order)
+    fn apply_sort(&mut self) {
+        let Some(sort) = self.sort.as_ref() else {
+            return;
+        };
+        let visible = self
+            .filtered_rows
+            .get_or_insert_with(|| (0..self.rows.len()).collect());
+        sort.sort(&self.rows, visible);
+    }
+
+    pub fn sort(&self) -> Option<&sort::SortConfig> {
+        self.sort.as_ref()
+    }
+
+    /// Sets the order of the visible rows (None for file order), the selected row stays selected
+    pub fn set_sort(&mut self, sort: Option<sort::SortConfig>, common_fields: &BTreeSet<String>) {
+        self.sort = sort.filter(|x| !x.is_empty());
+        self.update_visible_rows(common_fields);
+    }
+
+    /// Removes the rows that repeat the value of the previous visible row from the visible rows (unless expanded)
+    fn collapse_duplicate_runs(&mut self) {
+        self.run_lengths.clear();
+        let Some(field_name) = self.collapse_duplicates_field.as_ref() else {
+            return;
+        };
+        let was_filtered = self.filtered_rows.is_some();
+        let visible = self
+            .filtered_rows
+            .take()
+            .unwrap_or_else(|| (0..self.rows.len()).collect());
+        let mut result = Vec::with_capacity(visible.len());
+        // Index into all rows of the first row of the current run and its value
+        let mut run_start: Option<(usize, String)> = None;
+        for i in visible {
+            let value = match self.row(i).field_value(field_name) {
+                content @ FieldContent::Present(_) => Some(content.display()),
+                FieldContent::Missing => None,
+            };
+            match (&run_start, value) {
+                (Some((start, start_value)), Some(value)) if &value == start_value => {
+                    *self.run_lengths.entry(*start).or_insert(1) += 1;
+                    if self.expanded_runs.contains(start) {
+                        result.push(i);
+                    }
+                }
+                (_, value) => {
+                    run_start = value.map(|value| (i, value));
+                    result.push(i);
+                }
+            }
+        }
+        self.filtered_rows = (was_filtered || !self.run_lengths.is_empty()).then_some(result);
+    }
+    fn on_visible_rows_changed(&mut self) {
+        self.is_cold_storage_current = false;
+        self.visible_rows_generation += 1;
+    }
+
+    /// Changes whenever the visible rows may have changed (only comparable for the same data)
+    pub fn visible_rows_generation(&self) -> u64 {
+        self.visible_rows_generation
+    }
+
+    /// Height of each visible row in display order based on the number of lines in the fields set in `settings`.
+    /// Only recomputed when the visible rows, `settings` or `text_height` change as it is needed every frame
+    pub fn row_heights(&mut self, settings: &RowHeight, text_height: f32) -> Arc<[f32]> {
+        let is_current =
+            self.row_heights
+                .key
+                .as_ref()
+                .is_some_and(|(generation, height, cached_settings)| {
+                    *generation == self.visible_rows_generation
+                        && *height == text_height
+                        && cached_settings == settings
+                });
+        if !is_current {
+            self.row_heights.heights = self
+                .rows_iter()
+                .map(|x| settings.line_count(x) as f32 * text_height)
+                .collect();
+            self.row_heights.key =
+                Some((self.visible_rows_generation, text_height, settings.clone()));
+        }
+        Arc::clone(&self.row_heights.heights)
+    }
+
+    /// Moves rows that are not visible into cold storage if enabled and few enough rows are visible.
+    /// Does nothing if the visible rows have not changed since the last call so it can be called every frame.
+    /// Rows return from cold storage when they are used (eg. when the filter is removed and they are shown)
+    pub fn update_cold_storage(&mut self, settings: &ColdStorageSettings) {
+        if self.is_cold_storage_current {
+            return;
+        }
+        self.is_cold_storage_current = true;
+        let Some(filtered) = self.filtered_rows.as_ref() else {
+            return;
+        };
+        if !settings.is_enabled
+            || filtered.len() * 100 > self.rows.len() * usize::from(settings.max_visible_percent)
+        {
+            return;
+        }
+        let visible = self.visible_real_indices();
+        let mut visible = visible.into_iter().peekable();
+        for i in 0..self.rows.len() {
+            if visible.next_if_eq(&i).is_some() {
+                self.rows.thaw(i);
+            } else {
+                self.rows.freeze(i);
+                self.display_cache.invalidate(i);
+            }
+        }
+    }
+
+    pub fn parse_failure_count(&self) -> usize {
+        self.parse_failure_count
+    }
+
+    /// The lines loaded if only part of the input was loaded
+    pub fn partial_load(&self) -> Option<LoadRange> {
+        self.partial_load
+    }
+
+    pub fn decoding(&self) -> Option<decode::Decoding> {
+        self.decoding
+    }
+
+    pub fn set_decoding(&mut self, decoding: Option<decode::Decoding>) {
+        self.decoding = decoding;
+    }
+
+    /// Rough number of bytes used by the rows (walks all rows so callers should not call it every frame)
+    pub fn approximate_memory_size(&self) -> usize {
+        self.rows.approximate_size()
+            + self.filtered_rows.as_ref().map_or(0, |x| x.capacity()) * size_of::<usize>()
+    }
+
+    /// Number of rows currently in cold storage
+    pub fn cold_count(&self) -> usize {
+        self.rows.cold_count()
+    }
+
+    /// Converts from an index into all rows to an index into the visible rows (None if it is not visible)
+    pub fn position_of_real(&self, real_index: usize) -> Option<usize> {
+        match self.filtered_rows.as_ref() {
+            Some(filtered) if self.sort.is_some() => filtered.iter().position(|&x| x == real_index),
+            Some(filtered) => filtered.binary_search(&real_index).ok(),
+            None => (real_index < self.rows.len()).then_some(real_index),
+        }
+    }
+
+    pub fn hidden_count(&self) -> usize {
+        self.hidden_rows.len()
+    }
+
+    /// Hides the selected row without needing a filter, the row that takes its place becomes selected
+    pub fn hide_selected(&mut self) {
+        let Some(selected) = self.selected_row else {
+            return;
+        };
+        let real_index = self.get_real_index(selected);
+        self.hidden_rows.push(real_index);
+        match self.filtered_rows.as_mut() {
+            Some(filtered) => {
+                filtered.remove(selected);
+            }
+            None => {
+                self.filtered_rows =
+                    Some((0..self.rows.len()).filter(|&i| i != real_index).collect())
+            }
+        }
+        if selected >= self.len() {
+            self.selected_row = self.len().checked_sub(1);
+        }
+        self.on_visible_rows_changed();
+    }
+
+    /// Restores the most recently hidden row and selects it if it is visible with the current filters
+    pub fn undo_hide(&mut self, common_fields: &BTreeSet<String>) {
+        let Some(real_index) = self.hidden_rows.pop() else {
+            return;
+        };
+        self.update_visible_rows(common_fields);
+        if let Some(restored) = self.position_of_real(real_index) {
+            self.selected_row = Some(restored);
+        }
+    }
+
+    pub fn restore_all_hidden(&mut self, common_fields: &BTreeSet<String>) {
+        self.hidden_rows.clear();
+        self.update_visible_rows(common_fields);
+    }
+
+    pub fn range_filters(&self) -> &[RangeFilter] {
+        &self.range_filters
+    }
+
+    /// Replaces the range filters and updates the visible rows to match
+    pub fn set_range_filters(
+        &mut self,
+        range_filters: Vec<RangeFilter>,
+        common_fields: &BTreeSet<String>,
+    ) {
+        self.range_filters = range_filters;
+        self.update_visible_rows(common_fields);
+    }
+
+    pub fn level_filter(&self) -> &LevelFilter {
+        &self.level_filter
+    }
+
+    /// Replaces the level filter and updates the visible rows to match
+    pub fn set_level_filter(
+        &mut self,
+        level_filter: LevelFilter,
+        common_fields: &BTreeSet<String>,
+    ) {
+        self.level_filter = level_filter;
+        self.update_visible_rows(common_fields);
+    }
+
+    pub fn collapse_duplicates_field(&self) -> Option<&str> {
+        self.collapse_duplicates_field.as_deref()
+    }
+
+    /// Sets the field used to find repeated rows (None to stop collapsing) and updates the visible rows to match
+    pub fn set_collapse_duplicates_field(
+        &mut self,
+        field_name: Option<String>,
+        common_fields: &BTreeSet<String>,
+    ) {
+        self.collapse_duplicates_field = field_name;
+        self.expanded_runs.clear();
+        self.update_visible_rows(common_fields);
+    }
+
+    /// Number of rows in the run of duplicates starting at `real_index` (None if no run starts there)
+    pub fn run_length(&self, real_index: usize) -> Option<usize> {
+        self.run_lengths.get(&real_index).copied()
+    }
+
+    pub fn is_run_expanded(&self, real_index: usize) -> bool {
+        self.expanded_runs.contains(&real_index)
+    }
+
+    /// Shows or hides the duplicates in the run starting at `real_index`
+    pub fn toggle_run(&mut self, real_index: usize, common_fields: &BTreeSet<String>) {
+        if !self.expanded_runs.remove(&real_index) {
+            self.expanded_runs.insert(real_index);
+        }
+        self.update_visible_rows(common_fields);
+    }
+
+    pub fn context_rows(&self) -> usize {
+        self.context_rows
+    }
+
+    /// Sets the number of rows shown around each match and updates the visible rows to match
+    pub fn set_context_rows(&mut self, context_rows: usize, common_fields: &BTreeSet<String>) {
+        self.context_rows = context_rows;
+        self.update_visible_rows(common_fields);
+    }
+
+    /// True if the row (index into all rows) is only visible as context around rows matching the filter
+    pub fn is_context_only_row(&self, real_index: usize) -> bool {
+        self.context_only_rows.contains(&real_index)
+    }
+
+    pub fn column_filters(&self) -> &BTreeMap<String, String> {
+        &self.column_filters
+    }
+
+    /// Replaces the column filters (empty text is ignored) and updates the visible rows to match
+    pub fn set_column_filters(
+        &mut self,
+        mut column_filters: BTreeMap<String, String>,
+        common_fields: &BTreeSet<String>,
+    ) {
+        column_filters.retain(|_, text| !text.is_empty());
+        self.column_filters = column_filters;
+        self.update_visible_rows(common_fields);
+    }
+
+    pub fn ignore_list(&self) -> &[IgnoreRule] {
+        &self.ignore_list
+    }
+
+    /// Replaces the ignore list and updates the visible rows to match
+    pub fn set_ignore_list(
+        &mut self,
+        ignore_list: Vec<IgnoreRule>,
+        common_fields: &BTreeSet<String>,
+    ) {
+        self.ignore_list = ignore_list;
+        self.update_visible_rows(common_fields);
+    }
+
+    /// Adds an enabled rule to the ignore list
+    pub fn ignore_matching(&mut self, filter: FilterConfig, common_fields: &BTreeSet<String>) {
+        self.ignore_list.push(IgnoreRule {
+            filter,
+            is_enabled: true,
+        });
+        self.update_visible_rows(common_fields);
+    }
+
+    /// Converts the levels of the rows already loaded with `settings` instead of the conversion they were loaded with.
+    /// Levels added by the previous conversion are removed, fields that were in the rows are kept
+    pub fn set_level_conversion(
+        &mut self,
+        settings: Option<&LevelConversion>,
+        common_fields: &BTreeSet<String>,
+    ) {
+        if self.level_conversion.as_ref() == settings {
+            return;
+        }
+        let previous = std::mem::replace(&mut self.level_conversion, settings.cloned());
+        for i in 0..self.rows.len() {
+            if let Some((key, value)) = previous
+                .as_ref()
+                .and_then(|previous| level_conversion_to_display(self.row(i), previous))
+            {
+                if self.rows.get(i, &key) == Some(&value) {
+                    self.rows.remove(i, &key);
+                }
+            }
+            if let Some((key, value)) =
+                settings.and_then(|settings| level_conversion_to_display(self.row(i), settings))
+            {
+                self.rows.or_insert(i, &key, value);
+            }
+            self.display_cache.invalidate(i);
+        }
+        if self.filtered_rows.is_some() {
+            self.update_visible_rows(common_fields);
+        } else {
+            self.on_visible_rows_changed();
+        }
+    }
+
+    /// Cheap enough to call every frame (does not read the rows so rows in cold storage stay there)
+    pub fn has_groups(&self) -> bool {
+        self.group_start_count > 0
+    }
+
+    /// If the row at `real_index` starts a CI log group returns the group's id and if it is collapsed
+    pub fn group_header(&self, real_index: usize) -> Option<(u64, bool)> {
+        let row = self.rows.row(real_index)?;
+        if !row.is_group_start() {
+            return None;
+        }
+        let id = row.group_id()?;
+        Some((id, self.collapsed_groups.contains(&id)))
+    }
+
+    pub fn toggle_group(&mut self, group_id: u64, common_fields: &BTreeSet<String>) {
+        if !self.collapsed_groups.remove(&group_id) {
+            self.collapsed_groups.insert(group_id);
+        }
+        self.update_visible_rows(common_fields);
+    }
+
+    pub fn set_all_groups_collapsed(
+        &mut self,
+        is_collapsed: bool,
+        common_fields: &BTreeSet<String>,
+    ) {
+        self.collapsed_groups = if is_collapsed {
+            self.rows.iter().filter_map(|row| row.group_id()).collect()
+        } else {
+            BTreeSet::new()
+        };
+        self.update_visible_rows(common_fields);
+    }
+
+    /// Numeric values (see [`FieldContent::as_f64`]) of the field across all rows (ignores filters)
+    pub fn numeric_values(&self, field_name: &str) -> Vec<f64> {
+        self.rows
+            .iter()
+            .filter_map(|row| row.field_value(field_name).as_f64())
+            .collect()
+    }
+
+    /// Names of the fields present in any row (sorted)
+    pub fn field_names(&self) -> Vec<String> {
+        self.rows
+            .field_names()
+            .into_iter()
+            .map(String::from)
+            .collect()
+    }
+
+    pub fn apply_filter(&mut self, common_fields: &BTreeSet<String>) {
+        let Some(mut job) = FilterJob::new(self) else {
+            warn!("Apply called but no filter is available");
+            return;
+        };
+        while !job.step(self, common_fields, usize::MAX) {}
+    }
+
+    /// Carries the filters and selection over from `other` (the previous data).
+    /// The selected row is found by its content (ignoring `row_idx_field_name`) as lines may have been added before it
+    pub fn take_config(
+        &mut self,
+        other: &mut Self,
+        common_fields: &BTreeSet<String>,
+        row_idx_field_name: Option<&str>,
+    ) {
+        let is_filtered = other.is_filtered();
+        let selected = other.selected_real_index().and_then(|real_index| {
+            let hash = other.rows.row(real_index)?.content_hash(row_idx_field_name);
+            Some((real_index, hash))
+        });
+        self.filter = other.filter.take();
+        self.hidden_rows = std::mem::take(&mut other.hidden_rows)
+            .into_iter()
+            .filter(|&i| i < self.rows.len())
+            .collect();
+        self.range_filters = std::mem::take(&mut other.range_filters);
+        self.ignore_list = std::mem::take(&mut other.ignore_list);
+        self.level_filter = std::mem::take(&mut other.level_filter);
+        self.column_filters = std::mem::take(&mut other.column_filters);
+        self.context_rows = other.context_rows;
+        self.collapse_duplicates_field = other.collapse_duplicates_field.take();
+        self.sort = other.sort.take();
+        if is_filtered {
+            self.apply_filter(common_fields);
+        } else {
+            self.update_visible_rows(common_fields);
+        }
+        let same_content = selected.and_then(|(old_real_index, hash)| {
+            // Closest in case there are multiple rows with the same content
+            let real_index = (0..self.rows.len())
+                .filter(|&i| self.row(i).content_hash(row_idx_field_name) == hash)
+                .min_by_key(|&i| i.abs_diff(old_real_index))?;
+            self.position_of_real(real_index)
+        });
+        if let Some(position) = same_content {
+            self.selected_row = Some(position);
+        } else if let Some(i) = other.selected_row {
+            if i < self.len() {
+                self.selected_row = Some(i);
+            }
+        }
+    }
+
+    pub fn applied_filter_display(&self) -> String {
+        let Some(filter) = self.applied_filter.as_ref() else {
+            debug_assert!(false, "We really shouldn't end up here");
+            return "No Filter Applied".to_string();
+        };
+        filter.to_string()
+    }
+
+    pub fn applied_filter(&self) -> Option<&FilterConfig> {
+        self.applied_filter.as_ref()
+    }
+
+    /// Indices (into all rows) of the rows currently visible in file order (even if the rows are sorted)
+    pub fn visible_real_indices(&self) -> Vec<usize> {
+        match self.filtered_rows.as_ref() {
+            Some(filtered) => {
+                let mut result = filtered.clone();
+                if self.sort.is_some() {
+                    result.sort_unstable();
+                }
+                result
+            }
+            None => (0..self.rows.len()).collect(),
+        }
+    }
+
+    /// Creates NDJSON with the rows that are only in one of the two sets of indices (which must be sorted).
+    /// The field `marker_field_name` is added to each row to show which set the row came from
+    pub fn export_symmetric_difference(
+        &self,
+        (label_a, rows_a): (&str, &[usize]),
+        (label_b, rows_b): (&str, &[usize]),
+        marker_field_name: &str,
+    ) -> anyhow::Result<String> {
+        let only_a = rows_a.iter().filter(|x| rows_b.binary_search(x).is_err());
+        let only_b = rows_b.iter().filter(|x| rows_a.binary_search(x).is_err());
+        let mut result = String::new();
+        for (label, row_index) in only_a
+            .map(|&x| (label_a, x))
+            .chain(only_b.map(|&x| (label_b, x)))
+        {
+            let mut row = self.row(row_index).to_owned_row();
+            row.data.insert(marker_field_name, label.into());
+            result.push_str(
+                &serde_json::to_string(&row.data)
+                    .with_context(|| format!("failed to serialize row {row_index}"))?,
+            );
+            result.push('\n');
+        }
+        Ok(result)
+    }
+}
+
+fn is_zero(value: &usize) -> bool {
+    *value == 0
+}
+
+/// If the row matches the filter then the indices (in `fields_and_values`, the row as shown in the details see
+/// [`Data::selected_row_data_as_slice`]) of the fields that match are returned or None if it does not match.
+/// Fields to filter on may be paths into nested values in which case the index of the top level field is returned
+fn matching_fields(
+    row: LogRow<'_>,
+    fields_and_values: RowSlice<'_>,
+    filter: &FilterConfig,
+) -> Option<Vec<usize>> {
+    let FilterConfig {
+        search_key,
+        filter_on,
+        comparator,
+        is_case_sensitive,
+    } = filter;
+    let search_key = if *is_case_sensitive {
+        search_key
+    } else {
+        &search_key.to_lowercase()
+    };
+    let iter = fields_and_values
+        .iter()
+        .map(|(k, v)| {
+            if *is_case_sensitive {
+                (Cow::Borrowed(k), Cow::Borrowed(v))
+            } else {
+                (Cow::Owned(k.to_lowercase()), Cow::Owned(v.to_lowercase()))
+            }
+        })
+        .enumerate();
+    let names: Option<Vec<Cow<'_, str>>> = filter_on.field_names().map(|names| {
+        names
+            .into_iter()
+            .map(|name| {
+                if *is_case_sensitive {
+                    Cow::Borrowed(name)
+                } else {
+                    Cow::Owned(name.to_lowercase())
+                }
+            })
+            .collect()
+    });
+    let is_checked = |field_name: &str| {
+        names
+            .as_ref()
+            .is_none_or(|x| x.iter().any(|x| x == field_name))
+    };
+    // Paths are only used for names that are not the name of a field
+    let paths: Vec<(&str, FieldContent<'_>)> = filter_on
+        .field_names()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|name| field_path::is_path(name) && row.get(name).is_none())
+        .map(|path| (path, row.field_value(path)))
+        .collect();
+    if *comparator == Comparator::IsMissing && names.is_some() {
+        // Matches without any field to highlight
+        let mut iter = iter;
+        return (!iter.any(|(_, (field_name, _))| is_checked(&field_name))
+            && paths
+                .iter()
+                .all(|(_, value)| *value == FieldContent::Missing))
+        .then(Vec::new);
+    }
+    let mut result: Vec<usize> = iter
+        .filter_map(|(i, (field_name, value))| {
+            (is_checked(&field_name) && comparator.apply(search_key, value.as_str())).then_some(i)
+        })
+        .collect();
+    for (path, value) in paths {
+        if value == FieldContent::Missing {
+            continue;
+        }
+        let value = if *is_case_sensitive {
+            value.display()
+        } else {
+            value.display().to_lowercase()
+        };
+        let root_index = fields_and_values
+            .iter()
+            .position(|(name, _)| name == field_path::root(path));
+        if let (true, Some(i)) = (comparator.apply(search_key, &value), root_index) {
+            if !result.contains(&i) {
+                result.push(i);
+            }
+        }
+    }
+
+    if result.is_empty() {
+        None
+    } else {
+        Some(result)
+    }
+}
+
+/// Parses a single line on its own with the format in the options (so parsers that need earlier lines will not work as expected)
+impl TryFrom<(&DataDisplayOptions, usize, &str)> for OwnedLogRow {
+    type Error = anyhow::Error;
+
+    fn try_from(
+        (data_display_options, row_idx_val, value): (&DataDisplayOptions, usize, &str),
+    ) -> Result<Self, Self::Error> {
+        let data = match new_line_parser(data_display_options)?.parse_line(value) {
+            Ok(Some(data)) => data,
+            Ok(None) => anyhow::bail!("line does not contain a row"),
+            Err(e) => failed_line_fields(data_display_options, value, e)?,
+        };
+        Ok(Self::from_fields(data_display_options, row_idx_val, data))
+    }
+}
+
+/// Cached result of [`Data::row_heights`]. Ignored when comparing data as it can be recomputed
+#[derive(Debug, Default)]
+struct RowHeightsCache {
+    /// Visible rows generation, text height and settings the heights were computed for
+    key: Option<(u64, f32, RowHeight)>,
+    heights: Arc<[f32]>,
+}
+
+impl PartialEq for RowHeightsCache {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+/// Holds the parser between lines. Ignored when comparing data as it is only needed to parse more lines
+#[derive(Default)]
+struct LineParserSlot(Option<Box<dyn LineParser>>);
+
+impl std::fmt::Debug for LineParserSlot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("LineParserSlot")
+            .field(&self.0.as_ref().map(|_| ".."))
+            .finish()
+    }
+}
+
+impl PartialEq for LineParserSlot {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+/// Handles a line that failed to parse as set in the options, returns the fields of the converted row
+fn failed_line_fields(
+    data_display_options: &DataDisplayOptions,
+    value: &str,
+    e: anyhow::Error,
+) -> anyhow::Result<BTreeMap<String, Value>> {
+    match &data_display_options.row_parse_error_handling {
+        RowParseErrorHandling::AbortOnAnyErrors => {
+            Err(e).context("Parse Error and mode is Abort On Error")
+        }
+        RowParseErrorHandling::ConvertFailedLines {
+            raw_line_field_name,
+            parse_error_field_name,
+        } => {
+            let mut result = BTreeMap::new();
+            result.insert(raw_line_field_name.clone(), value.into());
+            if let Some(err_field) = parse_error_field_name {
+                result.insert(err_field.clone(), format!("{e:#}").into());
+            }
+            Ok(result)
+        }
+        RowParseErrorHandling::JoinToPreviousRow { field_name } => {
+            Ok([(field_name.clone(), value.into())].into())
+        }
+    }
+}
+
+impl OwnedLogRow {
+    /// Adds the fields derived from the options (eg. row number) to the fields parsed from the line
+    fn from_fields(
+        data_display_options: &DataDisplayOptions,
+        row_idx_val: usize,
+        data: BTreeMap<String, Value>,
+    ) -> Self {
+        let flatten_nested = &data_display_options.flatten_nested;
+        let data = if flatten_nested.is_enabled {
+            flatten_fields(data, flatten_nested.max_depth)
+        } else {
+            data
+        };
+        let mut result = Self { data: data.into() };
+        if let Some(key) = data_display_options.row_idx_field_name.as_ref() {
+            result.or_insert(key, row_idx_val.into());
+        }
+        if let Some(settings) = data_display_options.level_conversion.as_ref() {
+            if let Some((key, value)) = level_conversion_to_display(result.as_row(), settings) {
+                result.or_insert(&key, value);
+            }
+        }
+        result
+    }
+}
+
+/// Replaces objects (up to `max_depth` levels deep) with their fields named `parent.child`.
+/// If a name is already used the existing field is kept
+fn flatten_fields(data: BTreeMap<String, Value>, max_depth: usize) -> BTreeMap<String, Value> {
+    fn add_nested(
+        result: &mut BTreeMap<String, Value>,
+        prefix: &str,
+        object: serde_json::Map<String, Value>,
+        depth_left: usize,
+    ) {
+        for (key, value) in object {
+            let name = format!("{prefix}.{key}");
+            match value {
+                Value::Object(object) if depth_left > 0 && !object.is_empty() => {
+                    add_nested(result, &name, object, depth_left - 1)
+                }
+                value => {
+                    result.entry(name).or_insert(value);
+                }
+            }
+        }
+    }
+
+    let (nested, mut result): (BTreeMap<String, Value>, BTreeMap<String, Value>) =
+        data.into_iter().partition(
+            |(_, value)| matches!(value, Value::Object(x) if max_depth > 0 && !x.is_empty()),
+        );
+    for (key, value) in nested {
+        let Value::Object(object) = value else {
+            unreachable!("only objects were partitioned into nested")
+        };
+        add_nested(&mut result, &key, object, max_depth - 1);
+    }
+    result
+}
+
+/// Returns None if the level cannot be converted (the mapping is user defined so this is not treated as a bug)
+fn level_conversion_to_display(
+    row: LogRow<'_>,
+    settings: &LevelConversion,
+) -> Option<(String, Value)> {
+    let FieldContent::Present(raw_value) = row.field_value(&settings.source_field_name) else {
+        return None;
+    };
+    let raw_value = match raw_value.as_i64() {
+        Some(x) => x,
+        None => {
+            warn!(
+                "Failed to convert raw for {:?} to i64: {raw_value:?}",
+                settings.source_field_name
+            );
+            return None;
+        }
+    };
+    match settings.convert_map.get(&raw_value) {
+        Some(converted_value) => Some((
+            settings.display_field_name.clone(),
+            converted_value.clone().into(),
+        )),
+        None => {
+            warn!("Failed to convert raw_value to a displayable log level: {raw_value:?}");
+            None
+        }
+    }
+}
+
+type ParserLines<'a> = std::iter::Peekable<
+    std::iter::Take<std::iter::Skip<std::iter::Enumerate<std::str::Lines<'a>>>>,
+>;
+
+/// Applies [`Data::filter`] a chunk of rows at a time so the caller can do other work (eg. draw frames) between chunks.
+/// The rows visible before stay visible until the last chunk is done
+#[derive(Debug)]
+pub struct FilterJob {
+    filter: FilterConfig,
+    /// Rows to check (indices into all rows) if only the visible rows need to be checked (see
+    /// [`FilterConfig::is_narrowing_of`]). Otherwise all rows are checked including rows added while the job runs
+    narrowed_rows: Option<Vec<usize>>,
+    /// Position of the next row to check (index into all rows if all rows are checked)
+    next: usize,
+    candidates: Vec<(usize, bool)>,
+    /// Of the data when the rows checked were last in sync with it, see [`Self::step`]
+    dropped_count: usize,
+    visible_rows_generation: u64,
+}
+
+impl FilterJob {
+    /// None if there is no filter to apply
+    pub fn new(data: &Data) -> Option<Self> {
+        let filter = data.filter.clone()?;
+        // Context rows may be needed around the remaining matches so all rows must be checked
+        let is_narrowing = data.context_rows == 0
+            && data.collapse_duplicates_field.is_none()
+            && data
+                .applied_filter
+                .as_ref()
+                .is_some_and(|applied| filter.is_narrowing_of(applied));
+        Some(Self {
+            filter,
+            narrowed_rows: is_narrowing.then(|| data.visible_real_indices()),
+            next: 0,
+            candidates: Vec::new(),
+            dropped_count: data.dropped_count,
+            visible_rows_generation: data.visible_rows_generation,
+        })
+    }
+
+    /// Fraction of the rows checked
+    pub fn progress(&self, data: &Data) -> f32 {
+        let total = self
+            .narrowed_rows
+            .as_ref()
+            .map_or(data.rows.len(), Vec::len);
+        self.next as f32 / total.max(1) as f32
+    }
+
+    /// Checks up to `max_rows` more rows, returns true once the filter is applied
+    pub fn step(
+        &mut self,
+        data: &mut Data,
+        common_fields: &BTreeSet<String>,
+        max_rows: usize,
+    ) -> bool {
+        if self.narrowed_rows.is_some()
+            && data.visible_rows_generation != self.visible_rows_generation
+        {
+            // The visible rows changed so they may not be the rows to narrow down anymore
+            self.narrowed_rows = None;
+            self.next = 0;
+            self.candidates.clear();
+        } else if data.dropped_count != self.dropped_count {
+            // Rows checked moved to lower indices
+            let dropped = data.dropped_count - self.dropped_count;
+            self.candidates
+                .retain_mut(|(i, _)| match i.checked_sub(dropped) {
+                    Some(moved) => {
+                        *i = moved;
+                        true
+                    }
+                    None => false,
+                });
+            self.next = self.next.saturating_sub(dropped);
+        }
+        self.dropped_count = data.dropped_count;
+
+        let total = self
+            .narrowed_rows
+            .as_ref()
+            .map_or(data.rows.len(), Vec::len);
+        let end = self.next.saturating_add(max_rows).min(total);
+        let candidates = match self.narrowed_rows.as_ref() {
+            Some(rows) => data.visible_candidates(
+                rows[self.next..end].iter().copied(),
+                Some(&self.filter),
+                common_fields,
+            ),
+            None => data.visible_candidates(self.next..end, Some(&self.filter), common_fields),
+        };
+        self.candidates.extend(candidates);
+        self.next = end;
+        if end < total {
+            return false;
+        }
+        data.applied_filter = Some(self.filter.clone());
+        data.set_visible_rows(Some(std::mem::take(&mut self.candidates)));
+        true
+    }
+}
+
+/// Parses the input a chunk of lines at a time so the caller can do other work between chunks
+/// (eg. on the web where parsing shares the thread with the UI)
+///
+/// Only the lines in [`DataDisplayOptions::load_range`] are parsed
+pub struct DataParser<'a> {
+    data_display_options: &'a DataDisplayOptions,
+    input_len: usize,
+    lines: ParserLines<'a>,
+    bytes_processed: usize,
+    /// Not the same as the number of rows as the row script may drop rows
+    lines_processed: usize,
+    /// Max number of lines parsed when only part of the input is loaded
+    max_lines: Option<usize>,
+    data: Data,
+}
+
+impl<'a> DataParser<'a> {
+    pub fn new(data_display_options: &'a DataDisplayOptions, value: &'a str) -> Self {
+        let load_range = data_display_options.load_range;
+        let (skip, take) = load_range.skip_and_take(|| value.lines().count());
+        let is_partial = load_range != LoadRange::All;
+        Self {
+            data_display_options,
+            input_len: value.len(),
+            lines: value.lines().enumerate().skip(skip).take(take).peekable(),
+            bytes_processed: 0,
+            lines_processed: 0,
+            max_lines: is_partial.then_some(take),
+            data: Data {
+                partial_load: is_partial.then_some(load_range),
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Fraction of the input processed
+    pub fn progress(&self) -> f32 {
+        match self.max_lines {
+            // Based on lines as most of the input may be skipped
+            Some(max_lines) => self.lines_processed as f32 / max_lines.max(1) as f32,
+            None => self.bytes_processed as f32 / self.input_len.max(1) as f32,
+        }
+    }
+
+    pub fn is_done(&mut self) -> bool {
+        self.lines.peek().is_none()
+    }
+
+    /// Parses up to `max_lines` more lines
+    pub fn parse_chunk(&mut self, max_lines: usize) -> anyhow::Result<()> {
+        for (i, line) in self.lines.by_ref().take(max_lines) {
+            self.bytes_processed += line.len() + 1;
+            self.lines_processed += 1;
+            self.data.push_line(self.data_display_options, i, line)?;
+        }
+        Ok(())
+    }
+
+    /// The parsed data (should only be called once [`Self::is_done`])
+    pub fn finish(mut self) -> Data {
+        debug_assert!(self.is_done());
+        self.data.finish_loading(self.data_display_options);
+        self.data
+    }
+}
+
+impl TryFrom<(&DataDisplayOptions, &str)> for Data {
+    type Error = anyhow::Error;
+
+    fn try_from(
+        (data_display_options, value): (&DataDisplayOptions, &str),
+    ) -> Result<Self, Self::Error> {
+        Ok(
+            Data::parse_with_progress(data_display_options, value, |_| true)?
+                .expect("never cancelled as the callback always returns true"),
+        )
+    }
+}
+
+impl Data {
+    /// Number of lines between progress reports
+    pub const PROGRESS_INTERVAL: usize = 1000;
+
+    /// Same as the `TryFrom` implementation but periodically reports the fraction of the input processed.
+    /// Parsing stops and None is returned if `on_progress` returns false.
+    pub fn parse_with_progress(
+        data_display_options: &DataDisplayOptions,
+        value: &str,
+        mut on_progress: impl FnMut(f32) -> bool,
+    ) -> anyhow::Result<Option<Self>> {
+        let mut parser = DataParser::new(data_display_options, value);
+        while !parser.is_done() {
+            if !on_progress(parser.progress()) {
+                return Ok(None);
+            }
+            parser.parse_chunk(Self::PROGRESS_INTERVAL)?;
+        }
+        Ok(Some(parser.finish()))
+    }
+
+    /// Parses `lines` and adds them after the existing rows (used for live sources).
+    /// Lines before a failure are kept.
+    pub fn append_lines<'a>(
+        &mut self,
+        data_display_options: &DataDisplayOptions,
+        lines: impl IntoIterator<Item = &'a str>,
+        common_fields: &BTreeSet<String>,
+    ) -> anyhow::Result<()> {
+        if self.rows.is_empty() {
+            self.level_conversion = data_display_options.level_conversion.clone();
+        }
+        let result = lines.into_iter().try_for_each(|line| {
+            let i = self.next_line_index.max(self.rows.len());
+            self.push_line(data_display_options, i, line)
+        });
+        if self.filtered_rows.is_some() {
+            self.update_visible_rows(common_fields);
+        } else {
+            self.on_visible_rows_changed();
+        }
+        result
+    }
+
+    /// Parses the line (`i` is its index in the input) and adds the row if it produces one
+    fn push_line(
+        &mut self,
+        data_display_options: &DataDisplayOptions,
+        i: usize,
+        line: &str,
+    ) -> anyhow::Result<()> {
+        let row = self
+            .parse_line(data_display_options, i, line)
+            .with_context(|| format!("failed to parse line {}", i + 1))?;
+        let Some(row) = row else {
+            return Ok(());
+        };
+        if row.as_row().is_group_start() {
+            self.group_start_count += 1;
+        }
+        self.rows.push(row.data);
+        Ok(())
+    }
+
+    /// Removes the first `count` rows (eg. to limit the rows kept while following a live source).
+    /// Indices into all rows kept by the data (hidden rows, expanded runs and the selection) are shifted to still refer to the same rows
+    pub fn drop_oldest_rows(&mut self, count: usize, common_fields: &BTreeSet<String>) {
+        let count = count.min(self.rows.len());
+        if count == 0 {
+            return;
+        }
+        let selected_real_index = self.selected_real_index();
+        self.group_start_count -= self
+            .rows
+            .iter()
+            .take(count)
+            .filter(|row| row.is_group_start())
+            .count();
+        self.rows.drain_front(count);
+        self.display_cache.drain_front(count);
+        self.dropped_count += count;
+        self.hidden_rows = self
+            .hidden_rows
+            .iter()
+            .filter_map(|x| x.checked_sub(count))
+            .collect();
+        self.expanded_runs = self
+            .expanded_runs
+            .iter()
+            .filter_map(|x| x.checked_sub(count))
+            .collect();
+        // The visible rows refer to rows that moved so they are recomputed without a selection to restore
+        self.filtered_rows = None;
+        self.selected_row = None;
+        self.update_visible_rows(common_fields);
+        self.selected_row = selected_real_index
+            .and_then(|x| x.checked_sub(count))
+            .and_then(|x| self.position_of_real(x));
+    }
+
+    /// Number of the oldest rows removed to stay within the limit of rows kept
+    pub fn dropped_count(&self) -> usize {
+        self.dropped_count
+    }
+
+    /// Called once all the lines of a new input are parsed
+    fn finish_loading(&mut self, data_display_options: &DataDisplayOptions) {
+        self.level_conversion = data_display_options.level_conversion.clone();
+        // Groups start folded so the log reads as a list of steps
+        if self.has_groups() {
+            self.set_all_groups_collapsed(true, data_display_options.common_fields());
+        }
+    }
+
+    fn parse_line(
+        &mut self,
+        data_display_options: &DataDisplayOptions,
+        row_idx_val: usize,
+        line: &str,
+    ) -> anyhow::Result<Option<OwnedLogRow>> {
+        self.next_line_index = row_idx_val + 1;
+        let parser = match self.line_parser.0.as_mut() {
+            Some(parser) => parser,
+            None => self
+                .line_parser
+                .0
+                .insert(new_line_parser(data_display_options)?),
+        };
+        let fields = match parser.parse_line(line) {
+            Ok(Some(fields)) => fields,
+            Ok(None) => return Ok(None),
+            Err(e) => {
+                if let (
+                    RowParseErrorHandling::JoinToPreviousRow { field_name },
+                    Some(previous_index),
+                ) = (
+                    &data_display_options.row_parse_error_handling,
+                    self.rows.len().checked_sub(1),
+                ) {
+                    self.append_line_to_field(previous_index, field_name, line);
+                    return Ok(None);
+                }
+                self.parse_failure_count += 1;
+                failed_line_fields(data_display_options, line, e)?
+            }
+        };
+        let Some(fields) = self.run_row_script(data_display_options, fields)? else {
+            return Ok(None);
+        };
+        Ok(Some(OwnedLogRow::from_fields(
+            data_display_options,
+            row_idx_val,
+            fields,
+        )))
+    }
+
+    /// Adds `line` to the end of the field on a new line (a value that is not a string is converted to text first)
+    fn append_line_to_field(&mut self, real_index: usize, field_name: &str, line: &str) {
+        let text = match self.rows.get(real_index, field_name) {
+            Some(value) => format!("{}\n{line}", FieldContent::Present(value).display()),
+            None => line.to_string(),
+        };
+        self.rows.insert(real_index, field_name, text.into());
+        self.display_cache.invalidate(real_index);
+    }
+
+    /// Returns None if the script dropped the row
+    fn run_row_script(
+        &mut self,
+        data_display_options: &DataDisplayOptions,
+        fields: BTreeMap<String, Value>,
+    ) -> anyhow::Result<Option<BTreeMap<String, Value>>> {
+        let settings = &data_display_options.row_script;
+        if !settings.is_enabled || settings.script.trim().is_empty() {
+            return Ok(Some(fields));
+        }
+        if self
+            .row_script
+            .as_ref()
+            .is_none_or(|x| x.source() != settings.script)
+        {
+            self.row_script = Some(RowScript::new(&settings.script)?);
+        }
+        self.row_script
+            .as_ref()
+            .expect("set above if it was missing")
+            .apply(fields)
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,164 @@
+use std::collections::BTreeMap;
+
+use chrono::DateTime;
+use serde_json::Value;
+
+/// Title of the group the row belongs to
+pub const GROUP_FIELD_NAME: &str = "ci_group";
+/// Identifies the group the row belongs to (numbered in the order the groups start)
+pub const GROUP_ID_FIELD_NAME: &str = "ci_group_id";
+/// Present (and true) on the row that starts a group
+pub const GROUP_START_FIELD_NAME: &str = "ci_group_start";
+
+/// Converts lines from CI runner logs (eg. GitHub Actions) into fields.
+///
+/// Lines look like `2024-05-01T10:00:00.1234567Z ##[group]Run cargo test` where the timestamp is optional.
+/// Both the `##[command]` form used in downloaded logs and the `::command::` form written by steps are recognized.
+/// Keeps track of the group currently open so it must see the lines in order.
+#[derive(Default, Debug, PartialEq, Eq, Clone)]
+pub struct CiLogParser {
+    /// Id and title of the group currently open
+    open_group: Option<(u64, String)>,
+    next_group_id: u64,
+}
+
+/// Commands that are recognized
+#[derive(Debug, PartialEq, Eq)]
+enum Command<'a> {
+    Group(&'a str),
+    EndGroup,
+    Level(&'static str, &'a str),
+    Text(&'a str),
+}
+
+impl CiLogParser {
+    pub fn parse_line(&mut self, line: &str) -> BTreeMap<String, Value> {
+        let mut result = BTreeMap::new();
+        let text = match split_timestamp(line) {
+            Some((time, rest)) => {
+                result.insert("time".to_string(), time.into());
+                rest
+            }
+            None => line,
+        };
+
+        let msg = match parse_command(text) {
+            Command::Group(title) => {
+                // Groups do not nest, starting a new one closes the previous
+                let id = self.next_group_id;
+                self.next_group_id += 1;
+                self.open_group = Some((id, title.to_string()));
+                result.insert(GROUP_START_FIELD_NAME.to_string(), true.into());
+                title
+            }
+            Command::EndGroup => {
+                // The end marker belongs to the group it closes so it is folded with it
+                if let Some((id, title)) = self.open_group.take() {
+                    result.insert(GROUP_ID_FIELD_NAME.to_string(), id.into());
+                    result.insert(GROUP_FIELD_NAME.to_string(), title.into());
+                }
+                ""
+            }
+            Command::Level(level, msg) => {
+                result.insert("level_str".to_string(), level.into());
+                msg
+            }
+            Command::Text(msg) => msg,
+        };
+        if let Some((id, title)) = self.open_group.as_ref() {
+            result.insert(GROUP_ID_FIELD_NAME.to_string(), (*id).into());
+            result.insert(GROUP_FIELD_NAME.to_string(), title.clone().into());
+        }
+        result.insert("msg".to_string(), msg.into());
+        result
+    }
+}
+
+/// Splits off the leading timestamp if the line starts with one
+fn split_timestamp(line: &str) -> Option<(&str, &str)> {
+    let (first, rest) = line.split_once(' ').unwrap_or((line, ""));
+    DateTime::parse_from_rfc3339(first).ok()?;
+    Some((first, rest))
+}
+
+fn parse_command(text: &str) -> Command<'_> {
+    let Some((name, rest)) = text
+        .strip_prefix("##[")
+        .and_then(|x| x.split_once(']'))
+        .or_else(|| {
+            // Workflow commands may have parameters (eg. `::error file=app.rs::msg`) which are kept in the message
+            let (name, rest) = text.strip_prefix("::")?.split_once("::")?;
+            let name = name.split_once(' ').map_or(name, |(name, _)| name);
+            Some((name, rest))
+        })
+    else {
+        return Command::Text(text);
+    };
+    match name {
+        "group" => Command::Group(rest),
+        "endgroup" => Command::EndGroup,
+        "error" => Command::Level("Error", rest),
+        "warning" => Command::Level("Warn", rest),
+        "notice" => Command::Level("Info", rest),
+        "debug" => Command::Level("Debug", rest),
+        _ => Command::Text(text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+    use serde_json::json;
+
+    use super::*;
+
+    #[rstest]
+    #[case::group("##[group]Run cargo test", Command::Group("Run cargo test"))]
+    #[case::workflow_group("::group::Build", Command::Group("Build"))]
+    #[case::end_group("##[endgroup]", Command::EndGroup)]
+    #[case::workflow_error("::error file=a.rs,line=1::boom", Command::Level("Error", "boom"))]
+    #[case::warning("##[warning]careful", Command::Level("Warn", "careful"))]
+    #[case::unknown("##[section]Starting", Command::Text("##[section]Starting"))]
+    #[case::plain("cargo test", Command::Text("cargo test"))]
+    fn commands(#[case] input: &str, #[case] expected: Command<'_>) {
+        assert_eq!(parse_command(input), expected);
+    }
+
+    #[test]
+    fn rows_in_group_are_tagged() {
+        let mut parser = CiLogParser::default();
+        let lines = [
+            "2024-05-01T10:00:00.1234567Z Starting",
+            "2024-05-01T10:00:01.0000000Z ##[group]Run cargo test",
+            "running 2 tests",
+            "2024-05-01T10:00:02.0000000Z ##[endgroup]",
+            "::error::failed",
+        ];
+        let actual: Vec<Value> = lines
+            .iter()
+            .map(|x| serde_json::to_value(parser.parse_line(x)).unwrap())
+            .collect();
+        assert_eq!(
+            actual,
+            vec![
+                json!({"time": "2024-05-01T10:00:00.1234567Z", "msg": "Starting"}),
+                json!({
+                    "time": "2024-05-01T10:00:01.0000000Z",
+                    "msg": "Run cargo test",
+                    "ci_group": "Run cargo test",
+                    "ci_group_id": 0,
+                    "ci_group_start": true
+                }),
+                json!({"msg": "running 2 tests", "ci_group": "Run cargo test", "ci_group_id": 0}),
+                json!({
+                    "time": "2024-05-01T10:00:02.0000000Z",
+                    "msg": "",
+                    "ci_group": "Run cargo test",
+                    "ci_group_id": 0
+                }),
+                json!({"msg": "failed", "level_str": "Error"}),
+            ]
+        );
+    }
+}
@@ -0,0 +1,301 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde_json::Value;
+
+use super::{
+    row_fields::{value_size, FieldMap, RowFields},
+    LogRow, OwnedLogRow,
+};
+
+/// The fields of all the rows of [`super::Data`] kept by field: each field name has a column with the value of every
+/// row (None for rows without the field) at the index of the row.
+///
+/// Most logs repeat the same fields in every row so each name is only kept once and checking a field only reads its
+/// column. Rows in cold storage are kept as JSON text instead (see [`RowFields`]) and their cells are left empty
+#[derive(Default)]
+pub struct Columns {
+    /// Index into `columns` of the column of each field name
+    column_indices: BTreeMap<Box<str>, usize>,
+    columns: Vec<Vec<Option<Value>>>,
+    /// One per row, set if the row is in cold storage
+    cold: Vec<Option<Box<RowFields>>>,
+}
+
+impl Columns {
+    pub fn len(&self) -> usize {
+        self.cold.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cold.is_empty()
+    }
+
+    pub fn row(&self, index: usize) -> Option<LogRow<'_>> {
+        (index < self.len()).then(|| LogRow::stored(self, index))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = LogRow<'_>> {
+        (0..self.len()).map(|index| LogRow::stored(self, index))
+    }
+
+    /// Adds a row with the fields at the end
+    pub fn push<N: AsRef<str>>(&mut self, fields: impl IntoIterator<Item = (N, Value)>) {
+        let index = self.len();
+        self.cold.push(None);
+        for column in self.columns.iter_mut() {
+            column.push(None);
+        }
+        for (name, value) in fields {
+            self.column_mut(name.as_ref())[index] = Some(value);
+        }
+    }
+
+    /// Value of the field in the row at `index` (parses the row again if it is cold)
+    pub fn get(&self, index: usize, name: &str) -> Option<&Value> {
+        match self.cold[index].as_deref() {
+            Some(cold) => cold.get(name),
+            None => {
+                let &column = self.column_indices.get(name)?;
+                self.columns[column][index].as_ref()
+            }
+        }
+    }
+
+    /// The fields of the row at `index` in order of their names
+    pub fn fields(&self, index: usize) -> impl Iterator<Item = (&str, &Value)> {
+        let (cold, stored) = match self.cold[index].as_deref() {
+            Some(cold) => (Some(cold.iter()), None),
+            None => (
+                None,
+                Some(
+                    self.column_indices
+                        .iter()
+                        .filter_map(move |(name, &column)| {
+                            Some((name.as_ref(), self.columns[column][index].as_ref()?))
+                        }),
+                ),
+            ),
+        };
+        cold.into_iter()
+            .flatten()
+            .chain(stored.into_iter().flatten())
+    }
+
+    /// Returns the previous value if the row already had the field
+    pub fn insert(&mut self, index: usize, name: &str, value: Value) -> Option<Value> {
+        self.thaw(index);
+        self.column_mut(name)[index].replace(value)
+    }
+
+    pub fn remove(&mut self, index: usize, name: &str) -> Option<Value> {
+        self.thaw(index);
+        let &column = self.column_indices.get(name)?;
+        self.columns[column][index].take()
+    }
+
+    /// Adds the field only if the row does not have it (a cold row stays cold if it has the field)
+    pub fn or_insert(&mut self, index: usize, name: &str, value: Value) {
+        if self.get(index, name).is_none() {
+            self.insert(index, name, value);
+        }
+    }
+
+    /// Removes the first `count` rows
+    pub fn drain_front(&mut self, count: usize) {
+        let count = count.min(self.len());
+        for column in self.columns.iter_mut() {
+            column.drain(..count);
+        }
+        self.cold.drain(..count);
+    }
+
+    /// Moves the row at `index` into cold storage
+    pub fn freeze(&mut self, index: usize) {
+        if let Some(cold) = self.cold[index].as_mut() {
+            // Drops the fields if they were parsed again since
+            cold.freeze();
+            return;
+        }
+        let fields: FieldMap = self
+            .column_indices
+            .iter()
+            .filter_map(|(name, &column)| Some((name, self.columns[column][index].take()?)))
+            .collect();
+        let mut fields = RowFields::from(fields);
+        fields.freeze();
+        self.cold[index] = Some(Box::new(fields));
+    }
+
+    /// Moves the row at `index` back into the columns if it is in cold storage
+    pub fn thaw(&mut self, index: usize) {
+        let Some(cold) = self.cold[index].take() else {
+            return;
+        };
+        for (name, value) in cold.into_fields() {
+            self.column_mut(&name)[index] = Some(value);
+        }
+    }
+
+    /// Number of rows in cold storage that were not read since they were frozen
+    pub fn cold_count(&self) -> usize {
+        self.cold.iter().flatten().filter(|x| x.is_cold()).count()
+    }
+
+    /// Names of the fields present in any row
+    pub fn field_names(&self) -> BTreeSet<&str> {
+        let stored = self
+            .column_indices
+            .iter()
+            .filter(|(_, &column)| self.columns[column].iter().any(Option::is_some))
+            .map(|(name, _)| name.as_ref());
+        let cold = self
+            .cold
+            .iter()
+            .flatten()
+            .flat_map(|cold| cold.iter().map(|(name, _)| name));
+        stored.chain(cold).collect()
+    }
+
+    /// Rough number of bytes used on the heap
+    pub fn approximate_size(&self) -> usize {
+        let names = self
+            .column_indices
+            .keys()
+            .map(|name| name.len() + size_of::<(Box<str>, usize)>())
+            .sum::<usize>();
+        let cells = self
+            .columns
+            .iter()
+            .map(|column| {
+                column.capacity() * size_of::<Option<Value>>()
+                    + column.iter().flatten().map(value_size).sum::<usize>()
+            })
+            .sum::<usize>();
+        let cold = self.cold.capacity() * size_of::<Option<Box<RowFields>>>()
+            + self
+                .cold
+                .iter()
+                .flatten()
+                .map(|cold| size_of::<RowFields>() + cold.approximate_size())
+                .sum::<usize>();
+        names + cells + cold
+    }
+
+    /// The column for the name, added if there is none yet
+    fn column_mut(&mut self, name: &str) -> &mut Vec<Option<Value>> {
+        let column = match self.column_indices.get(name) {
+            Some(&column) => column,
+            None => {
+                self.columns.push(vec![None; self.len()]);
+                let column = self.columns.len() - 1;
+                self.column_indices.insert(name.into(), column);
+                column
+            }
+        };
+        &mut self.columns[column]
+    }
+}
+
+impl FromIterator<OwnedLogRow> for Columns {
+    fn from_iter<T: IntoIterator<Item = OwnedLogRow>>(iter: T) -> Self {
+        let mut result = Self::default();
+        for row in iter {
+            result.push(row.data);
+        }
+        result
+    }
+}
+
+impl PartialEq for Columns {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl std::fmt::Debug for Columns {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+/// Same as a list of rows so it does not show how the rows are stored
+impl serde::Serialize for Columns {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Columns {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::<OwnedLogRow>::deserialize(deserializer).map(|rows| rows.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use super::*;
+
+    fn columns(rows: serde_json::Value) -> Columns {
+        serde_json::from_value::<Vec<BTreeMap<String, Value>>>(rows)
+            .unwrap()
+            .into_iter()
+            .fold(Columns::default(), |mut result, row| {
+                result.push(row);
+                result
+            })
+    }
+
+    #[test]
+    fn rows_read_back_from_columns() {
+        let mut columns = columns(json!([{"msg": "a", "n": 1}, {"msg": "b"}, {"x": true}]));
+        assert_eq!(columns.column_indices.len(), 3);
+        assert_eq!(columns.get(1, "msg"), Some(&json!("b")));
+        assert_eq!(columns.get(1, "n"), None);
+        assert_eq!(
+            columns.fields(0).collect::<Vec<_>>(),
+            [("msg", &json!("a")), ("n", &json!(1))]
+        );
+
+        assert_eq!(columns.insert(1, "n", 2.into()), None);
+        columns.or_insert(1, "n", 3.into());
+        assert_eq!(columns.remove(0, "msg"), Some(json!("a")));
+        columns.drain_front(1);
+        assert_eq!(
+            columns
+                .iter()
+                .map(|row| row.to_json_string())
+                .collect::<Vec<_>>(),
+            [r#"{"msg":"b","n":2}"#, r#"{"x":true}"#]
+        );
+        assert_eq!(columns.field_names(), BTreeSet::from(["msg", "n", "x"]));
+    }
+
+    #[test]
+    fn cold_rows_leave_the_columns() {
+        let mut columns = columns(json!([{"msg": "a"}, {"msg": "b", "n": 1}]));
+        let before: Vec<String> = columns.iter().map(|row| row.to_json_string()).collect();
+        columns.freeze(1);
+        assert_eq!(columns.cold_count(), 1);
+        assert_eq!(columns.columns[columns.column_indices["msg"]][1], None);
+        assert_eq!(columns.get(1, "n"), Some(&json!(1)));
+        assert_eq!(columns.cold_count(), 0);
+        assert!(columns.cold[1].is_some());
+        assert_eq!(columns.field_names(), BTreeSet::from(["msg", "n"]));
+
+        // Changing a cold row moves it back into the columns
+        columns.insert(1, "x", true.into());
+        assert!(columns.cold[1].is_none());
+        assert_eq!(columns.get(1, "msg"), Some(&json!("b")));
+        columns.remove(1, "x");
+        assert_eq!(
+            columns
+                .iter()
+                .map(|row| row.to_json_string())
+                .collect::<Vec<_>>(),
+            before
+        );
+    }
+}
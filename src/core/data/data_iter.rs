@@ -13,7 +13,7 @@ impl<'a> DataIter<'a> {
 }
 
 impl<'a> Iterator for DataIter<'a> {
-    type Item = &'a LogRow;
+    type Item = LogRow<'a>;
 
     fn next(&mut self) -> Option<Self::Item> {
         if self.pos >= self.data.len() {
@@ -21,7 +21,7 @@ impl<'a> Iterator for DataIter<'a> {
         }
         let real_index = self.data.get_real_index(self.pos);
         self.pos += 1;
-        Some(&self.data.rows[real_index])
+        self.data.rows.row(real_index)
     }
 
     fn nth(&mut self, n: usize) -> Option<Self::Item> {
@@ -43,13 +43,13 @@ mod tests {
         let row0 = super::super::tests::create_log_row_no_extra();
         let row1 = super::super::tests::create_log_row_with_extra();
         let data = Data {
-            rows: vec![row0.clone(), row1.clone()],
+            rows: [row0.clone(), row1.clone()].into_iter().collect(),
             ..Default::default()
         };
         let mut iter = data.rows_iter();
-        assert_eq!(iter.nth(0), Some(&row0));
+        assert_eq!(iter.nth(0), Some(row0.as_row()));
         assert_eq!(iter.nth(0), None);
-        assert_eq!(iter.nth(1), Some(&row1));
+        assert_eq!(iter.nth(1), Some(row1.as_row()));
         assert_eq!(iter.nth(1), None);
         assert_eq!(iter.next(), None);
     }
@@ -59,12 +59,12 @@ mod tests {
         let row0 = super::super::tests::create_log_row_no_extra();
         let row1 = super::super::tests::create_log_row_with_extra();
         let data = Data {
-            rows: vec![row0.clone(), row1.clone()],
+            rows: [row0.clone(), row1.clone()].into_iter().collect(),
             ..Default::default()
         };
         let mut iter = data.rows_iter();
-        assert_eq!(iter.next(), Some(&row0));
-        assert_eq!(iter.next(), Some(&row1));
+        assert_eq!(iter.next(), Some(row0.as_row()));
+        assert_eq!(iter.next(), Some(row1.as_row()));
         assert_eq!(iter.next(), None);
     }
 }
@@ -0,0 +1,162 @@
+use std::{char::REPLACEMENT_CHARACTER, fmt::Display};
+
+/// Encoding the bytes of the input were decoded from
+#[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TextEncoding {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    /// Guessed when no bytes form multi-byte UTF-8 characters (each byte is one character)
+    Latin1,
+}
+
+/// How the input was converted to text when it was not valid UTF-8 (without a byte order mark)
+#[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Decoding {
+    pub encoding: TextEncoding,
+    /// Number of bytes that were not valid in the encoding and were replaced by U+FFFD
+    pub replaced_bytes: usize,
+}
+
+pub const UTF8_BOM: [u8; 3] = [0xEF, 0xBB, 0xBF];
+const UTF16_LE_BOM: [u8; 2] = [0xFF, 0xFE];
+const UTF16_BE_BOM: [u8; 2] = [0xFE, 0xFF];
+
+/// Converts the bytes to text without failing, invalid bytes are replaced.
+///
+/// The encoding is taken from the byte order mark if there is one. Returns None for the decoding if the bytes were valid UTF-8
+pub fn decode(bytes: Vec<u8>) -> (String, Option<Decoding>) {
+    if let Some(rest) = bytes.strip_prefix(&UTF8_BOM) {
+        let (text, replaced_bytes) = decode_utf8_lossy(rest);
+        return (text, replaced_decoding(TextEncoding::Utf8, replaced_bytes));
+    }
+    if let Some(rest) = bytes.strip_prefix(&UTF16_LE_BOM) {
+        return decode_utf16(rest, TextEncoding::Utf16Le, u16::from_le_bytes);
+    }
+    if let Some(rest) = bytes.strip_prefix(&UTF16_BE_BOM) {
+        return decode_utf16(rest, TextEncoding::Utf16Be, u16::from_be_bytes);
+    }
+    let bytes = match String::from_utf8(bytes) {
+        Ok(text) => return (text, None),
+        Err(e) => e.into_bytes(),
+    };
+    if bytes.utf8_chunks().all(|chunk| chunk.valid().is_ascii()) {
+        let text = bytes.iter().map(|&b| char::from(b)).collect();
+        let decoding = Decoding {
+            encoding: TextEncoding::Latin1,
+            replaced_bytes: 0,
+        };
+        return (text, Some(decoding));
+    }
+    let (text, replaced_bytes) = decode_utf8_lossy(&bytes);
+    (text, replaced_decoding(TextEncoding::Utf8, replaced_bytes))
+}
+
+/// True if the bytes start with a UTF-16 byte order mark (the text cannot be split into lines before it is decoded)
+pub fn has_utf16_bom(bytes: &[u8]) -> bool {
+    bytes.starts_with(&UTF16_LE_BOM) || bytes.starts_with(&UTF16_BE_BOM)
+}
+
+fn replaced_decoding(encoding: TextEncoding, replaced_bytes: usize) -> Option<Decoding> {
+    (replaced_bytes > 0).then_some(Decoding {
+        encoding,
+        replaced_bytes,
+    })
+}
+
+/// Returns the text and the number of bytes replaced
+fn decode_utf8_lossy(bytes: &[u8]) -> (String, usize) {
+    let mut text = String::with_capacity(bytes.len());
+    let mut replaced_bytes = 0;
+    for chunk in bytes.utf8_chunks() {
+        text.push_str(chunk.valid());
+        if !chunk.invalid().is_empty() {
+            text.push(REPLACEMENT_CHARACTER);
+            replaced_bytes += chunk.invalid().len();
+        }
+    }
+    (text, replaced_bytes)
+}
+
+fn decode_utf16(
+    bytes: &[u8],
+    encoding: TextEncoding,
+    to_unit: fn([u8; 2]) -> u16,
+) -> (String, Option<Decoding>) {
+    let mut replaced_bytes = bytes.len() % 2; // Incomplete last unit
+    let units = bytes
+        .chunks_exact(2)
+        .map(|pair| to_unit([pair[0], pair[1]]));
+    let mut text: String = char::decode_utf16(units)
+        .map(|x| {
+            x.unwrap_or_else(|_| {
+                replaced_bytes += 2;
+                REPLACEMENT_CHARACTER
+            })
+        })
+        .collect();
+    if bytes.len() % 2 == 1 {
+        text.push(REPLACEMENT_CHARACTER);
+    }
+    let decoding = Decoding {
+        encoding,
+        replaced_bytes,
+    };
+    (text, Some(decoding))
+}
+
+impl Display for TextEncoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                TextEncoding::Utf8 => "UTF-8",
+                TextEncoding::Utf16Le => "UTF-16LE",
+                TextEncoding::Utf16Be => "UTF-16BE",
+                TextEncoding::Latin1 => "Latin-1",
+            }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+
+    fn decoding(encoding: TextEncoding, replaced_bytes: usize) -> Option<Decoding> {
+        Some(Decoding {
+            encoding,
+            replaced_bytes,
+        })
+    }
+
+    #[rstest]
+    #[case::utf8("{\"msg\": \"café\"}".as_bytes().to_vec(), "{\"msg\": \"café\"}", None)]
+    #[case::utf8_bom(b"\xEF\xBB\xBFabc".to_vec(), "abc", None)]
+    #[case::latin1(b"caf\xE9".to_vec(), "café", decoding(TextEncoding::Latin1, 0))]
+    #[case::bad_bytes_in_utf8(
+        "é\u{1F600}".bytes().chain(*b"\xFF\xFEx").collect(),
+        "é\u{1F600}\u{FFFD}\u{FFFD}x",
+        decoding(TextEncoding::Utf8, 2)
+    )]
+    #[case::utf16_le(b"\xFF\xFEh\0i\0".to_vec(), "hi", decoding(TextEncoding::Utf16Le, 0))]
+    #[case::utf16_be(b"\xFE\xFF\0h\0i".to_vec(), "hi", decoding(TextEncoding::Utf16Be, 0))]
+    #[case::utf16_lone_surrogate_and_odd_byte(
+        b"\xFF\xFE\x00\xD8h\0x".to_vec(),
+        "\u{FFFD}h\u{FFFD}",
+        decoding(TextEncoding::Utf16Le, 3)
+    )]
+    fn decoded(
+        #[case] bytes: Vec<u8>,
+        #[case] expected_text: &str,
+        #[case] expected_decoding: Option<Decoding>,
+    ) {
+        let (text, decoding) = decode(bytes);
+        assert_eq!(text, expected_text);
+        assert_eq!(decoding, expected_decoding);
+    }
+}
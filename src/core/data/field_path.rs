@@ -0,0 +1,88 @@
+use serde_json::Value;
+
+use super::LogRow;
+
+/// Finds the value at a path into nested values (eg. `req.method` or `spans[0].name`).
+///
+/// Returns None if the path is not valid or any part of it is missing
+pub fn resolve<'a>(row: LogRow<'a>, path: &str) -> Option<&'a Value> {
+    if !is_path(path) {
+        return None;
+    }
+    let mut segments = path.split('.');
+    let (name, indices) = parse_segment(segments.next()?)?;
+    let mut value = index_into(row.get(name)?, indices)?;
+    for segment in segments {
+        let (name, indices) = parse_segment(segment)?;
+        value = index_into(value.as_object()?.get(name)?, indices)?;
+    }
+    Some(value)
+}
+
+/// True if the name could be a path (rather than only the name of a field)
+pub fn is_path(name: &str) -> bool {
+    name.contains(['.', '['])
+}
+
+/// The name of the top level field the path starts from
+pub fn root(path: &str) -> &str {
+    path.split(['.', '[']).next().unwrap_or(path)
+}
+
+/// Splits `name[1][2]` into the name and the text of the indices (`[1][2]`)
+fn parse_segment(segment: &str) -> Option<(&str, &str)> {
+    let (name, indices) = segment.split_at(segment.find('[').unwrap_or(segment.len()));
+    (!name.is_empty()).then_some((name, indices))
+}
+
+fn index_into<'a>(mut value: &'a Value, mut indices: &str) -> Option<&'a Value> {
+    while !indices.is_empty() {
+        let (index, rest) = indices.strip_prefix('[')?.split_once(']')?;
+        value = value.as_array()?.get(index.trim().parse::<usize>().ok()?)?;
+        indices = rest;
+    }
+    Some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+    use serde_json::json;
+    use std::collections::BTreeMap;
+
+    use super::super::OwnedLogRow;
+    use super::*;
+
+    #[rstest]
+    #[case("req.method", Some(json!("GET")))]
+    #[case("res.statusCode", Some(json!(200)))]
+    #[case("spans[0].name", Some(json!("db")))]
+    #[case("spans[1].tags[0]", Some(json!("slow")))]
+    #[case("spans[2].name", None)]
+    #[case("req.missing", None)]
+    #[case("req[0]", None)]
+    #[case("spans[x]", None)]
+    #[case("spans[0", None)]
+    #[case("req..method", None)]
+    #[case("req", None)]
+    fn resolve_paths(#[case] path: &str, #[case] expected: Option<Value>) {
+        let fields: BTreeMap<String, Value> = serde_json::from_value(json!({
+            "req": {"method": "GET"},
+            "res": {"statusCode": 200},
+            "spans": [{"name": "db"}, {"name": "http", "tags": ["slow"]}],
+        }))
+        .unwrap();
+        let row = OwnedLogRow {
+            data: fields.into(),
+        };
+        assert_eq!(resolve(row.as_row(), path), expected.as_ref());
+    }
+
+    #[test]
+    fn root_of_path() {
+        assert_eq!(root("req.method"), "req");
+        assert_eq!(root("spans[0].name"), "spans");
+        assert_eq!(root("msg"), "msg");
+    }
+}
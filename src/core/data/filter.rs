@@ -0,0 +1,344 @@
+use std::{borrow::Cow, collections::BTreeSet, fmt::Display};
+
+use super::FieldContent;
+
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize, PartialEq, Eq, Clone)]
+#[serde(default)]
+pub struct FilterConfig {
+    pub search_key: String,
+    pub filter_on: FilterOn,
+    pub is_case_sensitive: bool,
+    pub comparator: Comparator,
+}
+
+impl FilterConfig {
+    /// True if every row matching `self` also matches `other` because the search key was refined
+    /// (so only rows matching `other` need to be checked). For example characters added to a contains search key
+    pub fn is_narrowing_of(&self, other: &Self) -> bool {
+        if self.comparator != other.comparator
+            || self.filter_on != other.filter_on
+            || self.is_case_sensitive != other.is_case_sensitive
+        {
+            return false;
+        }
+        let (key, other_key) = if self.is_case_sensitive {
+            (
+                Cow::Borrowed(self.search_key.as_str()),
+                Cow::Borrowed(other.search_key.as_str()),
+            )
+        } else {
+            (
+                Cow::Owned(self.search_key.to_lowercase()),
+                Cow::Owned(other.search_key.to_lowercase()),
+            )
+        };
+        match self.comparator {
+            Comparator::Contains => key.contains(other_key.as_ref()),
+            Comparator::NotContains => other_key.contains(key.as_ref()),
+            // Values containing the characters of the key in order also contain any subsequence of them
+            Comparator::Fuzzy => is_fuzzy_match(&other_key, &key),
+            Comparator::Equal
+            | Comparator::NotEqual
+            | Comparator::LessThan
+            | Comparator::LessThanEqual
+            | Comparator::GreaterThan
+            | Comparator::GreaterThanEqual
+            | Comparator::Template => key == other_key,
+            Comparator::IsPresent | Comparator::IsMissing => true,
+        }
+    }
+}
+
+/// Limits rows to those where the numeric value of the field falls within the range (inclusive)
+#[derive(Debug, serde::Serialize, serde::Deserialize, PartialEq, Clone)]
+pub struct RangeFilter {
+    pub field_name: String,
+    pub min: f64,
+    pub max: f64,
+}
+
+impl RangeFilter {
+    /// Rows without a numeric value for the field never match
+    pub fn matches(&self, value: Option<f64>) -> bool {
+        value.is_some_and(|x| self.min <= x && x <= self.max)
+    }
+}
+
+/// Quick severity filter, hides rows with the chosen levels (rows without the field are always shown)
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize, PartialEq, Eq, Clone)]
+#[serde(default)]
+pub struct LevelFilter {
+    pub field_name: String,
+    pub hidden_levels: BTreeSet<String>,
+    /// Levels less severe than the minimum level (kept separate so toggling levels does not change the minimum)
+    pub below_min: BTreeSet<String>,
+}
+
+impl LevelFilter {
+    pub fn is_empty(&self) -> bool {
+        self.hidden_levels.is_empty() && self.below_min.is_empty()
+    }
+
+    pub fn matches(&self, level: FieldContent<'_>) -> bool {
+        match level {
+            FieldContent::Present(_) => {
+                if self.is_empty() {
+                    return true;
+                }
+                let level = level.display_str();
+                !self.hidden_levels.contains(level.as_ref())
+                    && !self.below_min.contains(level.as_ref())
+            }
+            FieldContent::Missing => true,
+        }
+    }
+}
+
+/// Rows matching the filter are excluded from view regardless of the main filter
+#[derive(Debug, serde::Serialize, serde::Deserialize, PartialEq, Eq, Clone)]
+pub struct IgnoreRule {
+    pub filter: FilterConfig,
+    /// Allows temporarily turning the rule off without losing it
+    pub is_enabled: bool,
+}
+
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize, PartialEq, Eq, Clone)]
+pub enum FilterOn {
+    #[default]
+    Any,
+    Field(FieldSpecifier),
+    /// Matches if any of the fields match
+    Fields(Vec<FieldSpecifier>),
+}
+
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize, PartialEq, Eq, Clone)]
+pub struct FieldSpecifier {
+    pub name: String,
+}
+
+impl FilterOn {
+    /// Returns `true` if the filter on is [`Any`].
+    ///
+    /// [`Any`]: FilterOn::Any
+    #[must_use]
+    pub fn is_any(&self) -> bool {
+        matches!(self, Self::Any)
+    }
+
+    /// Returns `true` if the filter on is [`Field`].
+    ///
+    /// [`Field`]: FilterOn::Field
+    #[must_use]
+    pub fn is_field(&self) -> bool {
+        matches!(self, Self::Field { .. })
+    }
+
+    /// Returns `true` if the filter on is [`Fields`].
+    ///
+    /// [`Fields`]: FilterOn::Fields
+    #[must_use]
+    pub fn is_fields(&self) -> bool {
+        matches!(self, Self::Fields { .. })
+    }
+
+    /// The names of the fields to check or None if all fields are checked
+    pub fn field_names(&self) -> Option<Vec<&str>> {
+        match self {
+            FilterOn::Any => None,
+            FilterOn::Field(FieldSpecifier { name }) => Some(vec![name]),
+            FilterOn::Fields(fields) => Some(fields.iter().map(|x| x.name.as_str()).collect()),
+        }
+    }
+}
+
+#[cfg_attr(test, derive(strum::EnumIter))]
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize, PartialEq, Eq, Clone, Copy)]
+pub enum Comparator {
+    LessThan,
+    LessThanEqual,
+    Equal,
+    GreaterThan,
+    GreaterThanEqual,
+    NotEqual,
+    #[default]
+    Contains,
+    NotContains,
+    /// The characters of the search key appear in order but not necessarily together (like fzf)
+    Fuzzy,
+    /// The field exists in the row (whatever the value). The search key is ignored
+    IsPresent,
+    /// The field does not exist in the row. The search key is ignored
+    IsMissing,
+    /// The value has the search key as its template (see [`super::template::message_template`])
+    Template,
+}
+
+impl Comparator {
+    pub const ALL: [Comparator; 12] = [
+        Self::LessThan,
+        Self::LessThanEqual,
+        Self::Equal,
+        Self::GreaterThan,
+        Self::GreaterThanEqual,
+        Self::NotEqual,
+        Self::Contains,
+        Self::NotContains,
+        Self::Fuzzy,
+        Self::IsPresent,
+        Self::IsMissing,
+        Self::Template,
+    ];
+
+    /// Returns `true` if only the presence of the field is checked
+    #[must_use]
+    pub fn is_presence_check(&self) -> bool {
+        matches!(self, Self::IsPresent | Self::IsMissing)
+    }
+
+    /// Compares a value that is present (see [`Self::is_presence_check`] for the comparators that check for missing values)
+    pub fn apply(&self, search_key: &str, value: &str) -> bool {
+        match self {
+            Comparator::LessThan => value < search_key,
+            Comparator::LessThanEqual => value <= search_key,
+            Comparator::Equal => value == search_key,
+            Comparator::GreaterThan => value > search_key,
+            Comparator::GreaterThanEqual => value >= search_key,
+            Comparator::NotEqual => value != search_key,
+            Comparator::Contains => value.contains(search_key),
+            Comparator::NotContains => !value.contains(search_key),
+            Comparator::Fuzzy => is_fuzzy_match(search_key, value),
+            Comparator::IsPresent => true,
+            Comparator::IsMissing => false,
+            Comparator::Template => super::template::message_template(value) == search_key,
+        }
+    }
+
+    /// Compares as numbers for the ordering and equality comparators, None for the others
+    pub fn apply_numeric(&self, search_key: f64, value: f64) -> Option<bool> {
+        Some(match self {
+            Comparator::LessThan => value < search_key,
+            Comparator::LessThanEqual => value <= search_key,
+            Comparator::Equal => value == search_key,
+            Comparator::GreaterThan => value > search_key,
+            Comparator::GreaterThanEqual => value >= search_key,
+            Comparator::NotEqual => value != search_key,
+            _ => return None,
+        })
+    }
+}
+
+/// True if the characters of `search_key` (ignoring whitespace) are a subsequence of `value`
+fn is_fuzzy_match(search_key: &str, value: &str) -> bool {
+    let mut value_chars = value.chars();
+    search_key
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .all(|c| value_chars.any(|x| x == c))
+}
+
+impl Display for Comparator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Comparator::LessThan => "Less Than",
+                Comparator::LessThanEqual => "Less than equal",
+                Comparator::Equal => "Equal",
+                Comparator::GreaterThan => "Greater than",
+                Comparator::GreaterThanEqual => "Greater than equal",
+                Comparator::NotEqual => "Not equal",
+                Comparator::Contains => "Contains",
+                Comparator::NotContains => "Not contains",
+                Comparator::Fuzzy => "Fuzzy",
+                Comparator::IsPresent => "Is present",
+                Comparator::IsMissing => "Is missing",
+                Comparator::Template => "Template",
+            }
+        )
+    }
+}
+
+impl Display for FilterConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Self {
+            search_key,
+            filter_on,
+            is_case_sensitive,
+            comparator,
+        } = self;
+        write!(
+            f,
+            "Search Key: {search_key} | Filter On: {filter_on} | Case Sensitive: {} | Comparator: {comparator}",
+            if *is_case_sensitive { "Yes" } else { "No" }
+        )
+    }
+}
+
+impl Display for FilterOn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FilterOn::Any => write!(f, "Any"),
+            FilterOn::Field(name) => write!(f, "[Field Named: {name}]"),
+            FilterOn::Fields(fields) => {
+                let names: Vec<&str> = fields.iter().map(|x| x.name.as_str()).collect();
+                write!(f, "[Fields Named: {}]", names.join(", "))
+            }
+        }
+    }
+}
+
+impl Display for FieldSpecifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.name.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("conn", Comparator::Contains, "connection", true)]
+    #[case("CONN", Comparator::Contains, "Connection", true)]
+    #[case("conn", Comparator::Fuzzy, "conn refused", true)]
+    #[case("conn", Comparator::Fuzzy, "coxnn", true)]
+    #[case("conn", Comparator::Fuzzy, "cnno", false)]
+    #[case("conn", Comparator::Contains, "con", false)]
+    #[case("conn", Comparator::Contains, "disconnect", true)]
+    #[case("conn", Comparator::Contains, "cxonn", false)]
+    #[case("conn", Comparator::NotContains, "connection", false)]
+    #[case("connection", Comparator::NotContains, "conn", true)]
+    #[case("conn", Comparator::Equal, "connection", false)]
+    #[case("conn", Comparator::Equal, "conn", true)]
+    fn narrowing(
+        #[case] old_key: &str,
+        #[case] comparator: Comparator,
+        #[case] new_key: &str,
+        #[case] expected: bool,
+    ) {
+        let old = FilterConfig {
+            search_key: old_key.into(),
+            comparator,
+            ..Default::default()
+        };
+        let new = FilterConfig {
+            search_key: new_key.into(),
+            ..old.clone()
+        };
+        assert_eq!(new.is_narrowing_of(&old), expected);
+    }
+
+    #[rstest]
+    #[case("conrefused", "connection refused", true)]
+    #[case("conn ref", "connection refused", true)]
+    #[case("", "anything", true)]
+    #[case("refcon", "connection refused", false)]
+    #[case("connn", "connection", true)]
+    #[case("connnn", "connection", false)]
+    fn fuzzy(#[case] search_key: &str, #[case] value: &str, #[case] expected: bool) {
+        assert_eq!(Comparator::Fuzzy.apply(search_key, value), expected);
+    }
+}
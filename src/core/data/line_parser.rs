@@ -0,0 +1,306 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use anyhow::{bail, Context};
+use serde_json::Value;
+
+use super::{ci_log::CiLogParser, tracing_json::TracingJsonParser};
+use crate::core::data_display_options::{DataDisplayOptions, LogFormat};
+
+/// Converts lines of the input into the fields of rows.
+///
+/// A new parser is created for each load and sees the lines in order so it may keep state between lines (eg. headers)
+pub trait LineParser: Send {
+    /// Returns None if the line does not produce a row (eg. a header) and an error if it is not in the expected format
+    fn parse_line(&mut self, line: &str) -> anyhow::Result<Option<BTreeMap<String, Value>>>;
+}
+
+/// Creates a parser for a custom format
+pub type LineParserFactory = Arc<dyn Fn() -> Box<dyn LineParser> + Send + Sync>;
+
+/// Parsers added by users of the library, selected with [`LogFormat::Custom`] using the name they were registered with
+#[derive(Default, Clone)]
+pub struct ParserRegistry {
+    factories: BTreeMap<String, LineParserFactory>,
+}
+
+impl ParserRegistry {
+    /// Replaces any parser already registered with the same name
+    pub fn register(&mut self, name: String, factory: LineParserFactory) {
+        self.factories.insert(name, factory);
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.factories.keys().map(String::as_str)
+    }
+}
+
+impl std::fmt::Debug for ParserRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.names()).finish()
+    }
+}
+
+impl PartialEq for ParserRegistry {
+    fn eq(&self, other: &Self) -> bool {
+        self.names().eq(other.names())
+    }
+}
+
+impl Eq for ParserRegistry {}
+
+/// Creates the parser for the format selected in the options
+pub fn new_line_parser(
+    data_display_options: &DataDisplayOptions,
+) -> anyhow::Result<Box<dyn LineParser>> {
+    Ok(match &data_display_options.log_format {
+        LogFormat::Json => Box::new(JsonParser),
+        LogFormat::TracingJson => Box::new(TracingJsonParser),
+        LogFormat::Logfmt => Box::new(LogfmtParser),
+        LogFormat::Regex => Box::new(RegexParser::new(&data_display_options.regex_pattern)?),
+        LogFormat::Csv => Box::<CsvParser>::default(),
+        LogFormat::CiRunner => Box::<CiLogParser>::default(),
+        LogFormat::Custom(name) => match data_display_options.parsers.factories.get(name) {
+            Some(factory) => factory(),
+            None => bail!("no parser registered with the name {name:?}"),
+        },
+    })
+}
+
+/// Each line is a JSON object (eg. bunyan)
+pub struct JsonParser;
+
+impl LineParser for JsonParser {
+    fn parse_line(&mut self, line: &str) -> anyhow::Result<Option<BTreeMap<String, Value>>> {
+        Ok(Some(serde_json::from_str(line)?))
+    }
+}
+
+/// Lines of `key=value` pairs separated by spaces where values with spaces are quoted (eg. `msg="hello world"`)
+pub struct LogfmtParser;
+
+impl LineParser for LogfmtParser {
+    fn parse_line(&mut self, line: &str) -> anyhow::Result<Option<BTreeMap<String, Value>>> {
+        let mut result = BTreeMap::new();
+        let mut chars = line.trim().chars().peekable();
+        loop {
+            while chars.next_if(|c| c.is_whitespace()).is_some() {}
+            if chars.peek().is_none() {
+                break;
+            }
+            let key: String =
+                std::iter::from_fn(|| chars.next_if(|&c| c != '=' && !c.is_whitespace())).collect();
+            if chars.next_if_eq(&'=').is_none() {
+                // A key on its own is a flag
+                result.insert(key, Value::Bool(true));
+                continue;
+            }
+            let value = if chars.next_if_eq(&'"').is_some() {
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => value.extend(chars.next()),
+                        Some(c) => value.push(c),
+                        None => bail!("unterminated quoted value for key {key:?}"),
+                    }
+                }
+                Value::String(value)
+            } else {
+                let value: String =
+                    std::iter::from_fn(|| chars.next_if(|c| !c.is_whitespace())).collect();
+                typed_value(&value)
+            };
+            if key.is_empty() {
+                bail!("value without a key");
+            }
+            result.insert(key, value);
+        }
+        Ok(Some(result))
+    }
+}
+
+/// Fields are taken from the named capture groups of a regular expression (eg. `(?P<time>\S+) (?P<msg>.*)`)
+pub struct RegexParser {
+    regex: regex::Regex,
+}
+
+impl RegexParser {
+    pub fn new(pattern: &str) -> anyhow::Result<Self> {
+        let regex = regex::Regex::new(pattern).context("invalid line format pattern")?;
+        if regex.capture_names().flatten().next().is_none() {
+            bail!("line format pattern has no named groups (eg. (?P<msg>.*))");
+        }
+        Ok(Self { regex })
+    }
+}
+
+impl LineParser for RegexParser {
+    fn parse_line(&mut self, line: &str) -> anyhow::Result<Option<BTreeMap<String, Value>>> {
+        let Some(captures) = self.regex.captures(line) else {
+            bail!("line does not match the line format pattern");
+        };
+        Ok(Some(
+            self.regex
+                .capture_names()
+                .flatten()
+                .filter_map(|name| {
+                    Some((name.to_string(), typed_value(captures.name(name)?.as_str())))
+                })
+                .collect(),
+        ))
+    }
+}
+
+/// Comma separated values where the first line has the field names
+#[derive(Default)]
+pub struct CsvParser {
+    headers: Option<Vec<String>>,
+}
+
+impl LineParser for CsvParser {
+    fn parse_line(&mut self, line: &str) -> anyhow::Result<Option<BTreeMap<String, Value>>> {
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .from_reader(line.as_bytes());
+        let record = match reader.records().next() {
+            Some(record) => record?,
+            None => return Ok(None), // Empty line
+        };
+        let Some(headers) = self.headers.as_ref() else {
+            self.headers = Some(record.iter().map(String::from).collect());
+            return Ok(None);
+        };
+        Ok(Some(
+            record
+                .iter()
+                .enumerate()
+                .map(|(i, value)| {
+                    let name = headers
+                        .get(i)
+                        .cloned()
+                        .unwrap_or_else(|| format!("column_{}", i + 1));
+                    (name, typed_value(value))
+                })
+                .collect(),
+        ))
+    }
+}
+
+impl LineParser for TracingJsonParser {
+    fn parse_line(&mut self, line: &str) -> anyhow::Result<Option<BTreeMap<String, Value>>> {
+        Ok(Some(TracingJsonParser::parse_line(self, line)?))
+    }
+}
+
+impl LineParser for CiLogParser {
+    fn parse_line(&mut self, line: &str) -> anyhow::Result<Option<BTreeMap<String, Value>>> {
+        Ok(Some(CiLogParser::parse_line(self, line)))
+    }
+}
+
+/// Text formats have no types so numbers and booleans are recognized to allow numeric filters and level conversion
+fn typed_value(text: &str) -> Value {
+    if let Ok(x) = text.parse::<i64>() {
+        return x.into();
+    }
+    if let Some(x) = text
+        .parse::<f64>()
+        .ok()
+        .and_then(serde_json::Number::from_f64)
+    {
+        return Value::Number(x);
+    }
+    match text {
+        "true" => true.into(),
+        "false" => false.into(),
+        _ => text.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use super::*;
+
+    fn parse_all(parser: &mut dyn LineParser, lines: &[&str]) -> Vec<Value> {
+        lines
+            .iter()
+            .filter_map(|line| parser.parse_line(line).unwrap())
+            .map(|fields| serde_json::to_value(fields).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn logfmt() {
+        let actual = parse_all(
+            &mut LogfmtParser,
+            &[r#"level=30 msg="hello \"world\"" ms=1.5 ok=true debug"#],
+        );
+        assert_eq!(
+            actual,
+            vec![
+                json!({"level": 30, "msg": "hello \"world\"", "ms": 1.5, "ok": true, "debug": true})
+            ]
+        );
+        assert!(LogfmtParser.parse_line(r#"msg="open"#).is_err());
+    }
+
+    #[test]
+    fn regex() {
+        let mut parser =
+            RegexParser::new(r"^(?P<time>\S+) \[(?P<level_str>\w+)\] (?P<msg>.*)$").unwrap();
+        let actual = parse_all(&mut parser, &["2024-01-01T00:00:00Z [Warn] disk at 90%"]);
+        assert_eq!(
+            actual,
+            vec![
+                json!({"time": "2024-01-01T00:00:00Z", "level_str": "Warn", "msg": "disk at 90%"})
+            ]
+        );
+        assert!(parser.parse_line("no match").is_err());
+        assert!(RegexParser::new(r"\S+").is_err());
+    }
+
+    #[test]
+    fn csv_uses_first_line_as_headers() {
+        let actual = parse_all(
+            &mut CsvParser::default(),
+            &["time,msg", r#"1,"a, b""#, "2,c,extra"],
+        );
+        assert_eq!(
+            actual,
+            vec![
+                json!({"time": 1, "msg": "a, b"}),
+                json!({"time": 2, "msg": "c", "column_3": "extra"}),
+            ]
+        );
+    }
+
+    #[test]
+    fn custom_parser_from_registry() {
+        struct Upper;
+        impl LineParser for Upper {
+            fn parse_line(
+                &mut self,
+                line: &str,
+            ) -> anyhow::Result<Option<BTreeMap<String, Value>>> {
+                Ok(Some(
+                    [("msg".to_string(), line.to_uppercase().into())].into(),
+                ))
+            }
+        }
+        let mut options = DataDisplayOptions::default();
+        options.log_format = LogFormat::Custom("upper".into());
+        assert!(new_line_parser(&options).is_err());
+
+        options
+            .parsers
+            .register("upper".into(), Arc::new(|| Box::new(Upper)));
+        let mut parser = new_line_parser(&options).unwrap();
+        assert_eq!(
+            parse_all(parser.as_mut(), &["hi"]),
+            vec![json!({"msg": "HI"})]
+        );
+    }
+}
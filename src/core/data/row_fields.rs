@@ -0,0 +1,301 @@
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashSet},
+    ops::{Deref, DerefMut},
+    sync::{Arc, Mutex, OnceLock, PoisonError},
+};
+
+use serde_json::Value;
+
+/// The fields of a row, which can be moved into cold storage to save memory.
+///
+/// In cold storage only the fields serialized as a JSON string are kept. Accessing the fields parses them again
+/// transparently so callers do not need to know if a row is cold.
+pub struct RowFields {
+    /// Empty only if the row is cold (`raw` is then set)
+    fields: OnceLock<FieldMap>,
+    /// The fields as JSON, kept after the fields are parsed again so freezing again is cheap
+    raw: Option<Box<str>>,
+}
+
+impl RowFields {
+    /// Moves the fields into cold storage
+    pub fn freeze(&mut self) {
+        let Some(fields) = self.fields.take() else {
+            return; // Already cold
+        };
+        if self.raw.is_none() {
+            let raw = serde_json::to_string(&fields)
+                .expect("map with string keys should always serialize");
+            self.raw = Some(raw.into_boxed_str());
+        }
+    }
+
+    pub fn is_cold(&self) -> bool {
+        self.fields.get().is_none()
+    }
+
+    /// Rough number of bytes used by the row on the heap (does not thaw a cold row).
+    ///
+    /// Names are shared between rows so they are not counted
+    pub fn approximate_size(&self) -> usize {
+        let raw = self.raw.as_ref().map_or(0, |x| x.len());
+        let fields = self.fields.get().map_or(0, |fields| {
+            fields.0.capacity() * size_of::<(Arc<str>, Value)>()
+                + fields
+                    .0
+                    .iter()
+                    .map(|(_, value)| value_size(value))
+                    .sum::<usize>()
+        });
+        raw + fields
+    }
+
+    /// The fields (parsed again if the row is cold)
+    pub fn into_fields(mut self) -> FieldMap {
+        let _ = self.deref(); // Ensure the fields are available
+        self.fields.take().expect("initialized by deref just above")
+    }
+}
+
+/// Rough number of bytes used by the value on the heap
+pub(super) fn value_size(value: &Value) -> usize {
+    match value {
+        Value::Null | Value::Bool(_) | Value::Number(_) => 0,
+        Value::String(s) => s.capacity(),
+        Value::Array(values) => {
+            values.capacity() * size_of::<Value>() + values.iter().map(value_size).sum::<usize>()
+        }
+        Value::Object(map) => map
+            .iter()
+            .map(|(k, v)| k.capacity() + size_of::<Value>() + value_size(v))
+            .sum(),
+    }
+}
+
+impl Deref for RowFields {
+    type Target = FieldMap;
+
+    fn deref(&self) -> &Self::Target {
+        self.fields.get_or_init(|| {
+            let raw = self
+                .raw
+                .as_deref()
+                .expect("raw should be set when fields are not");
+            serde_json::from_str::<BTreeMap<String, Value>>(raw)
+                .expect("should be valid as we serialized it")
+                .into()
+        })
+    }
+}
+
+impl DerefMut for RowFields {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        let _ = self.deref(); // Ensure the fields are available
+        self.raw = None; // Would be out of date after the change
+        self.fields
+            .get_mut()
+            .expect("initialized by deref just above")
+    }
+}
+
+impl From<FieldMap> for RowFields {
+    fn from(value: FieldMap) -> Self {
+        Self {
+            fields: OnceLock::from(value),
+            raw: None,
+        }
+    }
+}
+
+impl From<BTreeMap<String, Value>> for RowFields {
+    fn from(value: BTreeMap<String, Value>) -> Self {
+        FieldMap::from(value).into()
+    }
+}
+
+/// The fields of a row sorted by name.
+///
+/// Most rows repeat the same field names so the names are shared between rows (see [`intern`]) and each row only
+/// holds a pointer to each name. Kept in a vector instead of a map as rows have few fields
+#[derive(Default, Clone, PartialEq, Eq)]
+pub struct FieldMap(Vec<(Arc<str>, Value)>);
+
+impl FieldMap {
+    fn position(&self, name: &str) -> Result<usize, usize> {
+        self.0.binary_search_by(|(x, _)| x.as_ref().cmp(name))
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.position(name).ok().map(|i| &self.0[i].1)
+    }
+
+    /// The fields in order of their names
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Value)> {
+        self.0.iter().map(|(name, value)| (name.as_ref(), value))
+    }
+
+    /// Returns the previous value if there was already a field with the name
+    pub fn insert(&mut self, name: &str, value: Value) -> Option<Value> {
+        match self.position(name) {
+            Ok(i) => Some(std::mem::replace(&mut self.0[i].1, value)),
+            Err(i) => {
+                self.0.insert(i, (intern(name), value));
+                None
+            }
+        }
+    }
+
+    /// Adds the field only if there is no field with the name
+    pub fn or_insert(&mut self, name: &str, value: Value) {
+        if let Err(i) = self.position(name) {
+            self.0.insert(i, (intern(name), value));
+        }
+    }
+}
+
+impl From<BTreeMap<String, Value>> for FieldMap {
+    fn from(value: BTreeMap<String, Value>) -> Self {
+        // Already sorted by name
+        Self(
+            value
+                .into_iter()
+                .map(|(name, value)| (intern(&name), value))
+                .collect(),
+        )
+    }
+}
+
+/// Names do not need to be in order
+impl<N: AsRef<str>> FromIterator<(N, Value)> for FieldMap {
+    fn from_iter<T: IntoIterator<Item = (N, Value)>>(iter: T) -> Self {
+        let mut result: Vec<(Arc<str>, Value)> = iter
+            .into_iter()
+            .map(|(name, value)| (intern(name.as_ref()), value))
+            .collect();
+        result.sort_by(|(a, _), (b, _)| a.cmp(b));
+        Self(result)
+    }
+}
+
+impl IntoIterator for FieldMap {
+    type Item = (Arc<str>, Value);
+    type IntoIter = std::vec::IntoIter<(Arc<str>, Value)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl std::fmt::Debug for FieldMap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl serde::Serialize for FieldMap {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.iter())
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for FieldMap {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        BTreeMap::deserialize(deserializer).map(Self::from)
+    }
+}
+
+/// Returns the shared copy of the name.
+///
+/// Names are kept until the program exits, this is fine as logs have few distinct field names. Each thread keeps the
+/// names it has seen so the lock on the names shared between threads is only taken for names new to the thread
+fn intern(name: &str) -> Arc<str> {
+    thread_local! {
+        static SEEN: RefCell<HashSet<Arc<str>>> = RefCell::default();
+    }
+    SEEN.with_borrow_mut(|seen| {
+        if let Some(existing) = seen.get(name) {
+            return Arc::clone(existing);
+        }
+        let result = intern_shared(name);
+        seen.insert(Arc::clone(&result));
+        result
+    })
+}
+
+fn intern_shared(name: &str) -> Arc<str> {
+    static NAMES: OnceLock<Mutex<HashSet<Arc<str>>>> = OnceLock::new();
+    let mut names = NAMES
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    if let Some(existing) = names.get(name) {
+        return Arc::clone(existing);
+    }
+    let result: Arc<str> = name.into();
+    names.insert(Arc::clone(&result));
+    result
+}
+
+impl std::fmt::Debug for RowFields {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Same as the map so whether the row is cold does not show
+        self.deref().fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn frozen_fields_read_back_the_same() {
+        let map: BTreeMap<String, Value> =
+            serde_json::from_value(json!({"msg": "hi", "n": 1.5, "obj": {"a": [1, 2]}})).unwrap();
+        let mut fields = RowFields::from(map.clone());
+        fields.freeze();
+        assert!(fields.is_cold());
+        assert_eq!(*fields, FieldMap::from(map));
+        assert!(!fields.is_cold());
+
+        // Changes are kept when frozen again
+        fields.insert("added", true.into());
+        fields.freeze();
+        assert_eq!(fields.get("added"), Some(&Value::Bool(true)));
+    }
+
+    #[test]
+    fn approximate_size_counts_cold_rows_without_thawing() {
+        let map: BTreeMap<String, Value> =
+            serde_json::from_value(json!({"msg": "hello world"})).unwrap();
+        let mut fields = RowFields::from(map);
+        assert!(fields.approximate_size() >= "hello world".len());
+        fields.freeze();
+        assert_eq!(fields.approximate_size(), r#"{"msg":"hello world"}"#.len());
+        assert!(fields.is_cold());
+    }
+
+    #[test]
+    fn names_shared_between_rows() {
+        let row = |value: i32| {
+            let map: BTreeMap<String, Value> =
+                serde_json::from_value(json!({"msg": "hi", "n": value})).unwrap();
+            FieldMap::from(map)
+        };
+        let (mut a, b) = (row(1), row(2));
+        assert!(Arc::ptr_eq(&a.0[0].0, &b.0[0].0));
+        let from_other_thread = std::thread::spawn(|| intern("msg")).join().unwrap();
+        assert!(Arc::ptr_eq(&a.0[0].0, &from_other_thread));
+
+        a.insert("m", 1.into());
+        a.or_insert("n", 5.into());
+        assert_eq!(
+            a.iter().map(|(name, _)| name).collect::<Vec<_>>(),
+            ["m", "msg", "n"]
+        );
+        assert_eq!(a.get("n"), Some(&Value::from(1)));
+    }
+}
@@ -0,0 +1,119 @@
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, Context};
+use rhai::{Dynamic, Engine, Scope, AST};
+use serde_json::Value;
+
+/// Name of the variable that holds the fields of the row in the script
+const ROW_VARIABLE: &str = "row";
+
+/// Limits the work done per row so a script with an infinite loop does not hang loading
+const MAX_OPERATIONS: u64 = 100_000;
+
+/// User script (rhai) run on the fields of each row as it is parsed (eg. to rename fields, derive values or drop rows).
+///
+/// The fields are in the map `row`, changes made to it are kept. The row is dropped if the script evaluates to `false`
+pub struct RowScript {
+    source: String,
+    engine: Engine,
+    ast: AST,
+}
+
+impl RowScript {
+    pub fn new(source: &str) -> anyhow::Result<Self> {
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+        let ast = engine
+            .compile(source)
+            .context("failed to compile row script")?;
+        Ok(Self {
+            source: source.to_string(),
+            engine,
+            ast,
+        })
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Returns the fields after the script ran or None if the row should be dropped
+    pub fn apply(
+        &self,
+        fields: BTreeMap<String, Value>,
+    ) -> anyhow::Result<Option<BTreeMap<String, Value>>> {
+        let mut scope = Scope::new();
+        scope.push_dynamic(ROW_VARIABLE, rhai::serde::to_dynamic(fields)?);
+        let result: Dynamic = self
+            .engine
+            .eval_ast_with_scope(&mut scope, &self.ast)
+            .map_err(|e| anyhow!("row script failed: {e}"))?;
+        if result.as_bool() == Ok(false) {
+            return Ok(None);
+        }
+        let row: Dynamic = scope
+            .get_value(ROW_VARIABLE)
+            .context("row script removed the row variable")?;
+        let fields = rhai::serde::from_dynamic(&row)
+            .map_err(|e| anyhow!("row script left row as something other than a map: {e}"))?;
+        Ok(Some(fields))
+    }
+}
+
+impl std::fmt::Debug for RowScript {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RowScript")
+            .field("source", &self.source)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for RowScript {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use super::*;
+
+    fn fields(value: Value) -> BTreeMap<String, Value> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn fields_changed_and_rows_dropped() {
+        let script = RowScript::new(
+            r#"
+            if row.msg == "noise" { return false; }
+            row.message = row.msg;
+            row.remove("msg");
+            row["duration.s"] = row.ms / 1000.0;
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            script
+                .apply(fields(json!({"msg": "hi", "ms": 1500.0})))
+                .unwrap(),
+            Some(fields(
+                json!({"message": "hi", "ms": 1500.0, "duration.s": 1.5})
+            ))
+        );
+        assert_eq!(
+            script
+                .apply(fields(json!({"msg": "noise", "ms": 1.0})))
+                .unwrap(),
+            None
+        );
+        assert!(RowScript::new("row.msg = ").is_err());
+        assert!(RowScript::new("loop {}")
+            .unwrap()
+            .apply(fields(json!({})))
+            .is_err());
+    }
+}
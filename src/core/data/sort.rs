@@ -0,0 +1,125 @@
+use std::{cmp::Ordering, fmt::Display};
+
+use chrono::{DateTime, FixedOffset};
+
+use super::{columns::Columns, FieldContent, LogRow};
+
+/// Order to show the visible rows in instead of file order.
+///
+/// Rows are compared by each key in turn, rows that are equal on all keys stay in file order
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize, PartialEq, Eq, Clone)]
+#[serde(default)]
+pub struct SortConfig {
+    pub keys: Vec<SortKey>,
+}
+
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize, PartialEq, Eq, Clone)]
+#[serde(default)]
+pub struct SortKey {
+    pub field_name: String,
+    pub is_descending: bool,
+    pub compare_as: SortCompare,
+}
+
+/// How the values of the field are compared. Rows without a value of the type always sort last
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize, PartialEq, Eq, Clone, Copy)]
+pub enum SortCompare {
+    #[default]
+    Text,
+    Numeric,
+    /// RFC 3339 timestamps compared as instants so different offsets sort correctly
+    Time,
+}
+
+impl SortCompare {
+    pub const ALL: [SortCompare; 3] = [Self::Text, Self::Numeric, Self::Time];
+}
+
+#[derive(Debug, PartialEq, PartialOrd)]
+enum SortValue {
+    Text(String),
+    Number(f64),
+    Time(DateTime<FixedOffset>),
+}
+
+impl SortKey {
+    fn value(&self, row: LogRow<'_>) -> Option<SortValue> {
+        let content = row.field_value(&self.field_name);
+        if content == FieldContent::Missing {
+            return None;
+        }
+        match self.compare_as {
+            SortCompare::Text => Some(SortValue::Text(content.display())),
+            SortCompare::Numeric => content.as_f64().map(SortValue::Number),
+            SortCompare::Time => content.as_time().map(SortValue::Time),
+        }
+    }
+}
+
+impl SortConfig {
+    pub fn is_empty(&self) -> bool {
+        self.keys.iter().all(|x| x.field_name.is_empty())
+    }
+
+    /// Reorders `indices` (indices into `rows`) by the keys
+    pub fn sort(&self, rows: &Columns, indices: &mut [usize]) {
+        let keys: Vec<&SortKey> = self
+            .keys
+            .iter()
+            .filter(|x| !x.field_name.is_empty())
+            .collect();
+        // Values are extracted once as they are expensive to get (eg. parsing times)
+        let mut with_values: Vec<(Vec<Option<SortValue>>, usize)> = indices
+            .iter()
+            .map(|&i| {
+                (
+                    keys.iter()
+                        .map(|key| rows.row(i).and_then(|row| key.value(row)))
+                        .collect(),
+                    i,
+                )
+            })
+            .collect();
+        with_values.sort_by(|(a, _), (b, _)| {
+            keys.iter()
+                .zip(a.iter().zip(b))
+                .map(|(key, (a, b))| compare_values(a.as_ref(), b.as_ref(), key.is_descending))
+                .find(|x| x.is_ne())
+                .unwrap_or(Ordering::Equal)
+        });
+        for (target, (_, i)) in indices.iter_mut().zip(with_values) {
+            *target = i;
+        }
+    }
+}
+
+/// Missing values are last in either direction
+fn compare_values(a: Option<&SortValue>, b: Option<&SortValue>, is_descending: bool) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => {
+            let ordering = a.partial_cmp(b).unwrap_or(Ordering::Equal);
+            if is_descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        }
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+impl Display for SortCompare {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                SortCompare::Text => "Text",
+                SortCompare::Numeric => "Numeric",
+                SortCompare::Time => "Time",
+            }
+        )
+    }
+}
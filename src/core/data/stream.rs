@@ -0,0 +1,217 @@
+use std::io::{BufRead, Seek, SeekFrom};
+
+use anyhow::Context;
+
+use super::{
+    decode::{self, UTF8_BOM},
+    Data, DataDisplayOptions, LoadRange,
+};
+
+/// Parses lines read from `reader` straight into [`Data`] a chunk at a time so only the parsed rows are
+/// kept in memory and not the whole input as text (like [`super::DataParser`] does)
+///
+/// Only works for UTF-8 input, other encodings need the whole input to be decoded first (see [`Self::finish`])
+pub struct StreamParser<'a, R> {
+    data_display_options: &'a DataDisplayOptions,
+    reader: R,
+    input_len: u64,
+    /// Index of the next line read from the input
+    line_index: usize,
+    /// Lines before this index are read but not parsed
+    skip: usize,
+    /// Number of lines still to be parsed
+    take: usize,
+    bytes_processed: u64,
+    /// Not the same as the number of rows as the row script may drop rows
+    lines_processed: usize,
+    /// Max number of lines parsed when only part of the input is loaded
+    max_lines: Option<usize>,
+    is_end_of_input: bool,
+    is_not_utf8: bool,
+    buf: Vec<u8>,
+    data: Data,
+}
+
+impl<'a, R: BufRead + Seek> StreamParser<'a, R> {
+    pub fn new(
+        data_display_options: &'a DataDisplayOptions,
+        mut reader: R,
+    ) -> anyhow::Result<Self> {
+        let input_len = reader
+            .seek(SeekFrom::End(0))
+            .context("failed to get length of input")?;
+        reader
+            .rewind()
+            .context("failed to seek to start of input")?;
+        let load_range = data_display_options.load_range;
+        let line_count = match load_range {
+            LoadRange::Tail(_) => Some(count_lines(&mut reader)?),
+            LoadRange::All | LoadRange::Head(_) | LoadRange::Lines { .. } => None,
+        };
+        let (skip, take) = load_range.skip_and_take(|| line_count.unwrap_or_default());
+        let is_partial = load_range != LoadRange::All;
+        let start = reader.fill_buf().context("failed to read input")?;
+        let is_not_utf8 = decode::has_utf16_bom(start);
+        let mut bytes_processed = 0;
+        if start.starts_with(&UTF8_BOM) {
+            reader.consume(UTF8_BOM.len());
+            bytes_processed = UTF8_BOM.len() as u64;
+        }
+        Ok(Self {
+            data_display_options,
+            reader,
+            input_len,
+            line_index: 0,
+            skip,
+            take,
+            bytes_processed,
+            lines_processed: 0,
+            max_lines: is_partial.then_some(take),
+            is_end_of_input: false,
+            is_not_utf8,
+            buf: Vec::new(),
+            data: Data {
+                partial_load: is_partial.then_some(load_range),
+                ..Default::default()
+            },
+        })
+    }
+
+    /// Fraction of the input processed
+    pub fn progress(&self) -> f32 {
+        match self.max_lines {
+            // Based on lines as most of the input may be skipped
+            Some(max_lines) => self.lines_processed as f32 / max_lines.max(1) as f32,
+            None => self.bytes_processed as f32 / self.input_len.max(1) as f32,
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.is_end_of_input || self.is_not_utf8 || self.take == 0
+    }
+
+    /// Parses up to `max_lines` more lines
+    pub fn parse_chunk(&mut self, max_lines: usize) -> anyhow::Result<()> {
+        let mut parsed_count = 0;
+        while parsed_count < max_lines && !self.is_done() {
+            self.buf.clear();
+            let read_count = self
+                .reader
+                .read_until(b'\n', &mut self.buf)
+                .with_context(|| format!("failed to read line {}", self.line_index + 1))?;
+            if read_count == 0 {
+                self.is_end_of_input = true;
+                break;
+            }
+            self.bytes_processed += read_count as u64;
+            let i = self.line_index;
+            self.line_index += 1;
+            if i < self.skip {
+                continue;
+            }
+            // Line endings are removed the same way as `str::lines`
+            if self.buf.ends_with(b"\n") {
+                self.buf.pop();
+                if self.buf.ends_with(b"\r") {
+                    self.buf.pop();
+                }
+            }
+            let Ok(line) = std::str::from_utf8(&self.buf) else {
+                self.is_not_utf8 = true;
+                break;
+            };
+            self.data.push_line(self.data_display_options, i, line)?;
+            self.lines_processed += 1;
+            self.take -= 1;
+            parsed_count += 1;
+        }
+        Ok(())
+    }
+
+    /// The parsed data (should only be called once [`Self::is_done`]).
+    /// Returns None if the input is not UTF-8, it needs to be read whole and decoded (see [`decode::decode`])
+    pub fn finish(mut self) -> Option<Data> {
+        debug_assert!(self.is_done());
+        if self.is_not_utf8 {
+            return None;
+        }
+        self.data.finish_loading(self.data_display_options);
+        Some(self.data)
+    }
+}
+
+/// Counts the lines the same way as `str::lines` (a final line without a line ending is counted) then goes back to the start
+fn count_lines(reader: &mut (impl BufRead + Seek)) -> anyhow::Result<usize> {
+    let mut result = 0;
+    let mut is_last_line_ended = true;
+    loop {
+        let buf = reader.fill_buf().context("failed to count lines")?;
+        let Some(&last) = buf.last() else {
+            break;
+        };
+        result += buf.iter().filter(|&&b| b == b'\n').count();
+        is_last_line_ended = last == b'\n';
+        let len = buf.len();
+        reader.consume(len);
+    }
+    if !is_last_line_ended {
+        result += 1;
+    }
+    reader
+        .rewind()
+        .context("failed to seek to start of input")?;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+
+    fn stream_parse(options: &DataDisplayOptions, bytes: &[u8]) -> Option<Data> {
+        let mut parser = StreamParser::new(options, Cursor::new(bytes)).unwrap();
+        while !parser.is_done() {
+            parser.parse_chunk(2).unwrap();
+        }
+        parser.finish()
+    }
+
+    #[rstest]
+    #[case::all(LoadRange::All)]
+    #[case::head(LoadRange::Head(2))]
+    #[case::tail(LoadRange::Tail(2))]
+    #[case::lines(LoadRange::Lines { first: 2, last: 3 })]
+    fn same_as_parsing_text(#[case] load_range: LoadRange) {
+        let mut options = DataDisplayOptions::default();
+        options.load_range = load_range;
+        let text = "{\"msg\": \"a\"}\r\n{\"msg\": \"b\"}\n\n{\"msg\": \"c\"}\n{\"msg\": \"d\"}";
+        let expected = Data::try_from((&options, text)).unwrap();
+
+        let actual = stream_parse(&options, text.as_bytes()).unwrap();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn utf8_bom_skipped() {
+        let options = DataDisplayOptions::default();
+        let expected = Data::try_from((&options, "{\"msg\": \"a\"}")).unwrap();
+        let bytes: Vec<u8> = UTF8_BOM
+            .iter()
+            .chain(b"{\"msg\": \"a\"}")
+            .copied()
+            .collect();
+        assert_eq!(stream_parse(&options, &bytes), Some(expected));
+    }
+
+    #[rstest]
+    #[case::latin1(b"{\"msg\": \"a\"}\n{\"msg\": \"caf\xE9\"}")]
+    #[case::utf16(b"\xFF\xFE{\0}\0")]
+    fn not_utf8(#[case] bytes: &[u8]) {
+        assert_eq!(stream_parse(&DataDisplayOptions::default(), bytes), None);
+    }
+}
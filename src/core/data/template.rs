@@ -0,0 +1,95 @@
+/// Replaces the variable parts of a message in its template
+pub const PLACEHOLDER: &str = "{}";
+
+/// The message with the parts that vary between occurrences (numbers, ids, addresses) replaced by [`PLACEHOLDER`]
+/// so messages logged by the same statement have the same template.
+///
+/// Words containing a digit are variable. A number followed by a short unit (eg. `300ms`) keeps the unit and
+/// only the value of `key=value` pairs is replaced. Punctuation around a word is kept.
+/// For example `connection to 10.0.0.1:5432 timed out after 300ms` becomes `connection to {} timed out after {}ms`
+pub fn message_template(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for piece in text.split_inclusive(char::is_whitespace) {
+        let word = piece.trim_end_matches(char::is_whitespace);
+        result.push_str(&word_template(word));
+        result.push_str(&piece[word.len()..]);
+    }
+    result
+}
+
+fn word_template(word: &str) -> String {
+    if !word.chars().any(|c| c.is_ascii_digit()) {
+        return word.to_string();
+    }
+    if let Some((key, value)) = word.split_once('=') {
+        if !key.is_empty() && !key.chars().any(|c| c.is_ascii_digit()) {
+            return format!("{key}={}", word_template(value));
+        }
+    }
+    let start = word.len() - word.trim_start_matches(is_edge_punctuation).len();
+    let end = word.trim_end_matches(is_edge_punctuation).len().max(start);
+    let (prefix, core, suffix) = (&word[..start], &word[start..end], &word[end..]);
+    format!(
+        "{prefix}{PLACEHOLDER}{}{suffix}",
+        unit(core).unwrap_or_default()
+    )
+}
+
+/// Punctuation that surrounds a word without being part of it (eg. quotes and brackets)
+fn is_edge_punctuation(c: char) -> bool {
+    matches!(
+        c,
+        '"' | '\''
+            | '('
+            | ')'
+            | '['
+            | ']'
+            | '{'
+            | '}'
+            | '<'
+            | '>'
+            | ','
+            | ';'
+            | ':'
+            | '.'
+            | '!'
+            | '?'
+    )
+}
+
+/// The unit after a number (eg. `ms` in `300ms`) if the word is a number followed by a short unit
+fn unit(word: &str) -> Option<&str> {
+    let digits = word.strip_prefix('-').unwrap_or(word);
+    let unit_start = digits.find(|c: char| !c.is_ascii_digit() && c != '.')?;
+    let (number, unit) = digits.split_at(unit_start);
+    let is_unit = !number.is_empty()
+        && unit.len() <= 3
+        && unit.chars().all(|c| c.is_ascii_alphabetic() || c == '%');
+    is_unit.then_some(unit)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::no_variables("server started", "server started")]
+    #[case::numbers_and_units(
+        "connection to 10.0.0.1:5432 timed out after 300ms",
+        "connection to {} timed out after {}ms"
+    )]
+    #[case::key_value("request id=42 took 1.5s", "request id={} took {}s")]
+    #[case::punctuation_kept(
+        "user \"u-1234\" (attempt 3), retrying.",
+        "user \"{}\" (attempt {}), retrying."
+    )]
+    #[case::percent("disk at 90%", "disk at {}%")]
+    #[case::ids("trace 4bf92f3577b34da6a3ce929d0e0e4736 done", "trace {} done")]
+    #[case::whitespace_kept("a  1\tb", "a  {}\tb")]
+    fn templates(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(message_template(input), expected);
+    }
+}
@@ -0,0 +1,1246 @@
+use std::fmt::{Debug, Display};
+
+use filter::{Comparator, FieldSpecifier, LevelFilter};
+use insta::glob;
+use pretty_assertions::assert_eq;
+use rstest::{fixture, rstest};
+use strum::IntoEnumIterator;
+
+use crate::core::data_display_options::{DataDisplayOptions, LoadRange, LogFormat, RowHeight};
+
+use super::*;
+
+const PATH_PROJECT_ROOT: &str = "../../../";
+const PATH_TEST_SAMPLES: &str = "tests/sample_logs/*.*";
+
+/// Formats to test serializing with
+///
+/// Even though in the application only RON is used for serialization we do round
+/// trip testing on Json because it helps identify problems that are format specific
+/// and avoid unnecessary debugging
+enum SerdeFormat {
+    Ron,
+    Json,
+}
+
+#[fixture]
+pub(crate) fn insta_settings() -> insta::Settings {
+    let mut result = insta::Settings::clone_current();
+    let cwd = std::env::current_dir().expect("failed to get cwd");
+    let path = cwd.join("tests").join("snapshots");
+    result.set_snapshot_path(path);
+    result
+}
+
+#[rstest]
+fn deserialize_rows_from_string(insta_settings: insta::Settings) {
+    glob!(PATH_PROJECT_ROOT, PATH_TEST_SAMPLES, |path| {
+        let input = std::fs::read_to_string(path).unwrap();
+        let data = Data::try_from((&DataDisplayOptions::default(), &input[..])).unwrap();
+        let log_filename = path.file_name().unwrap().to_string_lossy().to_string();
+        insta_settings.bind(|| insta::assert_ron_snapshot!(format!("{log_filename}_ron"), data));
+        insta_settings.bind(|| insta::assert_yaml_snapshot!(format!("{log_filename}_yaml"), data));
+        insta_settings
+            .bind(|| insta::assert_debug_snapshot!(format!("{log_filename}_debug"), data));
+    });
+}
+
+#[rstest]
+#[case::ron(SerdeFormat::Ron)]
+#[case::json(SerdeFormat::Json)]
+fn round_trip_from_samples(#[case] serde_format: SerdeFormat) {
+    // Function needed because rustfmt doesn't play nicely with formatting long strings in macros
+    fn fail_with(path: impl Debug, row: usize, e: impl Debug, s: impl Display) -> OwnedLogRow {
+        panic!(
+            "failed to deserialize back into struct.\nFile: {path:?}\nRow: {row}\nError: {e:?}\nSerialized Data: {s}"
+        )
+    }
+
+    glob!(PATH_PROJECT_ROOT, PATH_TEST_SAMPLES, |path| {
+        let input = std::fs::read_to_string(path).unwrap();
+        let rows_before = Data::try_from((&DataDisplayOptions::default(), &input[..])).unwrap();
+
+        // Test individual rows
+        for (i, row_before) in rows_before.rows_iter().enumerate() {
+            let as_string = match serde_format {
+                SerdeFormat::Ron => ron::to_string(&row_before).unwrap(),
+                SerdeFormat::Json => serde_json::to_string(&row_before).unwrap(),
+            };
+
+            let row_after: OwnedLogRow = match serde_format {
+                SerdeFormat::Ron => {
+                    ron::from_str(&as_string).unwrap_or_else(|e| fail_with(path, i, e, as_string))
+                }
+                SerdeFormat::Json => serde_json::from_str(&as_string)
+                    .unwrap_or_else(|e| fail_with(path, i, e, as_string)),
+            };
+            assert_eq!(row_after.as_row(), row_before);
+        }
+
+        // Test composition of all rows
+        let as_string = match serde_format {
+            SerdeFormat::Ron => ron::to_string(&rows_before).unwrap(),
+            SerdeFormat::Json => serde_json::to_string(&rows_before).unwrap(),
+        };
+        let rows_after: Data = match serde_format {
+            SerdeFormat::Ron => ron::from_str(&dbg!(as_string)).unwrap(),
+            SerdeFormat::Json => serde_json::from_str(&dbg!(as_string)).unwrap(),
+        };
+        assert_eq!(rows_after, rows_before);
+    });
+}
+
+pub fn create_log_row_no_extra() -> OwnedLogRow {
+    let mut result = OwnedLogRow::default();
+    result.data.insert("time", "time value".into());
+    result.data.insert("otel.name", "HTTP GET /status".into());
+    result
+}
+
+pub fn create_log_row_with_extra() -> OwnedLogRow {
+    let mut result = create_log_row_no_extra();
+    result.data.insert("http.status_code", 200.into());
+    result
+}
+
+#[rstest]
+fn round_trip_from_manual(
+    #[values(SerdeFormat::Ron, SerdeFormat::Json)] serde_format: SerdeFormat,
+    #[values(create_log_row_no_extra(), create_log_row_with_extra())] before: OwnedLogRow,
+) {
+    let as_string = match serde_format {
+        SerdeFormat::Ron => ron::to_string(&before.as_row()).unwrap(),
+        SerdeFormat::Json => serde_json::to_string(&before.as_row()).unwrap(),
+    };
+    println!("Serialized data:\n{as_string}");
+    let after: OwnedLogRow = match serde_format {
+        SerdeFormat::Ron => ron::from_str(&as_string).unwrap(),
+        SerdeFormat::Json => serde_json::from_str(&as_string).unwrap(),
+    };
+    assert_eq!(after, before);
+}
+
+#[rstest]
+fn comparisons_specific_field(insta_settings: insta::Settings) {
+    let row0 = create_log_row_no_extra();
+    let row1 = create_log_row_with_extra();
+    let mut data = Data {
+        rows: [row0.clone(), row1.clone()].into_iter().collect(),
+        ..Default::default()
+    };
+
+    data.filter = Some(FilterConfig {
+        search_key: "200".to_string(),
+        filter_on: filter::FilterOn::Field(FieldSpecifier {
+            name: "http.status_code".to_string(),
+        }),
+        is_case_sensitive: false,
+        comparator: Default::default(),
+    });
+
+    let display_options = DataDisplayOptions::default();
+    let common_fields = display_options.common_fields();
+
+    for comparator in Comparator::iter() {
+        data.filter.as_mut().unwrap().comparator = comparator;
+        data.apply_filter(common_fields);
+        insta_settings.bind(|| insta::assert_yaml_snapshot!(data));
+    }
+}
+
+#[rstest]
+fn comparisons_any(insta_settings: insta::Settings) {
+    let row0 = create_log_row_no_extra();
+    let row1 = create_log_row_with_extra();
+    let mut data = Data {
+        rows: [row0.clone(), row1.clone()].into_iter().collect(),
+        ..Default::default()
+    };
+
+    data.filter = Some(FilterConfig {
+        search_key: "20".to_string(),
+        filter_on: filter::FilterOn::Any,
+        is_case_sensitive: false,
+        comparator: Default::default(),
+    });
+
+    let display_options = DataDisplayOptions::default();
+    let common_fields = display_options.common_fields();
+
+    for comparator in Comparator::iter() {
+        data.filter.as_mut().unwrap().comparator = comparator;
+        data.apply_filter(common_fields);
+        insta_settings.bind(|| insta::assert_yaml_snapshot!(data));
+    }
+}
+
+#[test]
+fn selected_maintenance_with_filtering() {
+    let test_field = String::from("test field");
+    let rows = (5..10)
+        .map(|i| {
+            let mut row = create_log_row_no_extra();
+            row.data.insert(&test_field, i.into());
+            row
+        })
+        .collect();
+    let mut data = Data {
+        rows,
+        ..Default::default()
+    };
+    let display_options = DataDisplayOptions::default();
+    let common_fields = display_options.common_fields();
+
+    // Set "7" as selected
+    data.selected_row = Some(2);
+
+    // Save selected row from before
+    let expected = data
+        .selected_row_data_as_slice(common_fields)
+        .unwrap()
+        .to_vec();
+
+    data.filter = Some(FilterConfig {
+        search_key: "7".to_string(),
+        ..Default::default()
+    });
+    data.apply_filter(DataDisplayOptions::default().common_fields());
+
+    // Test that 7 is still selected
+    let actual = data
+        .selected_row_data_as_slice(common_fields)
+        .unwrap()
+        .to_vec();
+
+    assert_eq!(actual, expected);
+
+    // Then reverse
+    data.unfilter(common_fields);
+
+    // Test that 7 is still selected
+    let actual = data
+        .selected_row_data_as_slice(common_fields)
+        .unwrap()
+        .to_vec();
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn selected_unselected_when_not_present() {
+    let test_field = String::from("test field");
+    let rows = (5..10)
+        .map(|i| {
+            let mut row = create_log_row_no_extra();
+            row.data.insert(&test_field, i.into());
+            row
+        })
+        .collect();
+    let mut data = Data {
+        rows,
+        ..Default::default()
+    };
+    let display_options = DataDisplayOptions::default();
+    let common_fields = display_options.common_fields();
+
+    // Set "7" as selected
+    data.selected_row = Some(2);
+
+    // Filter for 6, so 7 is not included
+    data.filter = Some(FilterConfig {
+        search_key: "6".to_string(),
+        ..Default::default()
+    });
+    data.apply_filter(DataDisplayOptions::default().common_fields());
+
+    let actual = data.selected_row_data_as_slice(common_fields);
+
+    assert!(actual.is_none());
+}
+
+#[test]
+fn hide_rows_with_and_without_filter() {
+    let test_field = String::from("test field");
+    let rows = (5..10)
+        .map(|i| {
+            let mut row = create_log_row_no_extra();
+            row.data.insert(&test_field, i.into());
+            row
+        })
+        .collect();
+    let mut data = Data {
+        rows,
+        ..Default::default()
+    };
+    let display_options = DataDisplayOptions::default();
+    let common_fields = display_options.common_fields();
+
+    // Hide "7", selection moves to "8" which took its place
+    data.selected_row = Some(2);
+    data.hide_selected();
+    assert_eq!(data.len(), 4);
+    assert_eq!(data.hidden_count(), 1);
+    assert_eq!(data.selected_row, Some(2));
+    assert_eq!(data.get_real_index(2), 3);
+
+    // Hidden rows stay hidden when a filter is applied (matches all rows)
+    data.filter = Some(FilterConfig {
+        search_key: "time".to_string(),
+        ..Default::default()
+    });
+    data.apply_filter(common_fields);
+    assert_eq!(data.len(), 4);
+
+    // Undo restores the row and selects it
+    data.undo_hide(common_fields);
+    assert_eq!(data.len(), 5);
+    assert_eq!(data.hidden_count(), 0);
+    assert_eq!(data.selected_row, Some(2));
+
+    // Removing the filter with nothing hidden shows all rows without an index layer
+    data.selected_row = Some(0);
+    data.hide_selected();
+    data.selected_row = Some(0);
+    data.hide_selected();
+    data.restore_all_hidden(common_fields);
+    data.unfilter(common_fields);
+    assert_eq!(data.len(), 5);
+    assert!(data.filtered_rows.is_none());
+}
+
+#[test]
+fn symmetric_difference_export() {
+    let rows = (0..4)
+        .map(|i| {
+            let mut row = OwnedLogRow::default();
+            row.data.insert("i", i.into());
+            row
+        })
+        .collect();
+    let data = Data {
+        rows,
+        ..Default::default()
+    };
+    let actual = data
+        .export_symmetric_difference(("A", &[0, 1, 2]), ("B", &[1, 3]), "diff")
+        .unwrap();
+    let expected = "{\"diff\":\"A\",\"i\":0}\n{\"diff\":\"A\",\"i\":2}\n{\"diff\":\"B\",\"i\":3}\n";
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn parse_with_progress_can_be_cancelled() {
+    let input = std::fs::read_to_string("tests/sample_logs/long.log").unwrap();
+    let options = DataDisplayOptions::default();
+
+    let mut progress_reports = Vec::new();
+    let data = Data::parse_with_progress(&options, &input, |progress| {
+        progress_reports.push(progress);
+        true
+    })
+    .unwrap()
+    .unwrap();
+    assert_eq!(data, Data::try_from((&options, &input[..])).unwrap());
+    assert_eq!(progress_reports, vec![0.0]);
+
+    let cancelled = Data::parse_with_progress(&options, &input, |_| false).unwrap();
+    assert!(cancelled.is_none());
+}
+
+#[test]
+fn range_filters_compose_with_text_filter() {
+    let test_field = String::from("test field");
+    let rows = (5..10)
+        .map(|i| {
+            let mut row = create_log_row_no_extra();
+            row.data.insert(&test_field, i.into());
+            row
+        })
+        .collect();
+    let mut data = Data {
+        rows,
+        ..Default::default()
+    };
+    let display_options = DataDisplayOptions::default();
+    let common_fields = display_options.common_fields();
+    let range = |min, max| {
+        vec![RangeFilter {
+            field_name: test_field.clone(),
+            min,
+            max,
+        }]
+    };
+
+    data.set_range_filters(range(6.0, 8.0), common_fields);
+    assert_eq!(data.visible_real_indices(), vec![1, 2, 3]);
+
+    // Text filter only matches "8" and "9"
+    data.filter = Some(FilterConfig {
+        search_key: "8".to_string(),
+        comparator: Comparator::GreaterThanEqual,
+        filter_on: filter::FilterOn::Field(FieldSpecifier {
+            name: test_field.clone(),
+        }),
+        ..Default::default()
+    });
+    data.apply_filter(common_fields);
+    assert_eq!(data.visible_real_indices(), vec![3]);
+
+    data.unfilter(common_fields);
+    assert_eq!(data.visible_real_indices(), vec![1, 2, 3]);
+
+    data.set_range_filters(Vec::new(), common_fields);
+    assert!(data.filtered_rows.is_none());
+}
+
+#[test]
+fn append_lines_respects_filter() {
+    let display_options = DataDisplayOptions::default();
+    let common_fields = display_options.common_fields();
+    let mut data = Data::try_from((&display_options, r#"{"msg": "keep 1"}"#)).unwrap();
+    data.filter = Some(FilterConfig {
+        search_key: "keep".to_string(),
+        ..Default::default()
+    });
+    data.apply_filter(common_fields);
+    data.selected_row = Some(0);
+
+    data.append_lines(
+        &display_options,
+        [r#"{"msg": "drop"}"#, r#"{"msg": "keep 2"}"#],
+        common_fields,
+    )
+    .unwrap();
+    assert_eq!(data.total_len_unfiltered(), 3);
+    assert_eq!(data.visible_real_indices(), vec![0, 2]);
+    assert_eq!(data.selected_row, Some(0));
+    assert_eq!(
+        data.row_by_real_index(2).unwrap().field_value("row#"),
+        FieldContent::Present(&2.into())
+    );
+}
+
+#[test]
+fn ignore_list_composes_with_filter() {
+    let display_options = DataDisplayOptions::default();
+    let common_fields = display_options.common_fields();
+    let text = [
+        r#"{"msg": "GET /health"}"#,
+        r#"{"msg": "GET /users"}"#,
+        r#"{"msg": "POST /users"}"#,
+        r#"{"msg": "GET /health"}"#,
+    ]
+    .join("\n");
+    let mut data = Data::try_from((&display_options, text.as_str())).unwrap();
+    data.ignore_matching(
+        FilterConfig {
+            search_key: "/health".to_string(),
+            ..Default::default()
+        },
+        common_fields,
+    );
+    assert_eq!(data.visible_real_indices(), vec![1, 2]);
+
+    data.filter = Some(FilterConfig {
+        search_key: "get".to_string(),
+        ..Default::default()
+    });
+    data.apply_filter(common_fields);
+    assert_eq!(data.visible_real_indices(), vec![1]);
+
+    // Disabled rules are kept but have no effect
+    let mut ignore_list = data.ignore_list().to_vec();
+    ignore_list[0].is_enabled = false;
+    data.set_ignore_list(ignore_list, common_fields);
+    assert_eq!(data.visible_real_indices(), vec![0, 1, 3]);
+}
+
+#[test]
+fn ci_log_groups_start_collapsed() {
+    let mut display_options = DataDisplayOptions::default();
+    display_options.log_format = LogFormat::CiRunner;
+    let common_fields = display_options.common_fields();
+    let text = [
+        "Job started",
+        "##[group]Run cargo build",
+        "Compiling app",
+        "##[endgroup]",
+        "##[group]Run cargo test",
+        "test ok",
+        "##[endgroup]",
+        "Job finished",
+    ]
+    .join("\n");
+    let mut data = Data::try_from((&display_options, text.as_str())).unwrap();
+    assert!(data.has_groups());
+    assert_eq!(data.visible_real_indices(), vec![0, 1, 4, 7]);
+    assert_eq!(data.group_header(1), Some((0, true)));
+    assert_eq!(data.group_header(2), None);
+
+    data.toggle_group(1, common_fields);
+    assert_eq!(data.visible_real_indices(), vec![0, 1, 4, 5, 6, 7]);
+    assert_eq!(data.group_header(4), Some((1, false)));
+
+    data.set_all_groups_collapsed(false, common_fields);
+    assert!(data.filtered_rows.is_none());
+}
+
+#[test]
+fn per_frame_calls_keep_rows_in_cold_storage() {
+    let display_options = DataDisplayOptions::default();
+    let common_fields = display_options.common_fields();
+    let text = (0..10)
+        .map(|i| format!(r#"{{"msg": "row {i}"}}"#))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let mut data = Data::try_from((&display_options, text.as_str())).unwrap();
+    let settings = ColdStorageSettings {
+        is_enabled: true,
+        max_visible_percent: 10,
+    };
+    data.filter = Some(FilterConfig {
+        search_key: "row 1".to_string(),
+        ..Default::default()
+    });
+    data.apply_filter(common_fields);
+    data.update_cold_storage(&settings);
+    assert_eq!(data.cold_count(), 9);
+
+    // What the main view uses every frame
+    for _ in 0..2 {
+        assert!(!data.has_groups());
+        assert_eq!(data.row_heights(&display_options.row_height, 10.0).len(), 1);
+        assert_eq!(data.rows_iter().count(), 1);
+        assert_eq!(data.group_header(1), None);
+        data.update_cold_storage(&settings);
+    }
+    assert_eq!(data.cold_count(), 9);
+}
+
+#[test]
+fn cold_storage_only_when_few_rows_visible() {
+    let display_options = DataDisplayOptions::default();
+    let common_fields = display_options.common_fields();
+    let text = (0..10)
+        .map(|i| format!(r#"{{"msg": "row {i}"}}"#))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let mut data = Data::try_from((&display_options, text.as_str())).unwrap();
+    let expected_row_3 = data.row_by_real_index(3).unwrap().to_owned_row();
+    let settings = ColdStorageSettings {
+        is_enabled: true,
+        max_visible_percent: 10,
+    };
+
+    data.filter = Some(FilterConfig {
+        search_key: "row 1".to_string(),
+        ..Default::default()
+    });
+    data.apply_filter(common_fields);
+    data.update_cold_storage(&settings);
+    assert_eq!(data.cold_count(), 9);
+
+    // Rows come out of cold storage when used
+    assert_eq!(data.row_by_real_index(3), Some(expected_row_3.as_row()));
+    assert_eq!(data.cold_count(), 8);
+
+    // Not used when too many rows are visible (filtering uses all rows so they leave cold storage)
+    data.filter = Some(FilterConfig {
+        search_key: "row".to_string(),
+        ..Default::default()
+    });
+    data.apply_filter(common_fields);
+    data.update_cold_storage(&settings);
+    assert_eq!(data.cold_count(), 0);
+}
+
+#[test]
+fn syslog_level_conversion() {
+    let mut display_options = DataDisplayOptions::default();
+    display_options.level_conversion = Some(LevelConversion::syslog());
+    let text = [
+        r#"{"severity": 3, "msg": "a"}"#,
+        r#"{"severity": 9, "msg": "b"}"#,
+        r#"{"severity": "high", "msg": "c"}"#,
+    ]
+    .join("\n");
+    let data = Data::try_from((&display_options, text.as_str())).unwrap();
+    let level = |i| data.row_by_real_index(i).unwrap().field_value("level_str");
+    assert_eq!(level(0), FieldContent::Present(&"Error".into()));
+    assert_eq!(level(1), FieldContent::Missing);
+    assert_eq!(level(2), FieldContent::Missing);
+}
+
+#[test]
+fn level_conversion_reapplied_to_loaded_rows() {
+    let display_options = DataDisplayOptions::default();
+    let common_fields = display_options.common_fields();
+    let text = [
+        r#"{"level": 30, "severity": 3, "msg": "a"}"#,
+        r#"{"level": 50, "level_str": "kept", "msg": "b"}"#,
+    ]
+    .join("\n");
+    let mut data = Data::try_from((&display_options, text.as_str())).unwrap();
+    let level = |data: &Data, i| {
+        data.row_by_real_index(i)
+            .unwrap()
+            .field_value("level_str")
+            .display()
+    };
+    assert_eq!(level(&data, 0), "Info");
+    assert_eq!(level(&data, 1), "kept");
+
+    data.set_level_conversion(Some(&LevelConversion::syslog()), common_fields);
+    assert_eq!(level(&data, 0), "Error");
+    assert_eq!(level(&data, 1), "kept");
+
+    data.set_level_conversion(None, common_fields);
+    assert_eq!(
+        data.row_by_real_index(0).unwrap().field_value("level_str"),
+        FieldContent::Missing
+    );
+    assert_eq!(level(&data, 1), "kept");
+}
+
+#[test]
+fn parse_failures_are_counted() {
+    let mut display_options = DataDisplayOptions::default();
+    let text = [r#"{"msg": "ok"}"#, "not json", r#"{"msg": "#].join("\n");
+    let data = Data::try_from((&display_options, text.as_str())).unwrap();
+    assert_eq!(data.parse_failure_count(), 2);
+    assert_eq!(
+        data.row_by_real_index(1).unwrap().field_value("msg"),
+        FieldContent::Present(&"not json".into())
+    );
+
+    display_options.row_parse_error_handling = RowParseErrorHandling::AbortOnAnyErrors;
+    assert!(Data::try_from((&display_options, text.as_str())).is_err());
+}
+
+#[test]
+fn failed_lines_joined_to_previous_row() {
+    let mut display_options = DataDisplayOptions::default();
+    display_options.row_parse_error_handling = RowParseErrorHandling::JoinToPreviousRow {
+        field_name: "err".into(),
+    };
+    let text = [
+        "Traceback (most recent call last):",
+        r#"{"msg": "failed", "err": "ValueError"}"#,
+        r#"  File "app.py", line 3"#,
+        "    raise ValueError",
+        r#"{"msg": "next"}"#,
+    ]
+    .join("\n");
+    let mut data = Data::try_from((&display_options, text.as_str())).unwrap();
+    data.append_lines(&display_options, ["more"], display_options.common_fields())
+        .unwrap();
+    let actual: Vec<String> = (0..data.total_len_unfiltered())
+        .map(|i| {
+            data.row_by_real_index(i)
+                .unwrap()
+                .field_value("err")
+                .display()
+        })
+        .collect();
+    assert_eq!(
+        actual,
+        vec![
+            "Traceback (most recent call last):".to_string(),
+            "ValueError\n  File \"app.py\", line 3\n    raise ValueError".to_string(),
+            "more".to_string(),
+        ]
+    );
+    assert_eq!(
+        data.parse_failure_count(),
+        1,
+        "only the line without a row before it"
+    );
+}
+
+#[test]
+fn move_to_match_wraps_around() {
+    let display_options = DataDisplayOptions::default();
+    let common_fields = display_options.common_fields();
+    let text = [
+        r#"{"msg": "error a"}"#,
+        r#"{"msg": "ok"}"#,
+        r#"{"msg": "error b"}"#,
+        r#"{"msg": "ok"}"#,
+    ]
+    .join("\n");
+    let mut data = Data::try_from((&display_options, text.as_str())).unwrap();
+    assert!(!data.move_selected_to_match(true, common_fields));
+
+    data.filter = Some(FilterConfig {
+        search_key: "error".to_string(),
+        ..Default::default()
+    });
+    assert!(data.move_selected_to_match(true, common_fields));
+    assert_eq!(data.selected_row, Some(0));
+    assert!(data.move_selected_to_match(true, common_fields));
+    assert_eq!(data.selected_row, Some(2));
+    assert!(data.move_selected_to_match(true, common_fields));
+    assert_eq!(data.selected_row, Some(0));
+    assert!(data.move_selected_to_match(false, common_fields));
+    assert_eq!(data.selected_row, Some(2));
+}
+
+#[test]
+fn presence_comparators() {
+    let mut data = Data {
+        rows: [create_log_row_no_extra(), create_log_row_with_extra()]
+            .into_iter()
+            .collect(),
+        ..Default::default()
+    };
+    let display_options = DataDisplayOptions::default();
+    let common_fields = display_options.common_fields();
+    let mut filter = FilterConfig {
+        search_key: "ignored".to_string(),
+        filter_on: filter::FilterOn::Field(FieldSpecifier {
+            name: "HTTP.status_code".to_string(),
+        }),
+        is_case_sensitive: false,
+        comparator: Comparator::IsPresent,
+    };
+    assert!(!data.row_matches(0, &filter, common_fields));
+    assert!(data.row_matches(1, &filter, common_fields));
+
+    filter.comparator = Comparator::IsMissing;
+    assert!(data.row_matches(0, &filter, common_fields));
+    assert!(!data.row_matches(1, &filter, common_fields));
+}
+
+#[test]
+fn field_names_from_all_rows() {
+    let data = Data {
+        rows: [create_log_row_no_extra(), create_log_row_with_extra()]
+            .into_iter()
+            .collect(),
+        ..Default::default()
+    };
+    assert_eq!(
+        data.field_names(),
+        vec!["http.status_code", "otel.name", "time"]
+    );
+}
+
+#[test]
+fn filter_on_multiple_fields() {
+    let mut data = Data {
+        rows: [create_log_row_no_extra(), create_log_row_with_extra()]
+            .into_iter()
+            .collect(),
+        ..Default::default()
+    };
+    let display_options = DataDisplayOptions::default();
+    let common_fields = display_options.common_fields();
+    let mut filter = FilterConfig {
+        search_key: "200".to_string(),
+        filter_on: filter::FilterOn::Fields(vec![
+            FieldSpecifier {
+                name: "otel.name".to_string(),
+            },
+            FieldSpecifier {
+                name: "http.status_code".to_string(),
+            },
+        ]),
+        is_case_sensitive: false,
+        comparator: Comparator::Contains,
+    };
+    assert!(!data.row_matches(0, &filter, common_fields));
+    assert!(data.row_matches(1, &filter, common_fields));
+
+    // Other fields are not checked
+    filter.search_key = "time value".to_string();
+    assert!(!data.row_matches(1, &filter, common_fields));
+    filter.search_key = "get".to_string();
+    assert!(data.row_matches(0, &filter, common_fields));
+
+    // Missing only if all are missing
+    filter.comparator = Comparator::IsMissing;
+    assert!(!data.row_matches(0, &filter, common_fields));
+}
+
+fn display_options_with_min(min_level: &str) -> DataDisplayOptions {
+    let mut result = DataDisplayOptions::default();
+    result.min_level = Some(min_level.into());
+    result
+}
+
+#[test]
+fn level_filter_hides_levels() {
+    let display_options = DataDisplayOptions::default();
+    let common_fields = display_options.common_fields();
+    let text = [
+        r#"{"level": 30, "msg": "a"}"#,
+        r#"{"level": 50, "msg": "b"}"#,
+        r#"{"msg": "no level"}"#,
+        r#"{"level": 20, "msg": "c"}"#,
+    ]
+    .join("\n");
+    let mut data = Data::try_from((&display_options, text.as_str())).unwrap();
+    data.set_level_filter(
+        LevelFilter {
+            field_name: "level_str".into(),
+            hidden_levels: ["Info", "Debug"].into_iter().map(String::from).collect(),
+            ..Default::default()
+        },
+        common_fields,
+    );
+    assert_eq!(data.filtered_rows, Some(vec![1, 2]));
+
+    data.set_level_filter(
+        LevelFilter {
+            field_name: "level_str".into(),
+            below_min: display_options_with_min("Error").levels_below_min(),
+            ..Default::default()
+        },
+        common_fields,
+    );
+    assert_eq!(data.filtered_rows, Some(vec![1, 2]));
+
+    data.set_level_filter(Default::default(), common_fields);
+    assert_eq!(data.filtered_rows, None);
+}
+
+#[test]
+fn column_filters_must_all_match() {
+    let display_options = DataDisplayOptions::default();
+    let common_fields = display_options.common_fields();
+    let text = [
+        r#"{"msg": "Connection Lost", "host": "a1"}"#,
+        r#"{"msg": "connection made", "host": "b2"}"#,
+        r#"{"msg": "other", "host": "a3"}"#,
+        r#"{"host": "a4"}"#,
+    ]
+    .join("\n");
+    let mut data = Data::try_from((&display_options, text.as_str())).unwrap();
+    let column_filters = [("msg", "connection"), ("host", "A"), ("time", "")]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    data.set_column_filters(column_filters, common_fields);
+    assert_eq!(data.filtered_rows, Some(vec![0]));
+    assert_eq!(data.column_filters().len(), 2, "empty text is dropped");
+}
+
+#[test]
+fn narrowing_filter_matches_full_apply() {
+    let input = std::fs::read_to_string("tests/sample_logs/long.log").unwrap();
+    let options = DataDisplayOptions::default();
+    let common_fields = options.common_fields();
+    let mut narrowed = Data::try_from((&options, &input[..])).unwrap();
+    let mut full = Data::try_from((&options, &input[..])).unwrap();
+    narrowed.filter = Some(FilterConfig {
+        search_key: "s".to_string(),
+        ..Default::default()
+    });
+    narrowed.apply_filter(common_fields);
+    narrowed.filter.as_mut().unwrap().search_key = "st".to_string();
+    narrowed.apply_filter(common_fields);
+
+    full.filter = narrowed.filter.clone();
+    full.apply_filter(common_fields);
+    assert_eq!(narrowed.filtered_rows, full.filtered_rows);
+    assert_ne!(narrowed.filtered_rows, Some(Vec::new()));
+}
+
+#[test]
+fn context_rows_around_matches() {
+    let display_options = DataDisplayOptions::default();
+    let common_fields = display_options.common_fields();
+    let text = (0..10)
+        .map(|i| {
+            let msg = if i == 3 || i == 8 { "match" } else { "other" };
+            format!(r#"{{"msg": "{msg}"}}"#)
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let mut data = Data::try_from((&display_options, text.as_str())).unwrap();
+    data.filter = Some(FilterConfig {
+        search_key: "match".to_string(),
+        ..Default::default()
+    });
+    data.apply_filter(common_fields);
+    assert_eq!(data.filtered_rows, Some(vec![3, 8]));
+
+    data.set_context_rows(1, common_fields);
+    assert_eq!(data.filtered_rows, Some(vec![2, 3, 4, 7, 8, 9]));
+    assert!(data.is_context_only_row(2));
+    assert!(!data.is_context_only_row(3));
+
+    // Hidden rows are skipped when counting context
+    data.hidden_rows = vec![2];
+    data.update_visible_rows(common_fields);
+    assert_eq!(data.filtered_rows, Some(vec![1, 3, 4, 7, 8, 9]));
+}
+
+#[test]
+fn collapse_duplicate_runs() {
+    let display_options = DataDisplayOptions::default();
+    let common_fields = display_options.common_fields();
+    let text = ["a", "retry", "retry", "retry", "b", "retry", "retry"]
+        .map(|msg| format!(r#"{{"msg": "{msg}"}}"#))
+        .join("\n");
+    let mut data = Data::try_from((&display_options, text.as_str())).unwrap();
+    data.set_collapse_duplicates_field(Some("msg".into()), common_fields);
+    assert_eq!(data.filtered_rows, Some(vec![0, 1, 4, 5]));
+    assert_eq!(data.run_length(1), Some(3));
+    assert_eq!(data.run_length(5), Some(2));
+    assert_eq!(data.run_length(0), None);
+
+    data.toggle_run(1, common_fields);
+    assert_eq!(data.filtered_rows, Some(vec![0, 1, 2, 3, 4, 5]));
+    assert_eq!(data.run_length(1), Some(3));
+
+    data.set_collapse_duplicates_field(None, common_fields);
+    assert_eq!(data.filtered_rows, None);
+}
+
+#[test]
+fn time_gap_between_visible_rows() {
+    let display_options = DataDisplayOptions::default();
+    let common_fields = display_options.common_fields();
+    let text = [
+        r#"{"time": "2024-01-01T00:00:00Z", "msg": "a"}"#,
+        r#"{"time": "2024-01-01T00:00:02Z", "msg": "b"}"#,
+        r#"{"msg": "no time"}"#,
+        r#"{"time": "2024-01-01T00:00:09Z", "msg": "a"}"#,
+    ]
+    .join("\n");
+    let mut data = Data::try_from((&display_options, text.as_str())).unwrap();
+    assert_eq!(data.time_gap_before(0, "time"), None);
+    assert_eq!(
+        data.time_gap_before(1, "time"),
+        Some(chrono::TimeDelta::seconds(2))
+    );
+    assert_eq!(data.time_gap_before(3, "time"), None);
+
+    data.filter = Some(FilterConfig {
+        search_key: "a".into(),
+        ..Default::default()
+    });
+    data.apply_filter(common_fields);
+    let gap = data.time_gap_before(1, "time").unwrap();
+    assert_eq!(gap, chrono::TimeDelta::seconds(9));
+    assert_eq!(display_options.time_gaps.marked_gap(gap), Some(gap));
+    assert_eq!(
+        display_options
+            .time_gaps
+            .marked_gap(chrono::TimeDelta::seconds(2)),
+        None
+    );
+}
+
+#[test]
+fn move_to_rows_with_same_value() {
+    let display_options = DataDisplayOptions::default();
+    let text = ["a", "b", "a", "c", "a"]
+        .map(|id| format!(r#"{{"request_id": "{id}"}}"#))
+        .join("\n");
+    let mut data = Data::try_from((&display_options, text.as_str())).unwrap();
+    assert!(!data.move_selected_to_same_value("request_id", true));
+
+    data.selected_row = Some(2);
+    assert!(data.move_selected_to_same_value("request_id", true));
+    assert_eq!(data.selected_row, Some(4));
+    assert!(data.move_selected_to_same_value("request_id", true));
+    assert_eq!(data.selected_row, Some(0));
+    assert!(data.move_selected_to_same_value("request_id", false));
+    assert_eq!(data.selected_row, Some(4));
+
+    data.selected_row = Some(1);
+    assert!(!data.move_selected_to_same_value("request_id", true));
+    assert_eq!(data.selected_row, Some(1));
+    assert!(!data.move_selected_to_same_value("missing", true));
+}
+
+#[test]
+fn move_to_ends_of_rows_with_same_value() {
+    let display_options = DataDisplayOptions::default();
+    let text = ["b", "a", "b", "a", "c"]
+        .map(|id| format!(r#"{{"request_id": "{id}"}}"#))
+        .join("\n");
+    let mut data = Data::try_from((&display_options, text.as_str())).unwrap();
+    assert!(!data.move_selected_to_same_value_end("request_id", true));
+
+    data.selected_row = Some(1);
+    assert!(data.move_selected_to_same_value_end("request_id", true));
+    assert_eq!(data.selected_row, Some(3));
+    assert!(data.move_selected_to_same_value_end("request_id", false));
+    assert_eq!(data.selected_row, Some(1));
+
+    data.selected_row = Some(4);
+    assert!(data.move_selected_to_same_value_end("request_id", false));
+    assert_eq!(data.selected_row, Some(4));
+    assert!(!data.move_selected_to_same_value_end("missing", false));
+}
+
+#[test]
+fn selection_kept_by_content_after_reload() {
+    let display_options = DataDisplayOptions::default();
+    let common_fields = display_options.common_fields();
+    let row_idx_field_name = display_options.row_idx_field_name.as_deref();
+    let text = ["a", "b", "c"]
+        .map(|msg| format!(r#"{{"msg": "{msg}"}}"#))
+        .join("\n");
+    let mut old = Data::try_from((&display_options, text.as_str())).unwrap();
+    old.selected_row = Some(1);
+
+    let text = format!("{}\n{text}", r#"{"msg": "new"}"#);
+    let mut data = Data::try_from((&display_options, text.as_str())).unwrap();
+    data.take_config(&mut old, common_fields, row_idx_field_name);
+    assert_eq!(data.selected_row, Some(2));
+
+    // Content no longer present so the position is kept instead
+    let mut old = data;
+    let text = [r#"{"msg": "x"}"#, r#"{"msg": "y"}"#, r#"{"msg": "z"}"#].join("\n");
+    let mut data = Data::try_from((&display_options, text.as_str())).unwrap();
+    data.take_config(&mut old, common_fields, row_idx_field_name);
+    assert_eq!(data.selected_row, Some(2));
+}
+
+#[rstest]
+#[case::all(LoadRange::All, &["1", "2", "3", "4", "5"])]
+#[case::head(LoadRange::Head(2), &["1", "2"])]
+#[case::tail(LoadRange::Tail(2), &["4", "5"])]
+#[case::tail_longer_than_input(LoadRange::Tail(9), &["1", "2", "3", "4", "5"])]
+#[case::lines(LoadRange::Lines { first: 2, last: 4 }, &["2", "3", "4"])]
+#[case::lines_past_end(LoadRange::Lines { first: 5, last: 9 }, &["5"])]
+fn partial_load(#[case] load_range: LoadRange, #[case] expected: &[&str]) {
+    let mut display_options = DataDisplayOptions::default();
+    display_options.load_range = load_range;
+    let text = (1..=5)
+        .map(|i| format!(r#"{{"msg": "{i}"}}"#))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let data = Data::try_from((&display_options, text.as_str())).unwrap();
+    let actual: Vec<String> = (0..data.total_len_unfiltered())
+        .map(|i| {
+            data.row_by_real_index(i)
+                .unwrap()
+                .field_value("msg")
+                .display()
+        })
+        .collect();
+    assert_eq!(actual, expected);
+    let expected_partial = (load_range != LoadRange::All).then_some(load_range);
+    assert_eq!(data.partial_load(), expected_partial);
+}
+
+#[test]
+fn row_script_applied_while_parsing() {
+    let mut display_options = DataDisplayOptions::default();
+    display_options.row_script.is_enabled = true;
+    display_options.row_script.script =
+        r#"if row.msg == "drop" { false } else { row.msg = "x" + row.msg; }"#.into();
+    let text = [r#"{"msg": "a"}"#, r#"{"msg": "drop"}"#, r#"{"msg": "b"}"#].join("\n");
+    let mut data = Data::try_from((&display_options, text.as_str())).unwrap();
+    data.append_lines(
+        &display_options,
+        [r#"{"msg": "drop"}"#, r#"{"msg": "c"}"#],
+        display_options.common_fields(),
+    )
+    .unwrap();
+    let actual: Vec<(String, String)> = (0..data.total_len_unfiltered())
+        .map(|i| {
+            let row = data.row_by_real_index(i).unwrap();
+            (
+                row.field_value("msg").display(),
+                row.field_value("row#").display(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        actual,
+        [("xa", "0"), ("xb", "2"), ("xc", "4")].map(|(a, b)| (a.to_string(), b.to_string()))
+    );
+
+    display_options.row_script.script = "row.msg = ".into();
+    assert!(Data::try_from((&display_options, text.as_str())).is_err());
+}
+
+#[test]
+fn nested_objects_flattened_to_max_depth() {
+    let mut display_options = DataDisplayOptions::default();
+    display_options.row_idx_field_name = None;
+    display_options.level_conversion = None;
+    display_options.flatten_nested.is_enabled = true;
+    display_options.flatten_nested.max_depth = 2;
+    let text = r#"{"req": {"method": "GET", "headers": {"host": "a", "extra": {"x": 1}}, "empty": {}}, "req.method": "kept", "tags": [{"a": 1}]}"#;
+    let row = OwnedLogRow::try_from((&display_options, 0, text)).unwrap();
+    let actual = serde_json::to_value(row.as_row().fields().collect::<BTreeMap<_, _>>()).unwrap();
+    assert_eq!(
+        actual,
+        serde_json::json!({
+            "req.method": "kept",
+            "req.headers.host": "a",
+            "req.headers.extra": {"x": 1},
+            "req.empty": {},
+            "tags": [{"a": 1}],
+        })
+    );
+}
+
+#[test]
+fn filter_and_columns_use_paths_into_nested_values() {
+    let display_options = DataDisplayOptions::default();
+    let common_fields = display_options.common_fields();
+    let text = [
+        r#"{"req": {"method": "GET"}, "spans": [{"name": "db"}]}"#,
+        r#"{"req": {"method": "POST"}, "spans": []}"#,
+        r#"{"msg": "no request"}"#,
+    ]
+    .join("\n");
+    let mut data = Data::try_from((&display_options, text.as_str())).unwrap();
+    let row = data.row_by_real_index(0).unwrap();
+    assert_eq!(row.field_value("spans[0].name").display(), "db");
+
+    let mut filter = FilterConfig {
+        search_key: "post".to_string(),
+        filter_on: filter::FilterOn::Field(FieldSpecifier {
+            name: "req.method".to_string(),
+        }),
+        is_case_sensitive: false,
+        comparator: Comparator::Equal,
+    };
+    assert!(!data.row_matches(0, &filter, common_fields));
+    assert!(data.row_matches(1, &filter, common_fields));
+
+    filter.comparator = Comparator::IsMissing;
+    assert!(!data.row_matches(0, &filter, common_fields));
+    assert!(data.row_matches(2, &filter, common_fields));
+
+    filter.comparator = Comparator::Contains;
+    filter.search_key = "G".to_string();
+    data.filter = Some(filter);
+    data.apply_filter(common_fields);
+    data.selected_row = Some(0);
+    let (slice, matching) = data
+        .selected_row_data_as_slice_with_filter_matching_fields(common_fields)
+        .unwrap();
+    assert_eq!(matching.len(), 1);
+    assert_eq!(slice[matching[0]].0, "req", "top level field of the path");
+}
+
+#[test]
+fn sort_by_multiple_keys_keeps_selection_and_filter() {
+    let display_options = DataDisplayOptions::default();
+    let common_fields = display_options.common_fields();
+    let text = [
+        r#"{"svc": "b", "ms": 5, "msg": "x"}"#,
+        r#"{"svc": "a", "ms": 20, "msg": "x"}"#,
+        r#"{"svc": "b", "ms": 100, "msg": "y"}"#,
+        r#"{"ms": 1, "msg": "x"}"#,
+        r#"{"svc": "a", "ms": 3, "msg": "x"}"#,
+    ]
+    .join("\n");
+    let mut data = Data::try_from((&display_options, text.as_str())).unwrap();
+    data.selected_row = Some(2);
+    let sort = sort::SortConfig {
+        keys: vec![
+            sort::SortKey {
+                field_name: "svc".into(),
+                ..Default::default()
+            },
+            sort::SortKey {
+                field_name: "ms".into(),
+                is_descending: true,
+                compare_as: sort::SortCompare::Numeric,
+            },
+        ],
+    };
+    data.set_sort(Some(sort), common_fields);
+    assert_eq!(data.filtered_rows, Some(vec![1, 4, 2, 0, 3]));
+    assert_eq!(data.selected_real_index(), Some(2));
+    assert_eq!(data.visible_real_indices(), vec![0, 1, 2, 3, 4]);
+
+    data.filter = Some(FilterConfig {
+        search_key: "x".into(),
+        filter_on: filter::FilterOn::Field(FieldSpecifier { name: "msg".into() }),
+        ..Default::default()
+    });
+    data.apply_filter(common_fields);
+    assert_eq!(data.filtered_rows, Some(vec![1, 4, 0, 3]));
+    data.select_real_index(0, common_fields);
+    assert_eq!(data.selected_row, Some(2));
+
+    data.set_sort(None, common_fields);
+    assert_eq!(data.filtered_rows, Some(vec![0, 1, 3, 4]));
+    assert_eq!(data.selected_row, Some(0));
+}
+
+#[test]
+fn row_heights_recomputed_when_visible_rows_or_text_height_change() {
+    let display_options = DataDisplayOptions::default();
+    let common_fields = display_options.common_fields();
+    let text = [
+        r#"{"msg": "one"}"#,
+        r#"{"msg": "two\nlines"}"#,
+        r#"{"msg": "three\nlines\nhere"}"#,
+    ]
+    .join("\n");
+    let mut data = Data::try_from((&display_options, text.as_str())).unwrap();
+    let mut settings = RowHeight::default();
+    assert_eq!(*data.row_heights(&settings, 10.0), [10.0, 20.0, 30.0]);
+    assert_eq!(*data.row_heights(&settings, 5.0), [5.0, 10.0, 15.0]);
+
+    settings.max_lines = Some(2);
+    assert_eq!(*data.row_heights(&settings, 5.0), [5.0, 10.0, 10.0]);
+
+    data.filter = Some(FilterConfig {
+        search_key: "lines".into(),
+        ..Default::default()
+    });
+    data.apply_filter(common_fields);
+    assert_eq!(*data.row_heights(&settings, 5.0), [10.0, 10.0]);
+}
+
+#[test]
+fn display_str_only_allocates_for_non_strings() {
+    let text = serde_json::Value::from("text");
+    let number = serde_json::Value::from(42);
+    assert!(matches!(
+        FieldContent::Present(&text).display_str(),
+        Cow::Borrowed("text")
+    ));
+    assert!(matches!(
+        FieldContent::Missing.display_str(),
+        Cow::Borrowed(FieldContent::TEXT_FOR_EMPTY)
+    ));
+    assert_eq!(FieldContent::Present(&number).display_str(), "42");
+}
+
+#[test]
+fn dropping_oldest_rows_keeps_hidden_rows_and_selection() {
+    let display_options = DataDisplayOptions::default();
+    let common_fields = display_options.common_fields();
+    let text = (0..6)
+        .map(|i| format!(r#"{{"msg": "row {i}"}}"#))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let mut data = Data::try_from((&display_options, text.as_str())).unwrap();
+    // Hide rows 1 and 4 then select row 5
+    data.selected_row = Some(1);
+    data.hide_selected();
+    data.selected_row = Some(3);
+    data.hide_selected();
+    data.select_real_index(5, common_fields);
+
+    data.drop_oldest_rows(2, common_fields);
+    assert_eq!(data.total_len_unfiltered(), 4);
+    assert_eq!(data.dropped_count(), 2);
+    assert_eq!(data.hidden_count(), 1);
+    assert_eq!(data.visible_real_indices(), vec![0, 1, 3]);
+    assert_eq!(data.selected_real_index(), Some(3));
+    assert_eq!(
+        data.selected_row().unwrap().field_value("msg").display(),
+        "row 5"
+    );
+
+    // Dropping the selected row clears the selection
+    data.drop_oldest_rows(10, common_fields);
+    assert_eq!(data.total_len_unfiltered(), 0);
+    assert_eq!(data.selected_row, None);
+}
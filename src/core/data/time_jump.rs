@@ -0,0 +1,146 @@
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime};
+
+/// Rows on each side of the binary search result checked for a closer time (covers small unsorted sections)
+const TOLERANCE_ROWS: usize = 500;
+
+/// Reads the time to jump to.
+///
+/// Accepts RFC 3339 (eg. `2024-05-01T14:32:00Z`), a date and time without an offset (eg. `2024-05-01 14:32`),
+/// a date on its own or only a time (eg. `14:32` or `14:32:05.250`).
+/// Missing parts (offset and date) are taken from `reference` (eg. the time of the selected row), UTC is used if there is no reference
+pub fn parse_target(
+    text: &str,
+    reference: Option<DateTime<FixedOffset>>,
+) -> Option<DateTime<FixedOffset>> {
+    let text = text.trim();
+    if let Ok(result) = DateTime::parse_from_rfc3339(text) {
+        return Some(result);
+    }
+    let offset = reference.map_or_else(|| FixedOffset::east_opt(0).unwrap(), |x| *x.offset());
+    let date_time = [
+        "%Y-%m-%d %H:%M:%S%.f",
+        "%Y-%m-%dT%H:%M:%S%.f",
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%dT%H:%M",
+    ]
+    .into_iter()
+    .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+    .or_else(|| {
+        let date = NaiveDate::parse_from_str(text, "%Y-%m-%d").ok()?;
+        Some(date.and_time(NaiveTime::MIN))
+    })
+    .or_else(|| {
+        let time = ["%H:%M:%S%.f", "%H:%M"]
+            .into_iter()
+            .find_map(|format| NaiveTime::parse_from_str(text, format).ok())?;
+        Some(reference?.date_naive().and_time(time))
+    })?;
+    date_time.and_local_timezone(offset).single()
+}
+
+/// Position (in `0..len`) of the row with the time closest to `target` where `time_at` returns the time of a row (if it has one).
+///
+/// Binary searches assuming the rows are sorted by time then checks the rows around the result in case they are not.
+/// Falls back to checking every row if the rows around the result do not surround the target (eg. the rows are not sorted at all)
+pub fn nearest_position(
+    len: usize,
+    time_at: impl Fn(usize) -> Option<DateTime<FixedOffset>>,
+    target: DateTime<FixedOffset>,
+) -> Option<usize> {
+    // Finds the first row at or after `position` (but before `end`) that has a time
+    let time_from = |position: usize, end: usize| {
+        (position..end).find_map(|i| time_at(i).map(|time| (i, time)))
+    };
+    let mut low = 0;
+    let mut high = len;
+    while low < high {
+        let mid = low + (high - low) / 2;
+        match time_from(mid, high) {
+            Some((i, time)) if time < target => low = i + 1,
+            Some(_) => high = mid,
+            // No times between mid and high
+            None => high = mid,
+        }
+    }
+
+    let closest = |range: std::ops::Range<usize>| {
+        let mut has_before = false;
+        let mut has_after = false;
+        let result = range
+            .filter_map(|i| {
+                let time = time_at(i)?;
+                has_before |= time <= target;
+                has_after |= time >= target;
+                Some(((time - target).abs(), i))
+            })
+            .min();
+        (result.map(|(_, i)| i), has_before && has_after)
+    };
+    let window = low.saturating_sub(TOLERANCE_ROWS)..(low + TOLERANCE_ROWS).min(len);
+    let is_whole = window.len() == len;
+    match closest(window) {
+        (Some(position), true) => Some(position),
+        (result, _) if is_whole => result,
+        // Either the target is outside the times in the log or the rows are not sorted so check them all
+        _ => closest(0..len).0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+
+    fn time(text: &str) -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339(text).unwrap()
+    }
+
+    #[rstest]
+    #[case::rfc3339("2024-05-01T14:32:00+02:00", "2024-05-01T14:32:00+02:00")]
+    #[case::no_offset("2024-05-02 14:32", "2024-05-02T14:32:00+01:00")]
+    #[case::date_only("2024-05-02", "2024-05-02T00:00:00+01:00")]
+    #[case::time_only(" 14:32 ", "2024-05-01T14:32:00+01:00")]
+    #[case::time_with_fraction("14:32:05.250", "2024-05-01T14:32:05.250+01:00")]
+    fn targets(#[case] input: &str, #[case] expected: &str) {
+        let reference = time("2024-05-01T23:59:00+01:00");
+        assert_eq!(parse_target(input, Some(reference)), Some(time(expected)));
+    }
+
+    #[test]
+    fn time_only_needs_reference() {
+        assert_eq!(parse_target("14:32", None), None);
+        assert_eq!(parse_target("soon", None), None);
+    }
+
+    #[test]
+    fn nearest_in_sorted_and_unsorted() {
+        let at = |seconds: &[Option<u32>]| {
+            let times: Vec<_> = seconds
+                .iter()
+                .map(|x| x.map(|s| time(&format!("2024-05-01T00:00:{s:02}Z"))))
+                .collect();
+            move |target: u32| {
+                let times = times.clone();
+                nearest_position(
+                    times.len(),
+                    |i| times[i],
+                    time(&format!("2024-05-01T00:00:{target:02}Z")),
+                )
+            }
+        };
+        let sorted = at(&[Some(10), None, Some(20), Some(30), None]);
+        assert_eq!(sorted(0), Some(0));
+        assert_eq!(sorted(19), Some(2));
+        assert_eq!(sorted(26), Some(3));
+        assert_eq!(sorted(59), Some(3));
+
+        let unsorted = at(&[Some(30), Some(10), Some(50), Some(20)]);
+        assert_eq!(unsorted(21), Some(3));
+        assert_eq!(unsorted(49), Some(2));
+
+        assert_eq!(at(&[None, None])(10), None);
+        assert_eq!(at(&[])(10), None);
+    }
+}
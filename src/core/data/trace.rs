@@ -0,0 +1,402 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, FixedOffset};
+
+use super::{Data, FieldContent, LogRow};
+
+/// Field names used to reconstruct requests and their spans from the rows
+#[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq, Eq, Clone)]
+#[serde(default)]
+pub struct TraceSettings {
+    /// Rows sharing a value in this field are grouped into one request
+    pub request_id_field_name: String,
+    /// Field that holds the span markers (eg. `[HTTP REQUEST - START]` or the `new`/`close` of span events)
+    pub message_field_name: String,
+    /// Field with the name of the span on span events (eg. `new`, `enter`, `exit` and `close` from `tracing-subscriber`)
+    pub span_name_field_name: String,
+    pub time_field_name: String,
+    /// If present on the row that ends a span this is used as the span's duration
+    pub elapsed_field_name: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct RequestTrace {
+    pub request_id: String,
+    /// Indices (into all rows not only filtered) of the rows belonging to this request in file order
+    pub rows: Vec<usize>,
+    pub start: Option<DateTime<FixedOffset>>,
+    pub end: Option<DateTime<FixedOffset>>,
+    pub nodes: Vec<TraceNode>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum TraceNode {
+    Span(SpanNode),
+    Event {
+        row: usize,
+        time: Option<DateTime<FixedOffset>>,
+    },
+}
+
+#[derive(Debug, PartialEq)]
+pub struct SpanNode {
+    pub name: String,
+    pub start_row: usize,
+    /// None if the end of the span was not found
+    pub end_row: Option<usize>,
+    pub start_time: Option<DateTime<FixedOffset>>,
+    pub end_time: Option<DateTime<FixedOffset>>,
+    pub duration_ms: Option<f64>,
+    pub children: Vec<TraceNode>,
+    /// Set if the span was started by an `enter` event so it ends at the matching `exit` instead of at `close`
+    is_started_by_enter: bool,
+}
+
+/// The role a row plays in a span based on the markers used by `tracing_bunyan_formatter` or span events
+#[derive(Debug, PartialEq, Eq)]
+enum SpanMarker<'a> {
+    Start(&'a str),
+    End(&'a str),
+    Enter(&'a str),
+    Exit(&'a str),
+    Other,
+}
+
+impl Default for TraceSettings {
+    fn default() -> Self {
+        Self {
+            request_id_field_name: "request_id".into(),
+            message_field_name: "msg".into(),
+            span_name_field_name: "otel.name".into(),
+            time_field_name: "time".into(),
+            elapsed_field_name: "elapsed_milliseconds".into(),
+        }
+    }
+}
+
+impl RequestTrace {
+    /// Total time covered by the rows of the request in milliseconds
+    pub fn duration_ms(&self) -> Option<f64> {
+        Some(duration_between_ms(self.start?, self.end?))
+    }
+
+    /// Offset in milliseconds from the start of the request
+    pub fn offset_ms(&self, time: Option<DateTime<FixedOffset>>) -> Option<f64> {
+        Some(duration_between_ms(self.start?, time?))
+    }
+}
+
+impl SpanNode {
+    fn new(name: &str, start_row: usize, start_time: Option<DateTime<FixedOffset>>) -> Self {
+        Self {
+            name: name.to_string(),
+            start_row,
+            end_row: None,
+            start_time,
+            end_time: None,
+            duration_ms: None,
+            children: Vec::new(),
+            is_started_by_enter: false,
+        }
+    }
+}
+
+impl<'a> From<&'a str> for SpanMarker<'a> {
+    fn from(value: &'a str) -> Self {
+        let Some(rest) = value.strip_prefix('[') else {
+            return Self::Other;
+        };
+        if let Some(name) = rest.strip_suffix(" - START]") {
+            Self::Start(name)
+        } else if let Some(name) = rest.strip_suffix(" - END]") {
+            Self::End(name)
+        } else {
+            Self::Other
+        }
+    }
+}
+
+impl<'a> SpanMarker<'a> {
+    /// Span events name the span in a separate field. Only `new`/`close` are used if a span has both so entering the
+    /// span again (eg. in async code) does not start another span
+    fn from_span_event(msg: &str, span_name: Option<&'a str>) -> Self {
+        let Some(name) = span_name else {
+            return Self::Other;
+        };
+        match msg {
+            "new" => Self::Start(name),
+            "close" => Self::End(name),
+            "enter" => Self::Enter(name),
+            "exit" => Self::Exit(name),
+            _ => Self::Other,
+        }
+    }
+}
+
+impl FieldContent<'_> {
+    /// Attempts to interpret the value as a RFC 3339 timestamp
+    pub fn as_time(&self) -> Option<DateTime<FixedOffset>> {
+        match self {
+            FieldContent::Present(val) => DateTime::parse_from_rfc3339(val.as_str()?).ok(),
+            FieldContent::Missing => None,
+        }
+    }
+}
+
+fn duration_between_ms(start: DateTime<FixedOffset>, end: DateTime<FixedOffset>) -> f64 {
+    (end - start).num_microseconds().unwrap_or(i64::MAX) as f64 / 1000.0
+}
+
+/// Removes spans from the stack until one named `name` is found and closes it.
+/// Spans that were removed without a matching end are attached to their parent unclosed.
+///
+/// Returns false if no span with that name was open (stack is left unchanged)
+fn close_span(
+    stack: &mut Vec<SpanNode>,
+    roots: &mut Vec<TraceNode>,
+    name: &str,
+    row_idx: usize,
+    row: LogRow<'_>,
+    settings: &TraceSettings,
+) -> bool {
+    if !stack.iter().any(|x| x.name == name) {
+        return false;
+    }
+    while let Some(mut span) = stack.pop() {
+        let is_match = span.name == name;
+        if is_match {
+            span.end_row = Some(row_idx);
+            span.end_time = row.field_value(&settings.time_field_name).as_time();
+            span.duration_ms = match row.field_value(&settings.elapsed_field_name) {
+                FieldContent::Present(val) => val.as_f64(),
+                FieldContent::Missing => None,
+            }
+            .or_else(|| Some(duration_between_ms(span.start_time?, span.end_time?)));
+        }
+        match stack.last_mut() {
+            Some(parent) => parent.children.push(TraceNode::Span(span)),
+            None => roots.push(TraceNode::Span(span)),
+        }
+        if is_match {
+            break;
+        }
+    }
+    true
+}
+
+impl Data {
+    /// Groups all rows (ignoring any filter) by request and builds the tree of spans for each request
+    pub fn build_traces(&self, settings: &TraceSettings) -> Vec<RequestTrace> {
+        let mut order: Vec<String> = Vec::new();
+        let mut in_progress: BTreeMap<String, (RequestTrace, Vec<SpanNode>)> = BTreeMap::new();
+
+        for (row_idx, row) in self.rows.iter().enumerate() {
+            let FieldContent::Present(request_id) =
+                row.field_value(&settings.request_id_field_name)
+            else {
+                continue;
+            };
+            let request_id = FieldContent::Present(request_id).display();
+            let (trace, stack) = in_progress.entry(request_id.clone()).or_insert_with(|| {
+                order.push(request_id.clone());
+                (
+                    RequestTrace {
+                        request_id,
+                        rows: Vec::new(),
+                        start: None,
+                        end: None,
+                        nodes: Vec::new(),
+                    },
+                    Vec::new(),
+                )
+            });
+            trace.rows.push(row_idx);
+            let time = row.field_value(&settings.time_field_name).as_time();
+            if let Some(time) = time {
+                trace.start = Some(trace.start.map_or(time, |x| x.min(time)));
+                trace.end = Some(trace.end.map_or(time, |x| x.max(time)));
+            }
+
+            let msg = row.field_value(&settings.message_field_name).display();
+            let marker = match SpanMarker::from(msg.as_str()) {
+                SpanMarker::Other => SpanMarker::from_span_event(
+                    &msg,
+                    row.field_value(&settings.span_name_field_name).as_str(),
+                ),
+                marker => marker,
+            };
+            let innermost = stack.last();
+            match marker {
+                SpanMarker::Start(name) => stack.push(SpanNode::new(name, row_idx, time)),
+                SpanMarker::Enter(name) if innermost.is_none_or(|x| x.name != name) => {
+                    let mut span = SpanNode::new(name, row_idx, time);
+                    span.is_started_by_enter = true;
+                    stack.push(span);
+                }
+                SpanMarker::Exit(name)
+                    if innermost.is_some_and(|x| x.name == name && x.is_started_by_enter) =>
+                {
+                    close_span(stack, &mut trace.nodes, name, row_idx, row, settings);
+                }
+                SpanMarker::End(name)
+                    if close_span(stack, &mut trace.nodes, name, row_idx, row, settings) => {}
+                SpanMarker::End(_)
+                | SpanMarker::Enter(_)
+                | SpanMarker::Exit(_)
+                | SpanMarker::Other => {
+                    let event = TraceNode::Event { row: row_idx, time };
+                    match stack.last_mut() {
+                        Some(span) => span.children.push(event),
+                        None => trace.nodes.push(event),
+                    }
+                }
+            }
+        }
+
+        order
+            .into_iter()
+            .map(|request_id| {
+                let (mut trace, mut stack) = in_progress
+                    .remove(&request_id)
+                    .expect("every id in order was inserted into the map");
+                // Attach any spans that were never closed
+                while let Some(span) = stack.pop() {
+                    match stack.last_mut() {
+                        Some(parent) => parent.children.push(TraceNode::Span(span)),
+                        None => trace.nodes.push(TraceNode::Span(span)),
+                    }
+                }
+                trace
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::core::data_display_options::DataDisplayOptions;
+
+    #[test]
+    fn span_markers() {
+        assert_eq!(
+            SpanMarker::from("[HTTP REQUEST - START]"),
+            SpanMarker::Start("HTTP REQUEST")
+        );
+        assert_eq!(
+            SpanMarker::from("[HTTP REQUEST - END]"),
+            SpanMarker::End("HTTP REQUEST")
+        );
+        assert_eq!(
+            SpanMarker::from("[HTTP REQUEST - EVENT] Error"),
+            SpanMarker::Other
+        );
+        assert_eq!(SpanMarker::from("starting 8 workers"), SpanMarker::Other);
+        assert_eq!(
+            SpanMarker::from_span_event("new", Some("GET /users")),
+            SpanMarker::Start("GET /users")
+        );
+        assert_eq!(
+            SpanMarker::from_span_event("exit", Some("GET /users")),
+            SpanMarker::Exit("GET /users")
+        );
+        assert_eq!(
+            SpanMarker::from_span_event("close", None),
+            SpanMarker::Other
+        );
+        assert_eq!(
+            SpanMarker::from_span_event("rows fetched", Some("GET /users")),
+            SpanMarker::Other
+        );
+    }
+
+    #[test]
+    fn spans_from_span_events() {
+        let input = [
+            r#"{"request_id":"a","msg":"new","otel.name":"GET /users","time":"2024-02-10T06:02:21.000Z"}"#,
+            r#"{"request_id":"a","msg":"enter","otel.name":"GET /users","time":"2024-02-10T06:02:21.001Z"}"#,
+            r#"{"request_id":"a","msg":"new","otel.name":"db query","time":"2024-02-10T06:02:21.002Z"}"#,
+            r#"{"request_id":"a","msg":"enter","otel.name":"db query","time":"2024-02-10T06:02:21.003Z"}"#,
+            r#"{"request_id":"a","msg":"rows fetched","time":"2024-02-10T06:02:21.004Z"}"#,
+            r#"{"request_id":"a","msg":"exit","otel.name":"db query","time":"2024-02-10T06:02:21.005Z"}"#,
+            r#"{"request_id":"a","msg":"close","otel.name":"db query","time":"2024-02-10T06:02:21.006Z"}"#,
+            r#"{"request_id":"a","msg":"exit","otel.name":"GET /users","time":"2024-02-10T06:02:21.009Z"}"#,
+            r#"{"request_id":"a","msg":"close","otel.name":"GET /users","time":"2024-02-10T06:02:21.010Z"}"#,
+            // Only enter and exit logged
+            r#"{"request_id":"b","msg":"enter","otel.name":"job","time":"2024-02-10T06:02:22.000Z"}"#,
+            r#"{"request_id":"b","msg":"exit","otel.name":"job","time":"2024-02-10T06:02:22.003Z"}"#,
+        ]
+        .join("\n");
+        let data = Data::try_from((&DataDisplayOptions::default(), &input[..])).unwrap();
+        let traces = data.build_traces(&Default::default());
+
+        let [a, b] = &traces[..] else {
+            panic!("expected two requests but got {traces:?}")
+        };
+        let [TraceNode::Span(request)] = &a.nodes[..] else {
+            panic!("expected exactly one root span but got {:?}", a.nodes)
+        };
+        assert_eq!(request.name, "GET /users");
+        assert_eq!((request.start_row, request.end_row), (0, Some(8)));
+        assert_eq!(request.duration_ms, Some(10.0));
+        let [TraceNode::Event { row: 1, .. }, TraceNode::Span(query), TraceNode::Event { row: 7, .. }] =
+            &request.children[..]
+        else {
+            panic!(
+                "expected the query span between the enter and exit events but got {:?}",
+                request.children
+            )
+        };
+        assert_eq!(query.name, "db query");
+        assert_eq!((query.start_row, query.end_row), (2, Some(6)));
+        assert_eq!(query.duration_ms, Some(4.0));
+        assert_eq!(query.children.len(), 3);
+
+        let [TraceNode::Span(job)] = &b.nodes[..] else {
+            panic!("expected exactly one root span but got {:?}", b.nodes)
+        };
+        assert_eq!((job.name.as_str(), job.end_row), ("job", Some(10)));
+        assert_eq!(job.duration_ms, Some(3.0));
+    }
+
+    #[test]
+    fn nested_spans_and_events() {
+        let input = [
+            r#"{"request_id":"a","msg":"[OUTER - START]","time":"2024-02-10T06:02:21.000Z"}"#,
+            r#"{"request_id":"b","msg":"unrelated","time":"2024-02-10T06:02:21.001Z"}"#,
+            r#"{"request_id":"a","msg":"[INNER - START]","time":"2024-02-10T06:02:21.002Z"}"#,
+            r#"{"request_id":"a","msg":"[INNER - EVENT] hi","time":"2024-02-10T06:02:21.003Z"}"#,
+            r#"{"request_id":"a","msg":"[INNER - END]","time":"2024-02-10T06:02:21.004Z"}"#,
+            r#"{"request_id":"a","msg":"[OUTER - END]","time":"2024-02-10T06:02:21.010Z","elapsed_milliseconds":9}"#,
+        ]
+        .join("\n");
+        let data = Data::try_from((&DataDisplayOptions::default(), &input[..])).unwrap();
+        let traces = data.build_traces(&Default::default());
+
+        assert_eq!(traces.len(), 2);
+        let trace = &traces[0];
+        assert_eq!(trace.request_id, "a");
+        assert_eq!(trace.rows, vec![0, 2, 3, 4, 5]);
+        assert_eq!(trace.duration_ms(), Some(10.0));
+        let [TraceNode::Span(outer)] = &trace.nodes[..] else {
+            panic!("expected exactly one root span but got {:?}", trace.nodes)
+        };
+        assert_eq!(outer.name, "OUTER");
+        assert_eq!(outer.end_row, Some(5));
+        assert_eq!(outer.duration_ms, Some(9.0));
+        let [TraceNode::Span(inner)] = &outer.children[..] else {
+            panic!(
+                "expected exactly one child span but got {:?}",
+                outer.children
+            )
+        };
+        assert_eq!(inner.name, "INNER");
+        assert_eq!(inner.duration_ms, Some(2.0));
+        assert!(matches!(
+            inner.children[..],
+            [TraceNode::Event { row: 3, .. }]
+        ));
+    }
+}
@@ -0,0 +1,134 @@
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+/// Names of the spans the event was recorded in from the outermost to the innermost (eg. `request > db_query`)
+pub const SPAN_CHAIN_FIELD_NAME: &str = "span_chain";
+
+/// Separates the span names in [`SPAN_CHAIN_FIELD_NAME`]
+const SPAN_CHAIN_SEPARATOR: &str = " > ";
+
+/// Converts lines written by the JSON format of `tracing-subscriber` into the fields the default options expect.
+///
+/// - The event's `fields` are moved to the top level (`message` becomes `msg`).
+///   A field with the same name as a top level key is kept as `fields.<name>`
+/// - `timestamp` becomes `time`
+/// - `level` (eg. `INFO`) is converted to the bunyan number so the default level conversion and level filter work.
+///   Levels that are not recognized are moved to `level_str` as is
+/// - The names of `spans` (or `span` if the list is not included) are joined into [`SPAN_CHAIN_FIELD_NAME`]
+#[derive(Default, Debug)]
+pub struct TracingJsonParser;
+
+impl TracingJsonParser {
+    pub fn parse_line(&self, line: &str) -> anyhow::Result<BTreeMap<String, Value>> {
+        let mut result: BTreeMap<String, Value> = serde_json::from_str(line)?;
+
+        if let Some(time) = result.remove("timestamp") {
+            result.entry("time".to_string()).or_insert(time);
+        }
+
+        if let Some(Value::String(level)) = result.get("level") {
+            match bunyan_level(level) {
+                Some(number) => {
+                    result.insert("level".to_string(), number.into());
+                }
+                None => {
+                    // Moved so the level conversion does not warn about every row
+                    let level = result.remove("level").expect("level was just found");
+                    result.insert("level_str".to_string(), level);
+                }
+            }
+        }
+
+        let span_chain = match (result.get("spans"), result.get("span")) {
+            (Some(Value::Array(spans)), _) => Some(span_names(spans.iter())),
+            (_, Some(span)) => Some(span_names(std::iter::once(span))),
+            _ => None,
+        };
+        if let Some(span_chain) = span_chain.filter(|x| !x.is_empty()) {
+            result.insert(SPAN_CHAIN_FIELD_NAME.to_string(), span_chain.into());
+        }
+
+        match result.remove("fields") {
+            Some(Value::Object(fields)) => {
+                for (key, value) in fields {
+                    let key = if key == "message" { "msg".into() } else { key };
+                    if result.contains_key(&key) {
+                        result.insert(format!("fields.{key}"), value);
+                    } else {
+                        result.insert(key, value);
+                    }
+                }
+            }
+            Some(other) => {
+                result.insert("fields".to_string(), other);
+            }
+            None => {}
+        }
+
+        Ok(result)
+    }
+}
+
+/// Tracing only has five levels, they are mapped to the bunyan numbers used by the default level conversion
+fn bunyan_level(level: &str) -> Option<i64> {
+    Some(match level.to_ascii_uppercase().as_str() {
+        "ERROR" => 50,
+        "WARN" => 40,
+        "INFO" => 30,
+        "DEBUG" => 20,
+        "TRACE" => 10,
+        _ => return None,
+    })
+}
+
+fn span_names<'a>(spans: impl Iterator<Item = &'a Value>) -> String {
+    spans
+        .filter_map(|span| span.get("name").and_then(Value::as_str))
+        .collect::<Vec<_>>()
+        .join(SPAN_CHAIN_SEPARATOR)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn fields_lifted_and_level_converted() {
+        let line = r#"{"timestamp":"2024-05-01T10:00:00.123Z","level":"WARN","fields":{"message":"slow query","elapsed_ms":120,"target":"shadowed"},"target":"app::db","span":{"name":"query","table":"users"},"spans":[{"name":"request","id":7},{"name":"query","table":"users"}]}"#;
+        let actual = TracingJsonParser.parse_line(line).unwrap();
+        assert_eq!(
+            serde_json::to_value(actual).unwrap(),
+            json!({
+                "time": "2024-05-01T10:00:00.123Z",
+                "level": 40,
+                "msg": "slow query",
+                "elapsed_ms": 120,
+                "target": "app::db",
+                "fields.target": "shadowed",
+                "span": {"name": "query", "table": "users"},
+                "spans": [{"name": "request", "id": 7}, {"name": "query", "table": "users"}],
+                "span_chain": "request > query",
+            })
+        );
+    }
+
+    #[test]
+    fn span_used_without_spans_and_unknown_level_kept() {
+        let line = r#"{"level":"NOTICE","fields":{"message":"hi"},"span":{"name":"only"}}"#;
+        let actual = TracingJsonParser.parse_line(line).unwrap();
+        assert_eq!(
+            serde_json::to_value(actual).unwrap(),
+            json!({
+                "level_str": "NOTICE",
+                "msg": "hi",
+                "span": {"name": "only"},
+                "span_chain": "only",
+            })
+        );
+        assert!(TracingJsonParser.parse_line("not json").is_err());
+    }
+}
@@ -0,0 +1,1013 @@
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+    sync::OnceLock,
+};
+
+use ecolor::Color32;
+
+use super::data::{filter::Comparator, line_parser::ParserRegistry, FieldContent, LogRow};
+
+/// Used as the level values when levels are not converted
+const DEFAULT_LEVELS: [&str; 6] = ["Trace", "Debug", "Info", "Warn", "Error", "Fatal"];
+
+#[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq, Eq, Clone)]
+#[serde(default)] // if we add new fields, give them default values when deserializing old state
+pub struct DataDisplayOptions {
+    main_list_fields: Vec<String>,
+
+    /// Lists fields to show last as they are not unique to a request
+    common_fields: BTreeSet<String>,
+
+    /// The field to use to highlight other related log entries
+    ///
+    /// WARNING: This must be a valid index into the list as this is assumed in method implementations
+    emphasize_if_matching_field_idx: Option<usize>,
+
+    /// When set adds a field with this name and populates it with the row numbers (Skips record if field name already exists)
+    pub row_idx_field_name: Option<String>,
+
+    /// Controls how errors during file loading are treated
+    pub row_parse_error_handling: RowParseErrorHandling,
+
+    /// How lines are converted into rows
+    pub log_format: LogFormat,
+
+    /// Used by [`LogFormat::Regex`], fields are taken from the named groups
+    pub regex_pattern: String,
+
+    /// Parsers added by users of the library (not saved as they are registered each time the app starts)
+    #[serde(skip)]
+    pub parsers: ParserRegistry,
+
+    /// Which lines of the file are loaded (allows looking at part of a very large file)
+    pub load_range: LoadRange,
+
+    /// Nested objects are replaced by their fields with dotted names (eg. `req.headers.host`) as rows are parsed
+    pub flatten_nested: FlattenNested,
+
+    /// Script run on each row as it is parsed
+    pub row_script: RowScriptSettings,
+
+    /// Reduces memory used by rows hidden by filters
+    pub cold_storage: ColdStorageSettings,
+
+    /// Limits the rows kept while receiving rows from a live source
+    pub live_row_limit: LiveRowLimit,
+
+    /// Used for optionally converting message levels to strings
+    pub level_conversion: Option<LevelConversion>,
+
+    /// Rows with a less severe level are hidden (regardless of other filters), None shows all levels
+    pub min_level: Option<String>,
+
+    /// Numeric fields to show range sliders for in the filter area
+    pub range_filter_fields: Vec<String>,
+
+    /// Fields always shown at the top of the details pane (in this order) when present
+    pub pinned_fields: Vec<String>,
+
+    /// Text colors for values of fields in the main list, keyed by field name then by the displayed value
+    pub colored_fields: BTreeMap<String, BTreeMap<String, Color32>>,
+
+    /// Checked in order when the value has no color in [`Self::colored_fields`], the first match sets the color
+    pub color_rules: Vec<ColorRule>,
+
+    /// How the values of fields are shown (the values themselves are not changed), keyed by field name
+    pub field_formatters: BTreeMap<String, FieldFormatter>,
+
+    /// Colors whole rows that match a condition (the first enabled rule that matches is used)
+    pub row_highlights: Vec<RowHighlightRule>,
+
+    /// Tints the background of whole rows using the color of their level
+    pub row_tint: RowTint,
+
+    /// Marks places where the time between consecutive rows is large (eg. stalls and restarts)
+    pub time_gaps: TimeGaps,
+
+    /// Decides how many lines tall each row in the main list is
+    pub row_height: RowHeight,
+
+    /// Fields that identify related rows (eg. all rows for a request), the details pane links to other rows with the same value
+    pub correlation_fields: Vec<String>,
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq, Eq, Clone)]
+pub enum RowParseErrorHandling {
+    AbortOnAnyErrors,
+    ConvertFailedLines {
+        raw_line_field_name: String,
+        /// If set the error message from the failure is placed in this field
+        parse_error_field_name: Option<String>,
+    },
+    /// Lines that fail to parse continue the previous row (eg. the lines of a stack trace) and are added on a new line to this field.
+    /// If there is no previous row the line is converted into a row with the line in this field
+    JoinToPreviousRow {
+        field_name: String,
+    },
+}
+
+/// See [`super::data::line_parser`] for the parser used for each format
+#[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq, Eq, Clone, Default)]
+pub enum LogFormat {
+    /// Each line is a JSON object (eg. bunyan)
+    #[default]
+    Json,
+    /// JSON from `tracing-subscriber` with the nested `fields` moved to the top level
+    TracingJson,
+    /// `key=value` pairs
+    Logfmt,
+    /// Uses [`DataDisplayOptions::regex_pattern`]
+    Regex,
+    /// Comma separated values with a header line
+    Csv,
+    /// Plain text from CI runners (eg. GitHub Actions) with optional timestamps and `::group::` markers
+    CiRunner,
+    /// A parser registered by a user of the library with this name
+    Custom(String),
+}
+
+/// Lines of the input that are parsed, the others are skipped without being parsed
+#[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum LoadRange {
+    #[default]
+    All,
+    /// The first N lines
+    Head(usize),
+    /// The last N lines
+    Tail(usize),
+    /// From line `first` to line `last` inclusive (numbered from 1)
+    Lines { first: usize, last: usize },
+}
+
+/// See [`super::data::row_script::RowScript`] for what the script can do
+#[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq, Eq, Clone, Default)]
+#[serde(default)]
+pub struct RowScriptSettings {
+    pub is_enabled: bool,
+    pub script: String,
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq, Eq, Clone)]
+#[serde(default)]
+pub struct FlattenNested {
+    pub is_enabled: bool,
+    /// Objects nested deeper than this are kept as a single field
+    pub max_depth: usize,
+}
+
+/// Rows not visible are kept only as JSON text (parsed again when needed) when few rows are visible
+#[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq, Eq, Clone)]
+#[serde(default)]
+pub struct ColdStorageSettings {
+    pub is_enabled: bool,
+    /// Only used when the visible rows are at most this percentage of all rows
+    pub max_visible_percent: u8,
+}
+
+/// The oldest rows are dropped once there are more than `max_rows` so memory does not grow without bound while following
+#[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq, Eq, Clone)]
+#[serde(default)]
+pub struct LiveRowLimit {
+    pub is_enabled: bool,
+    pub max_rows: usize,
+}
+
+/// Colors values of a field that match a condition (for fields with too many values to list each one)
+#[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq, Eq, Clone)]
+#[serde(default)]
+pub struct ColorRule {
+    pub field_name: String,
+    pub condition: ColorCondition,
+    pub color: Color32,
+    /// Compiled from the pattern of [`ColorCondition::Regex`] on first use
+    #[serde(skip)]
+    regex: RegexCache,
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq, Eq, Clone)]
+pub enum ColorCondition {
+    /// The displayed value matches the regular expression (eg. `^5\d\d$`)
+    Regex(String),
+    /// The value is a number within the bounds (inclusive), a missing bound is unlimited
+    Range { min: Option<i64>, max: Option<i64> },
+}
+
+/// Cleared by [`ColorRule::invalidate`] when the pattern changes. Ignored when comparing rules
+#[derive(Default, Debug, Clone)]
+struct RegexCache(OnceLock<Result<regex::Regex, String>>);
+
+impl PartialEq for RegexCache {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for RegexCache {}
+
+/// Colors a whole row when the value of a field matches (eg. `elapsed_milliseconds` greater than 1000)
+#[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq, Eq, Clone)]
+#[serde(default)]
+pub struct RowHighlightRule {
+    pub is_enabled: bool,
+    pub field_name: String,
+    /// Compares as numbers if both the value and [`Self::value`] are numbers
+    pub comparator: Comparator,
+    pub value: String,
+    pub background: Option<Color32>,
+    pub text_color: Option<Color32>,
+}
+
+/// Uses the colors set for the level field in [`DataDisplayOptions::colored_fields`]
+#[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq, Eq, Clone)]
+#[serde(default)]
+pub struct RowTint {
+    pub is_enabled: bool,
+    /// Kept low so the striping of rows is still visible
+    pub alpha: u8,
+}
+
+/// Changes how a value is shown, values it does not apply to (eg. text for a numeric formatter) are shown as is
+#[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub enum FieldFormatter {
+    /// Number of bytes shown with binary units (eg. 1.5 KiB)
+    Bytes,
+    /// Seconds since the unix epoch shown as a UTC date and time
+    EpochSeconds,
+    /// Milliseconds since the unix epoch shown as a UTC date and time
+    EpochMillis,
+    /// A duration in milliseconds shown in the largest unit that fits (eg. 1.2 s)
+    Milliseconds,
+    /// Only the first characters are shown (eg. to shorten UUIDs)
+    Truncate(usize),
+}
+
+/// Rows in the main list are as tall as the value with the most lines among the fields
+#[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq, Eq, Clone)]
+#[serde(default)]
+pub struct RowHeight {
+    pub field_names: Vec<String>,
+    /// Rows are never taller than this many lines, None for no limit
+    pub max_lines: Option<usize>,
+}
+
+/// Separator drawn above a row when much time passed since the row before it
+#[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq, Eq, Clone)]
+#[serde(default)]
+pub struct TimeGaps {
+    pub is_enabled: bool,
+    /// Expected to hold RFC 3339 timestamps
+    pub time_field_name: String,
+    pub threshold_ms: u64,
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq, Eq, Clone)]
+pub struct LevelConversion {
+    /// Skips record if field name already exists
+    pub display_field_name: String,
+    /// Skips conversion if source field cannot be found
+    pub source_field_name: String,
+    pub convert_map: BTreeMap<i64, String>,
+}
+
+impl DataDisplayOptions {
+    pub fn main_list_fields(&self) -> &[String] {
+        &self.main_list_fields
+    }
+    pub fn set_main_list_fields(&mut self, fields: Vec<String>) {
+        self.main_list_fields = fields;
+        if self
+            .emphasize_if_matching_field_idx
+            .is_some_and(|x| x >= self.main_list_fields.len())
+        {
+            self.emphasize_if_matching_field_idx = None;
+        }
+    }
+    pub fn emphasize_if_matching_field_idx(&self) -> &Option<usize> {
+        &self.emphasize_if_matching_field_idx
+    }
+    pub fn common_fields(&self) -> &BTreeSet<String> {
+        &self.common_fields
+    }
+
+    pub fn toggle_common_field(&mut self, field_name: &str) {
+        if !self.common_fields.remove(field_name) {
+            self.common_fields.insert(field_name.to_string());
+        }
+    }
+
+    pub fn is_pinned(&self, field_name: &str) -> bool {
+        self.pinned_fields.iter().any(|x| x == field_name)
+    }
+
+    pub fn toggle_pinned(&mut self, field_name: &str) {
+        if self.is_pinned(field_name) {
+            self.pinned_fields.retain(|x| x != field_name);
+        } else {
+            self.pinned_fields.push(field_name.to_string());
+        }
+    }
+
+    pub fn is_correlation_field(&self, field_name: &str) -> bool {
+        self.correlation_fields.iter().any(|x| x == field_name)
+    }
+
+    pub fn toggle_correlation_field(&mut self, field_name: &str) {
+        if self.is_correlation_field(field_name) {
+            self.correlation_fields.retain(|x| x != field_name);
+        } else {
+            self.correlation_fields.push(field_name.to_string());
+        }
+    }
+
+    /// The color to show `value` in if one is set for the field
+    pub fn field_color(&self, field_name: &str, value: &str) -> Option<Color32> {
+        if let Some(color) = self
+            .colored_fields
+            .get(field_name)
+            .and_then(|x| x.get(value))
+        {
+            return Some(*color);
+        }
+        self.color_rules
+            .iter()
+            .find(|rule| rule.field_name == field_name && rule.matches(value))
+            .map(|rule| rule.color)
+    }
+
+    /// The value as it should be shown for the field (formatted if a formatter is set and applies)
+    pub fn formatted_value<'a>(&self, field_name: &str, text: &'a str) -> Cow<'a, str> {
+        match self
+            .field_formatters
+            .get(field_name)
+            .and_then(|x| x.format(text))
+        {
+            Some(formatted) => Cow::Owned(formatted),
+            None => Cow::Borrowed(text),
+        }
+    }
+
+    /// The field that holds the level as shown to the user
+    pub fn level_field_name(&self) -> &str {
+        self.level_conversion
+            .as_ref()
+            .map_or("level_str", |x| x.display_field_name.as_str())
+    }
+
+    /// The level values in order of increasing severity
+    pub fn level_names(&self) -> Vec<&str> {
+        match self.level_conversion.as_ref() {
+            Some(level_conversion) => level_conversion
+                .convert_map
+                .values()
+                .map(String::as_str)
+                .collect(),
+            None => DEFAULT_LEVELS.to_vec(),
+        }
+    }
+
+    /// The levels less severe than [`Self::min_level`] (none if it is not set or not one of the levels)
+    pub fn levels_below_min(&self) -> BTreeSet<String> {
+        let Some(min_level) = self.min_level.as_deref() else {
+            return BTreeSet::new();
+        };
+        let levels = self.level_names();
+        match levels.iter().position(|x| *x == min_level) {
+            Some(position) => levels[..position].iter().map(|x| x.to_string()).collect(),
+            None => BTreeSet::new(),
+        }
+    }
+
+    /// The first enabled highlight rule that matches the row
+    pub fn row_highlight(&self, row: LogRow<'_>) -> Option<&RowHighlightRule> {
+        self.row_highlights
+            .iter()
+            .find(|rule| rule.is_enabled && rule.matches(row))
+    }
+
+    /// The background color for a row with the level `level` if tinting is enabled
+    pub fn row_tint_color(&self, level: &str) -> Option<Color32> {
+        if !self.row_tint.is_enabled {
+            return None;
+        }
+        let [r, g, b, _] = self.field_color(self.level_field_name(), level)?.to_array();
+        Some(Color32::from_rgba_unmultiplied(
+            r,
+            g,
+            b,
+            self.row_tint.alpha,
+        ))
+    }
+
+    /// Returns the order to display the fields in the details pane as indices into `field_names`.
+    /// Pinned fields come first (in the order they were pinned) followed by the rest in their original order
+    pub fn details_display_order<'a>(
+        &self,
+        field_names: impl Iterator<Item = &'a str> + Clone,
+    ) -> Vec<usize> {
+        let mut result: Vec<usize> = self
+            .pinned_fields
+            .iter()
+            .filter_map(|pinned_name| {
+                field_names
+                    .clone()
+                    .position(|field_name| field_name == pinned_name)
+            })
+            .collect();
+        result.extend(
+            field_names
+                .enumerate()
+                .filter(|(_, field_name)| !self.is_pinned(field_name))
+                .map(|(i, _)| i),
+        );
+        result
+    }
+}
+
+impl Default for DataDisplayOptions {
+    fn default() -> Self {
+        Self {
+            // TODO 3: Add ability to show, select and reorder selected fields
+            main_list_fields: [
+                "row#",
+                "level_str",
+                "time",
+                "request_id",
+                "otel.name",
+                "msg",
+            ]
+            .into_iter()
+            .map(String::from)
+            .collect(),
+            common_fields: [
+                "elapsed_milliseconds",
+                "file",
+                "hostname",
+                "http.flavor",
+                "http.host",
+                "http.method",
+                "http.route",
+                "http.scheme",
+                "http.target",
+                "http.user_agent",
+                "level",
+                "line",
+                "name",
+                "otel.kind",
+                "pid",
+                "req",
+                "request_id",
+                "res",
+                "target",
+                "time",
+                "v",
+            ]
+            .into_iter()
+            .map(String::from)
+            .collect(),
+            emphasize_if_matching_field_idx: Some(3),
+            row_idx_field_name: Some("row#".to_string()),
+            row_parse_error_handling: Default::default(),
+            log_format: Default::default(),
+            regex_pattern: r"^(?P<time>\S+) (?P<level_str>\S+) (?P<msg>.*)$".into(),
+            parsers: Default::default(),
+            load_range: Default::default(),
+            flatten_nested: Default::default(),
+            row_script: Default::default(),
+            cold_storage: Default::default(),
+            live_row_limit: Default::default(),
+            level_conversion: Some(Default::default()),
+            min_level: None,
+            range_filter_fields: vec!["elapsed_milliseconds".to_string()],
+            pinned_fields: Vec::new(),
+            colored_fields: [(
+                "level_str".to_string(),
+                [
+                    ("Fatal", Color32::from_rgb(200, 0, 200)),
+                    ("Error", Color32::from_rgb(230, 60, 60)),
+                    ("Warn", Color32::from_rgb(230, 170, 0)),
+                ]
+                .into_iter()
+                .map(|(value, color)| (value.to_string(), color))
+                .collect(),
+            )]
+            .into_iter()
+            .collect(),
+            color_rules: Vec::new(),
+            field_formatters: Default::default(),
+            row_highlights: Vec::new(),
+            row_tint: Default::default(),
+            time_gaps: Default::default(),
+            row_height: Default::default(),
+            correlation_fields: ["request_id", "trace_id", "user_id"]
+                .into_iter()
+                .map(String::from)
+                .collect(),
+        }
+    }
+}
+
+impl Default for FlattenNested {
+    fn default() -> Self {
+        Self {
+            is_enabled: false,
+            max_depth: 3,
+        }
+    }
+}
+
+impl Default for ColdStorageSettings {
+    fn default() -> Self {
+        Self {
+            is_enabled: false,
+            max_visible_percent: 10,
+        }
+    }
+}
+
+impl Default for LiveRowLimit {
+    fn default() -> Self {
+        Self {
+            is_enabled: false,
+            max_rows: 200_000,
+        }
+    }
+}
+
+impl LiveRowLimit {
+    /// Number of the oldest rows to drop to be within the limit
+    pub fn excess(&self, row_count: usize) -> usize {
+        if self.is_enabled {
+            row_count.saturating_sub(self.max_rows)
+        } else {
+            0
+        }
+    }
+}
+
+impl Default for RowTint {
+    fn default() -> Self {
+        Self {
+            is_enabled: false,
+            alpha: 40,
+        }
+    }
+}
+
+impl Default for RowParseErrorHandling {
+    fn default() -> Self {
+        Self::ConvertFailedLines {
+            raw_line_field_name: "msg".into(),
+            parse_error_field_name: Some("parse_err".into()),
+        }
+    }
+}
+
+impl LevelConversion {
+    /// Severities from syslog (RFC 5424) which go from 0 (most severe) to 7
+    pub fn syslog() -> Self {
+        let convert_map = [
+            "Emergency",
+            "Alert",
+            "Critical",
+            "Error",
+            "Warning",
+            "Notice",
+            "Info",
+            "Debug",
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(i, name)| (i as i64, name.to_string()))
+        .collect();
+        Self {
+            display_field_name: "level_str".into(),
+            source_field_name: "severity".into(),
+            convert_map,
+        }
+    }
+}
+
+impl Default for ColorRule {
+    fn default() -> Self {
+        Self::new(String::new(), ColorCondition::Regex(String::new()))
+    }
+}
+
+impl ColorRule {
+    pub fn new(field_name: String, condition: ColorCondition) -> Self {
+        Self {
+            field_name,
+            condition,
+            color: Color32::LIGHT_BLUE,
+            regex: Default::default(),
+        }
+    }
+
+    /// Must be called after the condition is changed
+    pub fn invalidate(&mut self) {
+        self.regex = Default::default();
+    }
+
+    /// An invalid pattern does not match any value
+    pub fn matches(&self, value: &str) -> bool {
+        match &self.condition {
+            ColorCondition::Regex(pattern) => self
+                .compiled_regex(pattern)
+                .as_ref()
+                .is_ok_and(|regex| regex.is_match(value)),
+            ColorCondition::Range { min, max } => {
+                let Ok(number) = value.trim().parse::<f64>() else {
+                    return false;
+                };
+                min.is_none_or(|min| number >= min as f64)
+                    && max.is_none_or(|max| number <= max as f64)
+            }
+        }
+    }
+
+    /// Error message if the condition cannot be used
+    pub fn error(&self) -> Option<String> {
+        match &self.condition {
+            ColorCondition::Regex(pattern) => self.compiled_regex(pattern).as_ref().err().cloned(),
+            ColorCondition::Range { .. } => None,
+        }
+    }
+
+    fn compiled_regex(&self, pattern: &str) -> &Result<regex::Regex, String> {
+        self.regex
+            .0
+            .get_or_init(|| regex::Regex::new(pattern).map_err(|e| e.to_string()))
+    }
+}
+
+impl Default for RowHighlightRule {
+    fn default() -> Self {
+        Self {
+            is_enabled: true,
+            field_name: "elapsed_milliseconds".into(),
+            comparator: Comparator::GreaterThan,
+            value: "1000".into(),
+            background: Some(Color32::from_rgba_unmultiplied(230, 170, 0, 60)),
+            text_color: None,
+        }
+    }
+}
+
+impl RowHighlightRule {
+    pub fn matches(&self, row: LogRow<'_>) -> bool {
+        let field_value = row.field_value(&self.field_name);
+        let is_present = matches!(field_value, FieldContent::Present(_));
+        match self.comparator {
+            Comparator::IsPresent => return is_present,
+            Comparator::IsMissing => return !is_present,
+            _ if !is_present => return false,
+            _ => {}
+        }
+        if let (Some(value), Ok(search_key)) =
+            (field_value.as_f64(), self.value.trim().parse::<f64>())
+        {
+            if let Some(result) = self.comparator.apply_numeric(search_key, value) {
+                return result;
+            }
+        }
+        self.comparator
+            .apply(&self.value, &field_value.display_str())
+    }
+}
+
+impl Default for RowHeight {
+    fn default() -> Self {
+        Self {
+            field_names: vec!["msg".into()],
+            max_lines: None,
+        }
+    }
+}
+
+impl RowHeight {
+    /// Number of lines the row takes up in the main list (at least 1)
+    pub fn line_count(&self, row: LogRow<'_>) -> usize {
+        let count = self
+            .field_names
+            .iter()
+            .map(|name| row.field_value(name).display_str().lines().count())
+            .max()
+            .unwrap_or_default()
+            .max(1);
+        match self.max_lines {
+            Some(max_lines) => count.min(max_lines.max(1)),
+            None => count,
+        }
+    }
+}
+
+impl Default for TimeGaps {
+    fn default() -> Self {
+        Self {
+            is_enabled: true,
+            time_field_name: "time".into(),
+            threshold_ms: 5000,
+        }
+    }
+}
+
+impl FieldFormatter {
+    pub const ALL: [FieldFormatter; 5] = [
+        Self::Bytes,
+        Self::EpochSeconds,
+        Self::EpochMillis,
+        Self::Milliseconds,
+        Self::Truncate(8),
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Bytes => "Bytes",
+            Self::EpochSeconds => "Epoch seconds",
+            Self::EpochMillis => "Epoch milliseconds",
+            Self::Milliseconds => "Duration (ms)",
+            Self::Truncate(_) => "Truncate",
+        }
+    }
+
+    /// Returns None if the formatter does not apply to the value
+    pub fn format(&self, text: &str) -> Option<String> {
+        let number = || text.trim().parse::<f64>().ok().filter(|x| x.is_finite());
+        match *self {
+            Self::Bytes => {
+                const UNITS: [&str; 5] = ["KiB", "MiB", "GiB", "TiB", "PiB"];
+                let mut value = number()?;
+                if value.abs() < 1024.0 {
+                    return Some(format!("{value} B"));
+                }
+                let mut unit = UNITS[0];
+                for next_unit in UNITS {
+                    unit = next_unit;
+                    value /= 1024.0;
+                    if value.abs() < 1024.0 {
+                        break;
+                    }
+                }
+                Some(format!("{value:.1} {unit}"))
+            }
+            Self::EpochSeconds => epoch_millis_text(number()? * 1000.0),
+            Self::EpochMillis => epoch_millis_text(number()?),
+            Self::Milliseconds => {
+                let ms = number()?;
+                Some(match ms.abs() {
+                    x if x < 1000.0 => format!("{ms} ms"),
+                    x if x < 60_000.0 => format!("{:.1} s", ms / 1000.0),
+                    x if x < 3_600_000.0 => format!("{:.1} min", ms / 60_000.0),
+                    _ => format!("{:.1} h", ms / 3_600_000.0),
+                })
+            }
+            Self::Truncate(max_chars) => {
+                let end = text.char_indices().nth(max_chars)?.0;
+                Some(format!("{}…", &text[..end]))
+            }
+        }
+    }
+}
+
+fn epoch_millis_text(millis: f64) -> Option<String> {
+    let time = chrono::DateTime::from_timestamp_millis(millis as i64)?;
+    Some(time.to_rfc3339_opts(chrono::SecondsFormat::Millis, true))
+}
+
+impl TimeGaps {
+    /// Returns the gap if it should be marked
+    pub fn marked_gap(&self, gap: chrono::TimeDelta) -> Option<chrono::TimeDelta> {
+        let threshold = chrono::TimeDelta::milliseconds(self.threshold_ms.try_into().ok()?);
+        (self.is_enabled && gap > threshold).then_some(gap)
+    }
+}
+
+impl LoadRange {
+    /// Returns the number of lines to skip and the max number of lines to parse after that.
+    /// `line_count` is only called if the number of lines in the input is needed
+    pub fn skip_and_take(self, line_count: impl FnOnce() -> usize) -> (usize, usize) {
+        match self {
+            LoadRange::All => (0, usize::MAX),
+            LoadRange::Head(n) => (0, n),
+            LoadRange::Tail(n) => (line_count().saturating_sub(n), n),
+            LoadRange::Lines { first, last } => {
+                let skip = first.saturating_sub(1);
+                (skip, last.saturating_sub(skip))
+            }
+        }
+    }
+}
+
+impl Display for LoadRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoadRange::All => write!(f, "all lines"),
+            LoadRange::Head(n) => write!(f, "first {n} lines"),
+            LoadRange::Tail(n) => write!(f, "last {n} lines"),
+            LoadRange::Lines { first, last } => write!(f, "lines {first} to {last}"),
+        }
+    }
+}
+
+impl Default for LevelConversion {
+    fn default() -> Self {
+        // See bunyan levels https://github.com/trentm/node-bunyan?tab=readme-ov-file#levels and note rust only goes up to Error
+        let convert_map = vec![
+            (60, "Fatal".to_string()),
+            (50, "Error".to_string()),
+            (40, "Warn".to_string()),
+            (30, "Info".to_string()),
+            (20, "Debug".to_string()),
+            (10, "Trace".to_string()),
+        ]
+        .into_iter()
+        .collect();
+        Self {
+            display_field_name: "level_str".into(),
+            source_field_name: "level".into(),
+            convert_map,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::super::data::OwnedLogRow;
+    use super::*;
+
+    #[test]
+    fn field_color_matches_exact_value() {
+        let mut options = DataDisplayOptions::default();
+        options
+            .colored_fields
+            .entry("status".into())
+            .or_default()
+            .insert("500".into(), Color32::RED);
+        assert_eq!(options.field_color("status", "500"), Some(Color32::RED));
+        assert_eq!(options.field_color("status", "200"), None);
+        assert_eq!(options.field_color("other", "500"), None);
+    }
+
+    #[test]
+    fn color_rules_checked_in_order_after_exact_values() {
+        let mut options = DataDisplayOptions::default();
+        options
+            .colored_fields
+            .entry("status".into())
+            .or_default()
+            .insert("503".into(), Color32::YELLOW);
+        options.color_rules = vec![
+            ColorRule {
+                color: Color32::RED,
+                ..ColorRule::new(
+                    "status".into(),
+                    ColorCondition::Range {
+                        min: Some(500),
+                        max: Some(599),
+                    },
+                )
+            },
+            ColorRule {
+                color: Color32::GREEN,
+                ..ColorRule::new("status".into(), ColorCondition::Regex(r"^\d+$".into()))
+            },
+            ColorRule::new("path".into(), ColorCondition::Regex("(".into())),
+        ];
+        assert_eq!(options.field_color("status", "503"), Some(Color32::YELLOW));
+        assert_eq!(options.field_color("status", "500"), Some(Color32::RED));
+        assert_eq!(options.field_color("status", "200"), Some(Color32::GREEN));
+        assert_eq!(options.field_color("status", "ok"), None);
+        assert_eq!(options.field_color("path", "("), None);
+        assert!(options.color_rules[2].error().is_some());
+    }
+
+    #[rstest]
+    #[case::numeric_not_text_order(Comparator::GreaterThan, "1000", r#"{"ms": 5000}"#, true)]
+    #[case::below(Comparator::GreaterThan, "1000", r#"{"ms": 999.5}"#, false)]
+    #[case::number_in_string(Comparator::GreaterThanEqual, "1000", r#"{"ms": "1000"}"#, true)]
+    #[case::missing(Comparator::GreaterThan, "1000", r#"{}"#, false)]
+    #[case::text(Comparator::Contains, "time", r#"{"ms": "timeout"}"#, true)]
+    #[case::is_missing(Comparator::IsMissing, "", r#"{"other": 1}"#, true)]
+    fn row_highlight_rule(
+        #[case] comparator: Comparator,
+        #[case] value: &str,
+        #[case] row_data: &str,
+        #[case] expected: bool,
+    ) {
+        let rule = RowHighlightRule {
+            field_name: "ms".into(),
+            comparator,
+            value: value.into(),
+            ..Default::default()
+        };
+        let row: OwnedLogRow = serde_json::from_str(&format!(r#"{{"data": {row_data}}}"#)).unwrap();
+        assert_eq!(rule.matches(row.as_row()), expected);
+    }
+
+    #[rstest]
+    #[case::default_msg(vec!["msg"], None, r#"{"msg": "a\nb"}"#, 2)]
+    #[case::missing_field(vec!["msg"], None, r#"{"message": "a\nb"}"#, 1)]
+    #[case::most_lines_of_fields(vec!["msg", "event"], None, r#"{"msg": "a", "event": "a\nb\nc"}"#, 3)]
+    #[case::clamped(vec!["msg"], Some(2), r#"{"msg": "a\nb\nc"}"#, 2)]
+    fn row_height_line_count(
+        #[case] field_names: Vec<&str>,
+        #[case] max_lines: Option<usize>,
+        #[case] row_data: &str,
+        #[case] expected: usize,
+    ) {
+        let row_height = RowHeight {
+            field_names: field_names.into_iter().map(String::from).collect(),
+            max_lines,
+        };
+        let row: OwnedLogRow = serde_json::from_str(&format!(r#"{{"data": {row_data}}}"#)).unwrap();
+        assert_eq!(row_height.line_count(row.as_row()), expected);
+    }
+
+    #[test]
+    fn levels_below_min() {
+        let mut options = DataDisplayOptions::default();
+        assert!(options.levels_below_min().is_empty());
+        options.min_level = Some("Warn".into());
+        assert_eq!(
+            options.levels_below_min(),
+            ["Trace", "Debug", "Info"].map(String::from).into()
+        );
+        options.min_level = Some("Unknown".into());
+        assert!(options.levels_below_min().is_empty());
+    }
+
+    #[test]
+    fn row_tint_uses_level_color() {
+        let mut options = DataDisplayOptions::default();
+        assert_eq!(options.row_tint_color("Error"), None);
+
+        options.row_tint.is_enabled = true;
+        options.row_tint.alpha = 50;
+        assert_eq!(
+            options.row_tint_color("Error"),
+            Some(Color32::from_rgba_unmultiplied(230, 60, 60, 50))
+        );
+        assert_eq!(options.row_tint_color("Info"), None);
+    }
+
+    #[rstest]
+    #[case::small_bytes(FieldFormatter::Bytes, "512", Some("512 B"))]
+    #[case::kib(FieldFormatter::Bytes, "1536", Some("1.5 KiB"))]
+    #[case::gib(FieldFormatter::Bytes, "3221225472", Some("3.0 GiB"))]
+    #[case::epoch_seconds(
+        FieldFormatter::EpochSeconds,
+        "1700000000",
+        Some("2023-11-14T22:13:20.000Z")
+    )]
+    #[case::epoch_millis(
+        FieldFormatter::EpochMillis,
+        "1700000000123",
+        Some("2023-11-14T22:13:20.123Z")
+    )]
+    #[case::ms(FieldFormatter::Milliseconds, "850", Some("850 ms"))]
+    #[case::seconds(FieldFormatter::Milliseconds, "1234", Some("1.2 s"))]
+    #[case::minutes(FieldFormatter::Milliseconds, "90000", Some("1.5 min"))]
+    #[case::uuid(
+        FieldFormatter::Truncate(8),
+        "4dd8dcd4-c5a6-400d-b6ba-b29658abe7e4",
+        Some("4dd8dcd4…")
+    )]
+    #[case::short(FieldFormatter::Truncate(8), "abc", None)]
+    #[case::not_a_number(FieldFormatter::Bytes, "lots", None)]
+    fn field_formatters(
+        #[case] formatter: FieldFormatter,
+        #[case] text: &str,
+        #[case] expected: Option<&str>,
+    ) {
+        assert_eq!(formatter.format(text).as_deref(), expected);
+    }
+
+    #[test]
+    fn pinned_fields_displayed_first() {
+        let fields = ["a", "msg", "b", "time"];
+        let mut options = DataDisplayOptions::default();
+        assert_eq!(
+            options.details_display_order(fields.iter().copied()),
+            vec![0, 1, 2, 3]
+        );
+
+        options.toggle_pinned("time");
+        options.toggle_pinned("missing");
+        options.toggle_pinned("msg");
+        assert_eq!(
+            options.details_display_order(fields.iter().copied()),
+            vec![3, 1, 0, 2]
+        );
+
+        options.toggle_pinned("time");
+        assert_eq!(
+            options.details_display_order(fields.iter().copied()),
+            vec![1, 0, 2, 3]
+        );
+    }
+}
@@ -0,0 +1,149 @@
+//! Named sets of display options picked automatically for the files they match
+
+use log::warn;
+use regex::Regex;
+
+use super::data_display_options::DataDisplayOptions;
+
+/// Bytes from the start of a file checked against [`DisplayProfile::content_pattern`]
+pub const SAMPLE_LEN: usize = 8 * 1024;
+
+#[derive(serde::Deserialize, serde::Serialize, Default, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct DisplayProfiles {
+    /// When set the first profile matching a file is used when the file is loaded
+    pub is_auto_select_enabled: bool,
+    /// Checked in order
+    pub profiles: Vec<DisplayProfile>,
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Default, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct DisplayProfile {
+    pub name: String,
+    /// Regex matched against the file name, ignored if empty
+    pub file_name_pattern: String,
+    /// Regex matched against the start of the file (see [`SAMPLE_LEN`]), ignored if empty
+    pub content_pattern: String,
+    pub options: DataDisplayOptions,
+}
+
+impl DisplayProfile {
+    /// True if every pattern set matches (never matches if no pattern is set)
+    pub fn matches(&self, file_name: &str, sample: &str) -> bool {
+        let checks = [
+            (&self.file_name_pattern, file_name),
+            (&self.content_pattern, sample),
+        ];
+        let mut has_pattern = false;
+        for (pattern, text) in checks {
+            if pattern.is_empty() {
+                continue;
+            }
+            has_pattern = true;
+            match Regex::new(pattern) {
+                Ok(regex) if regex.is_match(text) => {}
+                Ok(_) => return false,
+                Err(e) => {
+                    warn!("Invalid pattern in display profile {:?}: {e}", self.name);
+                    return false;
+                }
+            }
+        }
+        has_pattern
+    }
+}
+
+impl DisplayProfiles {
+    /// The first profile that matches the file if auto selection is enabled
+    pub fn find_match(&self, file_name: &str, sample: &[u8]) -> Option<&DisplayProfile> {
+        if !self.is_auto_select_enabled {
+            return None;
+        }
+        let sample = String::from_utf8_lossy(&sample[..sample.len().min(SAMPLE_LEN)]);
+        self.profiles
+            .iter()
+            .find(|profile| profile.matches(file_name, &sample))
+    }
+
+    pub fn get(&self, name: &str) -> Option<&DisplayProfile> {
+        self.profiles.iter().find(|x| x.name == name)
+    }
+
+    /// Saves the options under the name, replacing the options of an existing profile with the name (patterns are kept)
+    pub fn save(&mut self, name: &str, options: &DataDisplayOptions) {
+        match self.profiles.iter_mut().find(|x| x.name == name) {
+            Some(profile) => profile.options = options.clone(),
+            None => self.profiles.push(DisplayProfile {
+                name: name.to_string(),
+                options: options.clone(),
+                ..Default::default()
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn profile(name: &str, file_name_pattern: &str, content_pattern: &str) -> DisplayProfile {
+        DisplayProfile {
+            name: name.into(),
+            file_name_pattern: file_name_pattern.into(),
+            content_pattern: content_pattern.into(),
+            options: Default::default(),
+        }
+    }
+
+    #[test]
+    fn first_matching_profile_chosen() {
+        let mut profiles = DisplayProfiles {
+            is_auto_select_enabled: true,
+            profiles: vec![
+                profile("none set", "", ""),
+                profile("bunyan", "", r#""v":0"#),
+                profile("tracing", r"^api.*\.log$", r#""fields":\{"message""#),
+                profile("invalid", "(", ""),
+            ],
+        };
+        let name = |file_name: &str, sample: &str| {
+            profiles
+                .find_match(file_name, sample.as_bytes())
+                .map(|x| x.name.clone())
+        };
+        assert_eq!(
+            name("a.log", r#"{"v":0,"msg":"hi"}"#),
+            Some("bunyan".into())
+        );
+        assert_eq!(
+            name("api-1.log", r#"{"fields":{"message":"hi"}}"#),
+            Some("tracing".into())
+        );
+        // Both patterns must match
+        assert_eq!(name("other.log", r#"{"fields":{"message":"hi"}}"#), None);
+
+        profiles.is_auto_select_enabled = false;
+        assert_eq!(
+            profiles
+                .find_match("a.log", br#"{"v":0}"#)
+                .map(|x| x.name.as_str()),
+            None
+        );
+    }
+
+    #[test]
+    fn save_replaces_options_but_keeps_patterns() {
+        let mut profiles = DisplayProfiles::default();
+        profiles.save("p", &Default::default());
+        profiles.profiles[0].content_pattern = "x".into();
+        let mut options = DataDisplayOptions::default();
+        options.min_level = Some("Warn".into());
+        profiles.save("p", &options);
+        assert_eq!(profiles.profiles.len(), 1);
+        assert_eq!(profiles.get("p").unwrap().content_pattern, "x");
+        assert_eq!(profiles.get("p").unwrap().options, options);
+    }
+}
@@ -0,0 +1,146 @@
+//! Exports the visible rows as a table to paste into issues and incident reports
+
+use std::fmt::Write as _;
+
+use super::{
+    data::{Data, LogRow},
+    data_display_options::DataDisplayOptions,
+};
+
+/// The visible rows (in display order) as a Markdown table with the columns of the main list
+pub fn markdown_table(data: &Data, options: &DataDisplayOptions) -> String {
+    let fields = options.main_list_fields();
+    let mut result = String::new();
+    let header: Vec<String> = fields.iter().map(|x| markdown_cell(x)).collect();
+    let _ = writeln!(result, "| {} |", header.join(" | "));
+    let _ = writeln!(result, "|{}", " --- |".repeat(fields.len()));
+    for row in data.rows_iter() {
+        let cells: Vec<String> = fields
+            .iter()
+            .map(|field_name| markdown_cell(&cell_text(row, field_name, options)))
+            .collect();
+        let _ = writeln!(result, "| {} |", cells.join(" | "));
+    }
+    result
+}
+
+/// The visible rows (in display order) as an HTML document with a table of the columns of the main list.
+/// The row tint, highlight and field colors are kept as inline styles
+pub fn html_report(data: &Data, options: &DataDisplayOptions, title: &str) -> String {
+    let fields = options.main_list_fields();
+    let mut result = String::new();
+    let title = html_escape(title);
+    let _ = writeln!(
+        result,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n\
+         <style>table {{ border-collapse: collapse; font-family: monospace; }} \
+         th, td {{ border: 1px solid #ccc; padding: 2px 6px; text-align: left; vertical-align: top; }}</style>\n\
+         </head>\n<body>\n<h1>{title}</h1>"
+    );
+    if let Some(filter) = data.applied_filter() {
+        let _ = writeln!(
+            result,
+            "<p>Filter: {}</p>",
+            html_escape(&filter.to_string())
+        );
+    }
+    let _ = writeln!(
+        result,
+        "<p>{} of {} rows</p>\n<table>\n<tr>",
+        data.len(),
+        data.total_len_unfiltered()
+    );
+    for field_name in fields {
+        let _ = writeln!(result, "<th>{}</th>", html_escape(field_name));
+    }
+    result.push_str("</tr>\n");
+    for row in data.rows_iter() {
+        let highlight = options.row_highlight(row);
+        let background = highlight.and_then(|x| x.background).or_else(|| {
+            options.row_tint_color(&row.field_value(options.level_field_name()).display_str())
+        });
+        match background {
+            Some(color) => {
+                let _ = writeln!(
+                    result,
+                    "<tr style=\"background-color: {}\">",
+                    color.to_hex()
+                );
+            }
+            None => result.push_str("<tr>\n"),
+        }
+        for field_name in fields {
+            let text = row.field_value(field_name).display_str();
+            let color = options
+                .field_color(field_name, &text)
+                .or_else(|| highlight.and_then(|x| x.text_color));
+            let text =
+                html_escape(&options.formatted_value(field_name, &text)).replace('\n', "<br>");
+            match color {
+                Some(color) => {
+                    let _ = writeln!(
+                        result,
+                        "<td style=\"color: {}\">{text}</td>",
+                        color.to_hex()
+                    );
+                }
+                None => {
+                    let _ = writeln!(result, "<td>{text}</td>");
+                }
+            }
+        }
+        result.push_str("</tr>\n");
+    }
+    result.push_str("</table>\n</body>\n</html>\n");
+    result
+}
+
+/// The value as shown in the main list
+fn cell_text(row: LogRow<'_>, field_name: &str, options: &DataDisplayOptions) -> String {
+    let text = row.field_value(field_name).display_str();
+    options.formatted_value(field_name, &text).into_owned()
+}
+
+fn markdown_cell(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('|', "\\|")
+        .replace("\r\n", "<br>")
+        .replace('\n', "<br>")
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn markdown_escapes_cells() {
+        let options = DataDisplayOptions::default();
+        let text = r#"{"time": "t1", "level_str": "INFO", "msg": "a | b\nnext line"}"#;
+        let data = Data::try_from((&options, text)).unwrap();
+        let result = markdown_table(&data, &options);
+        let lines: Vec<&str> = result.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[1].starts_with("| --- |"));
+        assert!(lines[2].contains(r"a \| b<br>next line"));
+    }
+
+    #[test]
+    fn html_escapes_and_colors() {
+        let options = DataDisplayOptions::default();
+        let text = r#"{"time": "t1", "level_str": "Error", "msg": "<b>&</b>"}"#;
+        let data = Data::try_from((&options, text)).unwrap();
+        let result = html_report(&data, &options, "Report");
+        assert!(result.contains("&lt;b&gt;&amp;&lt;/b&gt;"));
+        let error_color = options.field_color("level_str", "Error").unwrap();
+        assert!(result.contains(&format!("color: {}", error_color.to_hex())));
+    }
+}
@@ -1,7 +1,11 @@
 #![warn(clippy::all, rust_2018_idioms)]
 
+#[cfg(feature = "gui")]
 mod app;
-pub use app::LogViewerApp;
+pub mod core;
+pub use crate::core::data::line_parser::LineParser;
+#[cfg(feature = "gui")]
+pub use app::{ActionGroup, LogViewerApp, MenuContext, RowAction, RowCommand, SimpleAction};
 
 // TODO 3: Add button to set to current value if a field is selected
 // TODO 3: Support auto reload (look into watching for changes) https://watchexec.github.io/